@@ -0,0 +1,26 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use wasmgame_factory::msg::{
+    ConfigResponse, ExecuteMsg, GameAddressResponse, GamesResponse, InstantiateMsg, QueryMsg,
+};
+use wasmgame_factory::state::{Config, Game};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(Game), &out_dir);
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(GamesResponse), &out_dir);
+    export_schema(&schema_for!(GameAddressResponse), &out_dir);
+}
@@ -0,0 +1,290 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdError,
+    StdResult, SubMsg, WasmMsg,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
+
+use wasmgame_contracts::contract::{validate_address, validate_reveal_stage, validate_stages};
+use wasmgame_contracts::msg::InstantiateMsg as GameInstantiateMsg;
+
+use crate::error::ContractError;
+use crate::instantiate2::{game_address, game_salt, instantiate2_msg};
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, GameAddressResponse, GameInfo, GamesResponse, InstantiateMsg, MigrateMsg,
+    QueryMsg,
+};
+use crate::state::{Config, Game, CONFIG, GAMES, PENDING_GAME, PENDING_GAME_ADDRESS};
+
+// Pagination of the queries.
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
+/// Reply to the instantiation of a game, carrying its address.
+const INSTANTIATE_GAME_REPLY_ID: u64 = 1;
+
+// Version info, for migration info
+const CONTRACT_NAME: &str = "crates.io:wasmgame-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    if version.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate {
+            previous_contract: version.contract,
+        });
+    }
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // If owner not in message, set it as sender.
+    let owner = msg
+        .owner
+        .map_or(Ok(info.sender), |o| deps.api.addr_validate(&o))?;
+    let config = Config {
+        owner,
+        game_code_id: msg.game_code_id,
+        game_checksum: msg.game_checksum.as_deref().map(decode_checksum).transpose()?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateGame {
+            label,
+            game,
+            deterministic,
+        } => execute_create_game(deps, env, info, label, game, deterministic.unwrap_or(false)),
+        ExecuteMsg::UpdateGameCodeId {
+            game_code_id,
+            game_checksum,
+        } => execute_update_game_code_id(deps, env, info, game_code_id, game_checksum),
+        ExecuteMsg::UpdateOwner { new_owner } => execute_update_owner(deps, env, info, new_owner),
+    }
+}
+
+/// Instantiates a game with the stored code id, once its parameters are checked, so that
+/// a misconfigured game fails with a clear error before being spawned. The game is
+/// indexed when the reply brings its address.
+pub fn execute_create_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    label: String,
+    mut game: GameInstantiateMsg,
+    deterministic: bool,
+) -> Result<Response, ContractError> {
+    if label.trim().is_empty() {
+        return Err(ContractError::InvalidLabel {});
+    }
+    if game.bins == 0 {
+        return Err(ContractError::InvalidBins {});
+    }
+    if game.ticket_price.amount.is_zero() {
+        return Err(ContractError::InvalidTicketPrice {});
+    }
+    validate_stages(&env, &game.stage_bid, &game.stage_claim_airdrop, &game.stage_claim_prize)?;
+    if let Some(stage_reveal) = &game.stage_reveal {
+        validate_reveal_stage(&game.stage_bid, stage_reveal, &game.stage_claim_airdrop)?;
+    }
+    validate_address(deps.as_ref(), &game.address_prefix, &game.cw20_token_address)?;
+
+    // The game would otherwise be owned by the factory.
+    let owner = match &game.owner {
+        Some(owner) => validate_address(deps.as_ref(), &game.address_prefix, owner)?,
+        None => info.sender.clone(),
+    };
+    game.owner = Some(owner.to_string());
+
+    let cfg = CONFIG.load(deps.storage)?;
+    PENDING_GAME.save(
+        deps.storage,
+        &Game {
+            code_id: cfg.game_code_id,
+            creator: info.sender.clone(),
+            label: label.clone(),
+            created_height: env.block.height,
+        },
+    )?;
+    let instantiate_msg = if deterministic {
+        // The address is checked in the reply, as the tooling may already have funded it.
+        let checksum = cfg.game_checksum.ok_or(ContractError::ChecksumNotSet {})?;
+        let salt = game_salt(&info.sender, &label);
+        let factory = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+        let address = game_address(&checksum, &factory, &salt)?;
+        PENDING_GAME_ADDRESS.save(deps.storage, &Binary::from(address.as_slice()))?;
+        instantiate2_msg(&env.contract.address, &info.sender, cfg.game_code_id, label, to_binary(&game)?, salt)?
+    } else {
+        WasmMsg::Instantiate {
+            admin: Some(info.sender.to_string()),
+            code_id: cfg.game_code_id,
+            msg: to_binary(&game)?,
+            funds: vec![],
+            label,
+        }
+        .into()
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(instantiate_msg, INSTANTIATE_GAME_REPLY_ID))
+        .add_attribute("action", "create_game")
+        .add_attribute("creator", info.sender)
+        .add_attribute("owner", owner)
+        .add_attribute("code_id", cfg.game_code_id.to_string()))
+}
+
+pub fn execute_update_game_code_id(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    game_code_id: u64,
+    game_checksum: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // The checksum of the previous code does not derive the address of the new games.
+    cfg.game_code_id = game_code_id;
+    cfg.game_checksum = game_checksum.as_deref().map(decode_checksum).transpose()?;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_game_code_id")
+        .add_attribute("code_id", game_code_id.to_string()))
+}
+
+pub fn execute_update_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    cfg.owner = deps.api.addr_validate(&new_owner)?;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_owner")
+        .add_attribute("owner", cfg.owner))
+}
+
+/// Indexes the game instantiated by `CreateGame` under its address.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != INSTANTIATE_GAME_REPLY_ID {
+        return Err(ContractError::UnknownReply { id: msg.id });
+    }
+    let res = parse_reply_instantiate_data(msg).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let address = deps.api.addr_validate(&res.contract_address)?;
+    if let Some(expected) = PENDING_GAME_ADDRESS.may_load(deps.storage)? {
+        PENDING_GAME_ADDRESS.remove(deps.storage);
+        let expected = CanonicalAddr::from(expected.to_vec());
+        if deps.api.addr_canonicalize(address.as_str())? != expected {
+            return Err(ContractError::UnexpectedGameAddress {
+                expected: deps.api.addr_humanize(&expected)?.to_string(),
+                actual: address.to_string(),
+            });
+        }
+    }
+    let game = PENDING_GAME.load(deps.storage)?;
+    PENDING_GAME.remove(deps.storage);
+    GAMES.save(deps.storage, &address, &game)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "index_game")
+        .add_attribute("game", address))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Games { start_after, limit } => to_binary(&query_games(deps, start_after, limit)?),
+        QueryMsg::GameAddress { creator, label } => to_binary(&query_game_address(deps, env, creator, label)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: cfg.owner.to_string(),
+        game_code_id: cfg.game_code_id,
+        game_checksum: cfg.game_checksum.map(|checksum| hex::encode(checksum.as_slice())),
+    })
+}
+
+/// Returns the games spawned by the factory, in ascending order of address.
+pub fn query_games(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<GamesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_after = start_after.map(|a| deps.api.addr_validate(&a)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let games = GAMES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, game) = item?;
+            Ok(GameInfo {
+                address: address.to_string(),
+                code_id: game.code_id,
+                creator: game.creator.to_string(),
+                label: game.label,
+                created_height: game.created_height,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(GamesResponse { games })
+}
+
+/// Returns the address of the game the creator instantiates with the label at a
+/// deterministic address, with the current game code.
+pub fn query_game_address(deps: Deps, env: Env, creator: String, label: String) -> StdResult<GameAddressResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let checksum = cfg
+        .game_checksum
+        .ok_or_else(|| StdError::generic_err(ContractError::ChecksumNotSet {}.to_string()))?;
+    let salt = game_salt(&deps.api.addr_validate(&creator)?, &label);
+    let factory = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let address = game_address(&checksum, &factory, &salt).map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(GameAddressResponse {
+        address: deps.api.addr_humanize(&address)?.to_string(),
+        salt,
+    })
+}
+
+fn decode_checksum(checksum: &str) -> Result<Binary, ContractError> {
+    match hex::decode(checksum) {
+        Ok(checksum) if checksum.len() == 32 => Ok(Binary::from(checksum)),
+        _ => Err(ContractError::InvalidChecksum {}),
+    }
+}
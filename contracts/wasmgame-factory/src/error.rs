@@ -0,0 +1,41 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Game(#[from] wasmgame_contracts::ContractError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Cannot migrate from different contract type: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("The label of the game cannot be empty")]
+    InvalidLabel {},
+
+    #[error("A game must have at least one bin")]
+    InvalidBins {},
+
+    #[error("The ticket price of a game cannot be zero")]
+    InvalidTicketPrice {},
+
+    #[error("The checksum of the game code must be 32 bytes, hex encoded")]
+    InvalidChecksum {},
+
+    #[error("The checksum of the game code is required to instantiate a game at a deterministic address")]
+    ChecksumNotSet {},
+
+    #[error("Deterministic instantiation requires the instantiate2 feature")]
+    Instantiate2Disabled {},
+
+    #[error("The game was instantiated at {actual} instead of {expected}")]
+    UnexpectedGameAddress { expected: String, actual: String },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply { id: u64 },
+}
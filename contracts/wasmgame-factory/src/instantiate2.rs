@@ -0,0 +1,177 @@
+use cosmwasm_std::{Addr, Binary, CanonicalAddr, CosmosMsg};
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+
+// ======================================================================================
+// Deterministic instantiation
+// ======================================================================================
+/// Returns the salt of the game created by the address with the label, so that the
+/// address of the game is known before it is created.
+pub fn game_salt(creator: &Addr, label: &str) -> Binary {
+    let mut key = Vec::<u8>::new();
+    key.extend_from_slice(&(creator.as_str().len() as u64).to_be_bytes());
+    key.extend_from_slice(creator.as_bytes());
+    key.extend_from_slice(label.as_bytes());
+    Binary::from(Sha256::digest(&key).to_vec())
+}
+
+/// Returns the canonical address of the contract instantiated by the creator with
+/// `MsgInstantiateContract2`, from the checksum of its code and the salt, as derived by
+/// wasmd without fixing the instantiation message.
+pub fn game_address(checksum: &[u8], creator: &CanonicalAddr, salt: &[u8]) -> Result<CanonicalAddr, ContractError> {
+    if checksum.len() != 32 {
+        return Err(ContractError::InvalidChecksum {});
+    }
+    let mut key = Vec::<u8>::new();
+    key.extend_from_slice(b"wasm\0");
+    for data in [checksum, creator.as_slice(), salt, b""] {
+        key.extend_from_slice(&(data.len() as u64).to_be_bytes());
+        key.extend_from_slice(data);
+    }
+    // Address of the module account, as defined by ADR-028.
+    let address = Sha256::new()
+        .chain(Sha256::digest(b"module"))
+        .chain(&key)
+        .finalize();
+    Ok(CanonicalAddr::from(address.to_vec()))
+}
+
+/// Returns the message instantiating the game at the address derived from the salt.
+/// Requires the `instantiate2` feature.
+pub fn instantiate2_msg(
+    sender: &Addr,
+    admin: &Addr,
+    code_id: u64,
+    label: String,
+    msg: Binary,
+    salt: Binary,
+) -> Result<CosmosMsg, ContractError> {
+    #[cfg(feature = "instantiate2")]
+    {
+        let msg = proto::MsgInstantiateContract2 {
+            sender: sender.to_string(),
+            admin: admin.to_string(),
+            code_id,
+            label,
+            msg: msg.to_vec(),
+            funds: vec![],
+            salt: salt.to_vec(),
+            fix_msg: false,
+        };
+        Ok(proto::stargate_msg(proto::MSG_INSTANTIATE_CONTRACT_2, &msg))
+    }
+    #[cfg(not(feature = "instantiate2"))]
+    {
+        let _ = (sender, admin, code_id, label, msg, salt);
+        Err(ContractError::Instantiate2Disabled {})
+    }
+}
+
+/// Protobuf encoding of the wasmd `MsgInstantiateContract2`.
+#[cfg(feature = "instantiate2")]
+mod proto {
+    use cosmwasm_std::{Binary, CosmosMsg};
+
+    pub const MSG_INSTANTIATE_CONTRACT_2: &str = "/cosmwasm.wasm.v1.MsgInstantiateContract2";
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgInstantiateContract2 {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(string, tag = "2")]
+        pub admin: String,
+        #[prost(uint64, tag = "3")]
+        pub code_id: u64,
+        #[prost(string, tag = "4")]
+        pub label: String,
+        #[prost(bytes = "vec", tag = "5")]
+        pub msg: Vec<u8>,
+        #[prost(message, repeated, tag = "6")]
+        pub funds: Vec<Coin>,
+        #[prost(bytes = "vec", tag = "7")]
+        pub salt: Vec<u8>,
+        #[prost(bool, tag = "8")]
+        pub fix_msg: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Coin {
+        #[prost(string, tag = "1")]
+        pub denom: String,
+        #[prost(string, tag = "2")]
+        pub amount: String,
+    }
+
+    pub fn stargate_msg(type_url: &str, msg: &impl prost::Message) -> CosmosMsg {
+        CosmosMsg::Stargate {
+            type_url: type_url.to_string(),
+            value: Binary::from(msg.encode_to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_address_matches_wasmd() {
+        // Test vectors of cosmwasm-std `instantiate2_address`, without message.
+        let checksum = hex::decode("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5").unwrap();
+        let creator = CanonicalAddr::from(hex::decode("9999999999aaaaaaaaaabbbbbbbbbbcccccccccc").unwrap());
+        let address = game_address(&checksum, &creator, b"a").unwrap();
+        assert_eq!(
+            hex::encode(address.as_slice()),
+            "5e865d3e45ad3e961f77fd77d46543417ced44d924dc3e079b5415ff6775f847"
+        );
+        let salt = hex::decode(
+            "aabbccddeeffffeeddbbccddaa66551155aaaabbcc787878789900aabbccddeeffffeeddbbccddaa66551155aaaabbcc787878789900aabbbbcc221100acadae",
+        )
+        .unwrap();
+        let address = game_address(&checksum, &creator, &salt).unwrap();
+        assert_eq!(
+            hex::encode(address.as_slice()),
+            "9384c6248c0bb171e306fd7da0993ec1e20eba006452a3a9e078883eb3594564"
+        );
+
+        assert_eq!(
+            game_address(&checksum[1..], &creator, b"a").unwrap_err(),
+            ContractError::InvalidChecksum {}
+        );
+    }
+
+    #[test]
+    fn game_salt_depends_on_creator_and_label() {
+        let salt = game_salt(&Addr::unchecked("creator"), "game");
+        assert_eq!(salt.len(), 32);
+        assert_ne!(salt, game_salt(&Addr::unchecked("creator"), "game2"));
+        assert_ne!(salt, game_salt(&Addr::unchecked("creator2"), "game"));
+        // The length of the creator separates it from the label.
+        assert_ne!(
+            game_salt(&Addr::unchecked("ab"), "c"),
+            game_salt(&Addr::unchecked("a"), "bc")
+        );
+    }
+
+    #[cfg(feature = "instantiate2")]
+    #[test]
+    fn instantiate2_msg_is_protobuf_encoded() {
+        let msg = instantiate2_msg(
+            &Addr::unchecked("factory"),
+            &Addr::unchecked("admin"),
+            7,
+            "game".to_string(),
+            Binary::from(b"{}".to_vec()),
+            Binary::from(b"salt".to_vec()),
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Stargate {
+                type_url: "/cosmwasm.wasm.v1.MsgInstantiateContract2".to_string(),
+                value: Binary::from(b"\x0a\x07factory\x12\x05admin\x18\x07\x22\x04game\x2a\x02{}\x3a\x04salt".to_vec()),
+            }
+        );
+    }
+}
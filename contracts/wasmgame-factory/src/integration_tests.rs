@@ -0,0 +1,265 @@
+#![cfg(test)]
+
+use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::{Duration, Scheduled};
+
+use wasmgame_contracts::msg::{ConfigResponse as GameConfigResponse, InstantiateMsg as GameInstantiateMsg, QueryMsg as GameQueryMsg};
+use wasmgame_contracts::state::Stage;
+use wasmgame_contracts::testing::{contract_game, game_instantiate_msg, mock_app, valid_stages};
+
+use crate::contract::{execute, instantiate, query, reply};
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, GameAddressResponse, GameInfo, GamesResponse, InstantiateMsg, QueryMsg,
+};
+use crate::ContractError;
+
+fn contract_factory() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    Box::new(contract)
+}
+
+fn stage(start: u64) -> Stage {
+    Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
+    }
+}
+
+fn game_msg(owner: Option<String>) -> GameInstantiateMsg {
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let ticket_price = Coin {
+        denom: "ujuno".into(),
+        amount: Uint128::new(10),
+    };
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        10,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some("token0000".to_string()),
+    );
+    msg.owner = owner;
+    msg
+}
+
+fn setup_factory(router: &mut App, owner: &Addr) -> Addr {
+    let game_code_id = router.store_code(contract_game());
+    let factory_code_id = router.store_code(contract_factory());
+    let msg = InstantiateMsg {
+        owner: None,
+        game_code_id,
+        game_checksum: None,
+    };
+    router
+        .instantiate_contract(factory_code_id, owner.clone(), &msg, &[], "factory", None)
+        .unwrap()
+}
+
+fn get_games(router: &App, factory_addr: &Addr, start_after: Option<String>, limit: Option<u32>) -> Vec<GameInfo> {
+    let res: GamesResponse = router
+        .wrap()
+        .query_wasm_smart(factory_addr, &QueryMsg::Games { start_after, limit })
+        .unwrap();
+    res.games
+}
+
+#[test]
+fn create_games() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner0000");
+    let creator = Addr::unchecked("creator0000");
+    let factory_addr = setup_factory(&mut router, &owner);
+
+    // The created game is owned by its creator, not by the factory.
+    let msg = ExecuteMsg::CreateGame {
+        label: "first game".to_string(),
+        game: game_msg(None),
+        deterministic: None,
+    };
+    router.execute_contract(creator.clone(), factory_addr.clone(), &msg, &[]).unwrap();
+    let games = get_games(&router, &factory_addr, None, None);
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].creator, creator.to_string());
+    assert_eq!(games[0].label, "first game");
+    assert_eq!(games[0].created_height, 199_999);
+    let config: GameConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&games[0].address, &GameQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.owner, Some(creator.to_string()));
+    let info = router.contract_data(&Addr::unchecked(&games[0].address)).unwrap();
+    assert_eq!(info.admin, Some(creator.clone()));
+
+    // A game can be created for another owner.
+    let msg = ExecuteMsg::CreateGame {
+        label: "second game".to_string(),
+        game: game_msg(Some(owner.to_string())),
+        deterministic: None,
+    };
+    router.execute_contract(creator.clone(), factory_addr.clone(), &msg, &[]).unwrap();
+    let games = get_games(&router, &factory_addr, None, None);
+    assert_eq!(games.len(), 2);
+    let config: GameConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&games[1].address, &GameQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.owner, Some(owner.to_string()));
+
+    // Paginated by address.
+    let page = get_games(&router, &factory_addr, None, Some(1));
+    assert_eq!(page, vec![games[0].clone()]);
+    let page = get_games(&router, &factory_addr, Some(games[0].address.clone()), None);
+    assert_eq!(page, vec![games[1].clone()]);
+}
+
+#[test]
+fn create_game_validates_params() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner0000");
+    let factory_addr = setup_factory(&mut router, &owner);
+
+    let create = |router: &mut App, label: &str, game: GameInstantiateMsg| -> ContractError {
+        let msg = ExecuteMsg::CreateGame {
+            label: label.to_string(),
+            game,
+            deterministic: None,
+        };
+        router
+            .execute_contract(owner.clone(), factory_addr.clone(), &msg, &[])
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    };
+
+    assert_eq!(create(&mut router, " ", game_msg(None)), ContractError::InvalidLabel {});
+    let game = GameInstantiateMsg { bins: 0, ..game_msg(None) };
+    assert_eq!(create(&mut router, "game", game), ContractError::InvalidBins {});
+    let mut game = game_msg(None);
+    game.ticket_price.amount = Uint128::zero();
+    assert_eq!(create(&mut router, "game", game), ContractError::InvalidTicketPrice {});
+    let game = GameInstantiateMsg { stage_bid: stage(199_000), ..game_msg(None) };
+    assert_eq!(
+        create(&mut router, "game", game),
+        ContractError::Game(wasmgame_contracts::ContractError::BidStartPassed {})
+    );
+    let game = GameInstantiateMsg { stage_claim_airdrop: stage(200_001), ..game_msg(None) };
+    assert!(matches!(
+        create(&mut router, "game", game),
+        ContractError::Game(wasmgame_contracts::ContractError::StagesOverlap { .. })
+    ));
+    assert!(get_games(&router, &factory_addr, None, None).is_empty());
+}
+
+#[test]
+fn update_game_code_id() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner0000");
+    let creator = Addr::unchecked("creator0000");
+    let factory_addr = setup_factory(&mut router, &owner);
+    let game_code_id = router.store_code(contract_game());
+
+    // Only the owner updates the code id.
+    let msg = ExecuteMsg::UpdateGameCodeId {
+        game_code_id,
+        game_checksum: None,
+    };
+    let err: ContractError = router
+        .execute_contract(creator.clone(), factory_addr.clone(), &msg, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Unauthorized {});
+    router.execute_contract(owner.clone(), factory_addr.clone(), &msg, &[]).unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&factory_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: owner.to_string(),
+            game_code_id,
+            game_checksum: None,
+        }
+    );
+
+    // The games created from now on use the new code id.
+    let msg = ExecuteMsg::CreateGame {
+        label: "game".to_string(),
+        game: game_msg(None),
+        deterministic: None,
+    };
+    router.execute_contract(creator, factory_addr.clone(), &msg, &[]).unwrap();
+    let games = get_games(&router, &factory_addr, None, None);
+    assert_eq!(games[0].code_id, game_code_id);
+
+    // Ownership transfer.
+    let msg = ExecuteMsg::UpdateOwner { new_owner: "owner0001".to_string() };
+    router.execute_contract(owner.clone(), factory_addr.clone(), &msg, &[]).unwrap();
+    let msg = ExecuteMsg::UpdateGameCodeId {
+        game_code_id: 1,
+        game_checksum: None,
+    };
+    let err: ContractError = router
+        .execute_contract(owner, factory_addr, &msg, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn deterministic_games() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner0000");
+    let creator = Addr::unchecked("creator0000");
+    let factory_addr = setup_factory(&mut router, &owner);
+    let create = |router: &mut App| -> ContractError {
+        let msg = ExecuteMsg::CreateGame {
+            label: "game".to_string(),
+            game: game_msg(None),
+            deterministic: Some(true),
+        };
+        router
+            .execute_contract(creator.clone(), factory_addr.clone(), &msg, &[])
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    };
+
+    // The address cannot be derived without the checksum of the game code.
+    assert_eq!(create(&mut router), ContractError::ChecksumNotSet {});
+    let query = QueryMsg::GameAddress {
+        creator: creator.to_string(),
+        label: "game".to_string(),
+    };
+    let err = router
+        .wrap()
+        .query_wasm_smart::<GameAddressResponse>(&factory_addr, &query)
+        .unwrap_err();
+    assert!(err.to_string().contains(&ContractError::ChecksumNotSet {}.to_string()));
+
+    let update = |router: &mut App, game_checksum: &str| {
+        let msg = ExecuteMsg::UpdateGameCodeId {
+            game_code_id: 1,
+            game_checksum: Some(game_checksum.to_string()),
+        };
+        router.execute_contract(owner.clone(), factory_addr.clone(), &msg, &[])
+    };
+    let err: ContractError = update(&mut router, "13a1fc99").unwrap_err().downcast().unwrap();
+    assert_eq!(err, ContractError::InvalidChecksum {});
+    let checksum = "13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5";
+    update(&mut router, checksum).unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&factory_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.game_checksum, Some(checksum.to_string()));
+
+    // MsgInstantiateContract2 is only sent with the instantiate2 feature.
+    #[cfg(not(feature = "instantiate2"))]
+    assert_eq!(create(&mut router), ContractError::Instantiate2Disabled {});
+    assert!(get_games(&router, &factory_addr, None, None).is_empty());
+}
@@ -0,0 +1,7 @@
+pub mod contract;
+mod error;
+pub mod instantiate2;
+pub mod msg;
+pub mod state;
+mod integration_tests;
+pub use crate::error::ContractError;
@@ -0,0 +1,96 @@
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use wasmgame_contracts::msg::InstantiateMsg as GameInstantiateMsg;
+
+// ======================================================================================
+// Entrypoints data structures
+// ======================================================================================
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Owner if none set to info.sender.
+    pub owner: Option<String>,
+    /// Code id of the game contract, stored on chain beforehand.
+    pub game_code_id: u64,
+    /// Hex encoded checksum of the game code, required to instantiate the games at a
+    /// deterministic address.
+    pub game_checksum: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum ExecuteMsg {
+    /// Instantiate a game contract with the stored code id and index it. The game is
+    /// owned by the sender if no owner is set, and the sender is the admin migrating it.
+    /// If deterministic, the game is instantiated with `MsgInstantiateContract2` at the
+    /// address returned by `GameAddress`, which requires the `instantiate2` feature.
+    CreateGame {
+        label: String,
+        game: GameInstantiateMsg,
+        deterministic: Option<bool>,
+    },
+    /// Update the code id, and its checksum, of the games created from now on (only
+    /// owner).
+    UpdateGameCodeId {
+        game_code_id: u64,
+        game_checksum: Option<String>,
+    },
+    /// Transfer the ownership of the factory (only owner).
+    UpdateOwner { new_owner: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Games spawned by the factory, in ascending order of address.
+    Games {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Address of the game the creator instantiates with the label at a deterministic
+    /// address, with its salt, so that it can be funded before being created.
+    GameAddress { creator: String, label: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+// ======================================================================================
+// Queries data structures
+// ======================================================================================
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub game_code_id: u64,
+    pub game_checksum: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameInfo {
+    /// Address of the game contract.
+    pub address: String,
+    /// Code id the game was instantiated with.
+    pub code_id: u64,
+    /// Address that created the game.
+    pub creator: String,
+    /// Label of the game contract.
+    pub label: String,
+    /// Height at which the game was created.
+    pub created_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GamesResponse {
+    pub games: Vec<GameInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameAddressResponse {
+    /// Address of the game once created.
+    pub address: String,
+    /// Salt of `MsgInstantiateContract2`, derived from the creator and the label.
+    pub salt: Binary,
+}
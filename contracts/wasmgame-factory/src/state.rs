@@ -0,0 +1,43 @@
+use cosmwasm_std::{Addr, Binary};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Struct to manage the factory configuration.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Owner updating the game code id.
+    pub owner: Addr,
+    /// Code id of the game contract instantiated by `CreateGame`.
+    pub game_code_id: u64,
+    /// Checksum of the game code, deriving the address of the games instantiated at a
+    /// deterministic address.
+    pub game_checksum: Option<Binary>,
+}
+
+/// Game spawned by the factory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Game {
+    /// Code id the game was instantiated with.
+    pub code_id: u64,
+    /// Address that created the game.
+    pub creator: Addr,
+    /// Label of the game contract.
+    pub label: String,
+    /// Height at which the game was created.
+    pub created_height: u64,
+}
+
+/// Storage to manage the factory configuration.
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Storage for the game being instantiated, indexed once its address is known from
+/// the reply.
+pub const PENDING_GAME: Item<Game> = Item::new("pending_game");
+
+/// Storage for the canonical address expected for the game being instantiated at a
+/// deterministic address.
+pub const PENDING_GAME_ADDRESS: Item<Binary> = Item::new("pending_game_address");
+
+/// Storage for the games spawned by the factory, by address.
+pub const GAMES: Map<&Addr, Game> = Map::new("games");
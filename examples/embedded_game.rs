@@ -0,0 +1,231 @@
+//! Example of a contract embedding the game as a library.
+//!
+//! The arcade contract dispatches a superset of the game messages: the game ones are
+//! forwarded to the game entry points, while the arcade keeps its own state next to
+//! the game state. Run its tests with:
+//!
+//! ``` shell
+//! cargo test --example embedded_game
+//! ```
+
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use wasmgame_contracts::contract as game;
+use wasmgame_contracts::msg::{
+    ExecuteMsg as GameExecuteMsg, InstantiateMsg as GameInstantiateMsg,
+    QueryMsg as GameQueryMsg,
+};
+use wasmgame_contracts::ContractError;
+
+/// Message shown by the arcade, stored next to the game state.
+const MOTD: Item<String> = Item::new("arcade_motd");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub motd: String,
+    pub game: GameInstantiateMsg,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum ExecuteMsg {
+    /// Update the message of the day.
+    SetMotd { motd: String },
+    /// Forward a message to the embedded game.
+    Game(GameExecuteMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Motd {},
+    /// Forward a query to the embedded game.
+    Game(GameQueryMsg),
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    MOTD.save(deps.storage, &msg.motd)?;
+    game::instantiate(deps, env, info, msg.game)
+}
+
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetMotd { motd } => {
+            MOTD.save(deps.storage, &motd)?;
+            Ok(Response::new().add_attribute("action", "set_motd"))
+        }
+        ExecuteMsg::Game(msg) => game::execute(deps, env, info, msg),
+    }
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Motd {} => to_binary(&MOTD.load(deps.storage)?),
+        QueryMsg::Game(msg) => game::query(deps, env, msg),
+    }
+}
+
+fn main() {
+    // The arcade is meant to be built as a contract crate; its behaviour is covered
+    // by the tests below.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{Addr, BlockInfo, Coin, Empty, Uint128};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use cw_utils::{Duration, Scheduled};
+    use wasmgame_contracts::msg::{BidResponse, ConfigResponse};
+    use wasmgame_contracts::state::Stage;
+
+    fn contract_arcade() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        Box::new(contract)
+    }
+
+    fn stage(start: u64) -> Stage {
+        Stage {
+            start: Scheduled::AtHeight(start),
+            duration: Duration::Height(2),
+        }
+    }
+
+    #[test]
+    fn arcade_embeds_game() {
+        let owner = Addr::unchecked("owner0000");
+        let player = Addr::unchecked("player0000");
+        let ticket_price = Coin {
+            denom: "ujuno".into(),
+            amount: Uint128::new(10),
+        };
+
+        let mut router = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &player, vec![ticket_price.clone()])
+                .unwrap()
+        });
+        let block = router.block_info();
+        router.set_block(BlockInfo { height: 199_999, ..block });
+
+        let code_id = router.store_code(contract_arcade());
+        let msg = InstantiateMsg {
+            motd: "Welcome".to_string(),
+            game: GameInstantiateMsg {
+                owner: None,
+                cw20_token_address: "token0000".to_string(),
+                ticket_price: ticket_price.clone(),
+                bins: 10,
+                stage_bid: stage(200_000),
+                stage_claim_airdrop: stage(201_000),
+                stage_claim_prize: stage(202_000),
+                first_bidder_bonus: None,
+                max_winners: None,
+                min_participants: None,
+                price_schedule: None,
+                stage_reveal: None,
+                charity_address: None,
+                address_prefix: None,
+                ticket_cw20_address: None,
+                max_claim_size: None,
+                max_bids: None,
+                max_per_bin: None,
+                parimutuel: None,
+                anti_snipe: None,
+                remove_bid_penalty_bps: None,
+                max_bid_changes: None,
+                bid_change_fee: None,
+                prize_denom: None,
+                prize_vesting: None,
+                staking_contract: None,
+                rollover_grace_period: None,
+                crank_bounty: None,
+                dust_policy: None,
+                protocol_fee_bps: None,
+                fee_collector: None,
+                revenue_split: None,
+                burn_unclaimed: None,
+                claim_deadline: None,
+                merkle_root_allowlist: None,
+                bid_token_gate: None,
+                bid_nft_gate: None,
+                referral_fee_bps: None,
+                season_pass: None,
+                bid_receipts: None,
+                winner_badges: None,
+                attestor: None,
+                resolution: None,
+                adjacent_tier_bps: None,
+                bin_boundaries: None,
+                ibc_denoms: None,
+                ibc_bidder_port: None,
+                hash_algorithm: None,
+            },
+        };
+        let arcade_addr = router
+            .instantiate_contract(code_id, owner.clone(), &msg, &[], "arcade", None)
+            .unwrap();
+
+        // Arcade and game state live side by side.
+        router
+            .execute_contract(
+                owner.clone(),
+                arcade_addr.clone(),
+                &ExecuteMsg::SetMotd { motd: "Bids are open".to_string() },
+                &[],
+            )
+            .unwrap();
+        let motd: String = router
+            .wrap()
+            .query_wasm_smart(&arcade_addr, &QueryMsg::Motd {})
+            .unwrap();
+        assert_eq!(motd, "Bids are open");
+
+        let config: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(&arcade_addr, &QueryMsg::Game(GameQueryMsg::Config {}))
+            .unwrap();
+        assert_eq!(config.owner, Some(owner.to_string()));
+
+        // Game messages are forwarded to the embedded game.
+        let block = router.block_info();
+        router.set_block(BlockInfo { height: 200_001, ..block });
+        router
+            .execute_contract(
+                player.clone(),
+                arcade_addr.clone(),
+                &ExecuteMsg::Game(GameExecuteMsg::Bid { bin: 3, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None }),
+                &[ticket_price],
+            )
+            .unwrap();
+
+        let bid: BidResponse = router
+            .wrap()
+            .query_wasm_smart(
+                &arcade_addr,
+                &QueryMsg::Game(GameQueryMsg::Bid {
+                    address: player.to_string(),
+                    round: None,
+                }),
+            )
+            .unwrap();
+        assert_eq!(bid.bid, Some(3));
+    }
+}
@@ -0,0 +1,290 @@
+//! Generates the Merkle root and the proofs of a list of `(address, amount)` or
+//! `(address, bin)` pairs, hashing the leaves as the contract does when verifying the
+//! claims, so that the registered roots and the proofs sent by the claimers match.
+//!
+//! ``` shell
+//! cargo run --example merkle_gen -- [--keccak256] testdata/airdrop_list.json [proofs.json]
+//! ```
+//!
+//! The list is either a JSON array of `{"address": ..., "amount": ...}` objects, the
+//! amount being replaced by a `bin` or a stake `weight` for the other trees, or a CSV
+//! file of `address,amount` lines. The root and the proofs of each address are written
+//! in the format of the `testdata` files, to the output file if set. The tree is hashed
+//! with sha256, or with keccak256 for the games instantiated with this `hash_algorithm`.
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use wasmgame_contracts::merkle::HashAlgorithm;
+
+#[derive(Deserialize)]
+struct Entry {
+    address: String,
+    #[serde(alias = "bin", alias = "weight")]
+    amount: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Proofs {
+    pub root: String,
+    pub addresses: Vec<AccountProof>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AccountProof {
+    pub account: String,
+    pub amount: String,
+    pub proofs: Vec<String>,
+}
+
+/// Returns the leaf of the pair, the hash of the address followed by the amount, the
+/// bin or the weight, as built by `ClaimAirdrop` and `ProveStake`.
+fn leaf(algorithm: HashAlgorithm, address: &str, amount: &str) -> [u8; 32] {
+    algorithm
+        .hash(format!("{}{}", address, amount).as_bytes())
+        .unwrap()
+}
+
+/// Hashes a pair of nodes, sorted first so that the proofs do not need to tell the
+/// side of each hash.
+fn hash_pair(algorithm: HashAlgorithm, a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut pair = [*a, *b];
+    pair.sort_unstable();
+    algorithm.hash(&pair.concat()).unwrap()
+}
+
+/// Builds the levels of the tree from the sorted leaves up to the root. The last node of
+/// a level with an odd number of nodes is carried to the next level as is.
+fn build_levels(algorithm: HashAlgorithm, mut leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    leaves.sort_unstable();
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels.last().unwrap();
+        let next = level
+            .chunks(2)
+            .map(|nodes| match nodes {
+                [a, b] => hash_pair(algorithm, a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Returns the siblings of the leaf at each level, from the leaf up to the root.
+fn proof(levels: &[Vec<[u8; 32]>], mut index: usize) -> Vec<String> {
+    let mut proofs = vec![];
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proofs.push(hex::encode(sibling));
+        }
+        index /= 2;
+    }
+    proofs
+}
+
+pub fn generate(algorithm: HashAlgorithm, pairs: &[(String, String)]) -> Result<Proofs, String> {
+    if pairs.is_empty() {
+        return Err("the list is empty".to_string());
+    }
+    let mut addresses = BTreeSet::new();
+    for (address, amount) in pairs {
+        if !addresses.insert(address) {
+            return Err(format!("{} is listed more than once", address));
+        }
+        if amount.parse::<u128>().is_err() {
+            return Err(format!(
+                "the amount {} of {} is not an integer",
+                amount, address
+            ));
+        }
+    }
+
+    let levels = build_levels(
+        algorithm,
+        pairs
+            .iter()
+            .map(|(address, amount)| leaf(algorithm, address, amount))
+            .collect(),
+    );
+    let addresses = pairs
+        .iter()
+        .map(|(address, amount)| {
+            let leaf = leaf(algorithm, address, amount);
+            let index = levels[0].iter().position(|l| *l == leaf).unwrap();
+            AccountProof {
+                account: address.clone(),
+                amount: amount.clone(),
+                proofs: proof(&levels, index),
+            }
+        })
+        .collect();
+    Ok(Proofs {
+        root: hex::encode(levels.last().unwrap()[0]),
+        addresses,
+    })
+}
+
+fn parse_json(input: &str) -> Result<Vec<(String, String)>, String> {
+    let entries: Vec<Entry> = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    entries
+        .into_iter()
+        .map(|entry| match entry.amount {
+            Value::String(amount) => Ok((entry.address, amount)),
+            Value::Number(amount) => Ok((entry.address, amount.to_string())),
+            amount => Err(format!("invalid amount {} of {}", amount, entry.address)),
+        })
+        .collect()
+}
+
+/// Parses the `address,amount` lines, skipping the empty lines and a header.
+fn parse_csv(input: &str) -> Result<Vec<(String, String)>, String> {
+    let mut pairs = vec![];
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != 2 {
+            return Err(format!("line {}: expected address,amount", number + 1));
+        }
+        if number == 0 && fields[1].parse::<u128>().is_err() {
+            continue;
+        }
+        pairs.push((fields[0].to_string(), fields[1].to_string()));
+    }
+    Ok(pairs)
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+    let algorithm = match args.iter().position(|arg| arg == "--keccak256") {
+        Some(index) => {
+            args.remove(index);
+            HashAlgorithm::Keccak256
+        }
+        None => HashAlgorithm::Sha256,
+    };
+    let input_path = args
+        .get(1)
+        .ok_or("usage: merkle_gen [--keccak256] <list.json|list.csv> [output.json]")?;
+    let input = fs::read_to_string(input_path).map_err(|e| format!("{}: {}", input_path, e))?;
+    let pairs = if Path::new(input_path)
+        .extension()
+        .is_some_and(|ext| ext == "csv")
+    {
+        parse_csv(&input)?
+    } else {
+        parse_json(&input)?
+    };
+    let proofs = generate(algorithm, &pairs)?;
+    let output = serde_json::to_string_pretty(&proofs).map_err(|e| e.to_string())?;
+    match args.get(2) {
+        Some(output_path) => {
+            fs::write(output_path, output).map_err(|e| format!("{}: {}", output_path, e))
+        }
+        None => {
+            println!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    if let Err(err) = run(env::args().collect()) {
+        eprintln!("merkle_gen: {}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmgame_contracts::merkle::merkle_root_matches;
+
+    /// The test data claimed on chain by the integration tests.
+    fn check_test_data(list: &str, test_data: &str) {
+        let pairs = parse_json(list).unwrap();
+        let proofs = generate(HashAlgorithm::Sha256, &pairs).unwrap();
+        let expected: Proofs = serde_json::from_str(test_data).unwrap();
+        assert_eq!(proofs.root, expected.root);
+        for account in expected.addresses {
+            assert!(
+                proofs.addresses.contains(&account),
+                "proof of {}",
+                account.account
+            );
+        }
+    }
+
+    #[test]
+    fn proofs_match_the_claimed_test_data() {
+        check_test_data(
+            include_str!("../testdata/airdrop_list.json"),
+            include_str!("../testdata/airdrop_test_data.json"),
+        );
+        check_test_data(
+            include_str!("../testdata/airdrop_game_list.json"),
+            include_str!("../testdata/airdrop_game_test_data.json"),
+        );
+        check_test_data(
+            include_str!("../testdata/airdrop_stake_list.json"),
+            include_str!("../testdata/airdrop_stake_test_data.json"),
+        );
+    }
+
+    #[test]
+    fn parses_csv_and_bins() {
+        let csv = "address,amount\nwasm1a, 10\n\nwasm1b,3\n";
+        let pairs = parse_csv(csv).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("wasm1a".to_string(), "10".to_string()),
+                ("wasm1b".to_string(), "3".to_string())
+            ]
+        );
+        let json = r#"[{"address": "wasm1a", "bin": 10}, {"address": "wasm1b", "bin": "3"}]"#;
+        assert_eq!(parse_json(json).unwrap(), pairs);
+
+        // A single leaf is the root.
+        let proofs = generate(HashAlgorithm::Sha256, &pairs[..1]).unwrap();
+        assert_eq!(
+            proofs.root,
+            hex::encode(leaf(HashAlgorithm::Sha256, "wasm1a", "10"))
+        );
+        assert!(proofs.addresses[0].proofs.is_empty());
+
+        assert!(generate(HashAlgorithm::Sha256, &[]).is_err());
+        assert!(generate(HashAlgorithm::Sha256, &[pairs[0].clone(), pairs[0].clone()]).is_err());
+        assert!(generate(
+            HashAlgorithm::Sha256,
+            &[("wasm1a".to_string(), "ten".to_string())]
+        )
+        .is_err());
+
+        // The keccak256 tree verifies with the proofs of the same algorithm.
+        let proofs = generate(HashAlgorithm::Keccak256, &pairs).unwrap();
+        assert_ne!(
+            proofs.root,
+            generate(HashAlgorithm::Sha256, &pairs).unwrap().root
+        );
+        for account in proofs.addresses {
+            let leaf = format!("{}{}", account.account, account.amount);
+            assert!(merkle_root_matches(
+                HashAlgorithm::Keccak256,
+                &leaf,
+                account.proofs,
+                &proofs.root
+            )
+            .unwrap());
+        }
+    }
+}
@@ -4,9 +4,17 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use wasmgame_contracts::msg::{
-    InstantiateMsg, ExecuteMsg, QueryMsg, ConfigResponse, StagesResponse,
-    BidResponse, MerkleRootsResponse, GameAmountsResponse
+    InstantiateMsg, ExecuteMsg, QueryMsg, ReceiveMsg, StakingReceiveMsg, ConfigResponse, StagesResponse,
+    BidResponse, MerkleRootsResponse, GameAmountsResponse, OutstandingResponse, RoundResponse,
+    BinCountsResponse, LimitsResponse, ClaimedAirdropsResponse,
+    IsClaimedResponse, ClaimableAmountsResponse, SponsorshipsResponse, NoticesResponse,
+    GameStatusResponse, TreasuryResponse, RebatesResponse, TicketPriceResponse, VestingResponse,
+    OwnershipResponse, SudoMsg, BlocklistResponse, ReferralsResponse, LoyaltyPointsResponse,
+    LoyaltyTiersResponse, SeasonPassResponse, BinMultipliersResponse,
+    BinBoundariesResponse, OddsResponse, ContributorsResponse, IbcDenomsResponse, VerifyProofResponse,
+    RemoteWinnersResponse,
 };
+use wasmgame_contracts::ibc::{IbcBidAck, IbcBidPacket};
 use wasmgame_contracts::state::{Config, Stage};
 
 fn main() {
@@ -20,10 +28,42 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+    export_schema(&schema_for!(StakingReceiveMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
+    export_schema(&schema_for!(IbcBidPacket), &out_dir);
+    export_schema(&schema_for!(IbcBidAck), &out_dir);
 
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(OwnershipResponse), &out_dir);
+    export_schema(&schema_for!(LimitsResponse), &out_dir);
+    export_schema(&schema_for!(RoundResponse), &out_dir);
     export_schema(&schema_for!(StagesResponse), &out_dir);
+    export_schema(&schema_for!(GameStatusResponse), &out_dir);
     export_schema(&schema_for!(BidResponse), &out_dir);
+    export_schema(&schema_for!(BinCountsResponse), &out_dir);
+    export_schema(&schema_for!(OddsResponse), &out_dir);
     export_schema(&schema_for!(MerkleRootsResponse), &out_dir);
     export_schema(&schema_for!(GameAmountsResponse), &out_dir);
+    export_schema(&schema_for!(OutstandingResponse), &out_dir);
+    export_schema(&schema_for!(TreasuryResponse), &out_dir);
+    export_schema(&schema_for!(ClaimedAirdropsResponse), &out_dir);
+    export_schema(&schema_for!(IsClaimedResponse), &out_dir);
+    export_schema(&schema_for!(ClaimableAmountsResponse), &out_dir);
+    export_schema(&schema_for!(SponsorshipsResponse), &out_dir);
+    export_schema(&schema_for!(ContributorsResponse), &out_dir);
+    export_schema(&schema_for!(RebatesResponse), &out_dir);
+    export_schema(&schema_for!(TicketPriceResponse), &out_dir);
+    export_schema(&schema_for!(NoticesResponse), &out_dir);
+    export_schema(&schema_for!(VestingResponse), &out_dir);
+    export_schema(&schema_for!(BlocklistResponse), &out_dir);
+    export_schema(&schema_for!(IbcDenomsResponse), &out_dir);
+    export_schema(&schema_for!(ReferralsResponse), &out_dir);
+    export_schema(&schema_for!(LoyaltyPointsResponse), &out_dir);
+    export_schema(&schema_for!(LoyaltyTiersResponse), &out_dir);
+    export_schema(&schema_for!(SeasonPassResponse), &out_dir);
+    export_schema(&schema_for!(BinMultipliersResponse), &out_dir);
+    export_schema(&schema_for!(BinBoundariesResponse), &out_dir);
+    export_schema(&schema_for!(VerifyProofResponse), &out_dir);
+    export_schema(&schema_for!(RemoteWinnersResponse), &out_dir);
 }
@@ -0,0 +1,117 @@
+use cosmwasm_std::Response;
+
+use crate::events::DEPRECATED;
+use crate::msg::ExecuteMsg;
+
+// ======================================================================================
+// Legacy messages
+// ======================================================================================
+/// Returns the name of the v1 message, if the message has the v1 shape. Every field
+/// added since v1 is optional, so the v1 messages are still accepted and mapped to the
+/// current round; they are flagged as deprecated so that clients move to the current
+/// shapes before the v1 ones are dropped. The separate withdraws, replaced by `Withdraw`,
+/// `RegisterMerkleRoots`, replaced by `RegisterAirdropRoot` and `RegisterGameRoot`, and
+/// `UpdateConfig`, replaced by `UpdateOwnership`, are always deprecated.
+pub fn legacy_message(msg: &ExecuteMsg) -> Option<&'static str> {
+    match msg {
+        ExecuteMsg::UpdateConfig { .. } => Some("update_config"),
+        ExecuteMsg::Bid { round: None, .. } => Some("bid"),
+        ExecuteMsg::ChangeBid { round: None, .. } => Some("change_bid"),
+        ExecuteMsg::RemoveBid { round: None } => Some("remove_bid"),
+        ExecuteMsg::RegisterMerkleRoots { .. } => Some("register_merkle_roots"),
+        ExecuteMsg::ClaimAirdrop { round: None, .. } => Some("claim_airdrop"),
+        ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None } => Some("claim_prize"),
+        ExecuteMsg::WithdrawAirdrop { .. } => Some("withdraw_airdrop"),
+        ExecuteMsg::WithdrawPrize { .. } => Some("withdraw_prize"),
+        _ => None,
+    }
+}
+
+/// Adds the deprecation attribute of a v1 message to the response.
+pub fn mark_deprecated(res: Response, legacy_message: Option<&'static str>) -> Response {
+    match legacy_message {
+        Some(name) => res.add_attribute(DEPRECATED, name),
+        None => res,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_slice, Addr, Uint128};
+
+    #[test]
+    fn v1_messages_are_mapped_to_the_current_round() {
+        let v1_messages = [
+            (
+                r#"{"update_config":{"new_owner":"owner0001"}}"#,
+                ExecuteMsg::UpdateConfig { new_owner: Some("owner0001".to_string()) },
+            ),
+            (r#"{"bid":{"bin":3}}"#, ExecuteMsg::Bid { bin: 3, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None }),
+            (r#"{"change_bid":{"bin":4}}"#, ExecuteMsg::ChangeBid { bin: 4, round: None }),
+            (r#"{"remove_bid":{}}"#, ExecuteMsg::RemoveBid { round: None }),
+            (
+                r#"{"register_merkle_roots":{"merkle_root_airdrop":"aa","total_amount_airdrop":"5","merkle_root_game":"bb","total_amount_game":null}}"#,
+                ExecuteMsg::RegisterMerkleRoots {
+                    merkle_root_airdrop: "aa".to_string(),
+                    total_amount_airdrop: Some(Uint128::new(5)),
+                    merkle_root_game: "bb".to_string(),
+                    total_amount_game: None,
+                    expected_recipients: None,
+                    prize_tokens: None,
+                    round: None,
+                },
+            ),
+            (
+                r#"{"claim_airdrop":{"amount":"7","proof_airdrop":["aa"],"proof_game":[]}}"#,
+                ExecuteMsg::ClaimAirdrop {
+                    amount: Uint128::new(7),
+                    proof_airdrop: vec!["aa".to_string()],
+                    proof_game: vec![],
+                    recipient: None,
+                    round: None,
+                },
+            ),
+            (r#"{"claim_prize":{}}"#, ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None }),
+            (
+                r#"{"withdraw_airdrop":{"address":"owner"}}"#,
+                ExecuteMsg::WithdrawAirdrop { address: Addr::unchecked("owner"), round: None },
+            ),
+            (
+                r#"{"withdraw_prize":{"address":"owner"}}"#,
+                ExecuteMsg::WithdrawPrize { address: Addr::unchecked("owner"), round: None },
+            ),
+        ];
+        for (json, expected) in v1_messages {
+            let msg: ExecuteMsg = from_slice(json.as_bytes()).unwrap();
+            assert_eq!(msg, expected);
+            assert!(legacy_message(&msg).is_some(), "{} is not flagged", json);
+        }
+    }
+
+    #[test]
+    fn current_messages_are_not_deprecated() {
+        // Except the withdraws replaced by `Withdraw`, the roots registered at once and the
+        // ownership updated at once.
+        assert_eq!(legacy_message(&ExecuteMsg::Bid { bin: 3, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: Some(1) }), None);
+        assert_eq!(legacy_message(&ExecuteMsg::ClaimVested {}), None);
+        assert_eq!(legacy_message(&ExecuteMsg::UpdateConfig { new_owner: None }), Some("update_config"));
+        assert_eq!(
+            legacy_message(&ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: "aa".to_string(),
+                total_amount_airdrop: None,
+                expected_recipients: None,
+                round: Some(1),
+            }),
+            None
+        );
+        assert_eq!(
+            legacy_message(&ExecuteMsg::WithdrawPrize { address: Addr::unchecked("owner"), round: Some(1) }),
+            Some("withdraw_prize")
+        );
+
+        let res = mark_deprecated(Response::new(), legacy_message(&ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None }));
+        assert_eq!(res.attributes[0].key, DEPRECATED);
+        assert_eq!(res.attributes[0].value, "claim_prize");
+    }
+}
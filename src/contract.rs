@@ -1,26 +1,86 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128, WasmMsg,
+    from_binary, to_binary, to_vec, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, QuerierWrapper, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
+use bech32::FromBase32;
 use cw2::{get_contract_version, set_contract_version};
-use cw20::Cw20ExecuteMsg;
+use cw20::{AllowanceResponse, BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use cw_utils::{Duration, Scheduled};
 use sha2::Digest;
-use std::convert::TryInto;
 
+use crate::compat;
+use crate::cw721;
 use crate::error::ContractError;
+use crate::events::{self, GameEvent};
+use crate::merkle::{merkle_root_matches, HashAlgorithm};
+use crate::nois::{self, NoisCallback};
+use crate::oracle;
+use crate::shares::{bps_amount, to_payout, weighted_amount, MAX_BPS};
+use crate::tokenfactory;
+use crate::treasury::{self, Bucket};
 use crate::msg::{
     BidResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MerkleRootsResponse,
-    MigrateMsg, QueryMsg, StagesResponse, GameAmountsResponse,
+    MigrateMsg, QueryMsg, ReceiveMsg, StagesResponse, GameAmountsResponse, OutstandingResponse,
+    RoundResponse, PlayerAction, BinCount, BinCountsResponse, LimitsResponse,
+    ClaimedAirdropsResponse, IsClaimedResponse, ClaimableAmountsResponse, SponsorPrize,
+    SponsorshipInfo, SponsorshipsResponse, NoticesResponse, GamePhase, GameStatusResponse,
+    BucketBalance, TreasuryResponse, ClaimStage, RebatesResponse, TicketPriceResponse,
+    PrizeTokenAmount, PrizeTokenInfo, VestingInfo, VestingResponse, StakingReceiveMsg,
+    OwnershipAction, OwnershipResponse, SudoMsg, RoundStage, BlocklistResponse, TokenGate,
+    Coupon, ReferralsResponse, LoyaltyPointsResponse, LoyaltyTiersResponse, SeasonPassResponse,
+    PriceOracle, Raffle, Resolution, BinMultipliersResponse, BinBoundariesResponse, BinRange,
+    OddsResponse, ContributorInfo, ContributorsResponse, IbcDenomsResponse, MerkleRootKind,
+    VerifyProofResponse, RemoteWinner, RemoteWinnersResponse,
 };
 use crate::state::{
-    Config, Stage, BIDS, CLAIMED_AIRDROP_AMOUNT, CLAIM_AIRDROP, CONFIG, STAGE_BID,
+    Bid, Config, Stage, BIDS, CLAIM_AIRDROP, CONFIG, STAGE_BID,
     STAGE_CLAIM_AIRDROP, STAGE_CLAIM_PRIZE, TICKET_PRICE, TOTAL_AIRDROP_AMOUNT, BINS,
-    MERKLE_ROOT_AIRDROP, MERKLE_ROOT_GAME, CLAIM_PRIZE, WINNERS, TOTAL_TICKET_PRIZE,
-    TOTAL_AIRDROP_GAME_AMOUNT, CLAIMED_PRIZE_AMOUNT,
+    MERKLE_ROOT_AIRDROP, MERKLE_ROOT_GAME, CLAIM_PRIZE, WINNING_BIN, WINNING_BINS, WINNERS,
+    TOTAL_AIRDROP_GAME_AMOUNT, BID_HEIGHTS, FIRST_BIDDER_BONUS,
+    FIRST_WINNER, FirstWinner, DONATED_PRIZE_AMOUNT, DONATED_AIRDROP_AMOUNT, EXPECTED_RECIPIENTS,
+    AIRDROP_CLAIMS, PRIZE_CLAIMS, CURRENT_ROUND, BIN_COUNTS, BID_WEIGHTS, MERKLE_ROOT_STAKE,
+    WINNERS_WEIGHT, SPONSORSHIPS, Sponsorship, NOTICES, Notice, MAX_WINNERS, SELECTED_WINNERS,
+    CONSOLATIONS, CANCELLED, REBATE, REBATES_PAID, MIN_PARTICIPANTS, EXTRA_BINS, STAGE_REVEAL,
+    COMMITMENTS, Commitment, BID_STAGE_EXTENSION, PRICE_SCHEDULE, PriceStep, PRIZE_TOKENS,
+    PrizeToken, VESTINGS, Vesting, ROLLED_OVER, ROLLOVER_EXPIRATION, FINALIZED_WINNERS,
+    FINAL_SHARES, FinalShare, DustPolicy, REDISTRIBUTED,
+    PENDING_OWNER, PendingOwner, PAUSED, PausedBy, BLOCKLIST,
+    MERKLE_ROOT_COUPON, COUPONS_USED, REFERRERS, REFERRALS, LOYALTY_POINTS, LOYALTY_TIERS,
+    LoyaltyTier, SEASON_PASSES, SeasonPassBalance, OracleResolution,
+    RaffleResolution, RANDOMNESS_JOB, PENDING_JOBS, GameResolution, ADJACENT_BIDDERS,
+    ADJACENT_WEIGHT, ADJACENT_PRIZE, BIN_MULTIPLIERS, WINNER_WEIGHTS, CONTRIBUTIONS, IBC_DENOMS,
+    CLAIM_PROOFS, REMOTE_BIDS, RemoteBid,
 };
 
+/// Default maximum size of the claim proofs, small enough to be signed by mobile wallets.
+const DEFAULT_MAX_CLAIM_SIZE: u32 = 4_096;
+
+/// Maximum protocol fee, in basis points, taken from the tickets prize.
+const MAX_PROTOCOL_FEE_BPS: u16 = 2_000;
+
+// Number of bids refunded by a single call, to bound its gas usage.
+const DEFAULT_REFUND_LIMIT: u32 = 10;
+const MAX_REFUND_LIMIT: u32 = 30;
+
+/// Maximum number of sponsorships of a round, to bound the gas used to pay the prizes.
+const MAX_SPONSORSHIPS: u32 = 5;
+
+/// Maximum payout multiplier of a bin.
+const MAX_BIN_MULTIPLIER: u64 = 100;
+
+/// Maximum number of tickets of a bid placed with an IBC packet.
+const MAX_REMOTE_TICKETS: u32 = 100;
+
+/// Maximum number of notices kept in the inbox of an address, dropping the oldest ones.
+const MAX_NOTICES: u32 = 10;
+
+// Pagination of the queries.
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
 // Version info, for migration info
 const CONTRACT_NAME: &str = "crates.io:cw20-merkle-airdrop";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -47,53 +107,189 @@ pub fn instantiate(
     // ======================================================================================
     // Contract configuration
     // ======================================================================================
+    let prefix = msg.address_prefix;
+
     // If owner not in message, set it as sender.
     let owner = msg
         .owner
-        .map_or(Ok(info.sender), |o| deps.api.addr_validate(&o))?;
-
-    let config = Config {
-        owner: Some(owner),
-        cw20_token_address: deps.api.addr_validate(&msg.cw20_token_address)?,
-    };
+        .map_or(Ok(info.sender), |o| validate_address(deps.as_ref(), &prefix, &o))?;
 
-    // ======================================================================================
-    // Stages validity checks
-    // ======================================================================================
-    let stage_bid_end = (msg.stage_bid.start + msg.stage_bid.duration)?;
-    let stage_claim_airdrop_end =
-        (msg.stage_claim_airdrop.start + msg.stage_claim_airdrop.duration)?;
+    let charity_address = msg
+        .charity_address
+        .map(|c| validate_address(deps.as_ref(), &prefix, &c))
+        .transpose()?;
 
-    // Bid stage haa to start after contract instantiation.
-    if msg.stage_bid.start.is_triggered(&env.block) {
-        return Err(ContractError::BidStartPassed {});
+    if msg.max_bids == Some(0) || msg.max_per_bin == Some(0) {
+        return Err(ContractError::InvalidBidLimit {});
     }
-
-    // Airdrop claim stage has to start after bidding stage end.
-    if stage_bid_end > msg.stage_claim_airdrop.start {
-        let first = String::from("bid");
-        let second = String::from("Claim airdrop");
-        return Err(ContractError::StagesOverlap { first, second });
+    if let Some(anti_snipe) = &msg.anti_snipe {
+        if anti_snipe.window == 0 || anti_snipe.extension == 0 {
+            return Err(ContractError::InvalidAntiSnipe {});
+        }
+    }
+    let remove_bid_penalty_bps = msg.remove_bid_penalty_bps.unwrap_or(0);
+    if remove_bid_penalty_bps > MAX_BPS {
+        return Err(ContractError::InvalidRemoveBidPenalty {});
+    }
+    if matches!(&msg.bid_change_fee, Some(fee) if fee.amount.is_zero()) {
+        return Err(ContractError::InvalidBidChangeFee {});
+    }
+    if msg.crank_bounty == Some(Uint128::zero()) {
+        return Err(ContractError::InvalidCrankBounty {});
+    }
+    if matches!(msg.prize_vesting, Some(Duration::Height(0)) | Some(Duration::Time(0))) {
+        return Err(ContractError::InvalidPrizeVesting {});
+    }
+    if matches!(msg.claim_deadline, Some(Duration::Height(0)) | Some(Duration::Time(0))) {
+        return Err(ContractError::InvalidClaimDeadline {});
+    }
+    let protocol_fee_bps = msg.protocol_fee_bps.unwrap_or(0);
+    if protocol_fee_bps > MAX_PROTOCOL_FEE_BPS {
+        return Err(ContractError::InvalidProtocolFee { max: MAX_PROTOCOL_FEE_BPS });
+    }
+    let fee_collector = msg
+        .fee_collector
+        .map(|f| validate_address(deps.as_ref(), &prefix, &f))
+        .transpose()?;
+    let revenue_split = msg
+        .revenue_split
+        .map(|split| validate_revenue_split(deps.as_ref(), &prefix, split))
+        .transpose()?;
+    if protocol_fee_bps > 0 && fee_collector.is_none() && revenue_split.is_none() {
+        return Err(ContractError::FeeCollectorRequired {});
+    }
+    let referral_fee_bps = msg.referral_fee_bps.unwrap_or(0);
+    if referral_fee_bps > MAX_BPS {
+        return Err(ContractError::InvalidReferralFee {});
+    }
+    if let Some(season_pass) = &msg.season_pass {
+        if season_pass.rounds == 0 || season_pass.price.amount.is_zero() || msg.ticket_cw20_address.is_some() {
+            return Err(ContractError::InvalidSeasonPass {});
+        }
+    }
+    if let Some(merkle_root_allowlist) = &msg.merkle_root_allowlist {
+        let mut root_buf: [u8; 32] = [0; 32];
+        hex::decode_to_slice(merkle_root_allowlist, &mut root_buf)?;
+    }
+    let bid_token_gate = msg
+        .bid_token_gate
+        .map(|gate| -> Result<_, ContractError> {
+            if gate.min_balance.is_zero() {
+                return Err(ContractError::InvalidTokenGate {});
+            }
+            Ok((validate_address(deps.as_ref(), &prefix, &gate.token)?, gate.min_balance))
+        })
+        .transpose()?;
+    let resolution = match msg.resolution.unwrap_or(Resolution::MerkleRoot {}) {
+        Resolution::MerkleRoot {} => GameResolution::MerkleRoot {},
+        Resolution::OwnerSetsBin {} => GameResolution::OwnerSetsBin {},
+        Resolution::Oracle(oracle) => {
+            if oracle.bin_width.is_zero() {
+                return Err(ContractError::InvalidPriceOracle {});
+            }
+            GameResolution::Oracle(OracleResolution {
+                oracle: validate_address(deps.as_ref(), &prefix, &oracle.contract)?,
+                asset: oracle.asset,
+                min_price: oracle.min_price,
+                bin_width: oracle.bin_width,
+                total_amount_game: oracle.total_amount_game,
+            })
+        }
+        Resolution::Randomness(raffle) => GameResolution::Randomness(RaffleResolution {
+            nois_proxy: validate_address(deps.as_ref(), &prefix, &raffle.nois_proxy)?,
+            total_amount_game: raffle.total_amount_game,
+        }),
+    };
+    let adjacent_tier_bps = msg.adjacent_tier_bps.unwrap_or(0);
+    if adjacent_tier_bps > MAX_BPS
+        || (adjacent_tier_bps > 0 && resolution == (GameResolution::MerkleRoot {}))
+    {
+        return Err(ContractError::InvalidAdjacentTier {});
     }
 
-    // Game prize claim has to start after airdrop claim stage end.
-    if stage_claim_airdrop_end > msg.stage_claim_prize.start {
-        let first = String::from("claim aidrop");
-        let second = String::from("Claim prize");
-        return Err(ContractError::StagesOverlap { first, second });
+    let config = Config {
+        owner: Some(owner),
+        cw20_token_address: validate_address(deps.as_ref(), &prefix, &msg.cw20_token_address)?,
+        charity_address,
+        address_prefix: prefix.clone(),
+        ticket_cw20_address: msg
+            .ticket_cw20_address
+            .map(|t| validate_address(deps.as_ref(), &prefix, &t))
+            .transpose()?,
+        max_claim_size: msg.max_claim_size.unwrap_or(DEFAULT_MAX_CLAIM_SIZE),
+        max_bids: msg.max_bids,
+        max_per_bin: msg.max_per_bin,
+        parimutuel: msg.parimutuel.unwrap_or(false),
+        anti_snipe: msg.anti_snipe,
+        remove_bid_penalty_bps,
+        max_bid_changes: msg.max_bid_changes,
+        bid_change_fee: msg.bid_change_fee,
+        prize_denom: msg.prize_denom,
+        mint_prize: false,
+        prize_vesting: msg.prize_vesting,
+        staking_contract: msg
+            .staking_contract
+            .map(|s| validate_address(deps.as_ref(), &prefix, &s))
+            .transpose()?,
+        rollover_grace_period: msg.rollover_grace_period,
+        crank_bounty: msg.crank_bounty,
+        dust_policy: msg.dust_policy,
+        protocol_fee_bps,
+        fee_collector,
+        revenue_split,
+        burn_unclaimed: msg.burn_unclaimed.unwrap_or(false),
+        claim_deadline: msg.claim_deadline,
+        merkle_root_allowlist: msg.merkle_root_allowlist,
+        bid_token_gate,
+        bid_nft_gate: msg
+            .bid_nft_gate
+            .map(|c| validate_address(deps.as_ref(), &prefix, &c))
+            .transpose()?,
+        referral_fee_bps,
+        season_pass: msg.season_pass,
+        bid_receipts: msg
+            .bid_receipts
+            .map(|c| validate_address(deps.as_ref(), &prefix, &c))
+            .transpose()?,
+        winner_badges: msg
+            .winner_badges
+            .map(|c| validate_address(deps.as_ref(), &prefix, &c))
+            .transpose()?,
+        attestor: msg
+            .attestor
+            .map(|a| validate_address(deps.as_ref(), &prefix, &a))
+            .transpose()?,
+        resolution,
+        adjacent_tier_bps,
+        bin_boundaries: msg.bin_boundaries,
+        ibc_bidder_port: msg.ibc_bidder_port,
+        hash_algorithm: msg.hash_algorithm.unwrap_or_default(),
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    for denom in msg.ibc_denoms.unwrap_or_default() {
+        validate_ibc_denom(&denom)?;
+        IBC_DENOMS.save(deps.storage, &denom, &true)?;
     }
 
     // ======================================================================================
-    // Contract initial state
+    // First round of the game
     // ======================================================================================
-    CONFIG.save(deps.storage, &config)?;
-    STAGE_BID.save(deps.storage, &msg.stage_bid)?;
-    STAGE_CLAIM_AIRDROP.save(deps.storage, &msg.stage_claim_airdrop)?;
-    STAGE_CLAIM_PRIZE.save(deps.storage, &msg.stage_claim_prize)?;
-    TICKET_PRICE.save(deps.storage, &msg.ticket_price)?;
-    BINS.save(deps.storage, &msg.bins)?;
-    WINNERS.save(deps.storage, &Uint128::new(0))?;
-    TOTAL_TICKET_PRIZE.save(deps.storage, &Uint128::new(0))?;
+    start_round(
+        deps,
+        env,
+        1,
+        msg.ticket_price,
+        msg.price_schedule,
+        msg.bins,
+        msg.stage_bid,
+        msg.stage_reveal,
+        msg.stage_claim_airdrop,
+        msg.stage_claim_prize,
+        msg.first_bidder_bonus,
+        msg.max_winners,
+        msg.min_participants,
+    )?;
 
     Ok(Response::default())
 }
@@ -105,597 +301,5957 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::UpdateConfig {
-            new_owner
-        } => execute_update_config(deps, env, info, new_owner),
+    // While paused, the players can only get back their tickets. The randomness already
+    // paid for is still delivered.
+    let allowed = matches!(
+        msg,
+        ExecuteMsg::Unpause {}
+            | ExecuteMsg::RefundBids { .. }
+            | ExecuteMsg::RefundTicket { .. }
+            | ExecuteMsg::NoisReceive { .. }
+    );
+    if PAUSED.may_load(deps.storage)?.is_some() && !allowed {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    let legacy_message = compat::legacy_message(&msg);
+    let res = match msg {
+        ExecuteMsg::UpdateConfig { new_owner } => {
+            let action = match new_owner {
+                Some(new_owner) => OwnershipAction::TransferOwnership { new_owner, expiry: None },
+                None => OwnershipAction::RenounceOwnership,
+            };
+            execute_update_ownership(deps, env, info, action)
+        }
+        ExecuteMsg::UpdateOwnership(action) => execute_update_ownership(deps, env, info, action),
+        ExecuteMsg::CreatePrizeDenom { subdenom } => execute_create_prize_denom(deps, env, info, subdenom),
+        ExecuteMsg::StartNewRound {
+            ticket_price,
+            price_schedule,
+            bins,
+            stage_bid,
+            stage_reveal,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus,
+            max_winners,
+            min_participants,
+        } => execute_start_new_round(
+            deps,
+            env,
+            info,
+            ticket_price,
+            price_schedule,
+            bins,
+            stage_bid,
+            stage_reveal,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus,
+            max_winners,
+            min_participants,
+        ),
+        ExecuteMsg::UpdateStages {
+            stage_bid,
+            stage_claim_airdrop,
+            stage_claim_prize,
+        } => execute_update_stages(deps, env, info, stage_bid, stage_claim_airdrop, stage_claim_prize),
+        ExecuteMsg::ExtendStage {
+            stage,
+            extra_duration,
+            round,
+        } => execute_extend_stage(deps, env, info, stage, extra_duration, round),
         ExecuteMsg::Bid {
-            bin 
-        } => execute_bid(deps, env, info, bin),
+            bin,
+            tickets,
+            recipient,
+            allowlist_proof,
+            coupon,
+            referrer,
+            prediction,
+            round
+        } => {
+            execute_bid(deps, env, info, bin, tickets, recipient, allowlist_proof, coupon, referrer, prediction, round)
+        }
+        ExecuteMsg::GiftBid {
+            recipient,
+            bin,
+            round
+        } => execute_gift_bid(deps, env, info, recipient, bin, round),
+        ExecuteMsg::BidMultiple {
+            bins,
+            round
+        } => execute_bid_multiple(deps, env, info, bins, round),
+        ExecuteMsg::CommitBid {
+            hash,
+            allowlist_proof,
+            round
+        } => execute_commit_bid(deps, env, info, hash, allowlist_proof, round),
+        ExecuteMsg::RevealBid {
+            bin,
+            salt,
+            round
+        } => execute_reveal_bid(deps, env, info, bin, salt, round),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::BatchActions {
+            actions
+        } => execute_batch_actions(deps, env, info, actions),
         ExecuteMsg::ChangeBid {
-            bin
-        } => execute_change_bid(deps, env, info, bin),
-        ExecuteMsg::RemoveBid {} => execute_remove_bid(deps, env, info),
+            bin,
+            round
+        } => execute_change_bid(deps, env, info, bin, round),
+        ExecuteMsg::TransferBid {
+            new_address,
+            round
+        } => execute_transfer_bid(deps, env, info, new_address, round),
+        ExecuteMsg::RemoveBid { round } => execute_remove_bid(deps, env, info, round),
+        ExecuteMsg::RegisterStakeRoot {
+            merkle_root_stake,
+            round
+        } => execute_register_stake_root(deps, env, info, merkle_root_stake, round),
+        ExecuteMsg::RegisterCouponRoot {
+            merkle_root_coupon,
+            round
+        } => execute_register_coupon_root(deps, info, merkle_root_coupon, round),
+        ExecuteMsg::ProveStake {
+            weight,
+            proof,
+            round
+        } => execute_prove_stake(deps, env, info, weight, proof, round),
+        ExecuteMsg::Pause {} => execute_pause(deps, info),
+        ExecuteMsg::Unpause {} => execute_unpause(deps, info),
+        ExecuteMsg::UpdateBlocklist { add, remove } => execute_update_blocklist(deps, info, add, remove),
+        ExecuteMsg::UpdateIbcDenoms { add, remove } => execute_update_ibc_denoms(deps, info, add, remove),
+        ExecuteMsg::BuySeasonPass {} => execute_buy_season_pass(deps, info),
+        ExecuteMsg::SetLoyaltyTiers { tiers } => execute_set_loyalty_tiers(deps, info, tiers),
+        ExecuteMsg::SetBinMultipliers { multipliers } => {
+            execute_set_bin_multipliers(deps, env, info, multipliers)
+        }
+        ExecuteMsg::CancelGame { round } => execute_cancel_game(deps, info, round),
+        ExecuteMsg::RefundBids {
+            addresses,
+            limit,
+            round
+        } => execute_refund_bids(deps, env, info, addresses, limit, round),
+        ExecuteMsg::RefundTicket { round } => execute_refund_ticket(deps, env, info, round),
         ExecuteMsg::RegisterMerkleRoots {
             merkle_root_airdrop,
             total_amount_airdrop,
             merkle_root_game,
-            total_amount_game
+            total_amount_game,
+            expected_recipients,
+            prize_tokens,
+            round,
         } => execute_register_merkle_roots(
-            deps, env, info, merkle_root_airdrop, total_amount_airdrop, merkle_root_game, total_amount_game
+            deps,
+            env,
+            info,
+            merkle_root_airdrop,
+            total_amount_airdrop,
+            merkle_root_game,
+            total_amount_game,
+            expected_recipients,
+            prize_tokens,
+            round,
+        ),
+        ExecuteMsg::RegisterAirdropRoot {
+            merkle_root_airdrop,
+            total_amount_airdrop,
+            expected_recipients,
+            round,
+        } => execute_register_airdrop_root(
+            deps,
+            env,
+            info,
+            merkle_root_airdrop,
+            total_amount_airdrop,
+            expected_recipients,
+            round,
         ),
+        ExecuteMsg::RegisterGameRoot {
+            merkle_root_game,
+            total_amount_game,
+            prize_tokens,
+            round,
+        } => execute_register_game_root(deps, env, info, merkle_root_game, total_amount_game, prize_tokens, round),
+        ExecuteMsg::SetWinningBin {
+            bin,
+            total_amount_game,
+            prize_tokens,
+            round,
+        } => execute_set_winning_bin(deps, env, info, bin, total_amount_game, prize_tokens, round),
+        ExecuteMsg::ResolveGame { round } => execute_resolve_game(deps, env, round),
+        ExecuteMsg::RequestRandomness { round } => execute_request_randomness(deps, env, info, round),
+        ExecuteMsg::NoisReceive { callback } => execute_nois_receive(deps, env, info, callback),
+        ExecuteMsg::RotateMerkleRoots {
+            merkle_root_airdrop,
+            merkle_root_game,
+            round,
+        } => execute_rotate_merkle_roots(deps, env, info, merkle_root_airdrop, merkle_root_game, round),
         ExecuteMsg::ClaimAirdrop {
             amount,
             proof_airdrop,
-            proof_game
-        } => execute_claim_airdrop(deps, env, info, amount, proof_airdrop, proof_game),
-        ExecuteMsg::ClaimPrize {} => execute_claim_prize(deps, env, info),
+            proof_game,
+            recipient,
+            round,
+        } => execute_claim_airdrop(deps, env, info, amount, proof_airdrop, proof_game, recipient, round),
+        ExecuteMsg::ClaimAirdropTo {
+            contract,
+            msg,
+            amount,
+            proof_airdrop,
+            proof_game,
+            round,
+        } => execute_claim_airdrop_to(deps, env, info, contract, msg, amount, proof_airdrop, proof_game, round),
+        ExecuteMsg::ClaimAirdropFor {
+            claimer,
+            amount,
+            proof_airdrop,
+            proof_game,
+            signature,
+            pubkey,
+            round,
+        } => execute_claim_airdrop_for(
+            deps, env, info, claimer, amount, proof_airdrop, proof_game, signature, pubkey, round,
+        ),
+        ExecuteMsg::RegisterClaimProofs {
+            proof_airdrop,
+            proof_game,
+            round,
+        } => execute_register_claim_proofs(deps, info, proof_airdrop, proof_game, round),
+        ExecuteMsg::ClearClaimProofs { round } => execute_clear_claim_proofs(deps, info, round),
+        ExecuteMsg::ClaimPrize {
+            recipient,
+            stake,
+            round,
+        } => execute_claim_prize(deps, env, info, recipient, stake.unwrap_or(false), round),
+        ExecuteMsg::ClaimAll {
+            amount,
+            proof_airdrop,
+            proof_game,
+            round,
+        } => execute_claim_all(deps, env, info, amount, proof_airdrop, proof_game, round),
+        ExecuteMsg::DonatePrize {
+            share_bps,
+            round
+        } => execute_donate_prize(deps, env, info, share_bps, round),
+        ExecuteMsg::ClaimPrizeAndReenter { bin } => execute_claim_prize_and_reenter(deps, env, info, bin),
+        ExecuteMsg::FinalizeGame { round } => execute_finalize_game(deps, env, info, round),
+        ExecuteMsg::RedistributeUnclaimed { round } => execute_redistribute_unclaimed(deps, env, info, round),
+        ExecuteMsg::RollOverJackpot { round } => execute_roll_over_jackpot(deps, env, round),
+        ExecuteMsg::ClaimVested {} => execute_claim_vested(deps, env, info),
+        ExecuteMsg::FundRebates { rebate, round } => execute_fund_rebates(deps, env, info, rebate, round),
+        ExecuteMsg::FundPrizePool { round } => execute_fund_prize_pool(deps, env, info, round),
+        ExecuteMsg::ReclaimSponsorship { round } => {
+            execute_reclaim_sponsorship(deps, env, info, round)
+        }
+        ExecuteMsg::AckNotices {} => execute_ack_notices(deps, env, info),
+        ExecuteMsg::Withdraw {
+            asset,
+            amount,
+            recipient,
+            round,
+        } => execute_withdraw(deps, env, info, asset, amount, &recipient, round),
         ExecuteMsg::WithdrawAirdrop {
-            address 
-        } => execute_withdraw_airdrop(deps, env, info, &address),
+            address,
+            round
+        } => execute_withdraw_airdrop(deps, env, info, &address, round),
         ExecuteMsg::WithdrawPrize {
-            address
-        } => execute_withdraw_prize(deps, env, info, &address)
-    }
+            address,
+            round
+        } => execute_withdraw_prize(deps, env, info, &address, round),
+        ExecuteMsg::BurnUnclaimed { round } => execute_burn_unclaimed(deps, env, round),
+        ExecuteMsg::WithdrawRollover { address } => execute_withdraw_rollover(deps, env, info, &address)
+    }?;
+    Ok(compat::mark_deprecated(res, legacy_message))
 }
 
-pub fn execute_update_config(
+/// Transfers the ownership in two steps, or renounces it.
+pub fn execute_update_ownership(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    new_owner: Option<String>,
+    action: OwnershipAction,
 ) -> Result<Response, ContractError> {
-    // Just the contract owner can update the config.
-    let cfg = CONFIG.load(deps.storage)?;
-    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let event = match action {
+        OwnershipAction::TransferOwnership { new_owner, expiry } => {
+            if cfg.owner.as_ref() != Some(&info.sender) {
+                return Err(ContractError::Unauthorized {});
+            }
+            let address = validate_address(deps.as_ref(), &cfg.address_prefix, &new_owner)?;
+            if matches!(expiry, Some(expiry) if expiry.is_expired(&env.block)) {
+                return Err(ContractError::OwnershipTransferExpired {});
+            }
+            PENDING_OWNER.save(deps.storage, &PendingOwner { address: address.clone(), expiry })?;
+            GameEvent::new("transfer_ownership").add(events::PENDING_OWNER, address)
+        }
+        OwnershipAction::AcceptOwnership => {
+            let pending = PENDING_OWNER
+                .may_load(deps.storage)?
+                .ok_or(ContractError::OwnershipTransferNotFound {})?;
+            if pending.address != info.sender {
+                return Err(ContractError::NotPendingOwner {});
+            }
+            if matches!(pending.expiry, Some(expiry) if expiry.is_expired(&env.block)) {
+                return Err(ContractError::OwnershipTransferExpired {});
+            }
+            PENDING_OWNER.remove(deps.storage);
+            cfg.owner = Some(pending.address.clone());
+            CONFIG.save(deps.storage, &cfg)?;
+            GameEvent::new("accept_ownership").add(events::OWNER, pending.address)
+        }
+        OwnershipAction::RenounceOwnership => {
+            if cfg.owner.as_ref() != Some(&info.sender) {
+                return Err(ContractError::Unauthorized {});
+            }
+            PENDING_OWNER.remove(deps.storage);
+            cfg.owner = None;
+            CONFIG.save(deps.storage, &cfg)?;
+            GameEvent::new("renounce_ownership")
+        }
+    };
+
+    Ok(event.apply(Response::new()))
+}
+
+/// Creates the TokenFactory denom of the contract paying the prize of the winners.
+pub fn execute_create_prize_denom(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subdenom: String,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can create the prize denom.
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
     if info.sender != owner {
         return Err(ContractError::Unauthorized {});
     }
-
-    let mut tmp_owner = None;
-    if let Some(addr) = new_owner {
-        tmp_owner = Some(deps.api.addr_validate(&addr)?)
+    if cfg.prize_denom.is_some() {
+        return Err(ContractError::PrizeDenomAlreadySet {});
     }
 
-    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
-        exists.owner = tmp_owner;
-        Ok(exists)
-    })?;
+    let create_denom_msg = tokenfactory::create_denom_msg(&env.contract.address, &subdenom)?;
+    let prize_denom = tokenfactory::factory_denom(&env.contract.address, &subdenom);
+    cfg.prize_denom = Some(prize_denom.clone());
+    cfg.mint_prize = true;
+    CONFIG.save(deps.storage, &cfg)?;
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    let res = GameEvent::new("create_prize_denom")
+        .add(events::PRIZE_DENOM, prize_denom)
+        .apply(Response::new().add_message(create_denom_msg));
+    Ok(res)
 }
 
-// TODO: add tests:
-// - send a fund different from the tiket.
-pub fn execute_bid(
-    deps: DepsMut,
+#[allow(clippy::too_many_arguments)]
+pub fn execute_start_new_round(
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    bin: u8,
+    ticket_price: Coin,
+    price_schedule: Option<Vec<PriceStep>>,
+    bins: u8,
+    stage_bid: Stage,
+    stage_reveal: Option<Stage>,
+    stage_claim_airdrop: Stage,
+    stage_claim_prize: Stage,
+    first_bidder_bonus: Option<Uint128>,
+    max_winners: Option<u32>,
+    min_participants: Option<u32>,
 ) -> Result<Response, ContractError> {
-    let stage_bid = STAGE_BID.load(deps.storage)?;
-    let stage_name = String::from("bid");
-    check_if_valid_stage(env, stage_bid, stage_name)?;
+    // Just the contract owner can start a new round.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    let ticket_price = TICKET_PRICE.load(deps.storage)?;
+    let round = CURRENT_ROUND.load(deps.storage)? + 1;
+    check_after_previous_round(deps.storage, round, &stage_bid)?;
 
-    // If a bid is already present for the sender, no other bids can be placed.
-    if BIDS.has(deps.storage, &info.sender) {
-        return Err(ContractError::CannotBidMoreThanOnce {});
-    };
+    // A previous round ended without winners leaves its prizes to the new round.
+    roll_over_jackpot(deps.branch(), &env, round - 1)?;
 
-    // If ticket price not paid, bid is not allowed.
-    let funds_sent = get_amount_for_denom(&info.funds, &ticket_price.denom);
-    if funds_sent.amount < ticket_price.amount {
-        return Err(ContractError::TicketPriceNotPaid {});
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    start_round(
+        deps.branch(),
+        env,
+        round,
+        ticket_price,
+        price_schedule,
+        bins,
+        stage_bid,
+        stage_reveal,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        first_bidder_bonus,
+        max_winners,
+        min_participants,
+    )?;
+
+    // Donations kept by the previous games are added to the prizes of the new round.
+    let carried_ticket_prize = treasury::drain(deps.storage, round, Bucket::Rollover, &ticket_asset)?;
+    treasury::credit(deps.storage, round, Bucket::TicketPot, &ticket_asset, carried_ticket_prize)?;
+    let prize_asset = prize_asset(&cfg);
+    let carried_airdrop_prize = treasury::drain(deps.storage, round, Bucket::Rollover, &prize_asset)?;
+    treasury::credit(deps.storage, round, Bucket::GameReserve, &prize_asset, carried_airdrop_prize)?;
+    ROLLOVER_EXPIRATION.remove(deps.storage);
+
+    let res = GameEvent::new("start_new_round")
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Checks the stages of a round and saves its initial state, making it the current
+/// round.
+#[allow(clippy::too_many_arguments)]
+fn start_round(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    ticket_price: Coin,
+    price_schedule: Option<Vec<PriceStep>>,
+    bins: u8,
+    stage_bid: Stage,
+    stage_reveal: Option<Stage>,
+    stage_claim_airdrop: Stage,
+    stage_claim_prize: Stage,
+    first_bidder_bonus: Option<Uint128>,
+    max_winners: Option<u32>,
+    min_participants: Option<u32>,
+) -> Result<(), ContractError> {
+    validate_stages(&env, &stage_bid, &stage_claim_airdrop, &stage_claim_prize)?;
+    if let Some(stage_reveal) = &stage_reveal {
+        validate_reveal_stage(&stage_bid, stage_reveal, &stage_claim_airdrop)?;
+    }
+    if max_winners == Some(0) {
+        return Err(ContractError::InvalidMaxWinners {});
+    }
+    if let Some(price_schedule) = &price_schedule {
+        validate_price_schedule(price_schedule)?;
+    }
+    let cfg = CONFIG.load(deps.storage)?;
+    if let Some(bin_boundaries) = &cfg.bin_boundaries {
+        validate_bin_boundaries(bin_boundaries, bins)?;
+    }
+    if first_bidder_bonus.is_some_and(|bonus| !bonus.is_zero()) && cfg.protocol_fee_bps == 0 {
+        return Err(ContractError::FirstBidderBonusWithoutFee {});
     }
+    check_ticket_denom(deps.storage, &cfg, &ticket_price)?;
 
-    // If selected bin not permitted, bid not allowed.
-    let bins = BINS.load(deps.storage)?;
-    if bin > bins {
-        return Err(ContractError::BinDoesNotExist { bins });
+    // ======================================================================================
+    // Round initial state
+    // ======================================================================================
+    CURRENT_ROUND.save(deps.storage, &round)?;
+    STAGE_BID.save(deps.storage, round, &stage_bid)?;
+    if let Some(stage_reveal) = stage_reveal {
+        STAGE_REVEAL.save(deps.storage, round, &stage_reveal)?;
+    }
+    STAGE_CLAIM_AIRDROP.save(deps.storage, round, &stage_claim_airdrop)?;
+    STAGE_CLAIM_PRIZE.save(deps.storage, round, &stage_claim_prize)?;
+    TICKET_PRICE.save(deps.storage, round, &ticket_price)?;
+    if let Some(price_schedule) = price_schedule {
+        PRICE_SCHEDULE.save(deps.storage, round, &price_schedule)?;
+    }
+    BINS.save(deps.storage, round, &bins)?;
+    WINNERS.save(deps.storage, round, &Uint128::new(0))?;
+    WINNERS_WEIGHT.save(deps.storage, round, &Uint128::zero())?;
+    FIRST_BIDDER_BONUS.save(
+        deps.storage,
+        round,
+        &first_bidder_bonus.unwrap_or_else(Uint128::zero),
+    )?;
+    if let Some(max_winners) = max_winners {
+        MAX_WINNERS.save(deps.storage, round, &max_winners)?;
+    }
+    if let Some(min_participants) = min_participants {
+        MIN_PARTICIPANTS.save(deps.storage, round, &min_participants)?;
     }
+    DONATED_PRIZE_AMOUNT.save(deps.storage, round, &Uint128::zero())?;
+    DONATED_AIRDROP_AMOUNT.save(deps.storage, round, &Uint128::zero())?;
 
-    // If sender sent funds higher than ticket price, return change.
-    let mut transfer_msg: Vec<CosmosMsg> = vec![];
-    if funds_sent.amount > ticket_price.amount {
-        transfer_msg.push(get_bank_transfer_to_msg(
-            &info.sender,
-            &funds_sent.denom,
-            funds_sent.amount - ticket_price.amount,
-        ))
+    Ok(())
+}
+
+/// Checks that the denom is an IBC voucher denom, `ibc/` followed by the hex encoded
+/// SHA-256 hash of the denom trace.
+fn validate_ibc_denom(denom: &str) -> Result<(), ContractError> {
+    match denom.strip_prefix("ibc/") {
+        Some(hash) if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) => Ok(()),
+        _ => Err(ContractError::InvalidIbcDenom { denom: denom.to_string() }),
+    }
+}
+
+/// Fails if the native ticket is paid with an IBC denom not accepted by the owner.
+fn check_ticket_denom(storage: &dyn Storage, cfg: &Config, ticket_price: &Coin) -> Result<(), ContractError> {
+    let ibc_ticket = cfg.ticket_cw20_address.is_none() && ticket_price.denom.starts_with("ibc/");
+    if ibc_ticket && !IBC_DENOMS.has(storage, &ticket_price.denom) {
+        return Err(ContractError::IbcDenomNotAccepted { denom: ticket_price.denom.clone() });
     }
+    Ok(())
+}
 
-    BIDS.save(deps.storage, &info.sender, &bin)?;
+/// Checks that the bid stage starts in the future and that the stages of a round
+/// follow each other without overlapping.
+pub fn validate_stages(
+    env: &Env,
+    stage_bid: &Stage,
+    stage_claim_airdrop: &Stage,
+    stage_claim_prize: &Stage,
+) -> Result<(), ContractError> {
+    let stage_bid_end = (stage_bid.start + stage_bid.duration)?;
+    let stage_claim_airdrop_end = (stage_claim_airdrop.start + stage_claim_airdrop.duration)?;
 
-    // Add payed ticket to the final prize.
-    TOTAL_TICKET_PRIZE.update(deps.storage, |mut actual_prize| -> StdResult<_> {
-        actual_prize += ticket_price.amount;
-        Ok(actual_prize)
-    })?;
+    // Bid stage haa to start after the round start.
+    if stage_bid.start.is_triggered(&env.block) {
+        return Err(ContractError::BidStartPassed {});
+    }
 
-    let res = Response::new()
-        .add_messages(transfer_msg)
-        .add_attribute("action", "bid")
-        .add_attribute("player", info.sender)
-        .add_attribute("bin", bin.to_string());
-    Ok(res)
+    // Airdrop claim stage has to start after bidding stage end.
+    if stage_bid_end > stage_claim_airdrop.start {
+        let first = String::from("bid");
+        let second = String::from("Claim airdrop");
+        return Err(ContractError::StagesOverlap { first, second });
+    }
+
+    // Game prize claim has to start after airdrop claim stage end.
+    if stage_claim_airdrop_end > stage_claim_prize.start {
+        let first = String::from("claim aidrop");
+        let second = String::from("Claim prize");
+        return Err(ContractError::StagesOverlap { first, second });
+    }
+
+    Ok(())
 }
 
-pub fn execute_change_bid(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    bin: u8,
-) -> Result<Response, ContractError> {
-    let stage_bid = STAGE_BID.load(deps.storage)?;
-    let stage_name = String::from("bid");
-    check_if_valid_stage(env, stage_bid, stage_name)?;
+/// Checks that the steps of the price schedule have a price and start one after the
+/// other, scheduled in the same unit.
+fn validate_price_schedule(price_schedule: &[PriceStep]) -> Result<(), ContractError> {
+    let ordered = price_schedule.windows(2).all(|steps| steps[0].start < steps[1].start);
+    if price_schedule.is_empty() || !ordered || price_schedule.iter().any(|step| step.amount.is_zero()) {
+        return Err(ContractError::InvalidPriceSchedule {});
+    }
+    Ok(())
+}
 
-    // If a previous bid doesn't exists for the sender, nothing can be changed.
-    if !BIDS.has(deps.storage, &info.sender) {
-        return Err(ContractError::BidNotPresent {});
-    };
+/// Checks that the bin boundaries are ascending and delimit the bins of the round.
+fn validate_bin_boundaries(bin_boundaries: &[Uint128], bins: u8) -> Result<(), ContractError> {
+    let expected = bins.saturating_sub(1);
+    let ordered = bin_boundaries.windows(2).all(|boundaries| boundaries[0] < boundaries[1]);
+    if bin_boundaries.len() != usize::from(expected) || !ordered {
+        return Err(ContractError::InvalidBinBoundaries { expected });
+    }
+    Ok(())
+}
 
-    BIDS.update(
-        deps.storage,
-        &info.sender,
-        |_bin: Option<u8>| -> StdResult<u8> { Ok(bin) },
-    )?;
+/// Checks that the reveal stage follows the bid stage and ends before the claim airdrop
+/// stage starts.
+pub fn validate_reveal_stage(
+    stage_bid: &Stage,
+    stage_reveal: &Stage,
+    stage_claim_airdrop: &Stage,
+) -> Result<(), ContractError> {
+    if (stage_bid.start + stage_bid.duration)? > stage_reveal.start {
+        let first = String::from("bid");
+        let second = String::from("Reveal");
+        return Err(ContractError::StagesOverlap { first, second });
+    }
+    if (stage_reveal.start + stage_reveal.duration)? > stage_claim_airdrop.start {
+        let first = String::from("reveal");
+        let second = String::from("Claim airdrop");
+        return Err(ContractError::StagesOverlap { first, second });
+    }
+    Ok(())
+}
 
-    let res = Response::new()
-        .add_attribute("action", "change_bid")
-        .add_attribute("player", info.sender)
-        .add_attribute("new_bin", bin.to_string());
-    Ok(res)
+/// Checks that the bid stage of a round starts after the end of the bid stage of the
+/// previous round, if any.
+fn check_after_previous_round(storage: &dyn Storage, round: u64, stage_bid: &Stage) -> Result<(), ContractError> {
+    if let Some(previous_stage_bid) = STAGE_BID.may_load(storage, round - 1)? {
+        if (previous_stage_bid.start + previous_stage_bid.duration)? > stage_bid.start {
+            let first = String::from("previous round bid");
+            let second = String::from("Bid");
+            return Err(ContractError::StagesOverlap { first, second });
+        }
+    }
+    Ok(())
 }
 
-pub fn execute_remove_bid(
+/// Replaces the stages of the current round, allowed until its bid stage starts.
+pub fn execute_update_stages(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    stage_bid: Stage,
+    stage_claim_airdrop: Stage,
+    stage_claim_prize: Stage,
 ) -> Result<Response, ContractError> {
-    let stage_bid = STAGE_BID.load(deps.storage)?;
-    let stage_name = String::from("bid");
-    check_if_valid_stage(env, stage_bid, stage_name)?;
-
-    // IF: check if a bid for the sender is not present.
-    // ELSE: if the bid is present, remove it and send back the ticket price to the sender.
-    if !BIDS.has(deps.storage, &info.sender) {
-        return Err(ContractError::BidNotPresent {});
+    // Just the contract owner can update the stages.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
     }
 
-    BIDS.remove(deps.storage, &info.sender);
+    let round = CURRENT_ROUND.load(deps.storage)?;
+    let current_stage_bid = STAGE_BID.load(deps.storage, round)?;
+    if current_stage_bid.start.is_triggered(&env.block) {
+        return Err(ContractError::StageStarted { stage_name: String::from("bid") });
+    }
 
-    // Remove from ticket prize a ticket.
-    let ticket_price = TICKET_PRICE.load(deps.storage)?;
-    TOTAL_TICKET_PRIZE.update(deps.storage, |mut actual_prize| -> StdResult<_> {
-        actual_prize -= ticket_price.amount;
-        Ok(actual_prize)
-    })?;
+    validate_stages(&env, &stage_bid, &stage_claim_airdrop, &stage_claim_prize)?;
+    if let Some(stage_reveal) = STAGE_REVEAL.may_load(deps.storage, round)? {
+        validate_reveal_stage(&stage_bid, &stage_reveal, &stage_claim_airdrop)?;
+    }
+    check_after_previous_round(deps.storage, round, &stage_bid)?;
 
-    let msg = get_bank_transfer_to_msg(
-        &info.sender,
-        &ticket_price.denom,
-        ticket_price.amount,
-    );
+    STAGE_BID.save(deps.storage, round, &stage_bid)?;
+    STAGE_CLAIM_AIRDROP.save(deps.storage, round, &stage_claim_airdrop)?;
+    STAGE_CLAIM_PRIZE.save(deps.storage, round, &stage_claim_prize)?;
 
-    let res = Response::new()
-        .add_message(msg)
-        .add_attribute("action", "remove_bid")
-        .add_attribute("player", info.sender)
-        .add_attribute("ticket_price_payback", ticket_price.amount);
+    let res = GameEvent::new("update_stages")
+        .round(round)
+        .apply(Response::new());
     Ok(res)
 }
 
-// ======================================================================================
-// Merkle root and claiming phase
-// ======================================================================================
-pub fn execute_register_merkle_roots(
+/// Extends the duration of a claim stage while it is active. The claim airdrop stage
+/// cannot be extended past the start of the claim prize stage.
+pub fn execute_extend_stage(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    merkle_root_airdrop: String,
-    total_amount_airdrop: Option<Uint128>,
-    merkle_root_game: String,
-    total_amount_game: Option<Uint128>,
+    stage: ClaimStage,
+    extra_duration: Duration,
+    round: Option<u64>,
 ) -> Result<Response, ContractError> {
-    // Just the contract owner can load the Merkle root.
+    // Just the contract owner can extend the stages.
     let cfg = CONFIG.load(deps.storage)?;
     let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
     if info.sender != owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // TODO: check sul periodo in cui poter depositare la merkle root. 
-    // Fissiamo che è possibile solo fino alll'inizio del claim?
-
-    // Check merkle root airdrop length.
-    let mut root_buf: [u8; 32] = [0; 32];
-    hex::decode_to_slice(&merkle_root_airdrop, &mut root_buf)?;
-
-    // Check merkle root game length.
-    let mut root_buf: [u8; 32] = [0; 32];
-    hex::decode_to_slice(&merkle_root_game, &mut root_buf)?;
-
-    // Save total amount of tokens to be airdropped.
-    let amount_airdrop = total_amount_airdrop.unwrap_or_else(Uint128::zero);
+    if extra_duration == Duration::Height(0) || extra_duration == Duration::Time(0) {
+        return Err(ContractError::InvalidExtraDuration {});
+    }
 
-    // Save total amount of token to be airdropped to game winners.
-    let amount_game = total_amount_game.unwrap_or_else(Uint128::zero);
+    let round = resolve_round(deps.storage, round)?;
+    let (stage_item, stage_name) = match stage {
+        ClaimStage::ClaimAirdrop => (STAGE_CLAIM_AIRDROP, "claim airdrop"),
+        ClaimStage::ClaimPrize => (STAGE_CLAIM_PRIZE, "claim prize"),
+    };
+    let mut extended = stage_item.load(deps.storage, round)?;
+    check_if_valid_stage(env, extended.clone(), String::from(stage_name))?;
 
-    MERKLE_ROOT_AIRDROP.save(deps.storage, &merkle_root_airdrop)?;
-    MERKLE_ROOT_GAME.save(deps.storage, &merkle_root_game)?;
-    TOTAL_AIRDROP_AMOUNT.save(deps.storage, &amount_airdrop)?;
-    TOTAL_AIRDROP_GAME_AMOUNT.save(deps.storage, &amount_game)?;
-    CLAIMED_AIRDROP_AMOUNT.save(deps.storage, &Uint128::zero())?;
-    CLAIMED_PRIZE_AMOUNT.save(deps.storage, &Uint128::zero())?;
+    extended.duration = (extended.duration + extra_duration)?;
+    let extended_end = (extended.start + extended.duration)?;
+    if stage == ClaimStage::ClaimAirdrop {
+        let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+        if extended_end > stage_claim_prize.start {
+            let first = String::from("claim aidrop");
+            let second = String::from("Claim prize");
+            return Err(ContractError::StagesOverlap { first, second });
+        }
+    }
+    stage_item.save(deps.storage, round, &extended)?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "register_merkle_roots"),
-        attr("merkle_root_airdrop", merkle_root_airdrop),
-        attr("total_amount_airdrop", amount_airdrop),
-        attr("merkle_root_game", merkle_root_game),
-    ]))
+    let res = GameEvent::new("extend_stage")
+        .stage(stage_name)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
 }
 
-pub fn execute_claim_airdrop(
+// TODO: add tests:
+// - send a fund different from the tiket.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_bid(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    amount: Uint128,
-    proof_airdrop: Vec<String>,
-    proof_game: Vec<String>
+    bin: u8,
+    tickets: Option<u32>,
+    recipient: Option<String>,
+    allowlist_proof: Option<Vec<String>>,
+    coupon: Option<Coupon>,
+    referrer: Option<String>,
+    prediction: Option<Uint128>,
+    round: Option<u64>,
 ) -> Result<Response, ContractError> {
-    // Check that the correct stage is active.
-    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage)?;
-    let stage_name = String::from("claim airdrop");
-    check_if_valid_stage(env, stage_claim_airdrop, stage_name)?;
+    // Native funds cannot pay a cw20 ticket.
+    let cfg = CONFIG.load(deps.storage)?;
+    if cfg.ticket_cw20_address.is_some() {
+        return Err(ContractError::InvalidTicketAsset {});
+    }
+    let bin = bid_bin(&cfg, bin, prediction)?;
 
-    // Verify that the user has not already made the claim.
-    let claimed = CLAIM_AIRDROP.may_load(deps.storage, &info.sender)?;
-    if claimed.is_some() {
-        return Err(ContractError::AlreadyClaimed {});
+    // The bid is placed for the recipient, if any, and paid by the sender.
+    let player = recipient
+        .map(|r| validate_address(deps.as_ref(), &cfg.address_prefix, &r))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+    check_allowlist(&cfg, player.as_str(), allowlist_proof)?;
+    let referrer = referrer
+        .map(|r| validate_address(deps.as_ref(), &cfg.address_prefix, &r))
+        .transpose()?;
+    if referrer.as_ref() == Some(&player) {
+        return Err(ContractError::SelfReferral {});
     }
 
+    let round = resolve_round(deps.storage, round)?;
+    let discount_bps = match coupon {
+        Some(coupon) => use_coupon(deps.storage, round, &player, coupon, cfg.hash_algorithm)?,
+        None => 0,
+    };
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let funds_sent = get_amount_for_denom(&info.funds, &ticket_price.denom);
+    let tickets = tickets.unwrap_or(1);
+
+    // A bid sent without funds by a season pass holder uses one of its entries.
+    let payment = match SEASON_PASSES.may_load(deps.storage, &info.sender)? {
+        Some(balance) if info.funds.is_empty() => {
+            use_season_pass_entry(deps.storage, &cfg, round, &ticket_price, &info.sender, balance)?
+        }
+        _ => Payment::Funds(funds_sent.amount),
+    };
+    place_bid(deps, env, "bid", round, &info.sender, player, vec![bin], tickets, payment, discount_bps, referrer)
+}
+
+/// Returns the bin of a bid, either given or covering the prediction of the bid.
+fn bid_bin(cfg: &Config, bin: u8, prediction: Option<Uint128>) -> Result<u8, ContractError> {
+    let prediction = match prediction {
+        Some(prediction) => prediction,
+        None => return Ok(bin),
+    };
+    if bin != 0 {
+        return Err(ContractError::BinAndPrediction {});
+    }
+    let bin_boundaries = cfg.bin_boundaries.as_ref().ok_or(ContractError::NoBinBoundaries {})?;
+    Ok(prediction_bin(bin_boundaries, prediction))
+}
+
+/// Returns the bin, from 1, covering the prediction: the number of boundaries reached
+/// by the prediction, plus one.
+fn prediction_bin(bin_boundaries: &[Uint128], prediction: Uint128) -> u8 {
+    bin_boundaries.partition_point(|boundary| *boundary <= prediction) as u8 + 1
+}
+
+/// Places a bid for the recipient, paying its ticket with native funds.
+pub fn execute_gift_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    bin: u8,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
-    let merkle_root_airdrop = MERKLE_ROOT_AIRDROP.load(deps.storage)?;
-    let merkle_root_game = MERKLE_ROOT_GAME.load(deps.storage)?;
+    if cfg.ticket_cw20_address.is_some() {
+        return Err(ContractError::InvalidTicketAsset {});
+    }
+    let recipient = validate_address(deps.as_ref(), &cfg.address_prefix, &recipient)?;
+    check_allowlist(&cfg, recipient.as_str(), None)?;
 
-    // Compare proofs: the proof sent by the user must be the same of the one
-    // produced with info.sender address.
-    let user_input = format!("{}{}", info.sender, amount);
-    let hash = sha2::Sha256::digest(user_input.as_bytes())
-        .as_slice()
-        .try_into()
-        .map_err(|_| ContractError::WrongLength {})?;
-
-    let hash = proof_airdrop.into_iter().try_fold(hash, |hash, p| {
-        let mut proof_buf = [0; 32];
-        hex::decode_to_slice(p, &mut proof_buf)?;
-        let mut hashes = [hash, proof_buf];
-        hashes.sort_unstable();
-        sha2::Sha256::digest(&hashes.concat())
-            .as_slice()
-            .try_into()
-            .map_err(|_| ContractError::WrongLength {})
-    })?;
+    let round = resolve_round(deps.storage, round)?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let funds_sent = get_amount_for_denom(&info.funds, &ticket_price.denom);
+    place_bid(deps, env, "gift_bid", round, &info.sender, recipient, vec![bin], 1, Payment::Funds(funds_sent.amount), 0, None)
+}
 
-    let mut root_buf: [u8; 32] = [0; 32];
-    hex::decode_to_slice(merkle_root_airdrop, &mut root_buf)?;
-    if root_buf != hash {
-        return Err(ContractError::VerificationFailed { merkle_root: "airdrop".to_string() });
+/// Places a bid on several bins, paying a ticket for each bin with native funds.
+pub fn execute_bid_multiple(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bins: Vec<u8>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if cfg.ticket_cw20_address.is_some() {
+        return Err(ContractError::InvalidTicketAsset {});
+    }
+    check_allowlist(&cfg, info.sender.as_str(), None)?;
+
+    let round = resolve_round(deps.storage, round)?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let funds_sent = get_amount_for_denom(&info.funds, &ticket_price.denom);
+    place_bid(deps, env, "bid", round, &info.sender, info.sender.clone(), bins, 1, Payment::Funds(funds_sent.amount), 0, None)
+}
+
+/// Handles the tickets and the sponsorships paid with a cw20 `Send` to the game
+/// contract.
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Bid { bin, tickets, allowlist_proof, prediction, round } => {
+            // Only the cw20 configured as ticket can pay the tickets.
+            if cfg.ticket_cw20_address != Some(info.sender) {
+                return Err(ContractError::InvalidTicketAsset {});
+            }
+            let bin = bid_bin(&cfg, bin, prediction)?;
+            check_allowlist(&cfg, sender.as_str(), allowlist_proof)?;
+            let round = resolve_round(deps.storage, round)?;
+            place_bid(deps, env, "bid", round, &sender, sender.clone(), vec![bin], tickets.unwrap_or(1), Payment::Funds(wrapper.amount), 0, None)
+        }
+        ReceiveMsg::Sponsor { ratio, round } => {
+            let round = resolve_round(deps.storage, round)?;
+            sponsor_round(deps, env, round, sender, info.sender, ratio, wrapper.amount)
+        }
+        ReceiveMsg::FundPrizePool { round } => {
+            let round = resolve_round(deps.storage, round)?;
+            fund_prize_pool(deps, env, &cfg, round, sender, info.sender.as_str(), wrapper.amount)
+        }
+    }
+}
+
+/// Records the bid of a player sent with an IBC packet by the sender contract of another
+/// chain on the channel. The player is an address of the sender chain, kept as an opaque
+/// key of the channel. The tickets are escrowed by the sender, so the bid stakes nothing
+/// on this chain, does not count toward the limits of the local bids and does not extend
+/// the bid stage. Its winner is paid by the sender.
+/// Nothing is written before the last check, so a rejected packet leaves no state.
+#[allow(clippy::too_many_arguments)]
+pub fn place_remote_bid(
+    deps: DepsMut,
+    env: Env,
+    channel_id: String,
+    player: String,
+    bin: u8,
+    tickets: Option<u32>,
+    allowlist_proof: Option<Vec<String>>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    if PAUSED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::ContractPaused {});
+    }
+    // The winners are known on chain only if the winning bin is.
+    let cfg = CONFIG.load(deps.storage)?;
+    if cfg.parimutuel || cfg.resolution == (GameResolution::MerkleRoot {}) {
+        return Err(ContractError::RemoteBidsNotAllowed {});
+    }
+    check_allowlist(&cfg, &player, allowlist_proof)?;
+    let tickets = tickets.unwrap_or(1);
+    if tickets == 0 {
+        return Err(ContractError::InvalidTickets {});
+    }
+    if tickets > MAX_REMOTE_TICKETS {
+        return Err(ContractError::TooManyRemoteTickets { max: MAX_REMOTE_TICKETS });
+    }
+
+    let round = resolve_round(deps.storage, round)?;
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    if STAGE_REVEAL.has(deps.storage, round) {
+        return Err(ContractError::CommitRequired {});
+    }
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    check_if_valid_stage(env, stage_bid, String::from("bid"))?;
+    let max_bin = BINS.load(deps.storage, round)?;
+    if bin > max_bin {
+        return Err(ContractError::BinDoesNotExist { bins: max_bin });
+    }
+    let key = (round, channel_id.as_str(), player.as_str());
+    if REMOTE_BIDS.has(deps.storage, key) {
+        return Err(ContractError::CannotBidMoreThanOnce {});
+    }
+
+    REMOTE_BIDS.save(deps.storage, key, &RemoteBid { bin, tickets })?;
+
+    let event = GameEvent::new("ibc_bid")
+        .add(events::CHANNEL_ID, channel_id)
+        .player(player)
+        .bin(bin)
+        .add(events::TICKETS, tickets.to_string())
+        .round(round);
+    Ok(event.apply(Response::new()))
+}
+
+/// Payment of the tickets of a bid.
+enum Payment {
+    /// Funds sent by the payer in the ticket asset.
+    Funds(Uint128),
+    /// Entry of a season pass, staking its part of the price of the pass whatever the
+    /// ticket price, with the entries left to the holder.
+    SeasonPass { stake: Uint128, entries_left: u32 },
+}
+
+/// Places a bid on the bins for the player, the payer having paid the tickets, buying
+/// the same number of tickets on each bin. In parimutuel mode all the funds sent are
+/// staked on the bid, otherwise the change is sent back to the payer.
+/// The cost is reduced by the coupon discount, in basis points, then by the loyalty
+/// discount of the player, and the referrer, if any, is recorded for the bid. The bid is
+/// reported with the given action.
+#[allow(clippy::too_many_arguments)]
+fn place_bid(
+    deps: DepsMut,
+    env: Env,
+    action: &str,
+    round: u64,
+    payer: &Addr,
+    player: Addr,
+    bins: Vec<u8>,
+    tickets: u32,
+    payment: Payment,
+    discount_bps: u16,
+    referrer: Option<Addr>,
+) -> Result<Response, ContractError> {
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    if STAGE_REVEAL.has(deps.storage, round) {
+        return Err(ContractError::CommitRequired {});
+    }
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_name = String::from("bid");
+    check_if_valid_stage(env.clone(), stage_bid, stage_name)?;
+    check_not_blocked(deps.storage, &[payer, &player])?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    check_token_gate(&deps.querier, &cfg, &player)?;
+    check_nft_gate(&deps.querier, &cfg, &player)?;
+    let ticket_price = current_ticket_price(deps.storage, &env.block, round)?;
+    check_ticket_denom(deps.storage, &cfg, &ticket_price)?;
+
+    // If a bid is already present for the player, no other bids can be placed.
+    if BIDS.has(deps.storage, (round, &player)) {
+        return Err(ContractError::CannotBidMoreThanOnce {});
+    };
+
+    // The bins must be at least one and cannot be repeated.
+    let mut sorted_bins = bins.clone();
+    sorted_bins.sort_unstable();
+    sorted_bins.dedup();
+    if bins.is_empty() || sorted_bins.len() != bins.len() {
+        return Err(ContractError::InvalidBins {});
+    }
+    if tickets == 0 {
+        return Err(ContractError::InvalidTickets {});
+    }
+
+    // If ticket price not paid for every ticket, bid is not allowed.
+    let cost = ticket_price
+        .amount
+        .checked_mul(Uint128::from(bins.len() as u64 * tickets as u64))
+        .map_err(|_| ContractError::BidCostOverflow { tickets, bins: bins.len() as u32 })?;
+    let cost = cost - to_payout(bps_amount(cost, discount_bps)?)?;
+    let loyalty_discount_bps = match payment {
+        Payment::Funds(_) => loyalty_discount(deps.storage, &player)?,
+        Payment::SeasonPass { .. } => 0,
+    };
+    let cost = cost - to_payout(bps_amount(cost, loyalty_discount_bps)?)?;
+    let (funds_sent, season_pass_entries) = match payment {
+        Payment::Funds(funds_sent) => {
+            if funds_sent < cost {
+                return Err(ContractError::TicketPriceNotPaid {});
+            }
+            (funds_sent, None)
+        }
+        Payment::SeasonPass { stake, entries_left } => {
+            if bins.len() > 1 || tickets > 1 {
+                return Err(ContractError::SeasonPassSingleTicket {});
+            }
+            (stake, Some(entries_left))
+        }
+    };
+
+    // If selected bin not permitted, bid not allowed.
+    let max_bin = BINS.load(deps.storage, round)?;
+    if bins.iter().any(|bin| *bin > max_bin) {
+        return Err(ContractError::BinDoesNotExist { bins: max_bin });
+    }
+    if let Some(limit) = cfg.max_bids {
+        if participants(deps.storage, round)? + bins.len() as u64 > limit as u64 {
+            return Err(ContractError::TooManyBids { limit });
+        }
+    }
+    for bin in &bins {
+        check_bin_capacity(deps.storage, &cfg, round, *bin)?;
+    }
+
+    // If player sent funds higher than the tickets price, return change, unless they
+    // are staked. The season pass entries are staked whole.
+    let stake = if cfg.parimutuel || season_pass_entries.is_some() { funds_sent } else { cost };
+    let mut transfer_msg: Vec<CosmosMsg> = vec![];
+    if funds_sent > stake {
+        transfer_msg.push(get_ticket_transfer_to_msg(
+            &cfg,
+            &ticket_price,
+            payer,
+            funds_sent - stake,
+        )?)
     }
 
-    // If the sender has an active bid, check if it wins or not.
-    let sender_bid = BIDS.may_load(deps.storage, &info.sender)?;
-    if sender_bid.is_some() {
-        let sender_bid = sender_bid.unwrap();
-
-        // The proof is computed by using as a leaf the value bidded by the sender.
-        let user_input = format!("{}{}", info.sender, sender_bid);
-        let hash = sha2::Sha256::digest(user_input.as_bytes())
-            .as_slice()
-            .try_into()
-            .map_err(|_| ContractError::WrongLength {})?;
-
-        let hash = proof_game.into_iter().try_fold(hash, |hash, p| {
-            let mut proof_buf = [0; 32];
-            hex::decode_to_slice(p, &mut proof_buf)?;
-            let mut hashes = [hash, proof_buf];
-            hashes.sort_unstable();
-            sha2::Sha256::digest(&hashes.concat())
-                .as_slice()
-                .try_into()
-                .map_err(|_| ContractError::WrongLength {})
+    let bid = Bid {
+        bin: bins[0],
+        tickets,
+        stake,
+        changes: 0,
+    };
+    BIDS.save(deps.storage, (round, &player), &bid)?;
+    if bins.len() > 1 {
+        EXTRA_BINS.save(deps.storage, (round, &player), &bins[1..].to_vec())?;
+    }
+    for bin in &bins {
+        increase_bin_count(deps.storage, round, *bin)?;
+    }
+    BID_HEIGHTS.save(deps.storage, (round, &player), &env.block.height)?;
+    add_loyalty_point(deps.storage, &player)?;
+    if let Some(referrer) = &referrer {
+        REFERRERS.save(deps.storage, (round, &player), referrer)?;
+        REFERRALS.update(deps.storage, referrer, |count| -> StdResult<_> {
+            Ok(count.unwrap_or_default() + 1)
         })?;
+    }
 
-        let mut root_buf: [u8; 32] = [0; 32];
-        hex::decode_to_slice(merkle_root_game, &mut root_buf)?;
-        // If the two root are equal:
-        // - Save the sender as a winner with unclaimed prize.
-        // - Increase the number of winners.
-        if root_buf == hash {
-            CLAIM_PRIZE.save(deps.storage, &info.sender, &false)?;
-            WINNERS.update(deps.storage, |mut winners_number| -> StdResult<_> {
-                winners_number += Uint128::new(1);
-                Ok(winners_number)
-            })?;
-        }
-    }
-        
-    // Mark the sender as a user that has received the airdrop.
-    CLAIM_AIRDROP.save(deps.storage, &info.sender, &true)?;
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let notice = Notice::ClaimAirdropWindow {
+        round,
+        closes_at: (stage_claim_airdrop.start + stage_claim_airdrop.duration)?,
+    };
+    push_notice(deps.storage, &player, notice)?;
 
-    // Increase the amount of airdropped tokens claimed.
-    CLAIMED_AIRDROP_AMOUNT.update(deps.storage, |mut claimed_amount| -> StdResult<_> {
-        claimed_amount += amount;
-        Ok(claimed_amount)
-    })?;
+    // Add payed tickets to the final prize.
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    treasury::credit(deps.storage, round, Bucket::TicketPot, &ticket_asset, stake)?;
+    let extension = extend_bid_stage(deps.storage, &env, &cfg, round)?;
 
-    let msg = get_cw20_transfer_to_msg(
-        &info.sender,
-        &cfg.cw20_token_address,
-        amount,
-    )?;
+    // Mint the receipt of the bid, if any collection set.
+    if let Some(collection) = &cfg.bid_receipts {
+        transfer_msg.push(cw721::mint_receipt_msg(collection, round, &player)?);
+    }
 
-    let res = Response::new()
-        .add_message(msg)
-        .add_attribute("action", "claim_airdrop")
-        .add_attribute("player", info.sender)
-        .add_attribute("airdrop_amount", amount);
+    let mut event = GameEvent::new(action).player(player.clone());
+    if payer != &player {
+        event = event.add(events::PAYER, payer);
+    }
+    for bin in bins {
+        event = event.bin(bin);
+    }
+    if tickets > 1 {
+        event = event.add(events::TICKETS, tickets.to_string());
+    }
+    if cfg.parimutuel {
+        event = event.amount(stake);
+    }
+    if extension > 0 {
+        event = event.add(events::STAGE_EXTENSION, extension.to_string());
+    }
+    if discount_bps > 0 {
+        event = event.add(events::COUPON_DISCOUNT_BPS, discount_bps.to_string());
+    }
+    if loyalty_discount_bps > 0 {
+        event = event.add(events::LOYALTY_DISCOUNT_BPS, loyalty_discount_bps.to_string());
+    }
+    if let Some(entries_left) = season_pass_entries {
+        event = event.add(events::SEASON_PASS_ENTRIES, entries_left.to_string());
+    }
+    if let Some(referrer) = referrer {
+        event = event.add(events::REFERRER, referrer);
+    }
+    if cfg.bid_receipts.is_some() {
+        event = event.add(events::RECEIPT, cw721::receipt_token_id(round, &player));
+    }
+    let res = event
+        .stage("bid")
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msg));
     Ok(res)
 }
 
-pub fn execute_claim_prize(
+/// Commits a bid, paid with native funds, as the hash of the address, the bin and a
+/// salt. The ticket is added to the prize even if the bid is never revealed.
+pub fn execute_commit_bid(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo
+    info: MessageInfo,
+    hash: String,
+    allowlist_proof: Option<Vec<String>>,
+    round: Option<u64>,
 ) -> Result<Response, ContractError> {
-    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage)?;
-    let stage_name = String::from("claim prize");
-    check_if_valid_stage(env, stage_claim_prize, stage_name)?;
+    let cfg = CONFIG.load(deps.storage)?;
+    if cfg.ticket_cw20_address.is_some() {
+        return Err(ContractError::InvalidTicketAsset {});
+    }
+    check_allowlist(&cfg, info.sender.as_str(), allowlist_proof)?;
 
-    // Verify that the user has not already made the claim.
-    let claimed = CLAIM_PRIZE.may_load(deps.storage, &info.sender)?;
-    if let Some(already_claimed) = claimed {
-        if already_claimed {
-            return Err(ContractError::AlreadyClaimed {});
+    let round = resolve_round(deps.storage, round)?;
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    if !STAGE_REVEAL.has(deps.storage, round) {
+        return Err(ContractError::RevealStageNotSet {});
+    }
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    check_if_valid_stage(env.clone(), stage_bid, String::from("bid"))?;
+    check_not_blocked(deps.storage, &[&info.sender])?;
+    check_token_gate(&deps.querier, &cfg, &info.sender)?;
+    check_nft_gate(&deps.querier, &cfg, &info.sender)?;
+
+    if COMMITMENTS.has(deps.storage, (round, &info.sender)) {
+        return Err(ContractError::CannotBidMoreThanOnce {});
+    }
+    let mut hash_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&hash, &mut hash_buf)?;
+
+    let ticket_price = current_ticket_price(deps.storage, &env.block, round)?;
+    check_ticket_denom(deps.storage, &cfg, &ticket_price)?;
+    let loyalty_discount_bps = loyalty_discount(deps.storage, &info.sender)?;
+    let cost = ticket_price.amount - to_payout(bps_amount(ticket_price.amount, loyalty_discount_bps)?)?;
+    let funds_sent = get_amount_for_denom(&info.funds, &ticket_price.denom).amount;
+    if funds_sent < cost {
+        return Err(ContractError::TicketPriceNotPaid {});
+    }
+    if let Some(limit) = cfg.max_bids {
+        let commitments = COMMITMENTS
+            .prefix(round)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .count();
+        if commitments as u64 >= limit as u64 {
+            return Err(ContractError::TooManyBids { limit });
         }
-    } else {
-        return Err(ContractError::NoteEligible {});
+    }
+
+    // If player sent funds higher than ticket price, return change, unless they are
+    // staked.
+    let stake = if cfg.parimutuel { funds_sent } else { cost };
+    let mut transfer_msg: Vec<CosmosMsg> = vec![];
+    if funds_sent > stake {
+        transfer_msg.push(get_ticket_transfer_to_msg(
+            &cfg,
+            &ticket_price,
+            &info.sender,
+            funds_sent - stake,
+        )?)
+    }
+
+    let commitment = Commitment {
+        hash: hash.to_lowercase(),
+        height: env.block.height,
+        stake,
     };
+    COMMITMENTS.save(deps.storage, (round, &info.sender), &commitment)?;
+    add_loyalty_point(deps.storage, &info.sender)?;
+
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    treasury::credit(deps.storage, round, Bucket::TicketPot, &ticket_asset, stake)?;
+    let extension = extend_bid_stage(deps.storage, &env, &cfg, round)?;
 
+    let mut event = GameEvent::new("commit_bid")
+        .player(info.sender)
+        .add(events::COMMITMENT, hash);
+    if extension > 0 {
+        event = event.add(events::STAGE_EXTENSION, extension.to_string());
+    }
+    if loyalty_discount_bps > 0 {
+        event = event.add(events::LOYALTY_DISCOUNT_BPS, loyalty_discount_bps.to_string());
+    }
+    let res = event
+        .stage("bid")
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msg));
+    Ok(res)
+}
+
+/// Reveals the bin of a committed bid, placing the bid as if it had been placed when
+/// committed.
+pub fn execute_reveal_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bin: u8,
+    salt: String,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    let stage_reveal = STAGE_REVEAL
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::RevealStageNotSet {})?;
+    check_if_valid_stage(env.clone(), stage_reveal, String::from("reveal"))?;
+    check_not_blocked(deps.storage, &[&info.sender])?;
+
+    let commitment = COMMITMENTS
+        .may_load(deps.storage, (round, &info.sender))?
+        .ok_or(ContractError::CommitmentNotPresent {})?;
+    if bid_commitment(&info.sender, round, &env.contract.address, bin, &salt) != commitment.hash {
+        return Err(ContractError::InvalidReveal {});
+    }
+    let bins = BINS.load(deps.storage, round)?;
+    if bin > bins {
+        return Err(ContractError::BinDoesNotExist { bins });
+    }
     let cfg = CONFIG.load(deps.storage)?;
-    let winners = WINNERS.load(deps.storage)?;
-    let ticket_price = TICKET_PRICE.load(deps.storage)?;
-    let ticket_prize = TOTAL_TICKET_PRIZE.load(deps.storage)?;
-    let airdrop_prize = TOTAL_AIRDROP_GAME_AMOUNT.load(deps.storage)?;
+    check_bin_capacity(deps.storage, &cfg, round, bin)?;
 
-    // Every winner will receive two prize: one given by the tickets of the game and
-    // one given by an incentive from the tokens airdrop. For both of them the
-    // amount received is given by the total divided by the number of winners.
-    let sender_ticket_prize = ticket_prize.checked_div(winners).unwrap();
-    let sender_airdrop_prize = airdrop_prize.checked_div(winners).unwrap();
+    COMMITMENTS.remove(deps.storage, (round, &info.sender));
+    let bid = Bid {
+        bin,
+        tickets: 1,
+        stake: commitment.stake,
+        changes: 0,
+    };
+    BIDS.save(deps.storage, (round, &info.sender), &bid)?;
+    increase_bin_count(deps.storage, round, bin)?;
+    BID_HEIGHTS.save(deps.storage, (round, &info.sender), &commitment.height)?;
 
-    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
-    transfer_msgs.push(get_bank_transfer_to_msg(
-        &info.sender,
-        &ticket_price.denom,
-        sender_ticket_prize,
-    ));
-    transfer_msgs.push(get_cw20_transfer_to_msg(
-        &info.sender,
-        &cfg.cw20_token_address,
-        sender_airdrop_prize,
-    )?);
-
-    CLAIM_PRIZE.update(deps.storage, &info.sender, |mut _already_claimed| -> StdResult<_>{
-        Ok(true)
-    })?;
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let notice = Notice::ClaimAirdropWindow {
+        round,
+        closes_at: (stage_claim_airdrop.start + stage_claim_airdrop.duration)?,
+    };
+    push_notice(deps.storage, &info.sender, notice)?;
 
-    // Update botht the airdrop and the prize claimed amount.
-    CLAIMED_AIRDROP_AMOUNT.update(deps.storage, |mut claimed_amount| -> StdResult<_> {
-        claimed_amount += sender_airdrop_prize;
-        Ok(claimed_amount)
-    })?;
-    CLAIMED_PRIZE_AMOUNT.update(deps.storage, |mut claimed_amount| -> StdResult<_> {
-        claimed_amount += sender_ticket_prize;
-        Ok(claimed_amount)
-    })?;
+    let mut res = Response::new();
+    if let Some(collection) = &cfg.bid_receipts {
+        res = res.add_message(cw721::mint_receipt_msg(collection, round, &info.sender)?);
+    }
 
-    let res = Response::new()
-        .add_messages(transfer_msgs)
-        .add_attribute("action", "claim_prize")
-        .add_attribute("player", info.sender)
-        .add_attribute("prize_from_tickets", sender_ticket_prize)
-        .add_attribute("prize_from_airdrop", sender_airdrop_prize);
+    let res = GameEvent::new("reveal_bid")
+        .player(info.sender)
+        .bin(bin)
+        .stage("reveal")
+        .round(round)
+        .apply(res);
     Ok(res)
 }
 
-// ======================================================================================
-// Withdraw of unclaimed tokens
-// ======================================================================================
-pub fn execute_withdraw_airdrop(
+/// Returns the hex encoded commitment of a bid, the SHA-256 hash of the player address,
+/// the round and the game address, each followed by `|`, then of the bin as a single
+/// byte and of the salt. The bin having a fixed width, a commitment opens a single bin.
+pub fn bid_commitment(player: &Addr, round: u64, contract: &Addr, bin: u8, salt: &str) -> String {
+    let mut data = format!("{}|{}|{}|", player, round, contract).into_bytes();
+    data.push(bin);
+    data.extend_from_slice(salt.as_bytes());
+    hex::encode(sha2::Sha256::digest(&data))
+}
+
+/// Saves the commitment of the sponsor to match the ticket sales of the round with
+/// the deposited token, up to the deposited amount.
+fn sponsor_round(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
+    round: u64,
+    sponsor: Addr,
+    token: Addr,
+    ratio: Decimal,
+    cap: Uint128,
+) -> Result<Response, ContractError> {
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+
+    // Ticket sales can be matched until they are final.
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_bid_end = (stage_bid.start + stage_bid.duration)?;
+    if stage_bid_end.is_triggered(&env.block) {
+        return Err(ContractError::StageEnded { stage_name: String::from("bid") });
+    }
+
+    if ratio.is_zero() || cap.is_zero() {
+        return Err(ContractError::InvalidSponsorship {});
+    }
+    if SPONSORSHIPS.has(deps.storage, (round, &sponsor)) {
+        return Err(ContractError::SponsorshipAlreadyPresent {});
+    }
+    let sponsorships = SPONSORSHIPS
+        .prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    if sponsorships >= MAX_SPONSORSHIPS as usize {
+        return Err(ContractError::TooManySponsorships { limit: MAX_SPONSORSHIPS });
+    }
+
+    let sponsorship = Sponsorship {
+        token,
+        ratio,
+        cap,
+        claimed: Uint128::zero(),
+        final_match: None,
+    };
+    SPONSORSHIPS.save(deps.storage, (round, &sponsor), &sponsorship)?;
+    treasury::credit(deps.storage, round, Bucket::Sponsorships, sponsorship.token.as_str(), cap)?;
+
+    let res = GameEvent::new("sponsor")
+        .add(events::SPONSOR, sponsor)
+        .add(events::TOKEN, sponsorship.token)
+        .add(events::MATCH_RATIO, ratio.to_string())
+        .amount(cap)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Donates the sent native token to the prize pool of the round.
+pub fn execute_fund_prize_pool(
+    deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    address: &Addr,
+    round: Option<u64>,
 ) -> Result<Response, ContractError> {
-    // Just the contract owner can withdraw the remaining tokens.
     let cfg = CONFIG.load(deps.storage)?;
-    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
-    if info.sender != owner {
-        return Err(ContractError::Unauthorized {});
+    let round = resolve_round(deps.storage, round)?;
+    let funds = match info.funds.as_slice() {
+        [funds] => funds,
+        _ => return Err(ContractError::InvalidPrizePoolFunds {}),
+    };
+    fund_prize_pool(deps, env, &cfg, round, info.sender.clone(), &funds.denom, funds.amount)
+}
+
+/// Adds the donation of the contributor to the prizes of the round: the ticket asset to
+/// the tickets prize, the prize asset to the game prize, earmarked before or after the
+/// registration of the game root.
+fn fund_prize_pool(
+    deps: DepsMut,
+    env: Env,
+    cfg: &Config,
+    round: u64,
+    contributor: Addr,
+    asset: &str,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
     }
 
-    // Check that the claiming prize stage has ended.
-    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage)?;
-    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
-    if !stage_claim_prize_end.is_triggered(&_env.block) {
-        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    // The prizes can grow until the bids are final.
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_bid_end = (stage_bid.start + stage_bid.duration)?;
+    if stage_bid_end.is_triggered(&env.block) {
+        return Err(ContractError::StageEnded { stage_name: String::from("bid") });
     }
 
-    let total_amount_airdrop = TOTAL_AIRDROP_AMOUNT.load(deps.storage)?;
-    let total_amount_prize = TOTAL_AIRDROP_GAME_AMOUNT.load(deps.storage)?;
-    let claimed_amount = CLAIMED_AIRDROP_AMOUNT.load(deps.storage)?;
-    let amount = total_amount_airdrop + total_amount_prize - claimed_amount;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidPrizePoolFunds {});
+    }
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let mut contribution = CONTRIBUTIONS.may_load(deps.storage, (round, &contributor))?.unwrap_or_default();
+    if asset == ticket_asset(cfg, &ticket_price) {
+        treasury::credit(deps.storage, round, Bucket::TicketPot, asset, amount)?;
+        contribution.ticket_prize += amount;
+    } else if asset == prize_asset(cfg) {
+        treasury::credit(deps.storage, round, Bucket::GameReserve, asset, amount)?;
+        if let Some(amount_game) = TOTAL_AIRDROP_GAME_AMOUNT.may_load(deps.storage, round)? {
+            TOTAL_AIRDROP_GAME_AMOUNT.save(deps.storage, round, &(amount_game + amount))?;
+        }
+        contribution.game_prize += amount;
+    } else {
+        return Err(ContractError::InvalidPrizePoolFunds {});
+    }
+    CONTRIBUTIONS.save(deps.storage, (round, &contributor), &contribution)?;
 
-    let msg = get_cw20_transfer_to_msg(
-        &address,
-        &cfg.cw20_token_address,
-        amount,
-    )?;
+    let res = GameEvent::new("fund_prize_pool")
+        .add(events::CONTRIBUTOR, contributor)
+        .add(events::ASSET, asset)
+        .amount(amount)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Performs several actions of the player atomically: if one of them fails, none is
+/// applied. The funds sent pay the tickets of the bids in order, the funds left are
+/// sent back and the bank transfers to the same recipient are merged.
+pub fn execute_batch_actions(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    actions: Vec<PlayerAction>,
+) -> Result<Response, ContractError> {
+    if actions.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    let actions_number = actions.len();
+    let bid_change_fee = CONFIG.load(deps.storage)?.bid_change_fee;
+    let mut available_funds = info.funds.clone();
+    let action_info = |funds: Vec<Coin>| MessageInfo {
+        sender: info.sender.clone(),
+        funds,
+    };
+
+    let mut res = Response::new();
+    for action in actions {
+        let action_res = match action {
+            PlayerAction::Bid { bin, tickets, round } => {
+                let bid_round = resolve_round(deps.storage, round)?;
+                let mut ticket_price = current_ticket_price(deps.storage, &env.block, bid_round)?;
+                ticket_price.amount *= Uint128::from(tickets.unwrap_or(1));
+                let ticket_funds = take_funds(&mut available_funds, &ticket_price);
+                execute_bid(deps.branch(), env.clone(), action_info(vec![ticket_funds]), bin, tickets, None, None, None, None, None, round)?
+            }
+            PlayerAction::ChangeBid { bin, round } => {
+                let fee_funds = bid_change_fee
+                    .iter()
+                    .map(|fee| take_funds(&mut available_funds, fee))
+                    .collect();
+                execute_change_bid(deps.branch(), env.clone(), action_info(fee_funds), bin, round)?
+            }
+            PlayerAction::RemoveBid { round } => {
+                execute_remove_bid(deps.branch(), env.clone(), action_info(vec![]), round)?
+            }
+            PlayerAction::ProveStake { weight, proof, round } => {
+                execute_prove_stake(deps.branch(), env.clone(), action_info(vec![]), weight, proof, round)?
+            }
+            PlayerAction::ClaimAirdrop {
+                amount,
+                proof_airdrop,
+                proof_game,
+                round,
+            } => execute_claim_airdrop(
+                deps.branch(),
+                env.clone(),
+                action_info(vec![]),
+                amount,
+                proof_airdrop,
+                proof_game,
+                None,
+                round,
+            )?,
+            PlayerAction::ClaimPrize { round } => {
+                execute_claim_prize(deps.branch(), env.clone(), action_info(vec![]), None, false, round)?
+            }
+            PlayerAction::DonatePrize { share_bps, round } => {
+                execute_donate_prize(deps.branch(), env.clone(), action_info(vec![]), share_bps, round)?
+            }
+        };
+        res = res
+            .add_submessages(action_res.messages)
+            .add_attributes(action_res.attributes)
+            .add_events(action_res.events);
+    }
 
-    let res = Response::new()
-        .add_message(msg)
-        .add_attribute("action", "withdraw_airdrop")
-        .add_attribute("address", address)
-        .add_attribute("amount", amount);
+    // Send back the funds not used to pay tickets.
+    let leftover_funds: Vec<Coin> = available_funds
+        .into_iter()
+        .filter(|c| !c.amount.is_zero())
+        .collect();
+    if !leftover_funds.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: leftover_funds,
+        });
+    }
+    res.messages = merge_bank_transfers(res.messages);
 
+    let res = GameEvent::new("batch_actions")
+        .player(info.sender)
+        .add(events::ACTIONS, actions_number.to_string())
+        .apply(res);
     Ok(res)
 }
 
-// TODO: si potrebbe unire a quello sopra.
-pub fn execute_withdraw_prize(
+pub fn execute_change_bid(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    address: &Addr,
+    bin: u8,
+    round: Option<u64>,
 ) -> Result<Response, ContractError> {
-    // Just the contract owner can withdraw the remaining tokens.
+    let round = resolve_round(deps.storage, round)?;
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_name = String::from("bid");
+    check_if_valid_stage(env.clone(), stage_bid, stage_name)?;
+
+    // If a previous bid doesn't exists for the sender, nothing can be changed.
+    if !BIDS.has(deps.storage, (round, &info.sender)) {
+        return Err(ContractError::BidNotPresent {});
+    };
+    // A bid placed on several bins can only be removed.
+    if EXTRA_BINS.has(deps.storage, (round, &info.sender)) {
+        return Err(ContractError::CannotChangeMultipleBins {});
+    }
+
     let cfg = CONFIG.load(deps.storage)?;
-    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
-    if info.sender != owner {
-        return Err(ContractError::Unauthorized {});
+    let old_bid = BIDS.load(deps.storage, (round, &info.sender))?;
+    if let Some(limit) = cfg.max_bid_changes {
+        if old_bid.changes >= limit {
+            return Err(ContractError::TooManyBidChanges { limit });
+        }
+    }
+    if bin != old_bid.bin {
+        check_bin_capacity(deps.storage, &cfg, round, bin)?;
     }
 
-    // Check that the claiming prize stage has ended.
-    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage)?;
-    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
-    if !stage_claim_prize_end.is_triggered(&_env.block) {
-        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    // The fee is kept by the contract, the funds sent above it are sent back.
+    let mut res = Response::new();
+    let mut event = GameEvent::new("change_bid")
+        .player(info.sender.clone())
+        .add(events::NEW_BIN, bin.to_string());
+    if let Some(fee) = &cfg.bid_change_fee {
+        let funds_sent = get_amount_for_denom(&info.funds, &fee.denom).amount;
+        if funds_sent < fee.amount {
+            return Err(ContractError::BidChangeFeeNotPaid {});
+        }
+        treasury::credit(deps.storage, round, Bucket::Fees, &fee.denom, fee.amount)?;
+        if funds_sent > fee.amount {
+            res = res.add_message(get_bank_transfer_to_msg(&info.sender, &fee.denom, funds_sent - fee.amount));
+        }
+        event = event.add(events::BID_CHANGE_FEE, fee.amount);
     }
 
-    let total_prize = TOTAL_TICKET_PRIZE.load(deps.storage)?;
-    let claimed_prize = CLAIMED_PRIZE_AMOUNT.load(deps.storage)?;
-    let amount = total_prize - claimed_prize;
+    // The tickets of the bid are moved to the new bin.
+    let new_bid = Bid {
+        bin,
+        changes: old_bid.changes + 1,
+        ..old_bid
+    };
+    BIDS.save(deps.storage, (round, &info.sender), &new_bid)?;
+    decrease_bin_count(deps.storage, round, old_bid.bin)?;
+    increase_bin_count(deps.storage, round, bin)?;
+    // The bid on the new bin is considered as placed at the current height.
+    BID_HEIGHTS.save(deps.storage, (round, &info.sender), &env.block.height)?;
+
+    let res = event
+        .stage("bid")
+        .round(round)
+        .apply(res);
+    Ok(res)
+}
+
+/// Moves the bid of the sender to a new address without a bid. The stake weight is
+/// proven for an address, so it is not moved with the bid.
+pub fn execute_transfer_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_address: String,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_name = String::from("bid");
+    check_if_valid_stage(env, stage_bid, stage_name)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let new_address = validate_address(deps.as_ref(), &cfg.address_prefix, &new_address)?;
+    let bid = BIDS
+        .may_load(deps.storage, (round, &info.sender))?
+        .ok_or(ContractError::BidNotPresent {})?;
+    if BIDS.has(deps.storage, (round, &new_address)) || COMMITMENTS.has(deps.storage, (round, &new_address)) {
+        return Err(ContractError::CannotBidMoreThanOnce {});
+    }
+
+    BIDS.remove(deps.storage, (round, &info.sender));
+    BIDS.save(deps.storage, (round, &new_address), &bid)?;
+    if let Some(extra_bins) = EXTRA_BINS.may_load(deps.storage, (round, &info.sender))? {
+        EXTRA_BINS.remove(deps.storage, (round, &info.sender));
+        EXTRA_BINS.save(deps.storage, (round, &new_address), &extra_bins)?;
+    }
+    let height = BID_HEIGHTS.load(deps.storage, (round, &info.sender))?;
+    BID_HEIGHTS.remove(deps.storage, (round, &info.sender));
+    BID_HEIGHTS.save(deps.storage, (round, &new_address), &height)?;
+    BID_WEIGHTS.remove(deps.storage, (round, &info.sender));
+    // The loyalty point of the round moves with the bid.
+    remove_loyalty_point(deps.storage, &info.sender)?;
+    add_loyalty_point(deps.storage, &new_address)?;
+
+    // The referral moves with the bid, unless the new address is the referrer itself.
+    if let Some(referrer) = REFERRERS.may_load(deps.storage, (round, &info.sender))? {
+        REFERRERS.remove(deps.storage, (round, &info.sender));
+        if referrer == new_address {
+            remove_referral(deps.storage, &referrer)?;
+        } else {
+            REFERRERS.save(deps.storage, (round, &new_address), &referrer)?;
+        }
+    }
+
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let notice = Notice::ClaimAirdropWindow {
+        round,
+        closes_at: (stage_claim_airdrop.start + stage_claim_airdrop.duration)?,
+    };
+    push_notice(deps.storage, &new_address, notice)?;
+
+    // The receipt is reissued to the new address.
+    let mut res = Response::new();
+    if let Some(collection) = &cfg.bid_receipts {
+        res = res
+            .add_message(cw721::burn_receipt_msg(collection, round, &info.sender)?)
+            .add_message(cw721::mint_receipt_msg(collection, round, &new_address)?);
+    }
+
+    let res = GameEvent::new("transfer_bid")
+        .player(info.sender)
+        .add(events::ADDRESS, new_address)
+        .stage("bid")
+        .round(round)
+        .apply(res);
+    Ok(res)
+}
+
+pub fn execute_remove_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_name = String::from("bid");
+    check_if_valid_stage(env, stage_bid, stage_name)?;
+
+    // IF: check if a bid for the sender is not present.
+    // ELSE: if the bid is present, remove it and send back the stake to the sender, less
+    // the penalty kept in the ticket prize. The committed bids have no receipt yet.
+    let placed = BIDS.has(deps.storage, (round, &info.sender));
+    let stake = clear_bid(deps.storage, round, &info.sender)?
+        .ok_or(ContractError::BidNotPresent {})?;
+    let cfg = CONFIG.load(deps.storage)?;
+    let penalty = to_payout(bps_amount(stake, cfg.remove_bid_penalty_bps)?)?;
+    let payback = stake - penalty;
+
+    // Remove from ticket prize the tickets of the bid.
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    treasury::debit(deps.storage, round, Bucket::TicketPot, &ticket_asset, payback)?;
+
+    let mut res = Response::new();
+    if !payback.is_zero() {
+        res = res.add_message(get_ticket_transfer_to_msg(
+            &cfg,
+            &ticket_price,
+            &info.sender,
+            payback,
+        )?);
+    }
+    if let (Some(collection), true) = (&cfg.bid_receipts, placed) {
+        res = res.add_message(cw721::burn_receipt_msg(collection, round, &info.sender)?);
+    }
+
+    let res = GameEvent::new("remove_bid")
+        .player(info.sender)
+        .add(events::TICKET_PRICE_PAYBACK, payback)
+        .add(events::REMOVE_BID_PENALTY, penalty)
+        .stage("bid")
+        .round(round)
+        .apply(res);
+    Ok(res)
+}
+
+pub fn execute_register_stake_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root_stake: String,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can load the Merkle root.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // All the players must bid knowing the stake weights.
+    let round = resolve_round(deps.storage, round)?;
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    if stage_bid.start.is_triggered(&env.block) {
+        return Err(ContractError::StakeRootAfterBidStart {});
+    }
+
+    // Check merkle root stake length.
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root_stake, &mut root_buf)?;
+
+    MERKLE_ROOT_STAKE.save(deps.storage, round, &merkle_root_stake)?;
+
+    let res = GameEvent::new("register_stake_root")
+        .add(events::MERKLE_ROOT_STAKE, merkle_root_stake)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Registers the Merkle root of the ticket coupons of the round, each leaf being an
+/// address followed by its discount in basis points.
+pub fn execute_register_coupon_root(
+    deps: DepsMut,
+    info: MessageInfo,
+    merkle_root_coupon: String,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can load the Merkle root.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Check merkle root coupon length.
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root_coupon, &mut root_buf)?;
+
+    let round = resolve_round(deps.storage, round)?;
+    MERKLE_ROOT_COUPON.save(deps.storage, round, &merkle_root_coupon)?;
+
+    let res = GameEvent::new("register_coupon_root")
+        .add(events::MERKLE_ROOT_COUPON, merkle_root_coupon)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+pub fn execute_prove_stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    weight: u64,
+    proof: Vec<String>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_name = String::from("bid");
+    check_if_valid_stage(env, stage_bid, stage_name)?;
+
+    if !BIDS.has(deps.storage, (round, &info.sender)) {
+        return Err(ContractError::BidNotPresent {});
+    }
+    if weight == 0 {
+        return Err(ContractError::InvalidStakeWeight {});
+    }
+
+    let merkle_root_stake = MERKLE_ROOT_STAKE
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::StakeRootNotRegistered {})?;
+    let cfg = CONFIG.load(deps.storage)?;
+    let leaf = format!("{}{}", info.sender, weight);
+    if !merkle_root_matches(cfg.hash_algorithm, &leaf, proof, &merkle_root_stake)? {
+        return Err(ContractError::VerificationFailed { merkle_root: "stake".to_string() });
+    }
+
+    BID_WEIGHTS.save(deps.storage, (round, &info.sender), &weight)?;
+
+    let res = GameEvent::new("prove_stake")
+        .player(info.sender)
+        .add(events::STAKE_WEIGHT, weight.to_string())
+        .stage("bid")
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Pauses the contract, leaving open only the refunds of the tickets, for example to stop
+/// the claims of wrongly registered Merkle roots.
+pub fn execute_pause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // Just the contract owner can pause the contract.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if PAUSED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::ContractPaused {});
+    }
+    PAUSED.save(deps.storage, &PausedBy::Owner)?;
+
+    let res = GameEvent::new("pause").apply(Response::new());
+    Ok(res)
+}
+
+/// Resumes the contract paused by the owner. A pause of the chain governance can only be
+/// lifted by the governance.
+pub fn execute_unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // Just the contract owner can unpause the contract.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match PAUSED.may_load(deps.storage)? {
+        None => return Err(ContractError::ContractNotPaused {}),
+        Some(PausedBy::Governance) => return Err(ContractError::Unauthorized {}),
+        Some(PausedBy::Owner) => PAUSED.remove(deps.storage),
+    }
+
+    let res = GameEvent::new("unpause").apply(Response::new());
+    Ok(res)
+}
+
+/// Adds and removes addresses of the blocklist, for example sanctioned or exploit-linked
+/// addresses. The bids already placed by a blocked address are kept, but cannot be claimed.
+pub fn execute_update_blocklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can update the blocklist.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut event = GameEvent::new("update_blocklist");
+    for address in add {
+        let address = validate_address(deps.as_ref(), &cfg.address_prefix, &address)?;
+        BLOCKLIST.save(deps.storage, &address, &true)?;
+        event = event.add(events::BLOCKED, address);
+    }
+    for address in remove {
+        let address = validate_address(deps.as_ref(), &cfg.address_prefix, &address)?;
+        BLOCKLIST.remove(deps.storage, &address);
+        event = event.add(events::UNBLOCKED, address);
+    }
+
+    let res = event.apply(Response::new());
+    Ok(res)
+}
+
+/// Adds and removes IBC voucher denoms accepted as ticket.
+pub fn execute_update_ibc_denoms(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can update the IBC denoms.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut event = GameEvent::new("update_ibc_denoms");
+    for denom in add {
+        validate_ibc_denom(&denom)?;
+        IBC_DENOMS.save(deps.storage, &denom, &true)?;
+        event = event.add(events::ACCEPTED_DENOM, denom);
+    }
+    for denom in remove {
+        IBC_DENOMS.remove(deps.storage, &denom);
+        event = event.add(events::REMOVED_DENOM, denom);
+    }
+
+    let res = event.apply(Response::new());
+    Ok(res)
+}
+
+/// Sells a season pass to the sender, adding its entries to the ones left, and sends back
+/// the change. The price is kept for the bids placed with the entries.
+pub fn execute_buy_season_pass(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let season_pass = cfg.season_pass.clone().ok_or(ContractError::SeasonPassNotOffered {})?;
+    check_not_blocked(deps.storage, &[&info.sender])?;
+
+    let funds_sent = get_amount_for_denom(&info.funds, &season_pass.price.denom).amount;
+    if funds_sent < season_pass.price.amount {
+        return Err(ContractError::TicketPriceNotPaid {});
+    }
+    let mut transfer_msg: Vec<CosmosMsg> = vec![];
+    if funds_sent > season_pass.price.amount {
+        transfer_msg.push(get_ticket_transfer_to_msg(
+            &cfg,
+            &season_pass.price,
+            &info.sender,
+            funds_sent - season_pass.price.amount,
+        )?)
+    }
+
+    let mut balance = SEASON_PASSES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or(SeasonPassBalance { entries: 0, amount: Uint128::zero() });
+    balance.entries += season_pass.rounds;
+    balance.amount += season_pass.price.amount;
+    SEASON_PASSES.save(deps.storage, &info.sender, &balance)?;
+    let round = resolve_round(deps.storage, None)?;
+    treasury::credit(deps.storage, round, Bucket::SeasonPasses, &season_pass.price.denom, season_pass.price.amount)?;
+
+    let res = GameEvent::new("buy_season_pass")
+        .player(info.sender)
+        .amount(season_pass.price.amount)
+        .add(events::SEASON_PASS_ENTRIES, balance.entries.to_string())
+        .apply(Response::new().add_messages(transfer_msg));
+    Ok(res)
+}
+
+/// Replaces the loyalty tiers, sorted by ascending points. An empty list removes the
+/// loyalty discounts.
+pub fn execute_set_loyalty_tiers(
+    deps: DepsMut,
+    info: MessageInfo,
+    mut tiers: Vec<LoyaltyTier>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can set the loyalty tiers.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    tiers.sort_unstable_by_key(|tier| tier.min_points);
+    let invalid_tier = tiers
+        .iter()
+        .any(|tier| tier.min_points == 0 || tier.discount_bps == 0 || tier.discount_bps > MAX_BPS);
+    if invalid_tier || tiers.windows(2).any(|pair| pair[0].min_points == pair[1].min_points) {
+        return Err(ContractError::InvalidLoyaltyTiers {});
+    }
+    LOYALTY_TIERS.save(deps.storage, &tiers)?;
+
+    let res = GameEvent::new("set_loyalty_tiers")
+        .add(events::LOYALTY_TIERS, tiers.len().to_string())
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Replaces the payout multipliers of the bins. An empty list removes the multipliers.
+/// They cannot change once the claim airdrop stage of the round has started, so that
+/// the weights of the winners of a round are multiplied in the same way.
+pub fn execute_set_bin_multipliers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut multipliers: Vec<(u8, Decimal)>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can set the bin multipliers.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The winners of a single winning bin all share its multiplier, which then has no
+    // effect on their shares.
+    if cfg.resolution != (GameResolution::MerkleRoot {}) {
+        return Err(ContractError::BinMultipliersSingleBin {});
+    }
+
+    let round = CURRENT_ROUND.load(deps.storage)?;
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    if stage_claim_airdrop.start.is_triggered(&env.block)
+        || AIRDROP_CLAIMS.may_load(deps.storage, round)?.unwrap_or_default() > 0
+    {
+        return Err(ContractError::BinMultipliersLocked {});
+    }
+
+    multipliers.sort_unstable_by_key(|(bin, _)| *bin);
+    let max_multiplier = Decimal::from_ratio(MAX_BIN_MULTIPLIER, 1u64);
+    let invalid_multiplier = multipliers
+        .iter()
+        .any(|(bin, multiplier)| *bin == 0 || multiplier.is_zero() || *multiplier > max_multiplier);
+    if invalid_multiplier || multipliers.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return Err(ContractError::InvalidBinMultipliers { max: MAX_BIN_MULTIPLIER });
+    }
+    let old_bins: Vec<u8> = BIN_MULTIPLIERS
+        .prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for bin in old_bins {
+        BIN_MULTIPLIERS.remove(deps.storage, (round, bin));
+    }
+    for (bin, multiplier) in &multipliers {
+        BIN_MULTIPLIERS.save(deps.storage, (round, *bin), multiplier)?;
+    }
+
+    let res = GameEvent::new("set_bin_multipliers")
+        .add(events::BIN_MULTIPLIERS, multipliers.len().to_string())
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Cancels the round before the Merkle roots are registered. The round is aborted from
+/// then on: no bid can be placed and the tickets can be refunded by anyone.
+pub fn execute_cancel_game(
+    deps: DepsMut,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can cancel the round.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let round = resolve_round(deps.storage, round)?;
+    if MERKLE_ROOT_GAME.has(deps.storage, round) {
+        return Err(ContractError::MerkleRootsAlreadyRegistered {});
+    }
+    if WINNING_BIN.has(deps.storage, round) {
+        return Err(ContractError::GameAlreadyResolved {});
+    }
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    CANCELLED.save(deps.storage, round, &true)?;
+
+    let res = GameEvent::new("cancel_game")
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Sends back the tickets of the bids of an aborted round, either of the given
+/// addresses or of the first bidders in address order. Addresses without a bid are
+/// skipped, so a refund is never paid twice.
+pub fn execute_refund_bids(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addresses: Option<Vec<String>>,
+    limit: Option<u32>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can push the refunds, unless the round has been
+    // cancelled or the owner missed the deadline to register the Merkle roots.
+    let cfg = CONFIG.load(deps.storage)?;
+    let round = resolve_round(deps.storage, round)?;
+    let open = CANCELLED.has(deps.storage, round) || is_roots_deadline_missed(deps.storage, &env, round)?;
+    if !open && Some(&info.sender) != cfg.owner.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !is_round_aborted(deps.as_ref(), &env, round)? {
+        return Err(ContractError::RoundNotAborted {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_REFUND_LIMIT).min(MAX_REFUND_LIMIT) as usize;
+    let players: Vec<Addr> = match addresses {
+        Some(addresses) => addresses
+            .iter()
+            .take(limit)
+            .map(|a| validate_address(deps.as_ref(), &cfg.address_prefix, a))
+            .collect::<Result<_, _>>()?,
+        None => BIDS
+            .prefix(round)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .chain(COMMITMENTS.prefix(round).keys(deps.storage, None, None, Order::Ascending))
+            .take(limit)
+            .collect::<StdResult<_>>()?,
+    };
+
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    let mut refunded_bids = 0;
+    for player in players {
+        if let Some(payback) = refund_bid(deps.storage, &cfg, round, &player)? {
+            if !payback.is_zero() {
+                transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, &player, payback)?);
+            }
+            refunded_bids += 1;
+        }
+    }
+
+    // One bounty is paid per call that refunds bids, from the part of the tickets prize
+    // not owed back to the bidders, for example the donations to the prize pool.
+    let mut event = GameEvent::new("refund_bids").add(events::REFUNDED_BIDS, refunded_bids.to_string());
+    if refunded_bids > 0 {
+        let ticket_asset = ticket_asset(&cfg, &ticket_price);
+        let ticket_prize = treasury::balance(deps.storage, round, Bucket::TicketPot, &ticket_asset)?;
+        let bounty = cfg
+            .crank_bounty
+            .unwrap_or_default()
+            .min(ticket_prize.saturating_sub(staked(deps.storage, round)?));
+        if !bounty.is_zero() {
+            treasury::debit(deps.storage, round, Bucket::TicketPot, &ticket_asset, bounty)?;
+            transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, &info.sender, bounty)?);
+            event = event.add(events::BOUNTY, bounty);
+        }
+    }
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Sends back to the sender the ticket of its bid in an aborted round.
+pub fn execute_refund_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    if !is_round_aborted(deps.as_ref(), &env, round)? {
+        return Err(ContractError::RoundNotAborted {});
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let payback = refund_bid(deps.storage, &cfg, round, &info.sender)?
+        .ok_or(ContractError::BidNotPresent {})?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    if !payback.is_zero() {
+        transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, &info.sender, payback)?);
+    }
+
+    let res = GameEvent::new("refund_ticket")
+        .player(info.sender)
+        .add(events::TICKET_PRICE_PAYBACK, payback)
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Removes the bid of the player, if any, and removes its tickets from the ticket prize.
+/// Returns the amount to pay back.
+fn refund_bid(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    round: u64,
+    player: &Addr,
+) -> Result<Option<Uint128>, ContractError> {
+    let payback = match clear_bid(storage, round, player)? {
+        Some(stake) => stake,
+        None => return Ok(None),
+    };
+
+    let ticket_price = TICKET_PRICE.load(storage, round)?;
+    let ticket_asset = ticket_asset(cfg, &ticket_price);
+    treasury::debit(storage, round, Bucket::TicketPot, &ticket_asset, payback)?;
+
+    let notice = Notice::Refunded {
+        round,
+        amount: payback,
+    };
+    push_notice(storage, player, notice)?;
+    Ok(Some(payback))
+}
+
+/// Returns the stakes of the bids of the round, revealed or not, still to be refunded.
+fn staked(storage: &dyn Storage, round: u64) -> StdResult<Uint128> {
+    let bids = BIDS
+        .prefix(round)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, bid)| bid.stake));
+    let commitments = COMMITMENTS
+        .prefix(round)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, commitment)| commitment.stake));
+    bids.chain(commitments).sum()
+}
+
+// ======================================================================================
+// Merkle root and claiming phase
+// ======================================================================================
+/// Registers the airdrop root and the game root at once. Deprecated in favour of
+/// `RegisterAirdropRoot` and `RegisterGameRoot`.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_register_merkle_roots(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root_airdrop: String,
+    total_amount_airdrop: Option<Uint128>,
+    merkle_root_game: String,
+    total_amount_game: Option<Uint128>,
+    expected_recipients: Option<u64>,
+    prize_tokens: Option<Vec<PrizeTokenAmount>>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can load the Merkle root.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let round = resolve_round(deps.storage, round)?;
+
+    let event = register_airdrop_root(
+        deps.storage,
+        &env,
+        &cfg,
+        round,
+        merkle_root_airdrop,
+        total_amount_airdrop,
+        expected_recipients,
+        GameEvent::new("register_merkle_roots"),
+    )?;
+    let (event, mint_msgs) = register_game_root(
+        deps.branch(),
+        &env,
+        &cfg,
+        round,
+        merkle_root_game,
+        total_amount_game,
+        prize_tokens,
+        event,
+    )?;
+    let fund_msgs = fund_prizes(deps.as_ref(), &env, &cfg, round)?;
+
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(fund_msgs).add_messages(mint_msgs));
+    Ok(res)
+}
+
+/// Registers the Merkle root of the airdrop, which can be known before the game.
+pub fn execute_register_airdrop_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root_airdrop: String,
+    total_amount_airdrop: Option<Uint128>,
+    expected_recipients: Option<u64>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can load the Merkle root.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let round = resolve_round(deps.storage, round)?;
+
+    let event = register_airdrop_root(
+        deps.storage,
+        &env,
+        &cfg,
+        round,
+        merkle_root_airdrop,
+        total_amount_airdrop,
+        expected_recipients,
+        GameEvent::new("register_airdrop_root"),
+    )?;
+    let fund_msgs = fund_prizes(deps.as_ref(), &env, &cfg, round)?;
+    Ok(event.round(round).apply(Response::new().add_messages(fund_msgs)))
+}
+
+/// Registers the Merkle root of the game result, once the bids of the round are final.
+pub fn execute_register_game_root(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root_game: String,
+    total_amount_game: Option<Uint128>,
+    prize_tokens: Option<Vec<PrizeTokenAmount>>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can load the Merkle root.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let round = resolve_round(deps.storage, round)?;
+
+    check_bids_final(deps.storage, &env, round)?;
+
+    let (event, mint_msgs) = register_game_root(
+        deps.branch(),
+        &env,
+        &cfg,
+        round,
+        merkle_root_game,
+        total_amount_game,
+        prize_tokens,
+        GameEvent::new("register_game_root"),
+    )?;
+    let fund_msgs = fund_prizes(deps.as_ref(), &env, &cfg, round)?;
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(fund_msgs).add_messages(mint_msgs));
+    Ok(res)
+}
+
+/// Sets the winning bin of the round, in place of the game root, once the bids of the
+/// round are final, earmarking the prize of the winners as `RegisterGameRoot`.
+pub fn execute_set_winning_bin(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bin: u8,
+    total_amount_game: Option<Uint128>,
+    prize_tokens: Option<Vec<PrizeTokenAmount>>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner or the attestor can set the winning bin.
+    let cfg = CONFIG.load(deps.storage)?;
+    if Some(&info.sender) != cfg.owner.as_ref() && Some(&info.sender) != cfg.attestor.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if cfg.resolution != (GameResolution::OwnerSetsBin {}) {
+        return Err(resolution_not_allowed(&cfg.resolution));
+    }
+    let round = resolve_round(deps.storage, round)?;
+    check_bids_final(deps.storage, &env, round)?;
+    if MERKLE_ROOT_GAME.has(deps.storage, round) || WINNING_BIN.has(deps.storage, round) {
+        return Err(ContractError::GameAlreadyResolved {});
+    }
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_roots_deadline(deps.storage, &env, round)?;
+    let bins = BINS.load(deps.storage, round)?;
+    if bin > bins {
+        return Err(ContractError::BinDoesNotExist { bins });
+    }
+
+    WINNING_BIN.save(deps.storage, round, &bin)?;
+    let event = GameEvent::new("set_winning_bin").add(events::WINNING_BIN, bin.to_string());
+    let (event, mint_msgs) =
+        earmark_game_prize(deps.branch(), &env, &cfg, round, total_amount_game, prize_tokens, event)?;
+    let fund_msgs = fund_prizes(deps.as_ref(), &env, &cfg, round)?;
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(fund_msgs).add_messages(mint_msgs));
+    Ok(res)
+}
+
+/// Sets the winning bin of the round from the price of the asset observed on the price
+/// oracle, once the bids of the round are final, earmarking the configured prize of the
+/// winners. Anyone can resolve the round, until the claim airdrop stage starts.
+pub fn execute_resolve_game(mut deps: DepsMut, env: Env, round: Option<u64>) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let price_oracle = match &cfg.resolution {
+        GameResolution::Oracle(price_oracle) => price_oracle.clone(),
+        resolution => return Err(resolution_not_allowed(resolution)),
+    };
+    let round = resolve_round(deps.storage, round)?;
+    check_bids_final(deps.storage, &env, round)?;
+    if WINNING_BIN.has(deps.storage, round) {
+        return Err(ContractError::GameAlreadyResolved {});
+    }
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_roots_deadline(deps.storage, &env, round)?;
+
+    let price = oracle::query_price(&deps.querier, &price_oracle.oracle, &price_oracle.asset)?;
+    let bins = BINS.load(deps.storage, round)?;
+    let bin = oracle::price_bin(price, price_oracle.min_price, price_oracle.bin_width, bins);
+
+    WINNING_BIN.save(deps.storage, round, &bin)?;
+    let event = GameEvent::new("resolve_game")
+        .add(events::PRICE, price.to_string())
+        .add(events::WINNING_BIN, bin.to_string());
+    let (event, mint_msgs) = earmark_game_prize(
+        deps.branch(),
+        &env,
+        &cfg,
+        round,
+        price_oracle.total_amount_game,
+        None,
+        event,
+    )?;
+    let fund_msgs = fund_prizes(deps.as_ref(), &env, &cfg, round)?;
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(fund_msgs).add_messages(mint_msgs));
+    Ok(res)
+}
+
+/// Requests to the nois-proxy the randomness drawing the winning bin of the round, once
+/// the bids of the round are final, forwarding the funds sent as the fee of the proxy.
+/// Anyone can request it, once per round.
+pub fn execute_request_randomness(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let raffle = match cfg.resolution {
+        GameResolution::Randomness(raffle) => raffle,
+        resolution => return Err(resolution_not_allowed(&resolution)),
+    };
+    let round = resolve_round(deps.storage, round)?;
+    check_bids_final(deps.storage, &env, round)?;
+    if RANDOMNESS_JOB.has(deps.storage, round) {
+        return Err(ContractError::RandomnessAlreadyRequested {});
+    }
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_roots_deadline(deps.storage, &env, round)?;
+
+    let job_id = nois::job_id(round);
+    RANDOMNESS_JOB.save(deps.storage, round, &job_id)?;
+    PENDING_JOBS.save(deps.storage, &job_id, &round)?;
+    let request_msg = nois::randomness_request_msg(&raffle.nois_proxy, job_id.clone(), info.funds)?;
+    let res = GameEvent::new("request_randomness")
+        .player(&info.sender)
+        .add(events::JOB_ID, job_id)
+        .round(round)
+        .apply(Response::new().add_message(request_msg));
+    Ok(res)
+}
+
+/// Draws the winning bin of the round from the randomness delivered by the nois-proxy,
+/// earmarking the configured prize of the winners, as long as the claim airdrop stage
+/// has not started.
+pub fn execute_nois_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    callback: NoisCallback,
+) -> Result<Response, ContractError> {
+    // Just the nois-proxy can deliver the randomness.
+    let cfg = CONFIG.load(deps.storage)?;
+    let raffle = match &cfg.resolution {
+        GameResolution::Randomness(raffle) => raffle.clone(),
+        resolution => return Err(resolution_not_allowed(resolution)),
+    };
+    if info.sender != raffle.nois_proxy {
+        return Err(ContractError::Unauthorized {});
+    }
+    let round = PENDING_JOBS
+        .may_load(deps.storage, &callback.job_id)?
+        .ok_or_else(|| ContractError::UnknownRandomnessJob { job_id: callback.job_id.clone() })?;
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_roots_deadline(deps.storage, &env, round)?;
+
+    let bins = BINS.load(deps.storage, round)?;
+    let bin = nois::random_bin(&callback.randomness, bins)?;
+    PENDING_JOBS.remove(deps.storage, &callback.job_id);
+    WINNING_BIN.save(deps.storage, round, &bin)?;
+    let event = GameEvent::new("nois_receive")
+        .add(events::JOB_ID, callback.job_id)
+        .add(events::RANDOMNESS, callback.randomness)
+        .add(events::WINNING_BIN, bin.to_string());
+    let (event, mint_msgs) = earmark_game_prize(deps, &env, &cfg, round, raffle.total_amount_game, None, event)?;
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(mint_msgs));
+    Ok(res)
+}
+
+/// Replaces the registered Merkle roots set, as long as the claim airdrop stage, where
+/// both roots are verified, has not started and nobody has claimed.
+pub fn execute_rotate_merkle_roots(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root_airdrop: Option<String>,
+    merkle_root_game: Option<String>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can rotate the Merkle roots.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let round = resolve_round(deps.storage, round)?;
+
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    if stage_claim_airdrop.start.is_triggered(&env.block)
+        || AIRDROP_CLAIMS.may_load(deps.storage, round)?.unwrap_or_default() > 0
+    {
+        return Err(ContractError::RootRotationClosed {});
+    }
+
+    let mut event = GameEvent::new("rotate_merkle_roots");
+    if let Some(merkle_root_airdrop) = merkle_root_airdrop {
+        let old_merkle_root_airdrop = MERKLE_ROOT_AIRDROP
+            .may_load(deps.storage, round)?
+            .ok_or(ContractError::MerkleRootNotRegistered { merkle_root: "airdrop".to_string() })?;
+        let mut root_buf: [u8; 32] = [0; 32];
+        hex::decode_to_slice(&merkle_root_airdrop, &mut root_buf)?;
+        MERKLE_ROOT_AIRDROP.save(deps.storage, round, &merkle_root_airdrop)?;
+        event = event
+            .add(events::OLD_MERKLE_ROOT_AIRDROP, old_merkle_root_airdrop)
+            .add(events::MERKLE_ROOT_AIRDROP, merkle_root_airdrop);
+    }
+    if let Some(merkle_root_game) = merkle_root_game {
+        let old_merkle_root_game = MERKLE_ROOT_GAME
+            .may_load(deps.storage, round)?
+            .ok_or(ContractError::MerkleRootNotRegistered { merkle_root: "game".to_string() })?;
+        let mut root_buf: [u8; 32] = [0; 32];
+        hex::decode_to_slice(&merkle_root_game, &mut root_buf)?;
+        MERKLE_ROOT_GAME.save(deps.storage, round, &merkle_root_game)?;
+        event = event
+            .add(events::OLD_MERKLE_ROOT_GAME, old_merkle_root_game)
+            .add(events::MERKLE_ROOT_GAME, merkle_root_game);
+    }
+
+    Ok(event.round(round).apply(Response::new()))
+}
+
+/// Saves the airdrop root of the round and earmarks the airdrop amount, once per round,
+/// before the claim airdrop stage starts.
+#[allow(clippy::too_many_arguments)]
+fn register_airdrop_root(
+    storage: &mut dyn Storage,
+    env: &Env,
+    cfg: &Config,
+    round: u64,
+    merkle_root_airdrop: String,
+    total_amount_airdrop: Option<Uint128>,
+    expected_recipients: Option<u64>,
+    event: GameEvent,
+) -> Result<GameEvent, ContractError> {
+    // The airdrop amounts are earmarked once per round.
+    if MERKLE_ROOT_AIRDROP.has(storage, round) {
+        return Err(ContractError::MerkleRootsAlreadyRegistered {});
+    }
+    if CANCELLED.has(storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_roots_deadline(storage, env, round)?;
+
+    // Check merkle root airdrop length.
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root_airdrop, &mut root_buf)?;
+
+    // Save total amount of tokens to be airdropped.
+    let amount_airdrop = total_amount_airdrop.unwrap_or_else(Uint128::zero);
+    treasury::credit(storage, round, Bucket::AirdropReserve, cfg.cw20_token_address.as_str(), amount_airdrop)?;
+
+    MERKLE_ROOT_AIRDROP.save(storage, round, &merkle_root_airdrop)?;
+    TOTAL_AIRDROP_AMOUNT.save(storage, round, &amount_airdrop)?;
+    EXPECTED_RECIPIENTS.save(storage, round, &expected_recipients.unwrap_or_default())?;
+    AIRDROP_CLAIMS.save(storage, round, &0)?;
+
+    Ok(event
+        .add(events::MERKLE_ROOT_AIRDROP, merkle_root_airdrop)
+        .add(events::TOTAL_AMOUNT_AIRDROP, amount_airdrop))
+}
+
+/// Saves the game root of the round and earmarks the prize of the winners, once per
+/// round, before the claim airdrop stage starts.
+#[allow(clippy::too_many_arguments)]
+fn register_game_root(
+    deps: DepsMut,
+    env: &Env,
+    cfg: &Config,
+    round: u64,
+    merkle_root_game: String,
+    total_amount_game: Option<Uint128>,
+    prize_tokens: Option<Vec<PrizeTokenAmount>>,
+    event: GameEvent,
+) -> Result<(GameEvent, Vec<CosmosMsg>), ContractError> {
+    if cfg.resolution != (GameResolution::MerkleRoot {}) {
+        return Err(resolution_not_allowed(&cfg.resolution));
+    }
+    // The prize amounts are earmarked once per round.
+    if MERKLE_ROOT_GAME.has(deps.storage, round) {
+        return Err(ContractError::MerkleRootsAlreadyRegistered {});
+    }
+    if WINNING_BIN.has(deps.storage, round) {
+        return Err(ContractError::GameAlreadyResolved {});
+    }
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_roots_deadline(deps.storage, env, round)?;
+
+    // Check merkle root game length.
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root_game, &mut root_buf)?;
+
+    MERKLE_ROOT_GAME.save(deps.storage, round, &merkle_root_game)?;
+    let event = event.add(events::MERKLE_ROOT_GAME, merkle_root_game);
+    earmark_game_prize(deps, env, cfg, round, total_amount_game, prize_tokens, event)
+}
+
+/// Fails unless the contract holds the airdrop and the prizes earmarked for the round,
+/// so that the players can rely on the registered amounts. The cw20 tokens missing are
+/// pulled from the owner, up to the allowance granted to the contract, with the returned
+/// messages, so that the funding is atomic with the registration. The prize denom minted
+/// by the contract is funded by the mint.
+fn fund_prizes(deps: Deps, env: &Env, cfg: &Config, round: u64) -> Result<Vec<CosmosMsg>, ContractError> {
+    let mut required: Vec<(String, Uint128)> = vec![];
+    for (bucket, asset, amount) in treasury::balances(deps.storage, round)? {
+        let minted = cfg.mint_prize && cfg.prize_denom.as_ref() == Some(&asset);
+        if (bucket != Bucket::AirdropReserve && bucket != Bucket::GameReserve) || minted {
+            continue;
+        }
+        match required.iter_mut().find(|(required_asset, _)| *required_asset == asset) {
+            Some((_, required_amount)) => *required_amount += amount,
+            None => required.push((asset, amount)),
+        }
+    }
+
+    let mut fund_msgs = vec![];
+    for (asset, required) in required {
+        if cfg.prize_denom.as_ref() == Some(&asset) {
+            let balance = deps.querier.query_balance(&env.contract.address, &asset)?.amount;
+            if balance < required {
+                return Err(ContractError::PrizesNotFunded { asset, balance, required });
+            }
+            continue;
+        }
+        let balance: BalanceResponse = deps.querier.query_wasm_smart(
+            &asset,
+            &Cw20QueryMsg::Balance { address: env.contract.address.to_string() },
+        )?;
+        if balance.balance >= required {
+            continue;
+        }
+        // The missing tokens are pulled from the owner, if its allowance covers them.
+        let missing = required - balance.balance;
+        let owner = match &cfg.owner {
+            Some(owner) => owner,
+            None => return Err(ContractError::PrizesNotFunded { asset, balance: balance.balance, required }),
+        };
+        let allowance: AllowanceResponse = deps.querier.query_wasm_smart(
+            &asset,
+            &Cw20QueryMsg::Allowance { owner: owner.to_string(), spender: env.contract.address.to_string() },
+        )?;
+        if allowance.expires.is_expired(&env.block) || allowance.allowance < missing {
+            return Err(ContractError::PrizesNotFunded { asset, balance: balance.balance, required });
+        }
+        let token_addr = deps.api.addr_validate(&asset)?;
+        fund_msgs.push(get_cw20_transfer_from_msg(owner, &env.contract.address, &token_addr, missing)?);
+    }
+    Ok(fund_msgs)
+}
+
+/// Earmarks the prize of the winners of the round, once its result is set. Returns the
+/// messages minting the prize denom created by the contract, if any.
+fn earmark_game_prize(
+    deps: DepsMut,
+    env: &Env,
+    cfg: &Config,
+    round: u64,
+    total_amount_game: Option<Uint128>,
+    prize_tokens: Option<Vec<PrizeTokenAmount>>,
+    event: GameEvent,
+) -> Result<(GameEvent, Vec<CosmosMsg>), ContractError> {
+    // Save total amount of token to be airdropped to game winners.
+    let amount_game = total_amount_game.unwrap_or_else(Uint128::zero);
+
+    // The prize of the winners includes the tokens carried over from the previous games.
+    // A prize denom created by the contract is minted for the new amount.
+    let prize_asset = prize_asset(cfg);
+    let mut mint_msgs: Vec<CosmosMsg> = vec![];
+    let minted = amount_game;
+    if cfg.mint_prize && !minted.is_zero() {
+        let coin = Coin { denom: prize_asset.clone(), amount: minted };
+        mint_msgs.push(tokenfactory::mint_msg(&env.contract.address, coin)?);
+    }
+    treasury::credit(deps.storage, round, Bucket::GameReserve, &prize_asset, amount_game)?;
+    let amount_game = treasury::balance(deps.storage, round, Bucket::GameReserve, &prize_asset)?;
+
+    // The other tokens of the prize basket are earmarked as well, each one once.
+    let mut tokens: Vec<PrizeToken> = vec![];
+    for prize_token in prize_tokens.unwrap_or_default() {
+        let token = validate_address(deps.as_ref(), &cfg.address_prefix, &prize_token.token)?;
+        if token == cfg.cw20_token_address || tokens.iter().any(|t| t.token == token) {
+            return Err(ContractError::InvalidPrizeTokens {});
+        }
+        treasury::credit(deps.storage, round, Bucket::GameReserve, token.as_str(), prize_token.amount)?;
+        tokens.push(PrizeToken {
+            token,
+            amount: prize_token.amount,
+            claimed: Uint128::zero(),
+        });
+    }
+
+    TOTAL_AIRDROP_GAME_AMOUNT.save(deps.storage, round, &amount_game)?;
+    PRIZE_CLAIMS.save(deps.storage, round, &Uint128::zero())?;
+
+    let mut event = event;
+    if !tokens.is_empty() {
+        let amounts: Vec<String> = tokens.iter().map(|t| format!("{}{}", t.amount, t.token)).collect();
+        event = event.add(events::PRIZE_TOKENS, amounts.join(","));
+    }
+    if !mint_msgs.is_empty() {
+        event = event.add(events::MINTED, format!("{}{}", minted, prize_asset));
+    }
+    PRIZE_TOKENS.save(deps.storage, round, &tokens)?;
+    Ok((event, mint_msgs))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_claim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    proof_airdrop: Vec<String>,
+    proof_game: Vec<String>,
+    recipient: Option<String>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let recipient = claim_recipient(deps.as_ref(), &cfg, &info.sender, recipient)?;
+    claim_airdrop(deps, env, info, amount, proof_airdrop, proof_game, recipient, None, round)
+}
+
+/// Claims the airdrop sending it to the contract, with the message it is handled with.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_claim_airdrop_to(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    msg: Binary,
+    amount: Uint128,
+    proof_airdrop: Vec<String>,
+    proof_game: Vec<String>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let contract = validate_address(deps.as_ref(), &cfg.address_prefix, &contract)?;
+    claim_airdrop(deps, env, info, amount, proof_airdrop, proof_game, contract, Some(msg), round)
+}
+
+/// Claims the airdrop of the claimer, relayed by the sender, checking the claimer signed
+/// the claim authorization.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_claim_airdrop_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    claimer: String,
+    amount: Uint128,
+    proof_airdrop: Vec<String>,
+    proof_game: Vec<String>,
+    signature: Binary,
+    pubkey: Binary,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let claimer = validate_address(deps.as_ref(), &cfg.address_prefix, &claimer)?;
+    let round = resolve_round(deps.storage, round)?;
+
+    let data = claim_authorization(&env.contract.address, round, amount, &proof_airdrop, &proof_game);
+    verify_adr36_signature(deps.as_ref(), &claimer, data.as_bytes(), &signature, &pubkey)?;
+
+    let relayer = info.sender;
+    let info = MessageInfo {
+        sender: claimer.clone(),
+        funds: vec![],
+    };
+    let res = claim_airdrop(deps, env, info, amount, proof_airdrop, proof_game, claimer, None, Some(round))?;
+    Ok(res.add_attribute(events::RELAYER, relayer))
+}
+
+/// Returns the text the claimer signs to authorize the claim of the airdrop amount of
+/// the round by a relayer. The proofs are bound by their hash, the hex encoded SHA-256
+/// hash of the airdrop proof hashes joined by `,`, then `|`, then the game proof hashes
+/// joined by `,`, so that the relayer cannot claim with other proofs, such as an empty
+/// game proof dropping the prize of a winner.
+pub fn claim_authorization(
+    contract: &Addr,
+    round: u64,
+    amount: Uint128,
+    proof_airdrop: &[String],
+    proof_game: &[String],
+) -> String {
+    let proofs = format!("{}|{}", proof_airdrop.join(","), proof_game.join(","));
+    format!(
+        "Claim {} of the airdrop of round {} from {} with the proofs {}",
+        amount,
+        round,
+        contract,
+        hex::encode(sha2::Sha256::digest(proofs.as_bytes()))
+    )
+}
+
+/// Appends a part of the proofs of the airdrop claim of the sender to the parts already
+/// registered, each part being within the claim size limit.
+pub fn execute_register_claim_proofs(
+    deps: DepsMut,
+    info: MessageInfo,
+    proof_airdrop: Vec<String>,
+    proof_game: Vec<String>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let cfg = CONFIG.load(deps.storage)?;
+    check_claim_size(&cfg, &proof_airdrop, &proof_game)?;
+    if CLAIM_AIRDROP.has(deps.storage, (round, &info.sender)) {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+
+    let mut claim_proofs = CLAIM_PROOFS
+        .may_load(deps.storage, (round, &info.sender))?
+        .unwrap_or_default();
+    claim_proofs.proof_airdrop.extend(proof_airdrop);
+    claim_proofs.proof_game.extend(proof_game);
+    CLAIM_PROOFS.save(deps.storage, (round, &info.sender), &claim_proofs)?;
+
+    let res = GameEvent::new("register_claim_proofs")
+        .player(info.sender)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Removes the proofs registered by the sender, for example to register them again.
+pub fn execute_clear_claim_proofs(
+    deps: DepsMut,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    CLAIM_PROOFS.remove(deps.storage, (round, &info.sender));
+
+    let res = GameEvent::new("clear_claim_proofs")
+        .player(info.sender)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Rejects the proofs too large to be handled by the wallets.
+fn check_claim_size(cfg: &Config, proof_airdrop: &[String], proof_game: &[String]) -> Result<(), ContractError> {
+    let size = (to_vec(proof_airdrop)?.len() + to_vec(proof_game)?.len()) as u64;
+    if size > cfg.max_claim_size as u64 {
+        return Err(ContractError::ClaimTooLarge {
+            size,
+            limit: cfg.max_claim_size,
+        });
+    }
+    Ok(())
+}
+
+/// Verifies the signature of the data by the signer, made over the ADR-36 sign doc the
+/// wallets use to sign arbitrary data, and that the public key is the one of the signer.
+fn verify_adr36_signature(
+    deps: Deps,
+    signer: &Addr,
+    data: &[u8],
+    signature: &Binary,
+    pubkey: &Binary,
+) -> Result<(), ContractError> {
+    let invalid = || ContractError::InvalidClaimSignature {
+        claimer: signer.to_string(),
+    };
+
+    let (_, signer_data, _) = bech32::decode(signer.as_str()).map_err(|_| invalid())?;
+    let signer_hash = Vec::<u8>::from_base32(&signer_data).map_err(|_| invalid())?;
+    let pubkey_hash = ripemd160::Ripemd160::digest(&sha2::Sha256::digest(pubkey.as_slice()));
+    if signer_hash != pubkey_hash.as_slice() {
+        return Err(invalid());
+    }
+
+    let sign_doc = format!(
+        r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+        Binary::from(data).to_base64(),
+        signer
+    );
+    let hash = sha2::Sha256::digest(sign_doc.as_bytes());
+    if !deps.api.secp256k1_verify(&hash, signature, pubkey).unwrap_or(false) {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Verifies the airdrop claim of the sender and sends the airdrop to the recipient:
+/// with a cw20 `Send` carrying the message, if any, otherwise with a plain transfer.
+#[allow(clippy::too_many_arguments)]
+fn claim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    proof_airdrop: Vec<String>,
+    proof_game: Vec<String>,
+    recipient: Addr,
+    send_msg: Option<Binary>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+
+    // Reject proofs too large to be handled by the wallets. Without proofs, the claim is
+    // verified with the proofs registered in parts by the sender, if any.
+    let cfg = CONFIG.load(deps.storage)?;
+    check_claim_size(&cfg, &proof_airdrop, &proof_game)?;
+    let registered = CLAIM_PROOFS.may_load(deps.storage, (round, &info.sender))?;
+    CLAIM_PROOFS.remove(deps.storage, (round, &info.sender));
+    let (proof_airdrop, proof_game) = match registered {
+        Some(registered) if proof_airdrop.is_empty() && proof_game.is_empty() => {
+            (registered.proof_airdrop, registered.proof_game)
+        }
+        _ => (proof_airdrop, proof_game),
+    };
+
+    // Check that the correct stage is active.
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let stage_name = String::from("claim airdrop");
+    check_if_valid_stage(env, stage_claim_airdrop, stage_name)?;
+    check_participants(deps.storage, round)?;
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    check_not_blocked(deps.storage, &[&info.sender, &recipient])?;
+
+    // Verify that the user has not already made the claim.
+    let claimed = CLAIM_AIRDROP.may_load(deps.storage, (round, &info.sender))?;
+    if claimed.is_some() {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+
+    check_address_prefix(&cfg.address_prefix, info.sender.as_str())?;
+    let merkle_root_airdrop = MERKLE_ROOT_AIRDROP.load(deps.storage, round)?;
+
+    // Compare proofs: the proof sent by the user must be the same of the one
+    // produced with info.sender address.
+    let user_input = format!("{}{}", info.sender, amount);
+    if !merkle_root_matches(cfg.hash_algorithm, &user_input, proof_airdrop, &merkle_root_airdrop)? {
+        return Err(ContractError::VerificationFailed { merkle_root: "airdrop".to_string() });
+    }
+
+    // The claims never exceed the registered total, even if the tree allows more.
+    let total = TOTAL_AIRDROP_AMOUNT.load(deps.storage, round)?;
+    let claimed = treasury::paid(deps.storage, round, Bucket::AirdropReserve, cfg.cw20_token_address.as_str())?;
+    if claimed.checked_add(amount).map_or(true, |claimed| claimed > total) {
+        return Err(ContractError::AirdropTotalExceeded { total, claimed, amount });
+    }
+
+    // If the sender has an active bid, check if it wins or not. A bid placed on several
+    // bins wins if one of its bins wins. Unless the game is resolved with the game root,
+    // the bins are checked against the winning bin set on chain and the game proof is
+    // ignored.
+    let sender_bins = bid_bins(deps.storage, round, &info.sender)?;
+    let mut winning_bin = None;
+    let mut adjacent = false;
+    match cfg.resolution {
+        GameResolution::OwnerSetsBin {} | GameResolution::Oracle(_) | GameResolution::Randomness(_) => {
+            let bin = WINNING_BIN.load(deps.storage, round)?;
+            winning_bin = sender_bins.iter().copied().find(|sender_bin| *sender_bin == bin);
+            adjacent = sender_bins.iter().any(|sender_bin| sender_bin.abs_diff(bin) == 1);
+        }
+        GameResolution::MerkleRoot {} => {
+            let merkle_root_game = MERKLE_ROOT_GAME.load(deps.storage, round)?;
+            for sender_bin in sender_bins {
+                // The proof is computed by using as a leaf the value bidded by the sender.
+                let user_input = format!("{}{}", info.sender, sender_bin);
+                if merkle_root_matches(cfg.hash_algorithm, &user_input, proof_game.clone(), &merkle_root_game)? {
+                    winning_bin = Some(sender_bin);
+                    break;
+                }
+            }
+        }
+    }
+
+    // If the two root are equal:
+    // - Save the sender as a winner with unclaimed prize.
+    // - Increase the number of winners and their prize share weight.
+    if let Some(winning_bin) = winning_bin {
+        WINNING_BINS.save(deps.storage, (round, &info.sender), &winning_bin)?;
+        let weight = multiplied_weight(deps.storage, round, &info.sender, winning_bin)?;
+        let height = BID_HEIGHTS.load(deps.storage, (round, &info.sender))?;
+        let left_out = add_winner(deps.storage, round, &info.sender, height, weight)?;
+
+        // Keep track of the earliest winning bid. Bids placed at the same height
+        // are ordered by address to keep the choice deterministic.
+        let is_first = match FIRST_WINNER.may_load(deps.storage, round)? {
+            Some(first) => {
+                (height, info.sender.as_str()) < (first.height, first.address.as_str())
+            }
+            None => true,
+        };
+        if is_first {
+            let first_winner = FirstWinner {
+                address: info.sender.clone(),
+                height,
+            };
+            FIRST_WINNER.save(deps.storage, round, &first_winner)?;
+        }
+
+        let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+        let closes_at = (stage_claim_prize.start + stage_claim_prize.duration)?;
+        if left_out.as_ref() != Some(&info.sender) {
+            push_notice(deps.storage, &info.sender, Notice::Won { round, closes_at })?;
+        }
+
+        // The winner left out by the cap gets back the tickets, which are no longer
+        // part of the prize.
+        if let Some(left_out) = left_out {
+            let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+            let consolation = BIDS.load(deps.storage, (round, &left_out))?.stake;
+            CONSOLATIONS.save(deps.storage, (round, &left_out), &false)?;
+            let ticket_asset = ticket_asset(&cfg, &ticket_price);
+            treasury::transfer(
+                deps.storage,
+                round,
+                Bucket::TicketPot,
+                Bucket::Consolations,
+                &ticket_asset,
+                consolation,
+            )?;
+            let notice = Notice::Consolation {
+                round,
+                amount: consolation,
+                closes_at,
+            };
+            push_notice(deps.storage, &left_out, notice)?;
+        }
+    } else if adjacent && cfg.adjacent_tier_bps > 0 {
+        // The bidders on a bin adjacent to the winning bin share the adjacent tier.
+        let weight = bid_weight(deps.storage, round, &info.sender)?;
+        ADJACENT_BIDDERS.save(deps.storage, (round, &info.sender), &false)?;
+        let adjacent_weight = ADJACENT_WEIGHT.may_load(deps.storage, round)?.unwrap_or_default();
+        ADJACENT_WEIGHT.save(deps.storage, round, &(adjacent_weight + weight))?;
+    }
+
+    // Mark the sender as a user that has received the airdrop.
+    CLAIM_AIRDROP.save(deps.storage, (round, &info.sender), &true)?;
+
+    // Increase the number of airdrop claims and the amount of airdropped tokens claimed.
+    AIRDROP_CLAIMS.update(deps.storage, round, |claims| -> StdResult<_> { Ok(claims + 1) })?;
+    treasury::pay(deps.storage, round, Bucket::AirdropReserve, cfg.cw20_token_address.as_str(), amount)?;
+
+    let msg = match send_msg {
+        Some(send_msg) => get_cw20_send_msg(&recipient, &cfg.cw20_token_address, amount, send_msg)?,
+        None => get_cw20_transfer_to_msg(&recipient, &cfg.cw20_token_address, amount)?,
+    };
+    let mut msgs = vec![msg];
+    let mut event = GameEvent::new("claim_airdrop")
+        .player(info.sender.clone())
+        .add(events::AIRDROP_AMOUNT, amount);
+    if recipient != info.sender {
+        event = event.add(events::RECIPIENT, recipient);
+    }
+
+    // Rebate the fees of the claim while the pool lasts.
+    if let Some(rebate) = pay_rebate(deps.storage, round)? {
+        event = event.add(events::REBATE, rebate.amount);
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![rebate],
+        }));
+    }
+
+    let res = event
+        .stage("claim airdrop")
+        .round(round)
+        .apply(Response::new().add_messages(msgs));
+    Ok(res)
+}
+
+pub fn execute_claim_prize(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+    stake: bool,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let cfg = CONFIG.load(deps.storage)?;
+    let recipient = claim_recipient(deps.as_ref(), &cfg, &info.sender, recipient)?;
+    if stake && (cfg.staking_contract.is_none() || cfg.prize_vesting.is_some() || cfg.prize_denom.is_some()) {
+        return Err(ContractError::PrizeNotStakeable {});
+    }
+    if CONSOLATIONS.has(deps.storage, (round, &info.sender)) {
+        return claim_consolation(deps, env, info, &recipient, round);
+    }
+    if ADJACENT_BIDDERS.has(deps.storage, (round, &info.sender)) {
+        return claim_adjacent_prize(deps, env, info, &recipient, round);
+    }
+    let winner = info.sender.clone();
+    let res = settle_prize(
+        deps.branch(),
+        env,
+        info,
+        &recipient,
+        round,
+        0,
+        stake,
+        Uint128::zero(),
+        "claim_prize",
+    )?;
+
+    // The badge goes to the winner, whoever receives the prize.
+    match &cfg.winner_badges {
+        Some(collection) => {
+            let bin = WINNING_BINS.load(deps.storage, (round, &winner))?;
+            Ok(res.add_message(cw721::mint_badge_msg(collection, round, &winner, bin)?))
+        }
+        None => Ok(res),
+    }
+}
+
+/// Claims the airdrop and, if the claim prize stage is open and the sender has a prize
+/// to claim, the prize as well.
+pub fn execute_claim_all(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    proof_airdrop: Vec<String>,
+    proof_game: Vec<String>,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let mut res = execute_claim_airdrop(
+        deps.branch(),
+        env.clone(),
+        info.clone(),
+        amount,
+        proof_airdrop,
+        proof_game,
+        None,
+        Some(round),
+    )?;
+
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_name = String::from("claim prize");
+    let prize_stage_open = check_if_valid_stage(env.clone(), stage_claim_prize, stage_name).is_ok();
+    let prize_unclaimed = CLAIM_PRIZE.may_load(deps.storage, (round, &info.sender))? == Some(false)
+        || CONSOLATIONS.may_load(deps.storage, (round, &info.sender))? == Some(false)
+        || ADJACENT_BIDDERS.may_load(deps.storage, (round, &info.sender))? == Some(false);
+    if prize_stage_open && prize_unclaimed {
+        let prize_res = execute_claim_prize(deps, env, info, None, false, Some(round))?;
+        res = res
+            .add_submessages(prize_res.messages)
+            .add_attributes(prize_res.attributes)
+            .add_events(prize_res.events);
+    }
+    Ok(res)
+}
+
+/// Returns the address receiving the tokens claimed by the sender: the recipient, if
+/// any, or the sender itself.
+fn claim_recipient(
+    deps: Deps,
+    cfg: &Config,
+    sender: &Addr,
+    recipient: Option<String>,
+) -> Result<Addr, ContractError> {
+    match recipient {
+        Some(recipient) => validate_address(deps, &cfg.address_prefix, &recipient),
+        None => Ok(sender.clone()),
+    }
+}
+
+/// Pays back the ticket of a winner left out by the cap on the winners.
+fn claim_consolation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: &Addr,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_name = String::from("claim prize");
+    check_if_valid_stage(env, stage_claim_prize, stage_name)?;
+    check_not_blocked(deps.storage, &[&info.sender, recipient])?;
+
+    if CONSOLATIONS.load(deps.storage, (round, &info.sender))? {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+    CONSOLATIONS.save(deps.storage, (round, &info.sender), &true)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let consolation = BIDS.load(deps.storage, (round, &info.sender))?.stake;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    treasury::pay(deps.storage, round, Bucket::Consolations, &ticket_asset, consolation)?;
+    let msg = get_ticket_transfer_to_msg(&cfg, &ticket_price, recipient, consolation)?;
+
+    let mut event = GameEvent::new("claim_consolation")
+        .player(info.sender.clone())
+        .amount(consolation);
+    if recipient != &info.sender {
+        event = event.add(events::RECIPIENT, recipient);
+    }
+    let res = event
+        .stage("claim prize")
+        .round(round)
+        .apply(Response::new().add_message(msg));
+    Ok(res)
+}
+
+/// Pays the share of the adjacent tier of a bidder on a bin adjacent to the winning bin,
+/// in proportion to its weight. The tier is taken from the tickets prize when the shares
+/// of the winners are fixed, so it is paid only if the round has winners.
+fn claim_adjacent_prize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: &Addr,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_name = String::from("claim prize");
+    check_if_valid_stage(env, stage_claim_prize, stage_name)?;
+    check_not_blocked(deps.storage, &[&info.sender, recipient])?;
+    if WINNERS.load(deps.storage, round)?.is_zero() {
+        return Err(ContractError::NoWinners {});
+    }
+
+    if ADJACENT_BIDDERS.load(deps.storage, (round, &info.sender))? {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+    ADJACENT_BIDDERS.save(deps.storage, (round, &info.sender), &true)?;
+
+    // The shares are fixed once, by `FinalizeGame` or by the first claim.
+    let (protocol_fee, referral_fees, first_bidder_bonus) = if FINALIZED_WINNERS.has(deps.storage, round) {
+        (Uint128::zero(), vec![], None)
+    } else {
+        let (_, protocol_fee, referral_fees, first_bidder_bonus, _, _) = finalize_round(deps.storage, round)?;
+        (protocol_fee, referral_fees, first_bidder_bonus)
+    };
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let adjacent_prize = ADJACENT_PRIZE.load(deps.storage, round)?;
+    let adjacent_weight = ADJACENT_WEIGHT.load(deps.storage, round)?;
+    let weight = bid_weight(deps.storage, round, &info.sender)?;
+    let amount = to_payout(weighted_amount(adjacent_prize, weight, adjacent_weight)?)?;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    treasury::pay(deps.storage, round, Bucket::Consolations, &ticket_asset, amount)?;
+
+    let referral_fee: Uint128 = referral_fees.iter().map(|(_, fee)| fee).sum();
+    let bonus = first_bidder_bonus.as_ref().map(|(_, bonus)| *bonus);
+    let mut msgs = protocol_fee_msgs(&cfg, &ticket_price, protocol_fee, referral_fees, first_bidder_bonus)?;
+    if !amount.is_zero() {
+        msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, recipient, amount)?);
+    }
+    let mut event = GameEvent::new("claim_adjacent_prize")
+        .player(info.sender.clone())
+        .amount(amount);
+    if recipient != &info.sender {
+        event = event.add(events::RECIPIENT, recipient);
+    }
+    if !protocol_fee.is_zero() {
+        event = event.add(events::PROTOCOL_FEE, protocol_fee);
+    }
+    if let Some(bonus) = bonus {
+        event = event.add(events::FIRST_BIDDER_BONUS, bonus);
+    }
+    if !referral_fee.is_zero() {
+        event = event.add(events::REFERRAL_FEE, referral_fee);
+    }
+    let res = event
+        .stage("claim prize")
+        .round(round)
+        .apply(Response::new().add_messages(msgs));
+    Ok(res)
+}
+
+pub fn execute_donate_prize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    share_bps: u16,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    if share_bps == 0 || share_bps > MAX_BPS {
+        return Err(ContractError::InvalidDonationShare {});
+    }
+    let round = resolve_round(deps.storage, round)?;
+    let sender = info.sender.clone();
+    settle_prize(deps, env, info, &sender, round, share_bps, false, Uint128::zero(), "donate_prize")
+}
+
+/// Claims the prize of the previous round, keeping in the contract the part of the
+/// tickets prize paying a ticket on the bin of the current round.
+pub fn execute_claim_prize_and_reenter(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bin: u8,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let next_round = CURRENT_ROUND.load(deps.storage)?;
+    let round = resolve_round(deps.storage, Some(next_round - 1))?;
+
+    // The ticket of the current round has to be paid in the asset of the prize.
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let next_ticket_price = current_ticket_price(deps.storage, &env.block, next_round)?;
+    if ticket_asset(&cfg, &ticket_price) != ticket_asset(&cfg, &next_ticket_price) {
+        return Err(ContractError::InvalidTicketAsset {});
+    }
+
+    let sender = info.sender.clone();
+    let mut res = settle_prize(
+        deps.branch(),
+        env.clone(),
+        info,
+        &sender,
+        round,
+        0,
+        false,
+        next_ticket_price.amount,
+        "claim_prize_and_reenter",
+    )?;
+    let bid_res = place_bid(
+        deps,
+        env,
+        "reenter",
+        next_round,
+        &sender,
+        sender.clone(),
+        vec![bin],
+        1,
+        Payment::Funds(next_ticket_price.amount),
+        0,
+        None,
+    )?;
+    res = res
+        .add_submessages(bid_res.messages)
+        .add_attributes(bid_res.attributes)
+        .add_events(bid_res.events);
+    Ok(res)
+}
+
+/// Fixes the prize of each winner of the round once its claim airdrop stage is over,
+/// paying the crank bounty, if any, to the sender.
+pub fn execute_finalize_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let stage_claim_airdrop_end = (stage_claim_airdrop.start + stage_claim_airdrop.duration)?;
+    if !stage_claim_airdrop_end.is_triggered(&env.block) {
+        return Err(ContractError::ClaimAirdropStageNotFinished {});
+    }
+    if FINALIZED_WINNERS.has(deps.storage, round) {
+        return Err(ContractError::AlreadyFinalized {});
+    }
+    if WINNERS.load(deps.storage, round)?.is_zero() {
+        return Err(ContractError::NoWinners {});
+    }
+
+    // The bounty is taken from the tickets prize before it is shared.
+    let cfg = CONFIG.load(deps.storage)?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    let ticket_prize = treasury::balance(deps.storage, round, Bucket::TicketPot, &ticket_asset)?;
+    let bounty = cfg.crank_bounty.unwrap_or_default().min(ticket_prize);
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    if !bounty.is_zero() {
+        treasury::debit(deps.storage, round, Bucket::TicketPot, &ticket_asset, bounty)?;
+        transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, &info.sender, bounty)?);
+    }
+
+    let (winners, protocol_fee, referral_fees, first_bidder_bonus, ticket_dust, airdrop_dust) =
+        finalize_round(deps.storage, round)?;
+    let referral_fee: Uint128 = referral_fees.iter().map(|(_, fee)| fee).sum();
+    let bonus = first_bidder_bonus.as_ref().map(|(_, bonus)| *bonus);
+    transfer_msgs.extend(protocol_fee_msgs(&cfg, &ticket_price, protocol_fee, referral_fees, first_bidder_bonus)?);
+    let mut event = GameEvent::new("finalize_game")
+        .add(events::WINNERS, winners)
+        .add(events::DUST_FROM_TICKETS, ticket_dust)
+        .add(events::DUST_FROM_AIRDROP, airdrop_dust);
+    if !bounty.is_zero() {
+        event = event.add(events::BOUNTY, bounty);
+    }
+    if !protocol_fee.is_zero() {
+        event = event.add(events::PROTOCOL_FEE, protocol_fee);
+    }
+    if let Some(bonus) = bonus {
+        event = event.add(events::FIRST_BIDDER_BONUS, bonus);
+    }
+    if !referral_fee.is_zero() {
+        event = event.add(events::REFERRAL_FEE, referral_fee);
+    }
+    let adjacent_prize = ADJACENT_PRIZE.load(deps.storage, round)?;
+    if !adjacent_prize.is_zero() {
+        event = event.add(events::ADJACENT_PRIZE, adjacent_prize);
+    }
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Takes the protocol fee and the adjacent tier from the tickets prize, then computes
+/// and saves the prize of each winner of the round, moving the remainders of the rounded down shares of the
+/// tickets prize and of the airdrop to the dust. Returns the number of winners, the
+/// protocol fee, the part of it to be paid to each referrer and the first bidder bonus,
+/// the rest being sent to the fee collector, and the dust of both prizes.
+#[allow(clippy::type_complexity)]
+fn finalize_round(
+    storage: &mut dyn Storage,
+    round: u64,
+) -> Result<(Uint128, Uint128, Vec<(Addr, Uint128)>, Option<(Addr, Uint128)>, Uint128, Uint128), ContractError> {
+    let cfg = CONFIG.load(storage)?;
+    let ticket_price = TICKET_PRICE.load(storage, round)?;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    let ticket_prize = treasury::balance(storage, round, Bucket::TicketPot, &ticket_asset)?;
+    let protocol_fee = to_payout(bps_amount(ticket_prize, cfg.protocol_fee_bps)?)?;
+    if !protocol_fee.is_zero() {
+        treasury::debit(storage, round, Bucket::TicketPot, &ticket_asset, protocol_fee)?;
+    }
+
+    // The bonus for the earliest winning bid is paid from the protocol fee, before the
+    // referral fees, and it cannot exceed the fee itself. It is not paid to a blocked
+    // address.
+    let first_bidder_bonus = match FIRST_WINNER.may_load(storage, round)? {
+        Some(first) if !BLOCKLIST.has(storage, &first.address) => {
+            let bonus = FIRST_BIDDER_BONUS.load(storage, round)?.min(protocol_fee);
+            Some((first.address, bonus)).filter(|(_, bonus)| !bonus.is_zero())
+        }
+        _ => None,
+    };
+    let bonus = first_bidder_bonus.as_ref().map(|(_, bonus)| *bonus).unwrap_or_default();
+    let referral_fees = referral_fees(storage, &cfg, round, protocol_fee - bonus)?;
+
+    // The adjacent tier is taken from the tickets prize before it is shared among the
+    // winners, if any bidder is on a bin adjacent to the winning bin.
+    let mut adjacent_prize = Uint128::zero();
+    if !ADJACENT_WEIGHT.may_load(storage, round)?.unwrap_or_default().is_zero() {
+        let ticket_prize = treasury::balance(storage, round, Bucket::TicketPot, &ticket_asset)?;
+        adjacent_prize = to_payout(bps_amount(ticket_prize, cfg.adjacent_tier_bps)?)?;
+        treasury::transfer(storage, round, Bucket::TicketPot, Bucket::Consolations, &ticket_asset, adjacent_prize)?;
+    }
+    ADJACENT_PRIZE.save(storage, round, &adjacent_prize)?;
+
+    // The sponsor matches are fixed on the tickets prize shared by the winners, as the
+    // ticket pot shrinks with the claims.
+    let ticket_prize = treasury::balance(storage, round, Bucket::TicketPot, &ticket_asset)?;
+    let sponsors: Vec<(Addr, Sponsorship)> = SPONSORSHIPS
+        .prefix(round)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for (sponsor, mut sponsorship) in sponsors {
+        sponsorship.final_match = Some(sponsorship.matched(ticket_prize));
+        SPONSORSHIPS.save(storage, (round, &sponsor), &sponsorship)?;
+    }
+
+    let winners: Vec<Addr> = CLAIM_PRIZE
+        .prefix(round)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let mut paid_ticket_prize = Uint128::zero();
+    let mut paid_airdrop_prize = Uint128::zero();
+    for winner in &winners {
+        let prize_share = compute_prize_share(storage, round, winner)?;
+        paid_ticket_prize += prize_share.ticket_prize;
+        paid_airdrop_prize += prize_share.airdrop_prize;
+        let final_share = FinalShare {
+            ticket_prize: prize_share.ticket_prize,
+            airdrop_prize: prize_share.airdrop_prize,
+        };
+        FINAL_SHARES.save(storage, (round, winner), &final_share)?;
+    }
+    let winners = Uint128::from(winners.len() as u128);
+    FINALIZED_WINNERS.save(storage, round, &winners)?;
+
+    // The shares are rounded down, so they never exceed the prizes escrowed for the
+    // round, whatever the multipliers of the winning bins.
+    let ticket_dust = ticket_prize
+        .checked_sub(paid_ticket_prize)
+        .map_err(|_| ContractError::PayoutsExceedEscrow {})?;
+    let airdrop_dust = TOTAL_AIRDROP_GAME_AMOUNT
+        .load(storage, round)?
+        .checked_sub(paid_airdrop_prize)
+        .map_err(|_| ContractError::PayoutsExceedEscrow {})?;
+    treasury::transfer(storage, round, Bucket::TicketPot, Bucket::Dust, &ticket_asset, ticket_dust)?;
+    treasury::transfer(storage, round, Bucket::GameReserve, Bucket::Dust, &prize_asset(&cfg), airdrop_dust)?;
+    Ok((winners, protocol_fee, referral_fees, first_bidder_bonus, ticket_dust, airdrop_dust))
+}
+
+/// Returns the cut of the protocol fee owed to each referrer: the referral fee of the
+/// protocol fee taken on the stakes of the bids it referred, never exceeding the
+/// protocol fee of the round.
+fn referral_fees(
+    storage: &dyn Storage,
+    cfg: &Config,
+    round: u64,
+    protocol_fee: Uint128,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    let mut referral_fees: Vec<(Addr, Uint128)> = vec![];
+    if protocol_fee.is_zero() || cfg.referral_fee_bps == 0 {
+        return Ok(referral_fees);
+    }
+    let mut fees_left = protocol_fee;
+    for item in REFERRERS.prefix(round).range(storage, None, None, Order::Ascending) {
+        let (player, referrer) = item?;
+        let stake = BIDS.load(storage, (round, &player))?.stake;
+        let stake_fee = to_payout(bps_amount(stake, cfg.protocol_fee_bps)?)?;
+        let fee = to_payout(bps_amount(stake_fee, cfg.referral_fee_bps)?)?.min(fees_left);
+        if fee.is_zero() {
+            continue;
+        }
+        fees_left -= fee;
+        match referral_fees.iter_mut().find(|(address, _)| *address == referrer) {
+            Some((_, referrer_fee)) => *referrer_fee += fee,
+            None => referral_fees.push((referrer, fee)),
+        }
+    }
+    Ok(referral_fees)
+}
+
+/// Returns the messages paying the protocol fee: the first bidder bonus to the earliest
+/// winner, the referral fees to the referrers and the rest to the fee collector, or split
+/// according to the revenue split.
+fn protocol_fee_msgs(
+    cfg: &Config,
+    ticket_price: &Coin,
+    protocol_fee: Uint128,
+    referral_fees: Vec<(Addr, Uint128)>,
+    first_bidder_bonus: Option<(Addr, Uint128)>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut fee_left = protocol_fee;
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if let Some((first_winner, bonus)) = first_bidder_bonus {
+        fee_left -= bonus;
+        msgs.push(get_ticket_transfer_to_msg(cfg, ticket_price, &first_winner, bonus)?);
+    }
+    for (referrer, fee) in referral_fees {
+        fee_left -= fee;
+        msgs.push(get_ticket_transfer_to_msg(cfg, ticket_price, &referrer, fee)?);
+    }
+    for (fee_collector, fee) in split_revenue(cfg, cfg.fee_collector.as_ref(), fee_left) {
+        msgs.push(get_ticket_transfer_to_msg(cfg, ticket_price, &fee_collector, fee)?);
+    }
+    Ok(msgs)
+}
+
+/// Shares the prizes of the winners who did not claim by the claim deadline among the
+/// winners who did, proportionally to their weight, paying them directly. The remainders
+/// of the rounded down shares are moved to the dust. If nobody claimed, the prizes are
+/// moved to the rollover. The matches of the sponsors and the other tokens of the prize
+/// basket are shared in the same way; if nobody claimed, they are left to the sponsors to
+/// reclaim and to the owner with the leftovers. The sender is paid the crank bounty, if
+/// any.
+pub fn execute_redistribute_unclaimed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let claim_deadline = cfg.claim_deadline.ok_or(ContractError::NoClaimDeadline {})?;
+    let round = resolve_round(deps.storage, round)?;
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    if !(stage_claim_prize.start + claim_deadline)?.is_triggered(&env.block) {
+        return Err(ContractError::ClaimDeadlineNotReached {});
+    }
+    if (stage_claim_prize.start + stage_claim_prize.duration)?.is_triggered(&env.block) {
+        return Err(ContractError::StageEnded { stage_name: String::from("claim prize") });
+    }
+    if REDISTRIBUTED.has(deps.storage, round) {
+        return Err(ContractError::AlreadyRedistributed {});
+    }
+    if WINNERS.load(deps.storage, round)?.is_zero() {
+        return Err(ContractError::NoWinners {});
+    }
+    REDISTRIBUTED.save(deps.storage, round, &true)?;
+
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    if !FINALIZED_WINNERS.has(deps.storage, round) {
+        let (_, protocol_fee, referral_fees, first_bidder_bonus, _, _) = finalize_round(deps.storage, round)?;
+        transfer_msgs.extend(protocol_fee_msgs(&cfg, &ticket_price, protocol_fee, referral_fees, first_bidder_bonus)?);
+    }
+
+    // First pass: the shares not claimed and the weight of the winners who claimed. The
+    // shares of the sponsor matches and of the other tokens of the prize basket are in
+    // the order of the sponsorships and of the tokens, the same for every winner.
+    let mut claimers: Vec<(Addr, Uint128)> = vec![];
+    let mut claimers_weight = Uint128::zero();
+    let mut unclaimed_ticket_prize = Uint128::zero();
+    let mut unclaimed_airdrop_prize = Uint128::zero();
+    let mut unclaimed_sponsor_prizes: Vec<(Addr, Addr, Uint128)> = SPONSORSHIPS
+        .prefix(round)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(sponsor, sponsorship)| (sponsor, sponsorship.token, Uint128::zero())))
+        .collect::<StdResult<_>>()?;
+    let mut unclaimed_token_prizes: Vec<(Addr, Uint128)> = PRIZE_TOKENS
+        .may_load(deps.storage, round)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|prize_token| (prize_token.token, Uint128::zero()))
+        .collect();
+    for item in CLAIM_PRIZE.prefix(round).range(deps.storage, None, None, Order::Ascending) {
+        let (winner, claimed) = item?;
+        if claimed {
+            let weight = winner_weight(deps.storage, round, &winner)?;
+            claimers_weight += weight;
+            claimers.push((winner, weight));
+        } else {
+            let prize_share = compute_prize_share(deps.storage, round, &winner)?;
+            unclaimed_ticket_prize += prize_share.ticket_prize;
+            unclaimed_airdrop_prize += prize_share.airdrop_prize;
+            for (unclaimed, (_, _, amount)) in unclaimed_sponsor_prizes.iter_mut().zip(prize_share.sponsor_prizes) {
+                unclaimed.2 += amount;
+            }
+            for (unclaimed, (_, amount)) in unclaimed_token_prizes.iter_mut().zip(prize_share.token_prizes) {
+                unclaimed.1 += amount;
+            }
+        }
+    }
+
+    // The bounty is taken from the tickets prize not claimed before it is shared.
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    let bounty = cfg.crank_bounty.unwrap_or_default().min(unclaimed_ticket_prize);
+    if !bounty.is_zero() {
+        treasury::debit(deps.storage, round, Bucket::TicketPot, &ticket_asset, bounty)?;
+        transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, &info.sender, bounty)?);
+        unclaimed_ticket_prize -= bounty;
+    }
+
+    let prize_asset = prize_asset(&cfg);
+    if claimers.is_empty() {
+        treasury::transfer(deps.storage, round, Bucket::TicketPot, Bucket::Rollover, &ticket_asset, unclaimed_ticket_prize)?;
+        treasury::transfer(deps.storage, round, Bucket::GameReserve, Bucket::Rollover, &prize_asset, unclaimed_airdrop_prize)?;
+        if let Some(grace_period) = cfg.rollover_grace_period {
+            ROLLOVER_EXPIRATION.save(deps.storage, &grace_period.after(&env.block))?;
+        }
+        let mut event = GameEvent::new("redistribute_unclaimed")
+            .add(events::ROLLOVER_FROM_TICKETS, unclaimed_ticket_prize)
+            .add(events::ROLLOVER_FROM_AIRDROP, unclaimed_airdrop_prize);
+        if !bounty.is_zero() {
+            event = event.add(events::BOUNTY, bounty);
+        }
+        let res = event
+            .round(round)
+            .apply(Response::new().add_messages(transfer_msgs));
+        return Ok(res);
+    }
+
+    // Second pass: the shares of the winners who claimed.
+    let mut paid_ticket_prize = Uint128::zero();
+    let mut paid_airdrop_prize = Uint128::zero();
+    let mut paid_sponsor_prizes = vec![Uint128::zero(); unclaimed_sponsor_prizes.len()];
+    let mut paid_token_prizes = vec![Uint128::zero(); unclaimed_token_prizes.len()];
+    for (claimer, weight) in &claimers {
+        let ticket_prize = to_payout(weighted_amount(unclaimed_ticket_prize, *weight, claimers_weight)?)?;
+        let airdrop_prize = to_payout(weighted_amount(unclaimed_airdrop_prize, *weight, claimers_weight)?)?;
+        if !ticket_prize.is_zero() {
+            transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, claimer, ticket_prize)?);
+        }
+        if !airdrop_prize.is_zero() {
+            transfer_msgs.push(get_prize_transfer_to_msg(&cfg, claimer, airdrop_prize)?);
+        }
+        paid_ticket_prize += ticket_prize;
+        paid_airdrop_prize += airdrop_prize;
+
+        for ((_, token, unclaimed), paid) in unclaimed_sponsor_prizes.iter().zip(paid_sponsor_prizes.iter_mut()) {
+            let sponsor_prize = to_payout(weighted_amount(*unclaimed, *weight, claimers_weight)?)?;
+            if !sponsor_prize.is_zero() {
+                transfer_msgs.push(get_cw20_transfer_to_msg(claimer, token, sponsor_prize)?);
+            }
+            *paid += sponsor_prize;
+        }
+        for ((token, unclaimed), paid) in unclaimed_token_prizes.iter().zip(paid_token_prizes.iter_mut()) {
+            let token_prize = to_payout(weighted_amount(*unclaimed, *weight, claimers_weight)?)?;
+            if !token_prize.is_zero() {
+                transfer_msgs.push(get_cw20_transfer_to_msg(claimer, token, token_prize)?);
+            }
+            *paid += token_prize;
+        }
+    }
+    treasury::pay(deps.storage, round, Bucket::TicketPot, &ticket_asset, paid_ticket_prize)?;
+    treasury::pay(deps.storage, round, Bucket::GameReserve, &prize_asset, paid_airdrop_prize)?;
+
+    // The remainders of the sponsor matches are left to the sponsors, and the ones of the
+    // other tokens to the owner with the leftovers.
+    let mut sponsor_prizes: Vec<String> = vec![];
+    for ((sponsor, token, _), paid) in unclaimed_sponsor_prizes.iter().zip(paid_sponsor_prizes) {
+        treasury::pay(deps.storage, round, Bucket::Sponsorships, token.as_str(), paid)?;
+        SPONSORSHIPS.update(deps.storage, (round, sponsor), |sponsorship| -> StdResult<_> {
+            let mut sponsorship = sponsorship.ok_or_else(|| StdError::not_found("sponsorship"))?;
+            sponsorship.claimed += paid;
+            Ok(sponsorship)
+        })?;
+        sponsor_prizes.push(format!("{}{}", paid, token));
+    }
+    let mut prize_tokens = PRIZE_TOKENS.may_load(deps.storage, round)?.unwrap_or_default();
+    let mut token_prizes: Vec<String> = vec![];
+    for ((token, _), paid) in unclaimed_token_prizes.iter().zip(paid_token_prizes) {
+        treasury::pay(deps.storage, round, Bucket::GameReserve, token.as_str(), paid)?;
+        if let Some(prize_token) = prize_tokens.iter_mut().find(|t| &t.token == token) {
+            prize_token.claimed += paid;
+        }
+        token_prizes.push(format!("{}{}", paid, token));
+    }
+    if !prize_tokens.is_empty() {
+        PRIZE_TOKENS.save(deps.storage, round, &prize_tokens)?;
+    }
+
+    let ticket_dust = unclaimed_ticket_prize - paid_ticket_prize;
+    let airdrop_dust = unclaimed_airdrop_prize - paid_airdrop_prize;
+    treasury::transfer(deps.storage, round, Bucket::TicketPot, Bucket::Dust, &ticket_asset, ticket_dust)?;
+    treasury::transfer(deps.storage, round, Bucket::GameReserve, Bucket::Dust, &prize_asset, airdrop_dust)?;
+
+    let mut event = GameEvent::new("redistribute_unclaimed")
+        .add(events::WINNERS, claimers.len().to_string())
+        .add(events::PRIZE_FROM_TICKETS, paid_ticket_prize)
+        .add(events::PRIZE_FROM_AIRDROP, paid_airdrop_prize)
+        .add(events::DUST_FROM_TICKETS, ticket_dust)
+        .add(events::DUST_FROM_AIRDROP, airdrop_dust);
+    if !sponsor_prizes.is_empty() {
+        event = event.add(events::PRIZE_FROM_SPONSORS, sponsor_prizes.join(","));
+    }
+    if !token_prizes.is_empty() {
+        event = event.add(events::PRIZE_FROM_TOKENS, token_prizes.join(","));
+    }
+    if !bounty.is_zero() {
+        event = event.add(events::BOUNTY, bounty);
+    }
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Moves the prizes of a round ended without winners to the rollover.
+pub fn execute_roll_over_jackpot(deps: DepsMut, env: Env, round: Option<u64>) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let (ticket_prize, airdrop_prize) =
+        roll_over_jackpot(deps, &env, round)?.ok_or(ContractError::JackpotNotRollable {})?;
+
+    let res = GameEvent::new("roll_over_jackpot")
+        .add(events::ROLLOVER_FROM_TICKETS, ticket_prize)
+        .add(events::ROLLOVER_FROM_AIRDROP, airdrop_prize)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Moves the ticket pot and the game reserve of the round to the rollover if its claim
+/// airdrop stage ended without winners, returning the amounts moved. Returns None if the
+/// round has winners, is aborted, is still in its claim airdrop stage or has already
+/// been rolled over.
+fn roll_over_jackpot(deps: DepsMut, env: &Env, round: u64) -> StdResult<Option<(Uint128, Uint128)>> {
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let stage_claim_airdrop_end = (stage_claim_airdrop.start + stage_claim_airdrop.duration)?;
+    if !stage_claim_airdrop_end.is_triggered(&env.block)
+        || ROLLED_OVER.has(deps.storage, round)
+        || !WINNERS.load(deps.storage, round)?.is_zero()
+        || is_round_aborted(deps.as_ref(), env, round)?
+    {
+        return Ok(None);
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    let prize_asset = prize_asset(&cfg);
+    let ticket_prize = treasury::drain(deps.storage, round, Bucket::TicketPot, &ticket_asset)?;
+    treasury::credit(deps.storage, round, Bucket::Rollover, &ticket_asset, ticket_prize)?;
+    let airdrop_prize = treasury::drain(deps.storage, round, Bucket::GameReserve, &prize_asset)?;
+    treasury::credit(deps.storage, round, Bucket::Rollover, &prize_asset, airdrop_prize)?;
+    ROLLED_OVER.save(deps.storage, round, &true)?;
+
+    // Without a new round, the rollover can be withdrawn once the grace period expires.
+    if let Some(grace_period) = cfg.rollover_grace_period {
+        ROLLOVER_EXPIRATION.save(deps.storage, &grace_period.after(&env.block))?;
+    }
+    Ok(Some((ticket_prize, airdrop_prize)))
+}
+
+/// Releases the vested part of the prizes of the sender, with one transfer per prize.
+pub fn execute_claim_vested(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut vestings = VESTINGS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    let mut released: Vec<String> = vec![];
+    for vesting in vestings.iter_mut() {
+        let amount = vesting.vested(&env.block) - vesting.released;
+        if amount.is_zero() {
+            continue;
+        }
+        treasury::debit(deps.storage, vesting.round, Bucket::Vesting, &vesting.asset, amount)?;
+        transfer_msgs.push(get_asset_transfer_to_msg(&cfg, &vesting.asset, &info.sender, amount)?);
+        vesting.released += amount;
+        released.push(format!("{}{}", amount, vesting.asset));
+    }
+    if released.is_empty() {
+        return Err(ContractError::NothingVested {});
+    }
+
+    // The prizes fully released are dropped.
+    vestings.retain(|vesting| vesting.released < vesting.amount);
+    if vestings.is_empty() {
+        VESTINGS.remove(deps.storage, &info.sender);
+    } else {
+        VESTINGS.save(deps.storage, &info.sender, &vestings)?;
+    }
+
+    let res = GameEvent::new("claim_vested")
+        .player(info.sender)
+        .add(events::RELEASED, released.join(","))
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Prize due to a winner.
+struct PrizeShare {
+    /// Share of the tickets prize.
+    ticket_prize: Uint128,
+    /// Share of the airdrop reserved to the winners.
+    airdrop_prize: Uint128,
+    /// Share of the match of each sponsorship, as (sponsor, token, amount).
+    sponsor_prizes: Vec<(Addr, Addr, Uint128)>,
+    /// Share of each token of the prize basket, as (token, amount).
+    token_prizes: Vec<(Addr, Uint128)>,
+}
+
+/// Computes the prize due to a winner given the winners verified so far. Once the round
+/// is finalized, the shares of the tickets prize and of the airdrop are the saved ones.
+fn compute_prize_share(storage: &dyn Storage, round: u64, winner: &Addr) -> StdResult<PrizeShare> {
+    // The share of a winner is always taken over a non zero total weight, which includes
+    // its own weight; a zero total is reported as a division by zero, never a panic.
+    let winners_weight = WINNERS_WEIGHT.load(storage, round)?;
+    let winner_weight = winner_weight(storage, round, winner)?;
+    let ticket_prize = ticket_prize(storage, round)?;
+    let airdrop_prize = TOTAL_AIRDROP_GAME_AMOUNT.load(storage, round)?;
+
+    // Every winner will receive two prize: one given by the tickets of the game and
+    // one given by an incentive from the tokens airdrop. For both of them the
+    // amount received is the share of the total given by the weight of the winner,
+    // which is one unless a stake weight has been proven.
+    // Sponsors add to the prize the amount matched for the ticket sales, shared in
+    // the same way.
+    let sponsor_prizes = SPONSORSHIPS
+        .prefix(round)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (sponsor, sponsorship) = item?;
+            let matched = sponsorship.matched(ticket_prize);
+            let amount = to_payout(weighted_amount(matched, winner_weight, winners_weight)?)?;
+            Ok((sponsor, sponsorship.token, amount))
+        })
+        .collect::<StdResult<_>>()?;
+
+    // The other tokens of the prize basket are shared as the airdrop.
+    let token_prizes = PRIZE_TOKENS
+        .may_load(storage, round)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|prize_token| {
+            let amount = to_payout(weighted_amount(prize_token.amount, winner_weight, winners_weight)?)?;
+            Ok((prize_token.token, amount))
+        })
+        .collect::<StdResult<_>>()?;
+
+    if let Some(final_share) = FINAL_SHARES.may_load(storage, (round, winner))? {
+        return Ok(PrizeShare {
+            ticket_prize: final_share.ticket_prize,
+            airdrop_prize: final_share.airdrop_prize,
+            sponsor_prizes,
+            token_prizes,
+        });
+    }
+
+    Ok(PrizeShare {
+        ticket_prize: to_payout(weighted_amount(ticket_prize, winner_weight, winners_weight)?)?,
+        airdrop_prize: to_payout(weighted_amount(airdrop_prize, winner_weight, winners_weight)?)?,
+        sponsor_prizes,
+        token_prizes,
+    })
+}
+
+/// Returns the tickets prize of the round, read from the treasury: the ticket pot and, once
+/// the round is finalized, the prizes paid from it and the dust of the rounded down shares.
+fn ticket_prize(storage: &dyn Storage, round: u64) -> StdResult<Uint128> {
+    let cfg = CONFIG.load(storage)?;
+    let ticket_asset = ticket_asset(&cfg, &TICKET_PRICE.load(storage, round)?);
+    Ok(treasury::balance(storage, round, Bucket::TicketPot, &ticket_asset)?
+        + treasury::paid(storage, round, Bucket::TicketPot, &ticket_asset)?
+        + dust(storage, round, &ticket_asset)?)
+}
+
+/// Returns the dust of an asset left by the rounded down shares of the round, still held
+/// or paid as set by the dust policy.
+fn dust(storage: &dyn Storage, round: u64, asset: &str) -> StdResult<Uint128> {
+    Ok(treasury::balance(storage, round, Bucket::Dust, asset)? + treasury::paid(storage, round, Bucket::Dust, asset)?)
+}
+
+/// Pays the prize of a winner to the recipient. A share of the prize, expressed in basis
+/// points, can be donated: it is sent to the charity address if configured, otherwise it
+/// is left in the contract as leftover for the next game. The share of the airdrop can be
+/// staked for the recipient in the staking contract. The `reentry` part of the tickets
+/// prize is kept in the contract to pay a bid of the next round.
+#[allow(clippy::too_many_arguments)]
+fn settle_prize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: &Addr,
+    round: u64,
+    donation_bps: u16,
+    stake: bool,
+    reentry: Uint128,
+    action: &str,
+) -> Result<Response, ContractError> {
+    let block = env.block.clone();
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_name = String::from("claim prize");
+    check_if_valid_stage(env, stage_claim_prize, stage_name)?;
+    check_not_blocked(deps.storage, &[&info.sender, recipient])?;
+
+    // The winners are final once the claim airdrop stage is over. Without winners there
+    // is no prize to share: the jackpot goes to the rollover with `RollOverJackpot`.
+    if WINNERS.load(deps.storage, round)?.is_zero() {
+        return Err(ContractError::NoWinners {});
+    }
+
+    // The prizes not claimed by the deadline are left to `RedistributeUnclaimed`.
+    let cfg = CONFIG.load(deps.storage)?;
+    if let Some(claim_deadline) = cfg.claim_deadline {
+        let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+        if (stage_claim_prize.start + claim_deadline)?.is_triggered(&block) {
+            return Err(ContractError::ClaimDeadlinePassed {});
+        }
+    }
+
+    // The shares of the winners are fixed once, by `FinalizeGame` or by the first claim.
+    let (protocol_fee, referral_fees, first_bidder_bonus) = if FINALIZED_WINNERS.has(deps.storage, round) {
+        (Uint128::zero(), vec![], None)
+    } else {
+        let (_, protocol_fee, referral_fees, first_bidder_bonus, _, _) = finalize_round(deps.storage, round)?;
+        (protocol_fee, referral_fees, first_bidder_bonus)
+    };
+
+    // Verify that the user has not already made the claim.
+    let claimed = CLAIM_PRIZE.may_load(deps.storage, (round, &info.sender))?;
+    if let Some(already_claimed) = claimed {
+        if already_claimed {
+            return Err(ContractError::AlreadyClaimed {});
+        }
+    } else {
+        return Err(ContractError::NoteEligible {});
+    };
+
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let prize_share = compute_prize_share(deps.storage, round, &info.sender)?;
+    let sender_ticket_prize = prize_share.ticket_prize;
+    let sender_airdrop_prize = prize_share.airdrop_prize;
+
+    // Split the prize between the sender and the donation.
+    let donated_ticket_prize = to_payout(bps_amount(sender_ticket_prize, donation_bps)?)?;
+    let donated_airdrop_prize = to_payout(bps_amount(sender_airdrop_prize, donation_bps)?)?;
+    let paid_ticket_prize = sender_ticket_prize - donated_ticket_prize;
+    let paid_airdrop_prize = sender_airdrop_prize - donated_airdrop_prize;
+    if paid_ticket_prize < reentry {
+        return Err(ContractError::TicketPriceNotPaid {});
+    }
+    let paid_ticket_prize = paid_ticket_prize - reentry;
+
+    // The whole prize leaves the reserves, the donations not sent to the charity are
+    // kept for the next game. Donations leaving the contract are paid as the prize, the
+    // ones kept for the next game are not.
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    let prize_asset = prize_asset(&cfg);
+    let (kept_ticket_prize, kept_airdrop_prize) = match cfg.charity_address {
+        Some(_) => (Uint128::zero(), Uint128::zero()),
+        None => (donated_ticket_prize, donated_airdrop_prize),
+    };
+    treasury::pay(deps.storage, round, Bucket::TicketPot, &ticket_asset, sender_ticket_prize - kept_ticket_prize)?;
+    treasury::pay(deps.storage, round, Bucket::GameReserve, &prize_asset, sender_airdrop_prize - kept_airdrop_prize)?;
+    treasury::transfer(deps.storage, round, Bucket::TicketPot, Bucket::Rollover, &ticket_asset, kept_ticket_prize)?;
+    treasury::transfer(deps.storage, round, Bucket::GameReserve, Bucket::Rollover, &prize_asset, kept_airdrop_prize)?;
+
+    let bonus = first_bidder_bonus.as_ref().map(|(_, bonus)| *bonus);
+    let mut transfer_msgs = protocol_fee_msgs(&cfg, &ticket_price, protocol_fee, referral_fees, first_bidder_bonus)?;
+    if !paid_ticket_prize.is_zero() {
+        transfer_msgs.push(get_ticket_transfer_to_msg(
+            &cfg,
+            &ticket_price,
+            recipient,
+            paid_ticket_prize,
+        )?);
+    }
+    // With a vesting, the share of the airdrop is recorded for the recipient and
+    // released as it vests. Otherwise it can be staked in the staking contract, on
+    // behalf of the recipient.
+    let mut vesting_prize = Uint128::zero();
+    let mut staked_prize = Uint128::zero();
+    if !paid_airdrop_prize.is_zero() {
+        match cfg.prize_vesting {
+            Some(duration) => {
+                vesting_prize = paid_airdrop_prize;
+                treasury::credit(deps.storage, round, Bucket::Vesting, &prize_asset, vesting_prize)?;
+                let start = match duration {
+                    Duration::Height(_) => block.height,
+                    Duration::Time(_) => block.time.seconds(),
+                };
+                let vesting = Vesting {
+                    round,
+                    asset: prize_asset.clone(),
+                    amount: vesting_prize,
+                    released: Uint128::zero(),
+                    start,
+                    duration,
+                };
+                let mut vestings = VESTINGS.may_load(deps.storage, recipient)?.unwrap_or_default();
+                vestings.push(vesting);
+                VESTINGS.save(deps.storage, recipient, &vestings)?;
+            }
+            None => match (&cfg.staking_contract, stake) {
+                (Some(staking_contract), true) => {
+                    let msg = to_binary(&StakingReceiveMsg::StakeFor { address: recipient.to_string() })?;
+                    transfer_msgs.push(get_cw20_send_msg(
+                        staking_contract,
+                        &cfg.cw20_token_address,
+                        paid_airdrop_prize,
+                        msg,
+                    )?);
+                    staked_prize = paid_airdrop_prize;
+                }
+                _ => {
+                    transfer_msgs.push(get_prize_transfer_to_msg(&cfg, recipient, paid_airdrop_prize)?);
+                }
+            },
+        }
+    }
+
+    if let Some(charity) = &cfg.charity_address {
+        if !donated_ticket_prize.is_zero() {
+            transfer_msgs.push(get_ticket_transfer_to_msg(
+                &cfg,
+                &ticket_price,
+                charity,
+                donated_ticket_prize,
+            )?);
+        }
+        if !donated_airdrop_prize.is_zero() {
+            transfer_msgs.push(get_prize_transfer_to_msg(&cfg, charity, donated_airdrop_prize)?);
+        }
+    }
+
+    // The sponsor matches are split as the rest of the prize, but the donations not
+    // leaving the contract are given back to the sponsors instead of the next game.
+    let mut paid_sponsor_prizes: Vec<String> = vec![];
+    let mut donated_sponsor_prizes: Vec<String> = vec![];
+    for (sponsor, token, sponsor_prize) in prize_share.sponsor_prizes {
+        let donated_sponsor_prize = to_payout(bps_amount(sponsor_prize, donation_bps)?)?;
+        let paid_sponsor_prize = sponsor_prize - donated_sponsor_prize;
+        if !paid_sponsor_prize.is_zero() {
+            transfer_msgs.push(get_cw20_transfer_to_msg(recipient, &token, paid_sponsor_prize)?);
+        }
+        let mut claimed_sponsor_prize = paid_sponsor_prize;
+        if let Some(charity) = &cfg.charity_address {
+            if !donated_sponsor_prize.is_zero() {
+                transfer_msgs.push(get_cw20_transfer_to_msg(charity, &token, donated_sponsor_prize)?);
+            }
+            claimed_sponsor_prize = sponsor_prize;
+        }
+        treasury::pay(deps.storage, round, Bucket::Sponsorships, token.as_str(), claimed_sponsor_prize)?;
+        SPONSORSHIPS.update(deps.storage, (round, &sponsor), |sponsorship| -> StdResult<_> {
+            let mut sponsorship = sponsorship.ok_or_else(|| StdError::not_found("sponsorship"))?;
+            sponsorship.claimed += claimed_sponsor_prize;
+            Ok(sponsorship)
+        })?;
+        paid_sponsor_prizes.push(format!("{}{}", paid_sponsor_prize, token));
+        donated_sponsor_prizes.push(format!("{}{}", donated_sponsor_prize, token));
+    }
+
+    // The other tokens of the prize basket are split in the same way, with one transfer
+    // per token. The donations not leaving the contract are left to the owner with the
+    // leftovers.
+    let mut prize_tokens = PRIZE_TOKENS.may_load(deps.storage, round)?.unwrap_or_default();
+    let mut paid_token_prizes: Vec<String> = vec![];
+    let mut donated_token_prizes: Vec<String> = vec![];
+    for (token, token_prize) in prize_share.token_prizes {
+        let donated_token_prize = to_payout(bps_amount(token_prize, donation_bps)?)?;
+        let paid_token_prize = token_prize - donated_token_prize;
+        if !paid_token_prize.is_zero() {
+            transfer_msgs.push(get_cw20_transfer_to_msg(recipient, &token, paid_token_prize)?);
+        }
+        let mut claimed_token_prize = paid_token_prize;
+        if let Some(charity) = &cfg.charity_address {
+            if !donated_token_prize.is_zero() {
+                transfer_msgs.push(get_cw20_transfer_to_msg(charity, &token, donated_token_prize)?);
+            }
+            claimed_token_prize = token_prize;
+        }
+        treasury::pay(deps.storage, round, Bucket::GameReserve, token.as_str(), claimed_token_prize)?;
+        if let Some(prize_token) = prize_tokens.iter_mut().find(|t| t.token == token) {
+            prize_token.claimed += claimed_token_prize;
+        }
+        paid_token_prizes.push(format!("{}{}", paid_token_prize, token));
+        donated_token_prizes.push(format!("{}{}", donated_token_prize, token));
+    }
+    if !prize_tokens.is_empty() {
+        PRIZE_TOKENS.save(deps.storage, round, &prize_tokens)?;
+    }
+
+    CLAIM_PRIZE.update(deps.storage, (round, &info.sender), |mut _already_claimed| -> StdResult<_>{
+        Ok(true)
+    })?;
+
+    let prize_claims = PRIZE_CLAIMS.update(deps.storage, round, |mut claims| -> StdResult<_> {
+        claims += Uint128::new(1);
+        Ok(claims)
+    })?;
+
+    // Once every winner has been paid, what is left of the prizes is the dust of the
+    // rounded down shares, disposed of as set by the dust policy.
+    let mut disposed_dust = None;
+    if prize_claims == WINNERS.load(deps.storage, round)? {
+        let ticket_dust = treasury::drain(deps.storage, round, Bucket::TicketPot, &ticket_asset)?;
+        treasury::credit(deps.storage, round, Bucket::Dust, &ticket_asset, ticket_dust)?;
+        let airdrop_dust = treasury::drain(deps.storage, round, Bucket::GameReserve, &prize_asset)?;
+        treasury::credit(deps.storage, round, Bucket::Dust, &prize_asset, airdrop_dust)?;
+        for prize_token in &prize_tokens {
+            let token_dust = treasury::drain(deps.storage, round, Bucket::GameReserve, prize_token.token.as_str())?;
+            treasury::credit(deps.storage, round, Bucket::Dust, prize_token.token.as_str(), token_dust)?;
+        }
+        if let Some(dust_policy) = cfg.dust_policy {
+            let dust_recipient = match dust_policy {
+                DustPolicy::LastClaimer => Some(recipient.clone()),
+                DustPolicy::Owner => cfg.owner.clone(),
+                DustPolicy::Burn => None,
+            };
+            // Without an owner, the dust is kept in the contract.
+            if dust_recipient.is_some() || dust_policy == DustPolicy::Burn {
+                let ticket_dust = treasury::balance(deps.storage, round, Bucket::Dust, &ticket_asset)?;
+                treasury::pay(deps.storage, round, Bucket::Dust, &ticket_asset, ticket_dust)?;
+                let airdrop_dust = treasury::balance(deps.storage, round, Bucket::Dust, &prize_asset)?;
+                treasury::pay(deps.storage, round, Bucket::Dust, &prize_asset, airdrop_dust)?;
+                for (asset, dust) in [(&ticket_asset, ticket_dust), (&prize_asset, airdrop_dust)] {
+                    if dust.is_zero() {
+                        continue;
+                    }
+                    transfer_msgs.push(match &dust_recipient {
+                        Some(dust_recipient) => get_treasury_transfer_to_msg(&cfg, asset, dust_recipient, dust)?,
+                        None => get_treasury_burn_msg(&cfg, asset, dust)?,
+                    });
+                }
+                disposed_dust = Some((dust_policy, ticket_dust, airdrop_dust));
+            }
+        }
+    }
+
+    // Keep track of the donated amounts.
+    DONATED_PRIZE_AMOUNT.update(deps.storage, round, |mut donated_amount| -> StdResult<_> {
+        donated_amount += donated_ticket_prize;
+        Ok(donated_amount)
+    })?;
+    DONATED_AIRDROP_AMOUNT.update(deps.storage, round, |mut donated_amount| -> StdResult<_> {
+        donated_amount += donated_airdrop_prize;
+        Ok(donated_amount)
+    })?;
+
+    let mut event = GameEvent::new(action)
+        .player(info.sender.clone())
+        .add(events::PRIZE_FROM_TICKETS, paid_ticket_prize)
+        .add(events::PRIZE_FROM_AIRDROP, paid_airdrop_prize);
+    if !paid_sponsor_prizes.is_empty() {
+        event = event.add(events::PRIZE_FROM_SPONSORS, paid_sponsor_prizes.join(","));
+    }
+    if !paid_token_prizes.is_empty() {
+        event = event.add(events::PRIZE_FROM_TOKENS, paid_token_prizes.join(","));
+    }
+    if !vesting_prize.is_zero() {
+        event = event.add(events::VESTING, vesting_prize);
+    }
+    if !staked_prize.is_zero() {
+        event = event.add(events::STAKED, staked_prize);
+    }
+    if !reentry.is_zero() {
+        event = event.add(events::REENTRY, reentry);
+    }
+    if !protocol_fee.is_zero() {
+        event = event.add(events::PROTOCOL_FEE, protocol_fee);
+    }
+    if let Some(bonus) = bonus {
+        event = event.add(events::FIRST_BIDDER_BONUS, bonus);
+    }
+    if recipient != &info.sender {
+        event = event.add(events::RECIPIENT, recipient);
+    }
+    if let Some((dust_policy, ticket_dust, airdrop_dust)) = disposed_dust {
+        event = event
+            .add(events::DUST_POLICY, dust_policy.as_str())
+            .add(events::DUST_FROM_TICKETS, ticket_dust)
+            .add(events::DUST_FROM_AIRDROP, airdrop_dust);
+    }
+    if donation_bps > 0 {
+        event = event
+            .add(events::DONATED_FROM_TICKETS, donated_ticket_prize)
+            .add(events::DONATED_FROM_AIRDROP, donated_airdrop_prize);
+        if !donated_sponsor_prizes.is_empty() {
+            event = event.add(events::DONATED_FROM_SPONSORS, donated_sponsor_prizes.join(","));
+        }
+        if !donated_token_prizes.is_empty() {
+            event = event.add(events::DONATED_FROM_TOKENS, donated_token_prizes.join(","));
+        }
+    }
+    let res = event
+        .stage("claim prize")
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+// ======================================================================================
+// Withdraw of unclaimed tokens
+// ======================================================================================
+pub fn execute_withdraw_airdrop(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: &Addr,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can withdraw the remaining tokens.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = &validate_address(deps.as_ref(), &cfg.address_prefix, address.as_str())?;
+    let round = resolve_round(deps.storage, round)?;
+
+    // Check that the claiming prize stage has ended.
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
+    if !stage_claim_prize_end.is_triggered(&_env.block) {
+        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    }
+
+    // Withdraw what is left of the airdrop reserves, unless burned by `BurnUnclaimed`.
+    // The buckets are emptied, so the leftovers cannot be withdrawn twice.
+    // The prize left is sent along with the airdrop when paid with the same token.
+    let prize_asset = prize_asset(&cfg);
+    let (mut amount, mut prize_left) = if cfg.burn_unclaimed {
+        (Uint128::zero(), Uint128::zero())
+    } else {
+        drain_unclaimed_airdrop(deps.storage, &cfg, round)?
+    };
+    if cfg.prize_denom.is_none() {
+        amount += prize_left;
+        prize_left = Uint128::zero();
+    }
+
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    for (recipient, amount) in split_revenue(&cfg, Some(address), amount) {
+        transfer_msgs.push(get_cw20_transfer_to_msg(&recipient, &cfg.cw20_token_address, amount)?);
+    }
+    for (recipient, prize_left) in split_revenue(&cfg, Some(address), prize_left) {
+        transfer_msgs.push(get_prize_transfer_to_msg(&cfg, &recipient, prize_left)?);
+    }
+
+    // Withdraw what is left of the other tokens of the prize basket.
+    let mut tokens_left: Vec<String> = vec![];
+    for prize_token in PRIZE_TOKENS.may_load(deps.storage, round)?.unwrap_or_default() {
+        let token = prize_token.token.as_str();
+        let token_left = treasury::drain(deps.storage, round, Bucket::GameReserve, token)?
+            + treasury::drain(deps.storage, round, Bucket::Dust, token)?;
+        if !token_left.is_zero() {
+            for (recipient, token_left) in split_revenue(&cfg, Some(address), token_left) {
+                transfer_msgs.push(get_cw20_transfer_to_msg(&recipient, &prize_token.token, token_left)?);
+            }
+            tokens_left.push(format!("{}{}", token_left, token));
+        }
+    }
+
+    // Withdraw what is left of the rebate pool as well.
+    let mut event = GameEvent::new("withdraw_airdrop")
+        .add(events::ADDRESS, address)
+        .amount(amount);
+    if !prize_left.is_zero() {
+        event = event.add(events::PRIZE_DENOM, format!("{}{}", prize_left, prize_asset));
+    }
+    if !tokens_left.is_empty() {
+        event = event.add(events::PRIZE_TOKENS, tokens_left.join(","));
+    }
+    if let Some(rebate) = REBATE.may_load(deps.storage, round)? {
+        let rebates_left = treasury::drain(deps.storage, round, Bucket::Rebates, &rebate.denom)?;
+        if !rebates_left.is_zero() {
+            event = event.add(events::REBATE, rebates_left);
+            for (recipient, rebates_left) in split_revenue(&cfg, Some(address), rebates_left) {
+                transfer_msgs.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: vec![Coin { denom: rebate.denom.clone(), amount: rebates_left }],
+                }));
+            }
+        }
+    }
+
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+
+    Ok(res)
+}
+
+/// Burns what is left of the airdrop and of the airdrop prize of the round once its
+/// claim prize stage has ended, if the game burns the unclaimed tokens.
+pub fn execute_burn_unclaimed(deps: DepsMut, env: Env, round: Option<u64>) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if !cfg.burn_unclaimed {
+        return Err(ContractError::BurnUnclaimedDisabled {});
+    }
+
+    let round = resolve_round(deps.storage, round)?;
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
+    if !stage_claim_prize_end.is_triggered(&env.block) {
+        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    }
+
+    // The buckets are emptied, so the leftovers cannot be burned twice. The prize left
+    // is burned along with the airdrop when paid with the same token.
+    let (mut amount, mut prize_left) = drain_unclaimed_airdrop(deps.storage, &cfg, round)?;
+    let prize_asset = prize_asset(&cfg);
+    if cfg.prize_denom.is_none() {
+        amount += prize_left;
+        prize_left = Uint128::zero();
+    }
+    let mut burn_msgs: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        burn_msgs.push(get_treasury_burn_msg(&cfg, cfg.cw20_token_address.as_str(), amount)?);
+    }
+    let mut event = GameEvent::new("burn_unclaimed").amount(amount);
+    if !prize_left.is_zero() {
+        burn_msgs.push(get_treasury_burn_msg(&cfg, &prize_asset, prize_left)?);
+        event = event.add(events::PRIZE_DENOM, format!("{}{}", prize_left, prize_asset));
+    }
+
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(burn_msgs));
+    Ok(res)
+}
+
+/// Empties the airdrop reserve and what is left of the airdrop prize of the round,
+/// returning the airdrop and the prize left.
+fn drain_unclaimed_airdrop(storage: &mut dyn Storage, cfg: &Config, round: u64) -> StdResult<(Uint128, Uint128)> {
+    let prize_asset = prize_asset(cfg);
+    let amount = treasury::drain(storage, round, Bucket::AirdropReserve, cfg.cw20_token_address.as_str())?;
+    let prize_left = treasury::drain(storage, round, Bucket::GameReserve, &prize_asset)?
+        + treasury::drain(storage, round, Bucket::Dust, &prize_asset)?;
+    Ok((amount, prize_left))
+}
+
+// TODO: si potrebbe unire a quello sopra.
+pub fn execute_withdraw_prize(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: &Addr,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can withdraw the remaining tokens.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = &validate_address(deps.as_ref(), &cfg.address_prefix, address.as_str())?;
+    let round = resolve_round(deps.storage, round)?;
+
+    // Check that the claiming prize stage has ended.
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
+    if !stage_claim_prize_end.is_triggered(&_env.block) {
+        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    }
+
+    // Withdraw what is left of the ticket pot. The buckets are emptied, so the
+    // leftovers cannot be withdrawn twice.
+    let ticket_price = TICKET_PRICE.load(deps.storage, round)?;
+    let ticket_asset = ticket_asset(&cfg, &ticket_price);
+    let amount = treasury::drain(deps.storage, round, Bucket::TicketPot, &ticket_asset)?
+        + treasury::drain(deps.storage, round, Bucket::Consolations, &ticket_asset)?
+        + treasury::drain(deps.storage, round, Bucket::Dust, &ticket_asset)?;
+
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    for (recipient, amount) in split_revenue(&cfg, Some(address), amount) {
+        transfer_msgs.push(get_ticket_transfer_to_msg(&cfg, &ticket_price, &recipient, amount)?);
+    }
+
+    // Withdraw the bid change fees as well.
+    let mut event = GameEvent::new("withdraw_prize")
+        .add(events::ADDRESS, address)
+        .amount(amount);
+    if let Some(fee) = &cfg.bid_change_fee {
+        let fees = treasury::drain(deps.storage, round, Bucket::Fees, &fee.denom)?;
+        if !fees.is_zero() {
+            event = event.add(events::BID_CHANGE_FEE, fees);
+            for (recipient, fees) in split_revenue(&cfg, Some(address), fees) {
+                transfer_msgs.push(get_bank_transfer_to_msg(&recipient, &fee.denom, fees));
+            }
+        }
+    }
+
+    let res = event
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+
+    Ok(res)
+}
+
+/// Withdraws an amount of an asset left in the round once its claim prize stage has
+/// ended, or all of it if no amount is given. The buckets holding the asset are debited
+/// in order; the vestings, the sponsorships and the rollover are never withdrawn, nor
+/// are the leftovers of the airdrop burned by `BurnUnclaimed`.
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: String,
+    amount: Option<Uint128>,
+    recipient: &str,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can withdraw the remaining tokens.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = validate_address(deps.as_ref(), &cfg.address_prefix, recipient)?;
+    let round = resolve_round(deps.storage, round)?;
+
+    // Check that the claiming prize stage has ended.
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
+    if !stage_claim_prize_end.is_triggered(&env.block) {
+        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    }
+
+    let mut buckets = vec![
+        Bucket::TicketPot,
+        Bucket::Consolations,
+        Bucket::Fees,
+        Bucket::Rebates,
+        Bucket::AirdropReserve,
+        Bucket::GameReserve,
+        Bucket::Dust,
+    ];
+    if cfg.burn_unclaimed {
+        if asset == cfg.cw20_token_address.as_str() {
+            buckets.retain(|bucket| *bucket != Bucket::AirdropReserve);
+        }
+        if asset == prize_asset(&cfg) {
+            buckets.retain(|bucket| *bucket != Bucket::GameReserve && *bucket != Bucket::Dust);
+        }
+    }
+    let mut available = Uint128::zero();
+    for bucket in &buckets {
+        available += treasury::balance(deps.storage, round, *bucket, &asset)?;
+    }
+    let amount = amount.unwrap_or(available);
+    if amount > available {
+        return Err(ContractError::WithdrawExceedsLeftovers { asset, available });
+    }
+    let mut left = amount;
+    for bucket in buckets {
+        let debited = treasury::balance(deps.storage, round, bucket, &asset)?.min(left);
+        treasury::debit(deps.storage, round, bucket, &asset, debited)?;
+        left -= debited;
+    }
+
+    // The tokens of the prize basket are cw20 tokens as well.
+    let prize_tokens = PRIZE_TOKENS.may_load(deps.storage, round)?.unwrap_or_default();
+    let cw20 = is_cw20_asset(&cfg, &asset) || prize_tokens.iter().any(|t| t.token == asset);
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    for (recipient, amount) in split_revenue(&cfg, Some(&recipient), amount) {
+        transfer_msgs.push(if cw20 {
+            get_cw20_transfer_to_msg(&recipient, &Addr::unchecked(&asset), amount)?
+        } else {
+            get_bank_transfer_to_msg(&recipient, &asset, amount)
+        });
+    }
+
+    let res = GameEvent::new("withdraw")
+        .add(events::ADDRESS, recipient)
+        .add(events::ASSET, asset)
+        .amount(amount)
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Withdraws the whole rollover once the grace period of the last jackpot rolled over
+/// has expired without a new round started.
+pub fn execute_withdraw_rollover(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: &Addr,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can withdraw the rollover.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.clone().ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = &validate_address(deps.as_ref(), &cfg.address_prefix, address.as_str())?;
+    let expiration = ROLLOVER_EXPIRATION.may_load(deps.storage)?;
+    if !matches!(expiration, Some(expiration) if expiration.is_expired(&env.block)) {
+        return Err(ContractError::RolloverLocked {});
+    }
+    ROLLOVER_EXPIRATION.remove(deps.storage);
+
+    let round = CURRENT_ROUND.load(deps.storage)?;
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    let mut withdrawn: Vec<String> = vec![];
+    for (asset, amount) in treasury::rollover(deps.storage)? {
+        treasury::drain(deps.storage, round, Bucket::Rollover, &asset)?;
+        for (recipient, amount) in split_revenue(&cfg, Some(address), amount) {
+            transfer_msgs.push(get_treasury_transfer_to_msg(&cfg, &asset, &recipient, amount)?);
+        }
+        withdrawn.push(format!("{}{}", amount, asset));
+    }
+
+    let res = GameEvent::new("withdraw_rollover")
+        .add(events::ADDRESS, address)
+        .add(events::ROLLOVER, withdrawn.join(","))
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+/// Adds the sent native token to the rebate pool of the round, setting the rebate paid
+/// with each airdrop claim.
+pub fn execute_fund_rebates(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rebate: Uint128,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Just the contract owner can fund the rebates.
+    let cfg = CONFIG.load(deps.storage)?;
+    let owner = cfg.owner.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let round = resolve_round(deps.storage, round)?;
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let stage_claim_airdrop_end = (stage_claim_airdrop.start + stage_claim_airdrop.duration)?;
+    if stage_claim_airdrop_end.is_triggered(&env.block) {
+        return Err(ContractError::StageEnded { stage_name: String::from("claim airdrop") });
+    }
+
+    // The pool holds a single denom, fixed by the first funding.
+    let funds = match info.funds.as_slice() {
+        [funds] if !funds.amount.is_zero() && !rebate.is_zero() => funds,
+        _ => return Err(ContractError::InvalidRebateFunds {}),
+    };
+    if let Some(current) = REBATE.may_load(deps.storage, round)? {
+        if current.denom != funds.denom {
+            return Err(ContractError::InvalidRebateFunds {});
+        }
+    }
+    REBATE.save(deps.storage, round, &Coin { denom: funds.denom.clone(), amount: rebate })?;
+    treasury::credit(deps.storage, round, Bucket::Rebates, &funds.denom, funds.amount)?;
+
+    let res = GameEvent::new("fund_rebates")
+        .add(events::REBATE, rebate)
+        .amount(funds.amount)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Pays the rebate of an airdrop claim, if the pool of the round still holds it.
+fn pay_rebate(storage: &mut dyn Storage, round: u64) -> Result<Option<Coin>, ContractError> {
+    let rebate = match REBATE.may_load(storage, round)? {
+        Some(rebate) => rebate,
+        None => return Ok(None),
+    };
+    if treasury::balance(storage, round, Bucket::Rebates, &rebate.denom)? < rebate.amount {
+        return Ok(None);
+    }
+    treasury::debit(storage, round, Bucket::Rebates, &rebate.denom, rebate.amount)?;
+    let paid = REBATES_PAID.may_load(storage, round)?.unwrap_or_default();
+    REBATES_PAID.save(storage, round, &(paid + rebate.amount))?;
+    Ok(Some(rebate))
+}
+
+/// Sends back to the sponsor the part of the sponsorship not matched by the ticket
+/// sales or not claimed by the winners.
+pub fn execute_reclaim_sponsorship(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let sponsorship = SPONSORSHIPS
+        .may_load(deps.storage, (round, &info.sender))?
+        .ok_or(ContractError::SponsorshipNotPresent {})?;
+
+    // Winners can claim the match until the end of the claim prize stage, unless the
+    // round has been aborted and there are no winners.
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    let stage_claim_prize_end = (stage_claim_prize.start + stage_claim_prize.duration)?;
+    if !stage_claim_prize_end.is_triggered(&env.block) && !is_round_aborted(deps.as_ref(), &env, round)? {
+        return Err(ContractError::ClaimPrizeStageNotFinished {});
+    }
+
+    SPONSORSHIPS.remove(deps.storage, (round, &info.sender));
+
+    let amount = sponsorship.cap - sponsorship.claimed;
+    treasury::debit(deps.storage, round, Bucket::Sponsorships, sponsorship.token.as_str(), amount)?;
+    let mut transfer_msgs: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        transfer_msgs.push(get_cw20_transfer_to_msg(&info.sender, &sponsorship.token, amount)?);
+    }
+
+    let res = GameEvent::new("reclaim_sponsorship")
+        .add(events::SPONSOR, info.sender)
+        .add(events::TOKEN, sponsorship.token)
+        .amount(amount)
+        .round(round)
+        .apply(Response::new().add_messages(transfer_msgs));
+    Ok(res)
+}
+
+// ======================================================================================
+// Notices
+// ======================================================================================
+/// Clears the inbox of the sender.
+pub fn execute_ack_notices(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let notices = NOTICES.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    NOTICES.remove(deps.storage, &info.sender);
+
+    let res = GameEvent::new("ack_notices")
+        .player(info.sender)
+        .add(events::NOTICES, notices.len().to_string())
+        .apply(Response::new());
+    Ok(res)
+}
+
+// ======================================================================================
+// Sudo
+// ======================================================================================
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Pause {} => sudo_pause(deps),
+        SudoMsg::Unpause {} => sudo_unpause(deps),
+        SudoMsg::AdjustStageEnd { stage, end, round } => sudo_adjust_stage_end(deps, stage, end, round),
+        SudoMsg::ForceRefunds { round } => sudo_force_refunds(deps, round),
+    }
+}
+
+/// Pauses the contract, leaving open only the refunds of the tickets. The governance can
+/// take over a pause of the owner, which can then no longer lift it.
+pub fn sudo_pause(deps: DepsMut) -> Result<Response, ContractError> {
+    if PAUSED.may_load(deps.storage)? == Some(PausedBy::Governance) {
+        return Err(ContractError::ContractPaused {});
+    }
+    PAUSED.save(deps.storage, &PausedBy::Governance)?;
+
+    let res = GameEvent::new("pause").apply(Response::new());
+    Ok(res)
+}
+
+pub fn sudo_unpause(deps: DepsMut) -> Result<Response, ContractError> {
+    if PAUSED.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::ContractNotPaused {});
+    }
+    PAUSED.remove(deps.storage);
+
+    let res = GameEvent::new("unpause").apply(Response::new());
+    Ok(res)
+}
+
+/// Moves the end of a stage of the round, keeping its start. Unlike the owner, the chain
+/// governance can shorten a stage or move the end of a started one, as long as it does
+/// not overlap the next stage.
+pub fn sudo_adjust_stage_end(
+    deps: DepsMut,
+    stage: RoundStage,
+    end: Scheduled,
+    round: Option<u64>,
+) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    let (stage_item, stage_name) = match stage {
+        RoundStage::Bid => (STAGE_BID, "bid"),
+        RoundStage::Reveal => (STAGE_REVEAL, "reveal"),
+        RoundStage::ClaimAirdrop => (STAGE_CLAIM_AIRDROP, "claim airdrop"),
+        RoundStage::ClaimPrize => (STAGE_CLAIM_PRIZE, "claim prize"),
+    };
+    let mut adjusted = stage_item
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::RevealStageNotSet {})?;
+    adjusted.duration = match (adjusted.start, end) {
+        (Scheduled::AtHeight(start), Scheduled::AtHeight(end)) if end > start => Duration::Height(end - start),
+        (Scheduled::AtTime(start), Scheduled::AtTime(end)) if end > start => {
+            Duration::Time(end.seconds() - start.seconds())
+        }
+        _ => {
+            return Err(ContractError::InvalidStageEnd {
+                stage_name: String::from(stage_name),
+            })
+        }
+    };
+
+    // The bid stage is followed by the reveal stage, if any, and by the bid stage of the
+    // next round, if already started.
+    let mut next_stages = vec![];
+    match stage {
+        RoundStage::Bid => {
+            match STAGE_REVEAL.may_load(deps.storage, round)? {
+                Some(stage_reveal) => next_stages.push((stage_reveal, "reveal")),
+                None => next_stages.push((STAGE_CLAIM_AIRDROP.load(deps.storage, round)?, "claim airdrop")),
+            }
+            if let Some(next_stage_bid) = STAGE_BID.may_load(deps.storage, round + 1)? {
+                next_stages.push((next_stage_bid, "next round bid"));
+            }
+        }
+        RoundStage::Reveal => next_stages.push((STAGE_CLAIM_AIRDROP.load(deps.storage, round)?, "claim airdrop")),
+        RoundStage::ClaimAirdrop => next_stages.push((STAGE_CLAIM_PRIZE.load(deps.storage, round)?, "claim prize")),
+        RoundStage::ClaimPrize => {}
+    }
+    let adjusted_end = (adjusted.start + adjusted.duration)?;
+    for (next_stage, next_name) in next_stages {
+        if adjusted_end > next_stage.start {
+            return Err(ContractError::StagesOverlap {
+                first: String::from(stage_name),
+                second: String::from(next_name),
+            });
+        }
+    }
+    stage_item.save(deps.storage, round, &adjusted)?;
+
+    let res = GameEvent::new("adjust_stage_end")
+        .stage(stage_name)
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+/// Cancels the round even if its Merkle roots have been registered, enabling the refunds
+/// of the tickets, unless the airdrop has already been claimed.
+pub fn sudo_force_refunds(deps: DepsMut, round: Option<u64>) -> Result<Response, ContractError> {
+    let round = resolve_round(deps.storage, round)?;
+    if CANCELLED.has(deps.storage, round) {
+        return Err(ContractError::RoundCancelled {});
+    }
+    if AIRDROP_CLAIMS.may_load(deps.storage, round)?.unwrap_or_default() > 0 {
+        return Err(ContractError::ClaimsStarted {});
+    }
+    CANCELLED.save(deps.storage, round, &true)?;
+
+    let res = GameEvent::new("force_refunds")
+        .round(round)
+        .apply(Response::new());
+    Ok(res)
+}
+
+// ======================================================================================
+// Queries
+// ======================================================================================
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Ownership {} => to_binary(&query_ownership(deps)?),
+        QueryMsg::Limits {} => to_binary(&query_limits(deps)?),
+        QueryMsg::Round {} => to_binary(&query_round(deps)?),
+        QueryMsg::Stages { round } => to_binary(&query_stages(deps, round)?),
+        QueryMsg::GameStatus { round } => to_binary(&query_game_status(deps, env, round)?),
+        QueryMsg::Bid { address, round } => to_binary(&query_bid(deps, address, round)?),
+        QueryMsg::BinCounts { round } => to_binary(&query_bin_counts(deps, round)?),
+        QueryMsg::Odds { bin, round } => to_binary(&query_odds(deps, bin, round)?),
+        QueryMsg::MerkleRoots { round } => to_binary(&query_merkle_root(deps, round)?),
+        QueryMsg::GameAmounts { round } => to_binary(&query_game_amounts(deps, round)?),
+        QueryMsg::Outstanding { round } => to_binary(&query_outstanding(deps, round)?),
+        QueryMsg::Treasury { round } => to_binary(&query_treasury(deps, round)?),
+        QueryMsg::ClaimedAirdrops {
+            start_after,
+            limit,
+            round,
+        } => to_binary(&query_claimed_airdrops(deps, start_after, limit, round)?),
+        QueryMsg::IsClaimed { address, round } => to_binary(&query_is_claimed(deps, address, round)?),
+        QueryMsg::ClaimableAmounts { address, round } => {
+            to_binary(&query_claimable_amounts(deps, address, round)?)
+        }
+        QueryMsg::Sponsorships { round } => to_binary(&query_sponsorships(deps, round)?),
+        QueryMsg::Contributors {
+            start_after,
+            limit,
+            round,
+        } => to_binary(&query_contributors(deps, start_after, limit, round)?),
+        QueryMsg::Rebates { round } => to_binary(&query_rebates(deps, round)?),
+        QueryMsg::TicketPrice { round } => to_binary(&query_ticket_price(deps, env, round)?),
+        QueryMsg::Notices { address } => to_binary(&query_notices(deps, address)?),
+        QueryMsg::Vesting { address } => to_binary(&query_vesting(deps, env, address)?),
+        QueryMsg::Blocklist { start_after, limit } => to_binary(&query_blocklist(deps, start_after, limit)?),
+        QueryMsg::IbcDenoms { start_after, limit } => to_binary(&query_ibc_denoms(deps, start_after, limit)?),
+        QueryMsg::RemoteWinners {
+            channel_id,
+            start_after,
+            limit,
+            round,
+        } => to_binary(&query_remote_winners(deps, channel_id, start_after, limit, round)?),
+        QueryMsg::Referrals { address } => to_binary(&query_referrals(deps, address)?),
+        QueryMsg::LoyaltyPoints { address } => to_binary(&query_loyalty_points(deps, address)?),
+        QueryMsg::LoyaltyTiers {} => to_binary(&query_loyalty_tiers(deps)?),
+        QueryMsg::BinMultipliers { round } => to_binary(&query_bin_multipliers(deps, round)?),
+        QueryMsg::BinBoundaries {} => to_binary(&query_bin_boundaries(deps)?),
+        QueryMsg::SeasonPass { address } => to_binary(&query_season_pass(deps, address)?),
+        QueryMsg::VerifyProof {
+            root_kind,
+            address,
+            amount_or_bin,
+            proof,
+            round,
+        } => to_binary(&query_verify_proof(deps, root_kind, address, amount_or_bin, proof, round)?),
+    }
+}
+
+pub fn query_ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let pending = PENDING_OWNER.may_load(deps.storage)?;
+    Ok(OwnershipResponse {
+        owner: cfg.owner.map(|o| o.to_string()),
+        pending_owner: pending.as_ref().map(|p| p.address.to_string()),
+        pending_expiry: pending.and_then(|p| p.expiry),
+    })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: cfg.owner.map(|o| o.to_string()),
+        cw20_token_address: cfg.cw20_token_address.to_string(),
+        charity_address: cfg.charity_address.map(|c| c.to_string()),
+        address_prefix: cfg.address_prefix,
+        ticket_cw20_address: cfg.ticket_cw20_address.map(|t| t.to_string()),
+        parimutuel: cfg.parimutuel,
+        anti_snipe: cfg.anti_snipe,
+        remove_bid_penalty_bps: cfg.remove_bid_penalty_bps,
+        max_bid_changes: cfg.max_bid_changes,
+        bid_change_fee: cfg.bid_change_fee,
+        prize_denom: cfg.prize_denom,
+        mint_prize: cfg.mint_prize,
+        prize_vesting: cfg.prize_vesting,
+        staking_contract: cfg.staking_contract.map(|s| s.to_string()),
+        rollover_grace_period: cfg.rollover_grace_period,
+        crank_bounty: cfg.crank_bounty,
+        dust_policy: cfg.dust_policy,
+        protocol_fee_bps: cfg.protocol_fee_bps,
+        fee_collector: cfg.fee_collector.map(|f| f.to_string()),
+        revenue_split: cfg
+            .revenue_split
+            .map(|split| split.into_iter().map(|(a, share)| (a.to_string(), share)).collect()),
+        burn_unclaimed: cfg.burn_unclaimed,
+        claim_deadline: cfg.claim_deadline,
+        merkle_root_allowlist: cfg.merkle_root_allowlist,
+        bid_token_gate: cfg.bid_token_gate.map(|(token, min_balance)| TokenGate {
+            token: token.to_string(),
+            min_balance,
+        }),
+        bid_nft_gate: cfg.bid_nft_gate.map(|c| c.to_string()),
+        referral_fee_bps: cfg.referral_fee_bps,
+        season_pass: cfg.season_pass,
+        bid_receipts: cfg.bid_receipts.map(|c| c.to_string()),
+        winner_badges: cfg.winner_badges.map(|c| c.to_string()),
+        attestor: cfg.attestor.map(|a| a.to_string()),
+        resolution: match cfg.resolution {
+            GameResolution::MerkleRoot {} => Resolution::MerkleRoot {},
+            GameResolution::OwnerSetsBin {} => Resolution::OwnerSetsBin {},
+            GameResolution::Oracle(o) => Resolution::Oracle(PriceOracle {
+                contract: o.oracle.to_string(),
+                asset: o.asset,
+                min_price: o.min_price,
+                bin_width: o.bin_width,
+                total_amount_game: o.total_amount_game,
+            }),
+            GameResolution::Randomness(r) => Resolution::Randomness(Raffle {
+                nois_proxy: r.nois_proxy.to_string(),
+                total_amount_game: r.total_amount_game,
+            }),
+        },
+        adjacent_tier_bps: cfg.adjacent_tier_bps,
+        bin_boundaries: cfg.bin_boundaries,
+        ibc_bidder_port: cfg.ibc_bidder_port,
+        hash_algorithm: cfg.hash_algorithm,
+        paused: PAUSED.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_limits(deps: Deps) -> StdResult<LimitsResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    Ok(LimitsResponse {
+        max_claim_size: cfg.max_claim_size,
+        default_refund_limit: DEFAULT_REFUND_LIMIT,
+        max_refund_limit: MAX_REFUND_LIMIT,
+        max_sponsorships: MAX_SPONSORSHIPS,
+        max_notices: MAX_NOTICES,
+        max_bids: cfg.max_bids,
+        max_per_bin: cfg.max_per_bin,
+    })
+}
+
+pub fn query_round(deps: Deps) -> StdResult<RoundResponse> {
+    let round = CURRENT_ROUND.load(deps.storage)?;
+    Ok(RoundResponse { round })
+}
+
+/// Returns stages's information.
+pub fn query_stages(deps: Deps, round: Option<u64>) -> StdResult<StagesResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let stage_bid = STAGE_BID.load(deps.storage, round)?;
+    let stage_reveal = STAGE_REVEAL.may_load(deps.storage, round)?;
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage, round)?;
+    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage, round)?;
+    Ok(StagesResponse {
+        stage_bid,
+        stage_reveal,
+        stage_claim_airdrop,
+        stage_claim_prize,
+    })
+}
+
+/// Returns the phase of the game at the current block and the time left before its end.
+pub fn query_game_status(deps: Deps, env: Env, round: Option<u64>) -> StdResult<GameStatusResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    if CANCELLED.has(deps.storage, round) {
+        return Ok(GameStatusResponse {
+            round,
+            phase: GamePhase::Cancelled,
+            phase_end: None,
+            blocks_remaining: None,
+            seconds_remaining: None,
+        });
+    }
+    let mut stages = vec![(GamePhase::Bidding, STAGE_BID.load(deps.storage, round)?)];
+    if let Some(stage_reveal) = STAGE_REVEAL.may_load(deps.storage, round)? {
+        stages.push((GamePhase::Revealing, stage_reveal));
+    }
+    stages.push((GamePhase::ClaimAirdrop, STAGE_CLAIM_AIRDROP.load(deps.storage, round)?));
+    stages.push((GamePhase::ClaimPrize, STAGE_CLAIM_PRIZE.load(deps.storage, round)?));
+
+    // The phase is the stage in progress, as checked by `check_if_valid_stage`, or
+    // pending until the next stage starts.
+    let mut phase = GamePhase::Ended;
+    let mut phase_end = None;
+    for (stage_phase, stage) in stages {
+        if !stage.start.is_triggered(&env.block) {
+            phase = GamePhase::Pending;
+            phase_end = Some(stage.start);
+            break;
+        }
+        let stage_end = (stage.start + stage.duration)?;
+        if !stage_end.is_triggered(&env.block) {
+            phase = stage_phase;
+            phase_end = Some(stage_end);
+            break;
+        }
+    }
+
+    let (blocks_remaining, seconds_remaining) = match phase_end {
+        Some(Scheduled::AtHeight(height)) => (Some(height - env.block.height), None),
+        Some(Scheduled::AtTime(time)) => {
+            (None, Some(time.seconds().saturating_sub(env.block.time.seconds())))
+        }
+        None => (None, None),
+    };
+    Ok(GameStatusResponse {
+        round,
+        phase,
+        phase_end,
+        blocks_remaining,
+        seconds_remaining,
+    })
+}
+
+pub fn query_bid(deps: Deps, address: String, round: Option<u64>) -> StdResult<BidResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let address = deps.api.addr_validate(&address)?;
+    let bid = BIDS.may_load(deps.storage, (round, &address))?;
+    let bins = bid_bins(deps.storage, round, &address)?;
+    Ok(BidResponse {
+        bid: bid.as_ref().map(|bid| bid.bin),
+        bins,
+        tickets: bid.as_ref().map_or(0, |bid| bid.tickets),
+        stake: bid.as_ref().map_or_else(Uint128::zero, |bid| bid.stake),
+        changes: bid.map_or(0, |bid| bid.changes),
+    })
+}
+
+/// Returns the number of bids placed on each bin.
+pub fn query_bin_counts(deps: Deps, round: Option<u64>) -> StdResult<BinCountsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let bin_counts = BIN_COUNTS
+        .prefix(round)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(bin, count)| BinCount { bin, count }))
+        .collect::<StdResult<_>>()?;
+    Ok(BinCountsResponse { bin_counts })
+}
+
+/// Returns the bids on the bin and the prizes each of them would get if the bin won
+/// with the current pools, the bids being counted once whatever their tickets. Until
+/// the round is finalized, the protocol fee is taken from the tickets prize as it would
+/// be then.
+pub fn query_odds(deps: Deps, bin: u8, round: Option<u64>) -> StdResult<OddsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    if bin == 0 || bin > BINS.load(deps.storage, round)? {
+        return Err(StdError::not_found(format!("bin {}", bin)));
+    }
+    let bin_bids = BIN_COUNTS.may_load(deps.storage, (round, bin))?.unwrap_or_default();
+    let total_bids = participants(deps.storage, round)?;
+
+    let mut ticket_prize = ticket_prize(deps.storage, round)?;
+    if !FINALIZED_WINNERS.has(deps.storage, round) {
+        let cfg = CONFIG.load(deps.storage)?;
+        ticket_prize -= to_payout(bps_amount(ticket_prize, cfg.protocol_fee_bps)?)?;
+    }
+    let airdrop_prize = TOTAL_AIRDROP_GAME_AMOUNT.may_load(deps.storage, round)?.unwrap_or_default();
+    let winners = Uint128::from(bin_bids.max(1));
+    Ok(OddsResponse {
+        bin,
+        bin_bids,
+        total_bids,
+        ticket_payout: ticket_prize / winners,
+        airdrop_payout: airdrop_prize / winners,
+    })
+}
+
+pub fn query_merkle_root(deps: Deps, round: Option<u64>) -> StdResult<MerkleRootsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let merkle_root_airdrop = MERKLE_ROOT_AIRDROP.load(deps.storage, round)?;
+    let total_amount = TOTAL_AIRDROP_AMOUNT.load(deps.storage, round)?;
+    let merkle_root_game = MERKLE_ROOT_GAME.may_load(deps.storage, round)?;
+    let winning_bin = WINNING_BIN.may_load(deps.storage, round)?;
+    let randomness_job = RANDOMNESS_JOB.may_load(deps.storage, round)?;
+
+    let resp = MerkleRootsResponse {
+        merkle_root_airdrop,
+        total_amount,
+        merkle_root_game,
+        winning_bin,
+        randomness_job,
+    };
+
+    Ok(resp)
+}
+
+pub fn query_game_amounts(deps: Deps, round: Option<u64>) -> StdResult<GameAmountsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let cfg = CONFIG.load(deps.storage)?;
+    let ticket_asset = ticket_asset(&cfg, &TICKET_PRICE.load(deps.storage, round)?);
+    let prize_asset = prize_asset(&cfg);
+    // Prizes
+    let total_ticket_prize = ticket_prize(deps.storage, round)?;
+    let total_airdrop_amount = TOTAL_AIRDROP_AMOUNT.load(deps.storage, round)?;
+    let total_airdrop_game_amount = TOTAL_AIRDROP_GAME_AMOUNT.may_load(deps.storage, round)?.unwrap_or_default();
+    // Number of winners
+    let winners_amount = WINNERS.load(deps.storage, round)?;
+    // Claimed amount, paid from the reserves of the round.
+    let total_claimed_airdrop =
+        treasury::paid(deps.storage, round, Bucket::AirdropReserve, cfg.cw20_token_address.as_str())?
+            + treasury::paid(deps.storage, round, Bucket::GameReserve, &prize_asset)?;
+    let total_claimed_prize = treasury::paid(deps.storage, round, Bucket::TicketPot, &ticket_asset)?;
+    // Bonus for the earliest winning bid.
+    let first_bidder_bonus = FIRST_BIDDER_BONUS.load(deps.storage, round)?;
+    let first_winner = FIRST_WINNER.may_load(deps.storage, round)?.map(|w| w.address.to_string());
+    let max_winners = MAX_WINNERS.may_load(deps.storage, round)?;
+    let min_participants = MIN_PARTICIPANTS.may_load(deps.storage, round)?;
+    // Donated amounts.
+    let total_donated_prize = DONATED_PRIZE_AMOUNT.load(deps.storage, round)?;
+    let total_donated_airdrop = DONATED_AIRDROP_AMOUNT.load(deps.storage, round)?;
+    // Other tokens of the prize basket.
+    let prize_tokens = PRIZE_TOKENS
+        .may_load(deps.storage, round)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|prize_token| PrizeTokenInfo {
+            token: prize_token.token.to_string(),
+            amount: prize_token.amount,
+            claimed: prize_token.claimed,
+        })
+        .collect();
+
+    let resp = GameAmountsResponse {
+        total_ticket_prize,
+        total_airdrop_amount,
+        total_airdrop_game_amount,
+        winners_amount,
+        total_claimed_airdrop,
+        total_claimed_prize,
+        first_bidder_bonus,
+        first_winner,
+        max_winners,
+        min_participants,
+        total_donated_prize,
+        total_donated_airdrop,
+        prize_tokens,
+        finalized: FINALIZED_WINNERS.has(deps.storage, round),
+        total_dust_prize: dust(deps.storage, round, &ticket_asset)?,
+        total_dust_airdrop: dust(deps.storage, round, &prize_asset)?,
+     };
+
+    Ok(resp)
+}
+
+/// Returns what is still to be claimed, to let the owner decide when to withdraw
+/// the leftovers.
+pub fn query_outstanding(deps: Deps, round: Option<u64>) -> StdResult<OutstandingResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let expected_recipients = EXPECTED_RECIPIENTS.load(deps.storage, round)?;
+    let airdrop_claims = AIRDROP_CLAIMS.load(deps.storage, round)?;
+    let prize_claims = PRIZE_CLAIMS.may_load(deps.storage, round)?.unwrap_or_default();
+    let unclaimed_winners = WINNERS.load(deps.storage, round)? - prize_claims;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let airdrop_asset = cfg.cw20_token_address.as_str();
+    let unclaimed_airdrop = treasury::balance(deps.storage, round, Bucket::AirdropReserve, airdrop_asset)?
+        + treasury::balance(deps.storage, round, Bucket::GameReserve, &prize_asset(&cfg))?;
+    let ticket_asset = ticket_asset(&cfg, &TICKET_PRICE.load(deps.storage, round)?);
+    let unclaimed_prize = treasury::balance(deps.storage, round, Bucket::TicketPot, &ticket_asset)?
+        + treasury::balance(deps.storage, round, Bucket::Consolations, &ticket_asset)?;
+
+    Ok(OutstandingResponse {
+        expected_recipients,
+        airdrop_claims,
+        unclaimed_recipients: expected_recipients.saturating_sub(airdrop_claims),
+        unclaimed_winners,
+        unclaimed_airdrop,
+        unclaimed_prize,
+    })
+}
+
+/// Returns the balances of the earmarked buckets of the treasury.
+pub fn query_treasury(deps: Deps, round: Option<u64>) -> StdResult<TreasuryResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let buckets = treasury::balances(deps.storage, round)?
+        .into_iter()
+        .map(|(bucket, asset, amount)| BucketBalance { bucket, asset, amount })
+        .collect();
+    Ok(TreasuryResponse { buckets })
+}
+
+/// Returns the addresses that claimed the airdrop, paginated by address.
+pub fn query_claimed_airdrops(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    round: Option<u64>,
+) -> StdResult<ClaimedAirdropsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_after = start_after
+        .map(|a| deps.api.addr_validate(&a))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses = CLAIM_AIRDROP
+        .prefix(round)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|address| address.map(String::from))
+        .collect::<StdResult<_>>()?;
+    Ok(ClaimedAirdropsResponse { addresses })
+}
+
+pub fn query_blocklist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BlocklistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_after = start_after
+        .map(|a| deps.api.addr_validate(&a))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses = BLOCKLIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|address| address.map(String::from))
+        .collect::<StdResult<_>>()?;
+    Ok(BlocklistResponse { addresses })
+}
+
+pub fn query_ibc_denoms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IbcDenomsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let denoms = IBC_DENOMS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(IbcDenomsResponse { denoms })
+}
+
+/// Returns the players of the channel whose bids, placed with IBC packets, are on the
+/// winning bin of the round, with their tickets, for the sender to pay them from the
+/// escrowed tickets. There are none until the winning bin is set.
+pub fn query_remote_winners(
+    deps: Deps,
+    channel_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    round: Option<u64>,
+) -> StdResult<RemoteWinnersResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let winning_bin = match WINNING_BIN.may_load(deps.storage, round)? {
+        Some(bin) => bin,
+        None => return Ok(RemoteWinnersResponse { winners: vec![] }),
+    };
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let winners = REMOTE_BIDS
+        .prefix((round, &channel_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, bid)) if bid.bin != winning_bin))
+        .take(limit)
+        .map(|item| {
+            let (player, bid) = item?;
+            Ok(RemoteWinner { player, bin: bid.bin, tickets: bid.tickets })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(RemoteWinnersResponse { winners })
+}
+
+/// Returns the number of bids referred by the address and not removed, of any round.
+pub fn query_referrals(deps: Deps, address: String) -> StdResult<ReferralsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let referrals = REFERRALS.may_load(deps.storage, &address)?.unwrap_or_default();
+    Ok(ReferralsResponse { referrals })
+}
+
+/// Returns the loyalty points of the address, with the discount they give on the tickets.
+pub fn query_loyalty_points(deps: Deps, address: String) -> StdResult<LoyaltyPointsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let points = LOYALTY_POINTS.may_load(deps.storage, &address)?.unwrap_or_default();
+    let discount_bps = loyalty_discount(deps.storage, &address)?;
+    Ok(LoyaltyPointsResponse { points, discount_bps })
+}
+
+pub fn query_loyalty_tiers(deps: Deps) -> StdResult<LoyaltyTiersResponse> {
+    let tiers = LOYALTY_TIERS.may_load(deps.storage)?.unwrap_or_default();
+    Ok(LoyaltyTiersResponse { tiers })
+}
+
+/// Returns the payout multipliers of the bins of the round, by ascending bin.
+pub fn query_bin_multipliers(deps: Deps, round: Option<u64>) -> StdResult<BinMultipliersResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let multipliers = BIN_MULTIPLIERS
+        .prefix(round)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    Ok(BinMultipliersResponse { multipliers })
+}
+
+/// Returns the range of predictions covered by each bin, from the bin boundaries.
+pub fn query_bin_boundaries(deps: Deps) -> StdResult<BinBoundariesResponse> {
+    let bin_boundaries = CONFIG.load(deps.storage)?.bin_boundaries.unwrap_or_default();
+    if bin_boundaries.is_empty() {
+        return Ok(BinBoundariesResponse { ranges: vec![] });
+    }
+    let ranges = (0..=bin_boundaries.len())
+        .map(|index| BinRange {
+            bin: index as u8 + 1,
+            min: index.checked_sub(1).map(|previous| bin_boundaries[previous]),
+            max: bin_boundaries.get(index).copied(),
+        })
+        .collect();
+    Ok(BinBoundariesResponse { ranges })
+}
+
+/// Returns the entries left of the season passes of the address.
+pub fn query_season_pass(deps: Deps, address: String) -> StdResult<SeasonPassResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = SEASON_PASSES.may_load(deps.storage, &address)?;
+    Ok(SeasonPassResponse {
+        entries: balance.as_ref().map_or(0, |balance| balance.entries),
+        amount: balance.map_or(Uint128::zero(), |balance| balance.amount),
+    })
+}
+
+/// Verifies the proof of the address against the Merkle root of the round, building the
+/// leaf as `ClaimAirdrop`, `ProveStake` and the coupons do. Only the proof is checked,
+/// not the stage or the previous claims.
+pub fn query_verify_proof(
+    deps: Deps,
+    root_kind: MerkleRootKind,
+    address: String,
+    amount_or_bin: String,
+    proof: Vec<String>,
+    round: Option<u64>,
+) -> StdResult<VerifyProofResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    // The value is formatted as the message field it stands for, "07" is the bin 7.
+    let parse_err = |kind: &str| StdError::parse_err(kind, format!("invalid amount or bin {}", amount_or_bin));
+    let (merkle_root, value) = match root_kind {
+        MerkleRootKind::Airdrop => (
+            MERKLE_ROOT_AIRDROP.may_load(deps.storage, round)?,
+            amount_or_bin.parse::<Uint128>().map_err(|_| parse_err("amount"))?.to_string(),
+        ),
+        MerkleRootKind::Game => (
+            MERKLE_ROOT_GAME.may_load(deps.storage, round)?,
+            amount_or_bin.parse::<u8>().map_err(|_| parse_err("bin"))?.to_string(),
+        ),
+        MerkleRootKind::Stake => (
+            MERKLE_ROOT_STAKE.may_load(deps.storage, round)?,
+            amount_or_bin.parse::<u64>().map_err(|_| parse_err("weight"))?.to_string(),
+        ),
+        MerkleRootKind::Coupon => (
+            MERKLE_ROOT_COUPON.may_load(deps.storage, round)?,
+            amount_or_bin.parse::<u16>().map_err(|_| parse_err("discount_bps"))?.to_string(),
+        ),
+    };
+    let merkle_root = merkle_root.ok_or_else(|| StdError::not_found("merkle root"))?;
+
+    let leaf = format!("{}{}", address, value);
+    let cfg = CONFIG.load(deps.storage)?;
+    let verified = merkle_root_matches(cfg.hash_algorithm, &leaf, proof, &merkle_root)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(VerifyProofResponse { verified })
+}
+
+/// Returns the claim status of an address.
+pub fn query_is_claimed(deps: Deps, address: String, round: Option<u64>) -> StdResult<IsClaimedResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    // Winners are saved with an unclaimed prize when they claim the airdrop.
+    let claim_prize = CLAIM_PRIZE.may_load(deps.storage, (round, &address))?;
+    Ok(IsClaimedResponse {
+        airdrop_claimed: CLAIM_AIRDROP.has(deps.storage, (round, &address)),
+        prize_eligible: claim_prize.is_some(),
+        prize_claimed: claim_prize.unwrap_or(false),
+    })
+}
+
+/// Returns the prize the address would receive by claiming it now. The amounts are
+/// zero if the address is not a winner or has already claimed the prize.
+pub fn query_claimable_amounts(
+    deps: Deps,
+    address: String,
+    round: Option<u64>,
+) -> StdResult<ClaimableAmountsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if CLAIM_PRIZE.may_load(deps.storage, (round, &address))? != Some(false) {
+        return Ok(ClaimableAmountsResponse {
+            ticket_prize: Uint128::zero(),
+            airdrop_prize: Uint128::zero(),
+            sponsor_prizes: vec![],
+            token_prizes: vec![],
+        });
+    }
+
+    let prize_share = compute_prize_share(deps.storage, round, &address)?;
+    Ok(ClaimableAmountsResponse {
+        ticket_prize: prize_share.ticket_prize,
+        airdrop_prize: prize_share.airdrop_prize,
+        sponsor_prizes: prize_share
+            .sponsor_prizes
+            .into_iter()
+            .map(|(sponsor, token, amount)| SponsorPrize {
+                sponsor: sponsor.to_string(),
+                token: token.to_string(),
+                amount,
+            })
+            .collect(),
+        token_prizes: prize_share
+            .token_prizes
+            .into_iter()
+            .map(|(token, amount)| PrizeTokenAmount { token: token.to_string(), amount })
+            .collect(),
+    })
+}
+
+/// Returns the sponsorships of the round with the amount matched by the ticket sales.
+pub fn query_sponsorships(deps: Deps, round: Option<u64>) -> StdResult<SponsorshipsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let ticket_sales = ticket_prize(deps.storage, round)?;
+    let sponsorships = SPONSORSHIPS
+        .prefix(round)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (sponsor, sponsorship) = item?;
+            Ok(SponsorshipInfo {
+                sponsor: sponsor.to_string(),
+                token: sponsorship.token.to_string(),
+                ratio: sponsorship.ratio,
+                cap: sponsorship.cap,
+                matched: sponsorship.matched(ticket_sales),
+                claimed: sponsorship.claimed,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(SponsorshipsResponse { sponsorships })
+}
+
+/// Returns the donations to the prize pool of the round, paginated by contributor.
+pub fn query_contributors(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    round: Option<u64>,
+) -> StdResult<ContributorsResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_after = start_after
+        .map(|a| deps.api.addr_validate(&a))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let contributors = CONTRIBUTIONS
+        .prefix(round)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, contribution) = item?;
+            Ok(ContributorInfo {
+                address: address.to_string(),
+                ticket_prize: contribution.ticket_prize,
+                game_prize: contribution.game_prize,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ContributorsResponse { contributors })
+}
+
+/// Returns the rebate of the airdrop claims, the pool left and the rebates paid.
+pub fn query_rebates(deps: Deps, round: Option<u64>) -> StdResult<RebatesResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    let rebate = REBATE.may_load(deps.storage, round)?;
+    let pool = match &rebate {
+        Some(rebate) => treasury::balance(deps.storage, round, Bucket::Rebates, &rebate.denom)?,
+        None => Uint128::zero(),
+    };
+    let total_paid = REBATES_PAID.may_load(deps.storage, round)?.unwrap_or_default();
+    Ok(RebatesResponse {
+        rebate,
+        pool,
+        total_paid,
+    })
+}
+
+/// Returns the price of a ticket bought at the current block and the price schedule.
+pub fn query_ticket_price(deps: Deps, env: Env, round: Option<u64>) -> StdResult<TicketPriceResponse> {
+    let round = resolve_round(deps.storage, round)?;
+    Ok(TicketPriceResponse {
+        ticket_price: current_ticket_price(deps.storage, &env.block, round)?,
+        price_schedule: PRICE_SCHEDULE.may_load(deps.storage, round)?.unwrap_or_default(),
+    })
+}
+
+/// Returns the notices in the inbox of an address.
+pub fn query_notices(deps: Deps, address: String) -> StdResult<NoticesResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let notices = NOTICES.may_load(deps.storage, &address)?.unwrap_or_default();
+    Ok(NoticesResponse { notices })
+}
+
+/// Returns the prizes of the address vesting, with the amounts vested so far.
+pub fn query_vesting(deps: Deps, env: Env, address: String) -> StdResult<VestingResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let vestings = VESTINGS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|vesting| {
+            let vested = vesting.vested(&env.block);
+            VestingInfo {
+                round: vesting.round,
+                asset: vesting.asset,
+                amount: vesting.amount,
+                vested,
+                released: vesting.released,
+                remaining: vesting.amount - vested,
+            }
+        })
+        .collect();
+    Ok(VestingResponse { vestings })
+}
+
+// ======================================================================================
+// Utils
+// ======================================================================================
+/// Returns the requested round, or the current one if none set. Fails if the round
+/// has not been started.
+pub fn resolve_round(storage: &dyn Storage, round: Option<u64>) -> StdResult<u64> {
+    let current_round = CURRENT_ROUND.load(storage)?;
+    match round {
+        None => Ok(current_round),
+        Some(round) if round >= 1 && round <= current_round => Ok(round),
+        Some(round) => Err(StdError::not_found(format!("round {}", round))),
+    }
+}
+
+pub fn check_if_valid_stage(
+    env: Env,
+    stage: Stage,
+    stage_name: String,
+) -> Result<(), ContractError> {
+    // The stage has not started.
+    if !stage.start.is_triggered(&env.block) {
+        return Err(ContractError::StageNotStarted { stage_name });
+    }
+
+    // The stage has ended.
+    let stage_end = (stage.start + stage.duration)?;
+    if stage_end.is_triggered(&env.block) {
+        return Err(ContractError::StageEnded { stage_name });
+    }
+
+    Ok(())
+}
+
+/// Extends the bid stage of the round when a bid arrives within the anti-sniping window,
+/// up to the maximum total extension and without overlapping the stage that follows.
+/// Returns the number of blocks the stage has been extended by.
+fn extend_bid_stage(storage: &mut dyn Storage, env: &Env, cfg: &Config, round: u64) -> StdResult<u64> {
+    let anti_snipe = match &cfg.anti_snipe {
+        Some(anti_snipe) => anti_snipe,
+        None => return Ok(0),
+    };
+    let mut stage_bid = STAGE_BID.load(storage, round)?;
+    let (start, duration) = match (stage_bid.start, stage_bid.duration) {
+        (Scheduled::AtHeight(start), Duration::Height(duration)) => (start, duration),
+        _ => return Ok(0),
+    };
+    let end = start + duration;
+    if end.saturating_sub(env.block.height) > anti_snipe.window {
+        return Ok(0);
+    }
+
+    // The bid stage cannot end after the start of the next stage of the round, nor
+    // after the start of the bids of the next round.
+    let next_stage = match STAGE_REVEAL.may_load(storage, round)? {
+        Some(stage_reveal) => stage_reveal,
+        None => STAGE_CLAIM_AIRDROP.load(storage, round)?,
+    };
+    let mut latest_end = match next_stage.start {
+        Scheduled::AtHeight(height) => height,
+        _ => return Ok(0),
+    };
+    if let Some(next_stage_bid) = STAGE_BID.may_load(storage, round + 1)? {
+        if let Scheduled::AtHeight(height) = next_stage_bid.start {
+            latest_end = latest_end.min(height);
+        }
+    }
+
+    let extended = BID_STAGE_EXTENSION.may_load(storage, round)?.unwrap_or_default();
+    let extension = anti_snipe
+        .extension
+        .min(anti_snipe.max_extension.saturating_sub(extended))
+        .min(latest_end.saturating_sub(end));
+    if extension == 0 {
+        return Ok(0);
+    }
+    stage_bid.duration = Duration::Height(duration + extension);
+    STAGE_BID.save(storage, round, &stage_bid)?;
+    BID_STAGE_EXTENSION.save(storage, round, &(extended + extension))?;
+    Ok(extension)
+}
+
+/// Returns the price of a ticket bought at the block: the price of the latest step of the
+/// schedule already started, or the ticket price of the round if none.
+fn current_ticket_price(storage: &dyn Storage, block: &BlockInfo, round: u64) -> StdResult<Coin> {
+    let mut ticket_price = TICKET_PRICE.load(storage, round)?;
+    let price_schedule = PRICE_SCHEDULE.may_load(storage, round)?.unwrap_or_default();
+    if let Some(step) = price_schedule.iter().rev().find(|step| step.start.is_triggered(block)) {
+        ticket_price.amount = step.amount;
+    }
+    Ok(ticket_price)
+}
+
+/// Fails if the bin already holds the maximum number of bids, if any.
+fn check_bin_capacity(storage: &dyn Storage, cfg: &Config, round: u64, bin: u8) -> Result<(), ContractError> {
+    if let Some(limit) = cfg.max_per_bin {
+        if BIN_COUNTS.may_load(storage, (round, bin))?.unwrap_or_default() >= limit as u64 {
+            return Err(ContractError::BinFull { bin, limit });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the bins of the bid of the player, empty if the player has no bid.
+fn bid_bins(storage: &dyn Storage, round: u64, player: &Addr) -> StdResult<Vec<u8>> {
+    let mut bins: Vec<u8> = BIDS.may_load(storage, (round, player))?.map(|bid| bid.bin).into_iter().collect();
+    if let Some(extra_bins) = EXTRA_BINS.may_load(storage, (round, player))? {
+        bins.extend(extra_bins);
+    }
+    Ok(bins)
+}
+
+/// Returns the prize share weight of the bid of the player. In parimutuel mode it is
+/// the amount staked, otherwise the stake weight proven, one if none, for each ticket
+/// bought on the bin.
+fn bid_weight(storage: &dyn Storage, round: u64, player: &Addr) -> StdResult<Uint128> {
+    let bid = BIDS.load(storage, (round, player))?;
+    if CONFIG.load(storage)?.parimutuel {
+        return Ok(bid.stake);
+    }
+    let stake_weight = BID_WEIGHTS.may_load(storage, (round, player))?.unwrap_or(1);
+    Ok(Uint128::from(stake_weight) * Uint128::from(bid.tickets))
+}
+
+/// Returns the prize share weight of the winning bid of the player, the bid weight
+/// multiplied by the multiplier of the winning bin. Once a multiplier is set, the
+/// weights are scaled by the decimal places of the multipliers to keep their precision.
+fn multiplied_weight(storage: &mut dyn Storage, round: u64, player: &Addr, bin: u8) -> StdResult<Uint128> {
+    let weight = bid_weight(storage, round, player)?;
+    if BIN_MULTIPLIERS.prefix(round).keys(storage, None, None, Order::Ascending).next().is_none() {
+        return Ok(weight);
+    }
+    let multiplier = BIN_MULTIPLIERS.may_load(storage, (round, bin))?.unwrap_or_else(Decimal::one);
+    let weight = weight.checked_mul(multiplier.atomics())?;
+    WINNER_WEIGHTS.save(storage, (round, player), &weight)?;
+    Ok(weight)
+}
+
+/// Returns the prize share weight of the winner: the multiplied weight if a multiplier
+/// was set when it won, its bid weight otherwise.
+fn winner_weight(storage: &dyn Storage, round: u64, winner: &Addr) -> StdResult<Uint128> {
+    match WINNER_WEIGHTS.may_load(storage, (round, winner))? {
+        Some(weight) => Ok(weight),
+        None => bid_weight(storage, round, winner),
+    }
+}
+
+/// Removes the bid of the player from all its bins, or its commitment if the bid has
+/// not been revealed, returning the amount paid for it, if any.
+fn clear_bid(storage: &mut dyn Storage, round: u64, player: &Addr) -> StdResult<Option<Uint128>> {
+    let stake = match BIDS.may_load(storage, (round, player))? {
+        Some(bid) => bid.stake,
+        None => {
+            let commitment = COMMITMENTS.may_load(storage, (round, player))?;
+            if commitment.is_some() {
+                COMMITMENTS.remove(storage, (round, player));
+                remove_loyalty_point(storage, player)?;
+            }
+            return Ok(commitment.map(|commitment| commitment.stake));
+        }
+    };
+    for bin in bid_bins(storage, round, player)? {
+        decrease_bin_count(storage, round, bin)?;
+    }
+    BIDS.remove(storage, (round, player));
+    EXTRA_BINS.remove(storage, (round, player));
+    BID_HEIGHTS.remove(storage, (round, player));
+    BID_WEIGHTS.remove(storage, (round, player));
+    remove_loyalty_point(storage, player)?;
+    if let Some(referrer) = REFERRERS.may_load(storage, (round, player))? {
+        REFERRERS.remove(storage, (round, player));
+        remove_referral(storage, &referrer)?;
+    }
+    Ok(Some(stake))
+}
+
+/// Uses an entry of the season passes of the holder, taking its part of the amount paid
+/// for the entries left out of the season passes bucket.
+fn use_season_pass_entry(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    round: u64,
+    ticket_price: &Coin,
+    holder: &Addr,
+    mut balance: SeasonPassBalance,
+) -> Result<Payment, ContractError> {
+    let season_pass = cfg.season_pass.as_ref().ok_or(ContractError::SeasonPassNotOffered {})?;
+    if season_pass.price.denom != ticket_price.denom {
+        return Err(ContractError::InvalidTicketAsset {});
+    }
+
+    // The last entry takes the remainder of the amount paid.
+    let stake = balance.amount / Uint128::from(balance.entries);
+    balance.entries -= 1;
+    balance.amount -= stake;
+    if balance.entries == 0 {
+        SEASON_PASSES.remove(storage, holder);
+    } else {
+        SEASON_PASSES.save(storage, holder, &balance)?;
+    }
+    treasury::debit(storage, round, Bucket::SeasonPasses, &season_pass.price.denom, stake)?;
+    Ok(Payment::SeasonPass { stake, entries_left: balance.entries })
+}
+
+/// Adds the loyalty point of the round played by the player.
+fn add_loyalty_point(storage: &mut dyn Storage, player: &Addr) -> StdResult<()> {
+    let points = LOYALTY_POINTS.may_load(storage, player)?.unwrap_or_default();
+    LOYALTY_POINTS.save(storage, player, &(points + 1))
+}
+
+/// Removes the loyalty point of a round no longer played by the player.
+fn remove_loyalty_point(storage: &mut dyn Storage, player: &Addr) -> StdResult<()> {
+    let points = LOYALTY_POINTS.may_load(storage, player)?.unwrap_or_default();
+    LOYALTY_POINTS.save(storage, player, &points.saturating_sub(1))
+}
+
+/// Returns the discount, in basis points, of the highest loyalty tier reached by the
+/// player, if any.
+fn loyalty_discount(storage: &dyn Storage, player: &Addr) -> StdResult<u16> {
+    let points = LOYALTY_POINTS.may_load(storage, player)?.unwrap_or_default();
+    let tiers = LOYALTY_TIERS.may_load(storage)?.unwrap_or_default();
+    let discount_bps = tiers
+        .iter()
+        .rev()
+        .find(|tier| tier.min_points <= points)
+        .map_or(0, |tier| tier.discount_bps);
+    Ok(discount_bps)
+}
+
+/// Decreases the number of bids referred by the referrer, once one of them is removed.
+fn remove_referral(storage: &mut dyn Storage, referrer: &Addr) -> StdResult<()> {
+    let count = REFERRALS.may_load(storage, referrer)?.unwrap_or_default();
+    REFERRALS.save(storage, referrer, &count.saturating_sub(1))
+}
+
+fn increase_bin_count(storage: &mut dyn Storage, round: u64, bin: u8) -> StdResult<()> {
+    let count = BIN_COUNTS.may_load(storage, (round, bin))?.unwrap_or_default();
+    BIN_COUNTS.save(storage, (round, bin), &(count + 1))
+}
+
+/// Decreases the bids of the bin, removing the bins left without bids.
+fn decrease_bin_count(storage: &mut dyn Storage, round: u64, bin: u8) -> StdResult<()> {
+    let count = BIN_COUNTS.may_load(storage, (round, bin))?.unwrap_or_default();
+    if count > 1 {
+        BIN_COUNTS.save(storage, (round, bin), &(count - 1))
+    } else {
+        BIN_COUNTS.remove(storage, (round, bin));
+        Ok(())
+    }
+}
 
-    let ticket_price = TICKET_PRICE.load(deps.storage)?;
+/// Records a verified winner, returning the winner left out when the winners are
+/// capped: the one with the latest bid, ordered by height and address.
+fn add_winner(
+    storage: &mut dyn Storage,
+    round: u64,
+    winner: &Addr,
+    height: u64,
+    weight: Uint128,
+) -> StdResult<Option<Addr>> {
+    CLAIM_PRIZE.save(storage, (round, winner), &false)?;
+    let winners = WINNERS.update(storage, round, |winners| -> StdResult<_> {
+        Ok(winners + Uint128::new(1))
+    })?;
+    WINNERS_WEIGHT.update(storage, round, |winners_weight| -> StdResult<_> {
+        Ok(winners_weight + weight)
+    })?;
 
-    let msg = get_bank_transfer_to_msg(
-        &address,
-        &ticket_price.denom,
-        amount,
-    );
+    let max_winners = match MAX_WINNERS.may_load(storage, round)? {
+        Some(max_winners) => max_winners,
+        None => return Ok(None),
+    };
+    SELECTED_WINNERS.save(storage, (round, height, winner), &weight)?;
+    if winners <= Uint128::from(max_winners) {
+        return Ok(None);
+    }
 
-    let res = Response::new()
-        .add_message(msg)
-        .add_attribute("action", "withdraw_prize")
-        .add_attribute("address", address)
-        .add_attribute("amount", amount);
+    let (last_height, last_winner) = SELECTED_WINNERS
+        .sub_prefix(round)
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .ok_or_else(|| StdError::not_found("selected winner"))??;
+    let last_weight = SELECTED_WINNERS.load(storage, (round, last_height, &last_winner))?;
+    SELECTED_WINNERS.remove(storage, (round, last_height, &last_winner));
+    CLAIM_PRIZE.remove(storage, (round, &last_winner));
+    WINNERS.save(storage, round, &(winners - Uint128::new(1)))?;
+    WINNERS_WEIGHT.update(storage, round, |winners_weight| -> StdResult<_> {
+        Ok(winners_weight - last_weight)
+    })?;
+    Ok(Some(last_winner))
+}
 
-    Ok(res)
+/// Records a notice in the inbox of the address, dropping the oldest ones when the
+/// inbox is full.
+fn push_notice(storage: &mut dyn Storage, address: &Addr, notice: Notice) -> StdResult<()> {
+    let mut notices = NOTICES.may_load(storage, address)?.unwrap_or_default();
+    notices.push(notice);
+    let excess = notices.len().saturating_sub(MAX_NOTICES as usize);
+    notices.drain(..excess);
+    NOTICES.save(storage, address, &notices)
 }
 
-// ======================================================================================
-// Queries
-// ======================================================================================
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::Stages {} => to_binary(&query_stages(deps)?),
-        QueryMsg::Bid { address } => to_binary(&query_bid(deps, address)?),
-        QueryMsg::MerkleRoots {} => to_binary(&query_merkle_root(deps)?),
-        QueryMsg::GameAmounts {} => to_binary(&query_game_amounts(deps)?),
+/// A round is aborted when it is cancelled, when the bid stage ends with fewer bidders
+/// than the minimum or when the claim airdrop stage starts without registered Merkle
+/// roots: no bid can win anymore and the tickets can only be refunded.
+pub fn is_round_aborted(deps: Deps, env: &Env, round: u64) -> StdResult<bool> {
+    if CANCELLED.has(deps.storage, round) || is_undersubscribed(deps.storage, env, round)? {
+        return Ok(true);
     }
+    is_roots_deadline_missed(deps.storage, env, round)
 }
 
-pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
-    Ok(ConfigResponse {
-        owner: cfg.owner.map(|o| o.to_string()),
-        cw20_token_address: cfg.cw20_token_address.to_string(),
-    })
+/// Returns true if the claim airdrop stage, the deadline to register the Merkle roots,
+/// has started without the airdrop root and the game result, either the game root or
+/// the winning bin.
+fn is_roots_deadline_missed(storage: &dyn Storage, env: &Env, round: u64) -> StdResult<bool> {
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(storage, round)?;
+    let resolved = MERKLE_ROOT_GAME.has(storage, round) || WINNING_BIN.has(storage, round);
+    Ok(stage_claim_airdrop.start.is_triggered(&env.block)
+        && !(MERKLE_ROOT_AIRDROP.has(storage, round) && resolved))
 }
 
-/// Returns stages's information.
-pub fn query_stages(deps: Deps) -> StdResult<StagesResponse> {
-    let stage_bid = STAGE_BID.load(deps.storage)?;
-    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(deps.storage)?;
-    let stage_claim_prize = STAGE_CLAIM_PRIZE.load(deps.storage)?;
-    Ok(StagesResponse {
-        stage_bid,
-        stage_claim_airdrop,
-        stage_claim_prize,
-    })
+/// Fails if the bids of the round are not final: they are once the bid stage is over,
+/// or the reveal stage if any.
+fn check_bids_final(storage: &dyn Storage, env: &Env, round: u64) -> Result<(), ContractError> {
+    let last_bid_stage = match STAGE_REVEAL.may_load(storage, round)? {
+        Some(stage_reveal) => stage_reveal,
+        None => STAGE_BID.load(storage, round)?,
+    };
+    if !(last_bid_stage.start + last_bid_stage.duration)?.is_triggered(&env.block) {
+        return Err(ContractError::BidStageNotFinished {});
+    }
+    Ok(())
 }
 
-pub fn query_bid(deps: Deps, address: String) -> StdResult<BidResponse> {
-    let bid = BIDS.may_load(deps.storage, &deps.api.addr_validate(&address)?)?;
-    Ok(BidResponse { bid })
+/// Returns the error of a result set with another mechanism than the configured one.
+fn resolution_not_allowed(resolution: &GameResolution) -> ContractError {
+    ContractError::ResolutionNotAllowed { resolution: resolution.name().to_string() }
 }
 
-pub fn query_merkle_root(deps: Deps) -> StdResult<MerkleRootsResponse> {
-    let merkle_root_airdrop = MERKLE_ROOT_AIRDROP.load(deps.storage)?;
-    let total_amount = TOTAL_AIRDROP_AMOUNT.load(deps.storage)?;
-    let merkle_root_game = MERKLE_ROOT_GAME.load(deps.storage)?;
+/// Fails if the claim airdrop stage, the deadline to register the Merkle roots, has
+/// started.
+fn check_roots_deadline(storage: &dyn Storage, env: &Env, round: u64) -> Result<(), ContractError> {
+    let stage_claim_airdrop = STAGE_CLAIM_AIRDROP.load(storage, round)?;
+    if stage_claim_airdrop.start.is_triggered(&env.block) {
+        return Err(ContractError::RootRegistrationClosed {});
+    }
+    Ok(())
+}
 
-    let resp = MerkleRootsResponse {
-        merkle_root_airdrop,
-        total_amount,
-        merkle_root_game
-    };
+/// Returns the number of bidders of the round, bounded by the number of bins.
+fn participants(storage: &dyn Storage, round: u64) -> StdResult<u64> {
+    BIN_COUNTS
+        .prefix(round)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, count)| count))
+        .sum()
+}
 
-    Ok(resp)
+/// Fails if an allowlist is set and the proof does not show that the player is in it.
+fn check_allowlist(cfg: &Config, player: &str, proof: Option<Vec<String>>) -> Result<(), ContractError> {
+    if let Some(merkle_root_allowlist) = &cfg.merkle_root_allowlist {
+        let proof = proof.ok_or(ContractError::AllowlistProofRequired {})?;
+        if !merkle_root_matches(cfg.hash_algorithm, player, proof, merkle_root_allowlist)? {
+            return Err(ContractError::VerificationFailed { merkle_root: "allowlist".to_string() });
+        }
+    }
+    Ok(())
 }
 
-pub fn query_game_amounts(deps: Deps) -> StdResult<GameAmountsResponse> {
-    // Prizes
-    let total_ticket_prize = TOTAL_TICKET_PRIZE.load(deps.storage)?;
-    let total_airdrop_amount = TOTAL_AIRDROP_AMOUNT.load(deps.storage)?;
-    let total_airdrop_game_amount = TOTAL_AIRDROP_GAME_AMOUNT.load(deps.storage)?;
-    // Number of winners
-    let winners_amount = WINNERS.load(deps.storage)?;
-    // Claimed amount.
-    let total_claimed_airdrop = CLAIMED_AIRDROP_AMOUNT.load(deps.storage)?;
-    let total_claimed_prize = CLAIMED_PRIZE_AMOUNT.load(deps.storage)?;
+/// Verifies the coupon of the player against the coupon Merkle root of the round and
+/// marks it as used, returning its discount in basis points.
+fn use_coupon(
+    storage: &mut dyn Storage,
+    round: u64,
+    player: &Addr,
+    coupon: Coupon,
+    hash_algorithm: HashAlgorithm,
+) -> Result<u16, ContractError> {
+    if coupon.discount_bps == 0 || coupon.discount_bps > MAX_BPS {
+        return Err(ContractError::InvalidCoupon {});
+    }
+    let merkle_root_coupon = MERKLE_ROOT_COUPON
+        .may_load(storage, round)?
+        .ok_or(ContractError::CouponRootNotRegistered {})?;
+    if COUPONS_USED.has(storage, (round, player)) {
+        return Err(ContractError::CouponAlreadyUsed {});
+    }
+    let leaf = format!("{}{}", player, coupon.discount_bps);
+    if !merkle_root_matches(hash_algorithm, &leaf, coupon.proof, &merkle_root_coupon)? {
+        return Err(ContractError::VerificationFailed { merkle_root: "coupon".to_string() });
+    }
+    COUPONS_USED.save(storage, (round, player), &true)?;
+    Ok(coupon.discount_bps)
+}
 
-    let resp = GameAmountsResponse {
-        total_ticket_prize,
-        total_airdrop_amount,
-        total_airdrop_game_amount,
-        winners_amount,
-        total_claimed_airdrop,
-        total_claimed_prize
-     };
+/// Fails if a token gate is set and the player holds less than its minimum balance,
+/// queried from the token at bid time.
+fn check_token_gate(querier: &QuerierWrapper, cfg: &Config, player: &Addr) -> Result<(), ContractError> {
+    if let Some((token, min_balance)) = &cfg.bid_token_gate {
+        let balance: BalanceResponse = querier.query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance { address: player.to_string() },
+        )?;
+        if balance.balance < *min_balance {
+            return Err(ContractError::TokenGateNotMet {
+                token: token.to_string(),
+                min_balance: *min_balance,
+            });
+        }
+    }
+    Ok(())
+}
 
-    Ok(resp)
+/// Fails if an NFT gate is set and the player holds no token of its collection.
+fn check_nft_gate(querier: &QuerierWrapper, cfg: &Config, player: &Addr) -> Result<(), ContractError> {
+    if let Some(collection) = &cfg.bid_nft_gate {
+        if !cw721::holds_token(querier, collection, player)? {
+            return Err(ContractError::NftGateNotMet {
+                collection: collection.to_string(),
+            });
+        }
+    }
+    Ok(())
 }
 
-// ======================================================================================
-// Utils
-// ======================================================================================
-pub fn check_if_valid_stage(
-    env: Env,
-    stage: Stage,
-    stage_name: String,
-) -> Result<(), ContractError> {
-    // The stage has not started.
-    if !stage.start.is_triggered(&env.block) {
-        return Err(ContractError::StageNotStarted { stage_name });
+/// Fails if any of the addresses is blocked.
+fn check_not_blocked(storage: &dyn Storage, addresses: &[&Addr]) -> Result<(), ContractError> {
+    for address in addresses {
+        if BLOCKLIST.has(storage, address) {
+            return Err(ContractError::AddressBlocked { address: address.to_string() });
+        }
+    }
+    Ok(())
+}
+
+/// Fails if the round has fewer bidders than the minimum, if any.
+fn check_participants(storage: &dyn Storage, round: u64) -> Result<(), ContractError> {
+    if let Some(min_participants) = MIN_PARTICIPANTS.may_load(storage, round)? {
+        let participants = participants(storage, round)?;
+        if participants < min_participants as u64 {
+            return Err(ContractError::NotEnoughParticipants { participants, min_participants });
+        }
     }
+    Ok(())
+}
 
-    // The stage has ended.
-    let stage_end = (stage.start + stage.duration)?;
-    if stage_end.is_triggered(&env.block) {
-        return Err(ContractError::StageEnded { stage_name });
+/// A round is undersubscribed when its bid stage ends with fewer bidders than the
+/// minimum.
+fn is_undersubscribed(storage: &dyn Storage, env: &Env, round: u64) -> StdResult<bool> {
+    let stage_bid = STAGE_BID.load(storage, round)?;
+    if !(stage_bid.start + stage_bid.duration)?.is_triggered(&env.block) {
+        return Ok(false);
     }
+    match MIN_PARTICIPANTS.may_load(storage, round)? {
+        Some(min_participants) => Ok(participants(storage, round)? < min_participants as u64),
+        None => Ok(false),
+    }
+}
 
+/// Checks that the address belongs to the chain of the expected bech32 prefix, if any.
+pub fn check_address_prefix(prefix: &Option<String>, address: &str) -> Result<(), ContractError> {
+    if let Some(prefix) = prefix {
+        if !address.starts_with(&format!("{}1", prefix)) {
+            return Err(ContractError::InvalidAddressPrefix {
+                address: address.to_string(),
+                prefix: prefix.clone(),
+            });
+        }
+    }
     Ok(())
 }
 
+/// Validates an externally supplied address, checking its bech32 prefix first to
+/// reject addresses from other chains with a clear error.
+pub fn validate_address(
+    deps: Deps,
+    prefix: &Option<String>,
+    address: &str,
+) -> Result<Addr, ContractError> {
+    check_address_prefix(prefix, address)?;
+    Ok(deps.api.addr_validate(address)?)
+}
+
 fn get_amount_for_denom(coins: &[Coin], denom: &str) -> Coin {
     let amount: Uint128 = coins
         .iter()
@@ -708,6 +6264,58 @@ fn get_amount_for_denom(coins: &[Coin], denom: &str) -> Coin {
     }
 }
 
+/// Takes from the available funds up to the ticket price.
+fn take_funds(available_funds: &mut [Coin], ticket_price: &Coin) -> Coin {
+    let mut amount = Uint128::zero();
+    if let Some(coin) = available_funds
+        .iter_mut()
+        .find(|c| c.denom == ticket_price.denom)
+    {
+        amount = coin.amount.min(ticket_price.amount);
+        coin.amount -= amount;
+    }
+    Coin {
+        amount,
+        denom: ticket_price.denom.clone(),
+    }
+}
+
+/// Merges the bank transfers to the same recipient into a single message, placed
+/// after the other messages.
+fn merge_bank_transfers(msgs: Vec<SubMsg>) -> Vec<SubMsg> {
+    let mut merged: Vec<SubMsg> = vec![];
+    let mut transfers: Vec<(String, Vec<Coin>)> = vec![];
+    for msg in msgs {
+        match msg {
+            SubMsg {
+                msg: CosmosMsg::Bank(BankMsg::Send { to_address, amount }),
+                reply_on: ReplyOn::Never,
+                ..
+            } => {
+                let index = match transfers.iter().position(|(to, _)| *to == to_address) {
+                    Some(index) => index,
+                    None => {
+                        transfers.push((to_address, vec![]));
+                        transfers.len() - 1
+                    }
+                };
+                for coin in amount {
+                    let coins = &mut transfers[index].1;
+                    match coins.iter_mut().find(|c| c.denom == coin.denom) {
+                        Some(c) => c.amount += coin.amount,
+                        None => coins.push(coin),
+                    }
+                }
+            }
+            msg => merged.push(msg),
+        }
+    }
+    merged.extend(transfers.into_iter().map(|(to_address, amount)| {
+        SubMsg::new(BankMsg::Send { to_address, amount })
+    }));
+    merged
+}
+
 fn get_bank_transfer_to_msg(recipient: &Addr, denom: &str, native_amount: Uint128) -> CosmosMsg {
     let transfer_bank_msg = cosmwasm_std::BankMsg::Send {
         to_address: recipient.into(),
@@ -721,6 +6329,165 @@ fn get_bank_transfer_to_msg(recipient: &Addr, denom: &str, native_amount: Uint12
     transfer_bank_cosmos_msg
 }
 
+/// Returns the identifier of the ticket asset in the treasury: the cw20 address or the
+/// native denom of the ticket price.
+fn ticket_asset(cfg: &Config, ticket_price: &Coin) -> String {
+    match &cfg.ticket_cw20_address {
+        Some(token_addr) => token_addr.to_string(),
+        None => ticket_price.denom.clone(),
+    }
+}
+
+/// Returns the asset paying the prize of the winners, as recorded in the treasury.
+fn prize_asset(cfg: &Config) -> String {
+    match &cfg.prize_denom {
+        Some(denom) => denom.clone(),
+        None => cfg.cw20_token_address.to_string(),
+    }
+}
+
+/// Returns the message to transfer an amount of the prize asset, either native or cw20.
+fn get_prize_transfer_to_msg(cfg: &Config, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    match &cfg.prize_denom {
+        Some(denom) => Ok(get_bank_transfer_to_msg(recipient, denom, amount)),
+        None => get_cw20_transfer_to_msg(recipient, &cfg.cw20_token_address, amount),
+    }
+}
+
+/// Returns the message to transfer an amount of a prize asset recorded in the treasury:
+/// the prize denom, if any, or a cw20 token.
+fn get_asset_transfer_to_msg(
+    cfg: &Config,
+    asset: &str,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    if cfg.prize_denom.as_deref() == Some(asset) {
+        Ok(get_bank_transfer_to_msg(recipient, asset, amount))
+    } else {
+        get_cw20_transfer_to_msg(recipient, &Addr::unchecked(asset), amount)
+    }
+}
+
+/// Validates the destinations of the revenue split: at least one, distinct, and with
+/// positive shares summing to 1.
+fn validate_revenue_split(
+    deps: Deps,
+    prefix: &Option<String>,
+    split: Vec<(String, Decimal)>,
+) -> Result<Vec<(Addr, Decimal)>, ContractError> {
+    let mut total = Decimal::zero();
+    let mut destinations: Vec<(Addr, Decimal)> = vec![];
+    for (address, share) in split {
+        let address = validate_address(deps, prefix, &address)?;
+        if share.is_zero() || destinations.iter().any(|(a, _)| a == &address) {
+            return Err(ContractError::InvalidRevenueSplit {});
+        }
+        total += share;
+        destinations.push((address, share));
+    }
+    if total != Decimal::one() {
+        return Err(ContractError::InvalidRevenueSplit {});
+    }
+    Ok(destinations)
+}
+
+/// Splits an amount paid out of the revenue of the contract among the destinations of
+/// the revenue split or, if none set, sends it to the given address. The shares are
+/// rounded down, the last destination being paid the remainder. Zero amounts are
+/// skipped.
+fn split_revenue(cfg: &Config, address: Option<&Addr>, amount: Uint128) -> Vec<(Addr, Uint128)> {
+    let mut payouts: Vec<(Addr, Uint128)> = match (&cfg.revenue_split, address) {
+        (Some(split), _) => {
+            let mut left = amount;
+            let mut payouts: Vec<(Addr, Uint128)> = split
+                .iter()
+                .map(|(destination, share)| {
+                    let payout = amount * *share;
+                    left -= payout;
+                    (destination.clone(), payout)
+                })
+                .collect();
+            if let Some((_, payout)) = payouts.last_mut() {
+                *payout += left;
+            }
+            payouts
+        }
+        (None, Some(address)) => vec![(address.clone(), amount)],
+        (None, None) => vec![],
+    };
+    payouts.retain(|(_, payout)| !payout.is_zero());
+    payouts
+}
+
+/// Returns whether an asset recorded in the treasury is a cw20 token: the cw20 token or
+/// the cw20 ticket. Any other asset is a native denom.
+fn is_cw20_asset(cfg: &Config, asset: &str) -> bool {
+    let cw20_ticket = cfg.ticket_cw20_address.as_ref().map(Addr::as_str);
+    asset == cfg.cw20_token_address.as_str() || cw20_ticket == Some(asset)
+}
+
+/// Returns the message to transfer an amount of an asset recorded in the treasury: the
+/// cw20 token, the cw20 ticket or a native denom.
+fn get_treasury_transfer_to_msg(
+    cfg: &Config,
+    asset: &str,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    if is_cw20_asset(cfg, asset) {
+        get_cw20_transfer_to_msg(recipient, &Addr::unchecked(asset), amount)
+    } else {
+        Ok(get_bank_transfer_to_msg(recipient, asset, amount))
+    }
+}
+
+/// Returns the message to burn an amount of an asset recorded in the treasury.
+fn get_treasury_burn_msg(cfg: &Config, asset: &str, amount: Uint128) -> StdResult<CosmosMsg> {
+    if is_cw20_asset(cfg, asset) {
+        let exec_cw20_burn = WasmMsg::Execute {
+            contract_addr: asset.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        };
+        Ok(exec_cw20_burn.into())
+    } else {
+        Ok(BankMsg::Burn { amount: vec![Coin { denom: asset.to_string(), amount }] }.into())
+    }
+}
+
+/// Returns the message to transfer an amount of the ticket asset, either native or cw20.
+fn get_ticket_transfer_to_msg(
+    cfg: &Config,
+    ticket_price: &Coin,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    match &cfg.ticket_cw20_address {
+        Some(token_addr) => get_cw20_transfer_to_msg(recipient, token_addr, amount),
+        None => Ok(get_bank_transfer_to_msg(recipient, &ticket_price.denom, amount)),
+    }
+}
+
+fn get_cw20_send_msg(
+    contract: &Addr,
+    token_addr: &Addr,
+    token_amount: Uint128,
+    msg: Binary,
+) -> StdResult<CosmosMsg> {
+    let send_cw20_msg = Cw20ExecuteMsg::Send {
+        contract: contract.into(),
+        amount: token_amount,
+        msg,
+    };
+    let exec_cw20_send = WasmMsg::Execute {
+        contract_addr: token_addr.into(),
+        msg: to_binary(&send_cw20_msg)?,
+        funds: vec![],
+    };
+    Ok(exec_cw20_send.into())
+}
+
 fn get_cw20_transfer_to_msg(
     recipient: &Addr,
     token_addr: &Addr,
@@ -739,14 +6506,32 @@ fn get_cw20_transfer_to_msg(
     Ok(cw20_transfer_cosmos_msg)
 }
 
+fn get_cw20_transfer_from_msg(
+    owner: &Addr,
+    recipient: &Addr,
+    token_addr: &Addr,
+    token_amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let transfer_from_cw20_msg = Cw20ExecuteMsg::TransferFrom {
+        owner: owner.into(),
+        recipient: recipient.into(),
+        amount: token_amount,
+    };
+    let exec_cw20_transfer_from = WasmMsg::Execute {
+        contract_addr: token_addr.into(),
+        msg: to_binary(&transfer_from_cw20_msg)?,
+        funds: vec![],
+    };
+    Ok(exec_cw20_transfer_from.into())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::state::Stage;
 
     use super::*;
-    use cosmwasm_std::from_binary;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cw_utils::{Duration, Scheduled};
+    use cw_utils::{Duration, Expiration, Scheduled};
 
     fn valid_stages() -> (Stage, Stage, Stage) {
         let stage_bid = Stage {
@@ -764,7 +6549,7 @@ mod tests {
             duration: Duration::Height(2),
         };
 
-        return (stage_bid, stage_claim_airdrop, stage_claim_prize);
+        (stage_bid, stage_claim_airdrop, stage_claim_prize)
     }
     #[test]
     fn proper_instantiation() {
@@ -780,9 +6565,50 @@ mod tests {
                 amount: Uint128::new(10)
             },
             bins: 10,
-            stage_bid: stage_bid,
-            stage_claim_airdrop: stage_claim_airdrop,
-            stage_claim_prize: stage_claim_prize,
+            stage_bid,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus: None,
+            max_winners: None,
+            min_participants: None,
+            price_schedule: None,
+            stage_reveal: None,
+            charity_address: None,
+            address_prefix: None,
+            ticket_cw20_address: None,
+            max_claim_size: None,
+            max_bids: None,
+            max_per_bin: None,
+            parimutuel: None,
+            anti_snipe: None,
+            remove_bid_penalty_bps: None,
+            max_bid_changes: None,
+            bid_change_fee: None,
+            prize_denom: None,
+            prize_vesting: None,
+            staking_contract: None,
+            rollover_grace_period: None,
+            crank_bounty: None,
+            dust_policy: None,
+            protocol_fee_bps: None,
+            fee_collector: None,
+            revenue_split: None,
+            burn_unclaimed: None,
+            claim_deadline: None,
+            merkle_root_allowlist: None,
+            bid_token_gate: None,
+            bid_nft_gate: None,
+            referral_fee_bps: None,
+            season_pass: None,
+            bid_receipts: None,
+            winner_badges: None,
+            attestor: None,
+            resolution: None,
+            adjacent_tier_bps: None,
+            bin_boundaries: None,
+            ibc_denoms: None,
+            ibc_bidder_port: None,
+            hash_algorithm: None,
         };
 
         let env = mock_env();
@@ -797,7 +6623,7 @@ mod tests {
         assert_eq!("owner0000", config.owner.unwrap().as_str());
         assert_eq!("random0000", config.cw20_token_address.as_str());
 
-        let res = query(deps.as_ref(), env, QueryMsg::Stages {}).unwrap();
+        let res = query(deps.as_ref(), env, QueryMsg::Stages { round: None }).unwrap();
         let stages_info: StagesResponse = from_binary(&res).unwrap();
         assert_eq!(Scheduled::AtHeight(200_000), stages_info.stage_bid.start);
     }
@@ -816,16 +6642,57 @@ mod tests {
                 amount: Uint128::new(10)
             },
             bins: 10,
-            stage_bid: stage_bid,
-            stage_claim_airdrop: stage_claim_airdrop,
-            stage_claim_prize: stage_claim_prize,
+            stage_bid,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus: None,
+            max_winners: None,
+            min_participants: None,
+            price_schedule: None,
+            stage_reveal: None,
+            charity_address: None,
+            address_prefix: None,
+            ticket_cw20_address: None,
+            max_claim_size: None,
+            max_bids: None,
+            max_per_bin: None,
+            parimutuel: None,
+            anti_snipe: None,
+            remove_bid_penalty_bps: None,
+            max_bid_changes: None,
+            bid_change_fee: None,
+            prize_denom: None,
+            prize_vesting: None,
+            staking_contract: None,
+            rollover_grace_period: None,
+            crank_bounty: None,
+            dust_policy: None,
+            protocol_fee_bps: None,
+            fee_collector: None,
+            revenue_split: None,
+            burn_unclaimed: None,
+            claim_deadline: None,
+            merkle_root_allowlist: None,
+            bid_token_gate: None,
+            bid_nft_gate: None,
+            referral_fee_bps: None,
+            season_pass: None,
+            bid_receipts: None,
+            winner_badges: None,
+            attestor: None,
+            resolution: None,
+            adjacent_tier_bps: None,
+            bin_boundaries: None,
+            ibc_denoms: None,
+            ibc_bidder_port: None,
+            hash_algorithm: None,
         };
 
         let env = mock_env();
         let info = mock_info("owner0000", &[]);
         let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
-        // Update owner
+        // Update owner, once the new owner accepts it.
         let env = mock_env();
         let info = mock_info("owner0000", &[]);
         let msg = ExecuteMsg::UpdateConfig {
@@ -834,6 +6701,8 @@ mod tests {
 
         let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
         assert_eq!(0, res.messages.len());
+        let accept_msg = ExecuteMsg::UpdateOwnership(OwnershipAction::AcceptOwnership);
+        execute(deps.as_mut(), env.clone(), mock_info("owner0001", &[]), accept_msg).unwrap();
 
         // it worked, let's query the state
         let res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
@@ -848,4 +6717,342 @@ mod tests {
         let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
         assert_eq!(res, ContractError::Unauthorized {});
     }
+
+    #[test]
+    fn update_ownership() {
+        let mut deps = mock_dependencies();
+
+        let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+        let msg = InstantiateMsg {
+            owner: Some("owner0000".to_string()),
+            cw20_token_address: "random0000".to_string(),
+            ticket_price: Coin {
+                denom: "ujuno".into(),
+                amount: Uint128::new(10)
+            },
+            bins: 10,
+            stage_bid,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus: None,
+            max_winners: None,
+            min_participants: None,
+            price_schedule: None,
+            stage_reveal: None,
+            charity_address: None,
+            address_prefix: None,
+            ticket_cw20_address: None,
+            max_claim_size: None,
+            max_bids: None,
+            max_per_bin: None,
+            parimutuel: None,
+            anti_snipe: None,
+            remove_bid_penalty_bps: None,
+            max_bid_changes: None,
+            bid_change_fee: None,
+            prize_denom: None,
+            prize_vesting: None,
+            staking_contract: None,
+            rollover_grace_period: None,
+            crank_bounty: None,
+            dust_policy: None,
+            protocol_fee_bps: None,
+            fee_collector: None,
+            revenue_split: None,
+            burn_unclaimed: None,
+            claim_deadline: None,
+            merkle_root_allowlist: None,
+            bid_token_gate: None,
+            bid_nft_gate: None,
+            referral_fee_bps: None,
+            season_pass: None,
+            bid_receipts: None,
+            winner_badges: None,
+            attestor: None,
+            resolution: None,
+            adjacent_tier_bps: None,
+            bin_boundaries: None,
+            ibc_denoms: None,
+            ibc_bidder_port: None,
+            hash_algorithm: None,
+        };
+
+        let env = mock_env();
+        let info = mock_info("owner0000", &[]);
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        // Propose a new owner, the owner is unchanged until the proposal is accepted.
+        let env = mock_env();
+        let accept_msg = ExecuteMsg::UpdateOwnership(OwnershipAction::AcceptOwnership);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner0001", &[]), accept_msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::OwnershipTransferNotFound {});
+
+        let msg = ExecuteMsg::UpdateOwnership(OwnershipAction::TransferOwnership {
+            new_owner: "owner0001".to_string(),
+            expiry: Some(Expiration::AtHeight(env.block.height + 10)),
+        });
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner0001", &[]), msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner0000", &[]), msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Ownership {}).unwrap();
+        let ownership: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(Some("owner0000".to_string()), ownership.owner);
+        assert_eq!(Some("owner0001".to_string()), ownership.pending_owner);
+
+        // Only the proposed owner can accept, before the expiry.
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner0002", &[]), accept_msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::NotPendingOwner {});
+        let mut expired_env = env.clone();
+        expired_env.block.height += 10;
+        let res = execute(deps.as_mut(), expired_env, mock_info("owner0001", &[]), accept_msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::OwnershipTransferExpired {});
+        execute(deps.as_mut(), env.clone(), mock_info("owner0001", &[]), accept_msg).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Ownership {}).unwrap();
+        let ownership: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(Some("owner0001".to_string()), ownership.owner);
+        assert_eq!(None, ownership.pending_owner);
+
+        // Unauthorized err
+        let msg = ExecuteMsg::UpdateOwnership(OwnershipAction::RenounceOwnership);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner0000", &[]), msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // Renounced, the contract gets locked.
+        execute(deps.as_mut(), env.clone(), mock_info("owner0001", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(None, config.owner);
+    }
+
+    #[test]
+    fn address_prefix_validation() {
+        let mut deps = mock_dependencies();
+
+        let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+        let mut msg = InstantiateMsg {
+            owner: Some("osmo1k9hwzxs889jpvd7env8z49gad3a3633vg350tq".to_string()),
+            cw20_token_address: "wasm1a4x6au55s0fusctyj2ulrxvfpmjcxa92k7ze2v".to_string(),
+            ticket_price: Coin {
+                denom: "ujuno".into(),
+                amount: Uint128::new(10)
+            },
+            bins: 10,
+            stage_bid,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus: None,
+            max_winners: None,
+            min_participants: None,
+            price_schedule: None,
+            stage_reveal: None,
+            charity_address: None,
+            address_prefix: Some("wasm".to_string()),
+            ticket_cw20_address: None,
+            max_claim_size: None,
+            max_bids: None,
+            max_per_bin: None,
+            parimutuel: None,
+            anti_snipe: None,
+            remove_bid_penalty_bps: None,
+            max_bid_changes: None,
+            bid_change_fee: None,
+            prize_denom: None,
+            prize_vesting: None,
+            staking_contract: None,
+            rollover_grace_period: None,
+            crank_bounty: None,
+            dust_policy: None,
+            protocol_fee_bps: None,
+            fee_collector: None,
+            revenue_split: None,
+            burn_unclaimed: None,
+            claim_deadline: None,
+            merkle_root_allowlist: None,
+            bid_token_gate: None,
+            bid_nft_gate: None,
+            referral_fee_bps: None,
+            season_pass: None,
+            bid_receipts: None,
+            winner_badges: None,
+            attestor: None,
+            resolution: None,
+            adjacent_tier_bps: None,
+            bin_boundaries: None,
+            ibc_denoms: None,
+            ibc_bidder_port: None,
+            hash_algorithm: None,
+        };
+
+        // Owner from another chain is rejected.
+        let env = mock_env();
+        let info = mock_info("wasm1uy9ucvgerneekxpnfwyfnpxvlsx5dzdpf0mzjd", &[]);
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InvalidAddressPrefix {
+                address: "osmo1k9hwzxs889jpvd7env8z49gad3a3633vg350tq".to_string(),
+                prefix: "wasm".to_string(),
+            }
+        );
+
+        msg.owner = None;
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // New owner from another chain is rejected.
+        let msg = ExecuteMsg::UpdateOwnership(OwnershipAction::TransferOwnership {
+            new_owner: "juno1k9hwzxs889jpvd7env8z49gad3a3633vg350tq".to_string(),
+            expiry: None,
+        });
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InvalidAddressPrefix {
+                address: "juno1k9hwzxs889jpvd7env8z49gad3a3633vg350tq".to_string(),
+                prefix: "wasm".to_string(),
+            }
+        );
+
+        let msg = ExecuteMsg::UpdateOwnership(OwnershipAction::TransferOwnership {
+            new_owner: "wasm1k9hwzxs889jpvd7env8z49gad3a3633vg350tq".to_string(),
+            expiry: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!("wasm", config.address_prefix.unwrap().as_str());
+    }
+
+    #[test]
+    fn prediction_bin_maps_the_boundaries() {
+        let bin_boundaries = vec![Uint128::new(100), Uint128::new(200), Uint128::new(300)];
+        let bin = |prediction: u128| prediction_bin(&bin_boundaries, Uint128::new(prediction));
+        assert_eq!(bin(0), 1);
+        assert_eq!(bin(99), 1);
+        assert_eq!(bin(100), 2);
+        assert_eq!(bin(299), 3);
+        assert_eq!(bin(300), 4);
+        assert_eq!(bin(u128::MAX), 4);
+        assert_eq!(prediction_bin(&[], Uint128::new(7)), 1);
+    }
+
+    #[test]
+    fn validate_ibc_denom_checks_the_hash() {
+        let hash = "27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+        assert!(validate_ibc_denom(&format!("ibc/{}", hash)).is_ok());
+        assert!(validate_ibc_denom(&format!("ibc/{}", hash.to_lowercase())).is_ok());
+        assert!(validate_ibc_denom(&format!("ibc/{}", &hash[1..])).is_err());
+        assert!(validate_ibc_denom(&format!("ibc/{}Z", &hash[1..])).is_err());
+        assert!(validate_ibc_denom(&format!("transfer/{}", hash)).is_err());
+        assert!(validate_ibc_denom("ujuno").is_err());
+    }
+
+    #[cfg(feature = "ibc")]
+    #[test]
+    fn ibc_bids() {
+        use crate::ibc::{self, IbcBidAck, IbcBidPacket, IBC_VERSION};
+        use cosmwasm_std::testing::{mock_ibc_channel, mock_ibc_packet_recv};
+        use cosmwasm_std::{IbcChannel, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcOrder};
+
+        let mut deps = mock_dependencies();
+        let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+        let msg = InstantiateMsg {
+            owner: Some("owner0000".to_string()),
+            cw20_token_address: "random0000".to_string(),
+            ticket_price: Coin {
+                denom: "ujuno".into(),
+                amount: Uint128::new(10)
+            },
+            bins: 10,
+            stage_bid,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus: None,
+            max_winners: None,
+            min_participants: None,
+            price_schedule: None,
+            stage_reveal: None,
+            charity_address: None,
+            address_prefix: None,
+            ticket_cw20_address: None,
+            max_claim_size: None,
+            max_bids: None,
+            max_per_bin: None,
+            parimutuel: None,
+            anti_snipe: None,
+            remove_bid_penalty_bps: None,
+            max_bid_changes: None,
+            bid_change_fee: None,
+            prize_denom: None,
+            prize_vesting: None,
+            staking_contract: None,
+            rollover_grace_period: None,
+            crank_bounty: None,
+            dust_policy: None,
+            protocol_fee_bps: None,
+            fee_collector: None,
+            revenue_split: None,
+            burn_unclaimed: None,
+            claim_deadline: None,
+            merkle_root_allowlist: None,
+            bid_token_gate: None,
+            bid_nft_gate: None,
+            referral_fee_bps: None,
+            season_pass: None,
+            bid_receipts: None,
+            winner_badges: None,
+            attestor: None,
+            resolution: Some(Resolution::OwnerSetsBin {}),
+            adjacent_tier_bps: None,
+            bin_boundaries: None,
+            ibc_denoms: None,
+            ibc_bidder_port: Some("wasm.sender".to_string()),
+            hash_algorithm: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+        // Only unordered channels of the game version opened by the bidder port.
+        let mut channel = mock_ibc_channel("channel-1", IbcOrder::Unordered, IBC_VERSION);
+        let open = |channel: &IbcChannel| IbcChannelOpenMsg::new_try(channel.clone(), IBC_VERSION);
+        assert!(ibc::ibc_channel_open(deps.as_mut(), mock_env(), open(&channel)).is_err());
+        channel.counterparty_endpoint.port_id = "wasm.sender".to_string();
+        ibc::ibc_channel_open(deps.as_mut(), mock_env(), open(&channel)).unwrap();
+        let mut ordered = channel.clone();
+        ordered.order = IbcOrder::Ordered;
+        assert!(ibc::ibc_channel_open(deps.as_mut(), mock_env(), open(&ordered)).is_err());
+        let msg = IbcChannelOpenMsg::new_try(channel.clone(), "ics20-1");
+        assert!(ibc::ibc_channel_open(deps.as_mut(), mock_env(), msg).is_err());
+        let msg = IbcChannelConnectMsg::new_ack(channel, IBC_VERSION);
+        ibc::ibc_channel_connect(deps.as_mut(), mock_env(), msg).unwrap();
+
+        // The bids of the packets are recorded for the channel and stake nothing, the
+        // tickets being escrowed by the sender. A rejected bid leaves no state.
+        let mut env = mock_env();
+        env.block.height = 200_001;
+        let packet = |bin: u8| IbcBidPacket {
+            player: "osmo1player".to_string(),
+            bin,
+            tickets: Some(2),
+            allowlist_proof: None,
+            round: None,
+        };
+        let msg = mock_ibc_packet_recv("channel-1", &packet(11)).unwrap();
+        let res = ibc::ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcBidAck = from_binary(&res.acknowledgement).unwrap();
+        assert_eq!(ack, IbcBidAck::Error(ContractError::BinDoesNotExist { bins: 10 }.to_string()));
+        assert!(!REMOTE_BIDS.has(&deps.storage, (1, "channel-1", "osmo1player")));
+
+        let msg = mock_ibc_packet_recv("channel-1", &packet(3)).unwrap();
+        let res = ibc::ibc_packet_receive(deps.as_mut(), env, msg).unwrap();
+        let ack: IbcBidAck = from_binary(&res.acknowledgement).unwrap();
+        assert_eq!(ack, IbcBidAck::Result(Binary::from(b"1".to_vec())));
+        let bid = REMOTE_BIDS.load(&deps.storage, (1, "channel-1", "osmo1player")).unwrap();
+        assert_eq!(bid, RemoteBid { bin: 3, tickets: 2 });
+        assert!(ticket_prize(&deps.storage, 1).unwrap().is_zero());
+    }
 }
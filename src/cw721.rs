@@ -0,0 +1,125 @@
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, QuerierWrapper, StdResult, WasmMsg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ======================================================================================
+// Cw721 queries
+// ======================================================================================
+/// Subset of the cw721 query messages used to gate the bids on the holders of a
+/// collection.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721QueryMsg {
+    /// Tokens owned by the address, in ascending order.
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
+
+/// Returns true if the owner holds at least one token of the collection.
+pub fn holds_token(querier: &QuerierWrapper, collection: &Addr, owner: &Addr) -> StdResult<bool> {
+    let msg = Cw721QueryMsg::Tokens {
+        owner: owner.to_string(),
+        start_after: None,
+        limit: Some(1),
+    };
+    let res: TokensResponse = querier.query_wasm_smart(collection, &msg)?;
+    Ok(!res.tokens.is_empty())
+}
+
+// ======================================================================================
+// Cw721 receipts
+// ======================================================================================
+/// Subset of the cw721 execute messages used to mint and burn the bid receipts. The
+/// game must be the minter of the collection.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721ExecuteMsg {
+    /// Mints a new token to the owner.
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: Option<Metadata>,
+    },
+    /// Burns the token.
+    Burn { token_id: String },
+}
+
+/// Subset of the on-chain metadata of the cw721 tokens, following the OpenSea metadata
+/// standard.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Metadata {
+    pub attributes: Option<Vec<Trait>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Trait {
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
+
+/// Id of the receipt of the bid of the player in the round.
+pub fn receipt_token_id(round: u64, player: &Addr) -> String {
+    format!("{}-{}", round, player)
+}
+
+/// Message minting to the player the receipt of their bid in the round.
+pub fn mint_receipt_msg(collection: &Addr, round: u64, player: &Addr) -> StdResult<CosmosMsg> {
+    let msg = Cw721ExecuteMsg::Mint {
+        token_id: receipt_token_id(round, player),
+        owner: player.to_string(),
+        token_uri: None,
+        extension: None,
+    };
+    execute_msg(collection, &msg)
+}
+
+/// Message burning the receipt of the bid of the player in the round.
+pub fn burn_receipt_msg(collection: &Addr, round: u64, player: &Addr) -> StdResult<CosmosMsg> {
+    let msg = Cw721ExecuteMsg::Burn {
+        token_id: receipt_token_id(round, player),
+    };
+    execute_msg(collection, &msg)
+}
+
+/// Id of the badge of the winner of the round.
+pub fn badge_token_id(round: u64, winner: &Addr) -> String {
+    format!("badge-{}-{}", round, winner)
+}
+
+/// Message minting to the winner the badge of the round, recording the round and the
+/// winning bin as attributes.
+pub fn mint_badge_msg(collection: &Addr, round: u64, winner: &Addr, bin: u8) -> StdResult<CosmosMsg> {
+    let attribute = |trait_type: &str, value: String| Trait {
+        display_type: None,
+        trait_type: trait_type.to_string(),
+        value,
+    };
+    let msg = Cw721ExecuteMsg::Mint {
+        token_id: badge_token_id(round, winner),
+        owner: winner.to_string(),
+        token_uri: None,
+        extension: Some(Metadata {
+            attributes: Some(vec![attribute("round", round.to_string()), attribute("bin", bin.to_string())]),
+        }),
+    };
+    execute_msg(collection, &msg)
+}
+
+fn execute_msg(collection: &Addr, msg: &Cw721ExecuteMsg) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: collection.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }
+    .into())
+}
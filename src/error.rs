@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use hex::FromHexError;
 use thiserror::Error;
 
@@ -13,18 +13,88 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("No ownership transfer is pending")]
+    OwnershipTransferNotFound {},
+
+    #[error("The ownership has not been transferred to the sender")]
+    NotPendingOwner {},
+
+    #[error("The ownership transfer has expired")]
+    OwnershipTransferExpired {},
+
+    #[error("The contract is paused")]
+    ContractPaused {},
+
+    #[error("The contract is not paused")]
+    ContractNotPaused {},
+
+    #[error("The address {address} is blocked")]
+    AddressBlocked { address: String },
+
     #[error("Invalid input")]
     InvalidInput {},
 
     #[error("Already claimed")]
     AlreadyClaimed {},
 
+    #[error("Address {address} does not have the expected prefix {prefix}")]
+    InvalidAddressPrefix { address: String, prefix: String },
+
+    #[error("Claiming {amount} would exceed the total airdrop of {total}, of which {claimed} is claimed")]
+    AirdropTotalExceeded {
+        total: Uint128,
+        claimed: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Claim proofs of {size} bytes exceed the limit of {limit} bytes: register them in parts with RegisterClaimProofs, then claim with empty proofs")]
+    ClaimTooLarge { size: u64, limit: u32 },
+
+    #[error("The claim is not signed by {claimer}")]
+    InvalidClaimSignature { claimer: String },
+
     #[error("Wrong length")]
     WrongLength {},
 
     #[error("Verification failed for {merkle_root}")]
     VerificationFailed { merkle_root: String },
 
+    #[error("Merkle roots already registered for the round")]
+    MerkleRootsAlreadyRegistered {},
+
+    #[error("No {merkle_root} Merkle root registered for the round")]
+    MerkleRootNotRegistered { merkle_root: String },
+
+    #[error("Merkle roots can no longer be rotated once the claims are open")]
+    RootRotationClosed {},
+
+    #[error("Merkle roots must be registered before the claim airdrop stage starts")]
+    RootRegistrationClosed {},
+
+    #[error("The game result of the round is already set")]
+    GameAlreadyResolved {},
+
+    #[error("The game result of the rounds is set with the {resolution} resolution")]
+    ResolutionNotAllowed { resolution: String },
+
+    #[error("Price oracle bins must have a positive width")]
+    InvalidPriceOracle {},
+
+    #[error("Randomness of the round is already requested")]
+    RandomnessAlreadyRequested {},
+
+    #[error("No randomness job {job_id} is pending")]
+    UnknownRandomnessJob { job_id: String },
+
+    #[error("Prize tokens must be distinct cw20 tokens, other than the airdrop token")]
+    InvalidPrizeTokens {},
+
+    #[error("The prize denom is already set")]
+    PrizeDenomAlreadySet {},
+
+    #[error("The contract is built without the tokenfactory feature")]
+    TokenFactoryDisabled {},
+
     #[error("Cannot migrate from different contract type: {previous_contract}")]
     CannotMigrate { previous_contract: String },
 
@@ -32,19 +102,89 @@ pub enum ContractError {
     #[error("Not eligible to claim game prize")]
     NoteEligible {},
 
+    #[error("The round has no winners, its jackpot can be rolled over")]
+    NoWinners {},
+
+    #[error("The round has already been finalized")]
+    AlreadyFinalized {},
+
+    #[error("Claim airdrop stage is not over yet")]
+    ClaimAirdropStageNotFinished {},
+
+    #[error("Bid stage is not over yet")]
+    BidStageNotFinished {},
+
+    #[error("Donation share must be between 1 and 10000 basis points")]
+    InvalidDonationShare {},
+
     #[error("Claim Prize stage is not over yet")]
     ClaimPrizeStageNotFinished {},
 
+    #[error("The prize vesting duration must be greater than zero")]
+    InvalidPrizeVesting {},
+
+    #[error("Nothing vested to release")]
+    NothingVested {},
+
+    #[error("The prize cannot be staked: no staking contract, or the prize is vested or not a cw20")]
+    PrizeNotStakeable {},
+
+    #[error("Round is not aborted: the Merkle roots have been registered or the claim airdrop stage is not over yet")]
+    RoundNotAborted {},
+
+    #[error("Round has been cancelled")]
+    RoundCancelled {},
+
+    #[error("The jackpot can be rolled over once, when the claim airdrop stage of a round not aborted ends without winners")]
+    JackpotNotRollable {},
+
+    #[error("The rollover can be withdrawn only once the grace period of a jackpot rolled over has expired")]
+    RolloverLocked {},
+
+    #[error("The claim deadline must be greater than zero")]
+    InvalidClaimDeadline {},
+
+    #[error("The game has no claim deadline")]
+    NoClaimDeadline {},
+
+    #[error("The claim deadline has not been reached yet")]
+    ClaimDeadlineNotReached {},
+
+    #[error("The claim deadline has passed")]
+    ClaimDeadlinePassed {},
+
+    #[error("The prizes not claimed have already been redistributed")]
+    AlreadyRedistributed {},
+
+    #[error("Cannot enable the refunds once the claims have started")]
+    ClaimsStarted {},
+
+    #[error("Round has {participants} bidders, fewer than the minimum of {min_participants}")]
+    NotEnoughParticipants { participants: u64, min_participants: u32 },
+
+    // Rebate errors.
+    #[error("Rebate pool must be funded with a single native token, the one of the rebate, and a non zero rebate")]
+    InvalidRebateFunds {},
+
     // General stage errors.
     #[error("The {stage_name} has not started")]
     StageNotStarted { stage_name: String },
 
+    #[error("The {stage_name} has started")]
+    StageStarted { stage_name: String },
+
     #[error("The {stage_name} has ended")]
     StageEnded { stage_name: String },
 
     #[error("{second} stage overlaps {first} stage.")]
     StagesOverlap { first: String, second: String },
 
+    #[error("Extra duration must be greater than zero")]
+    InvalidExtraDuration {},
+
+    #[error("The {stage_name} stage must end after it starts")]
+    InvalidStageEnd { stage_name: String },
+
     // Bid errors.
     #[error("Bid stage cannot start in the past.")]
     BidStartPassed {},
@@ -52,12 +192,215 @@ pub enum ContractError {
     #[error("Fund sent insufficent for paying the bid price")]
     TicketPriceNotPaid {},
 
+    #[error("The ticket must be paid with the configured ticket asset")]
+    InvalidTicketAsset {},
+
+    #[error("Max winners must be at least 1")]
+    InvalidMaxWinners {},
+
+    #[error("The price schedule must have non zero prices, ordered by start")]
+    InvalidPriceSchedule {},
+
     #[error("Cannot be placed more than one bid per address")]
     CannotBidMoreThanOnce {},
 
+    #[error("Bid limits must be at least 1")]
+    InvalidBidLimit {},
+
+    #[error("The anti-sniping window and extension must be at least 1 block")]
+    InvalidAntiSnipe {},
+
+    #[error("The remove bid penalty cannot exceed 10000 basis points")]
+    InvalidRemoveBidPenalty {},
+
+    #[error("The crank bounty must be greater than zero")]
+    InvalidCrankBounty {},
+
+    #[error("The bid change fee must be greater than zero")]
+    InvalidBidChangeFee {},
+
+    #[error("The protocol fee cannot exceed {max} basis points")]
+    InvalidProtocolFee { max: u16 },
+
+    #[error("A fee collector is required to take a protocol fee")]
+    FeeCollectorRequired {},
+
+    #[error("The first bidder bonus is paid from the protocol fee, which is not set")]
+    FirstBidderBonusWithoutFee {},
+
+    #[error("The referral fee cannot exceed 10000 basis points")]
+    InvalidReferralFee {},
+
+    #[error("The adjacent tier cannot exceed 10000 basis points and requires a winning bin set on chain")]
+    InvalidAdjacentTier {},
+
+    #[error("The revenue split needs distinct destinations with shares summing to 1")]
+    InvalidRevenueSplit {},
+
+    #[error("The unclaimed tokens are not burned by this game")]
+    BurnUnclaimedDisabled {},
+
+    #[error("A bid cannot be changed more than {limit} times")]
+    TooManyBidChanges { limit: u32 },
+
+    #[error("The bid change fee was not paid")]
+    BidChangeFeeNotPaid {},
+
+    #[error("Cannot be placed more than {limit} bids per round")]
+    TooManyBids { limit: u32 },
+
+    #[error("Bin {bin} already holds the maximum of {limit} bids")]
+    BinFull { bin: u8, limit: u32 },
+
     #[error("A bid must be placed before changing it")]
     BidNotPresent {},
 
+    #[error("A bid must be placed on at least one bin, without repeating bins")]
+    InvalidBins {},
+
+    #[error("The bin boundaries must be {expected} ascending values, one less than the bins")]
+    InvalidBinBoundaries { expected: u8 },
+
+    #[error("No bin boundaries are set to map the predictions to bins")]
+    NoBinBoundaries {},
+
+    #[error("Invalid IBC denom {denom}: expected ibc/ followed by 64 hex characters")]
+    InvalidIbcDenom { denom: String },
+
+    #[error("The IBC denom {denom} is not accepted as ticket")]
+    IbcDenomNotAccepted { denom: String },
+
+    #[error("Bids cannot be placed with IBC packets in parimutuel mode or with the Merkle root resolution")]
+    RemoteBidsNotAllowed {},
+
+    #[error("A bid placed with an IBC packet buys at most {max} tickets")]
+    TooManyRemoteTickets { max: u32 },
+
+    #[error("Invalid IBC channel: {reason}")]
+    InvalidIbcChannel { reason: String },
+
+    #[error("A bid is placed either on a bin or on a prediction, the bin being 0")]
+    BinAndPrediction {},
+
+    #[error("A bid on several bins cannot be changed, it can be removed")]
+    CannotChangeMultipleBins {},
+
+    #[error("A bid must buy at least one ticket")]
+    InvalidTickets {},
+
+    #[error("The cost of {tickets} tickets on {bins} bins overflows")]
+    BidCostOverflow { tickets: u32, bins: u32 },
+
+    #[error("A player cannot refer itself")]
+    SelfReferral {},
+
+    #[error("An allowlist proof is required to bid")]
+    AllowlistProofRequired {},
+
+    #[error("Token gate must have a positive minimum balance")]
+    InvalidTokenGate {},
+
+    #[error("The player must hold at least {min_balance} {token} to bid")]
+    TokenGateNotMet { token: String, min_balance: Uint128 },
+
+    #[error("The player must hold a token of the {collection} collection to bid")]
+    NftGateNotMet { collection: String },
+
+    // Commit-reveal errors.
+    #[error("The bids of a round with a reveal stage must be committed")]
+    CommitRequired {},
+
+    #[error("The round has no reveal stage")]
+    RevealStageNotSet {},
+
+    #[error("A bid must be committed before revealing it")]
+    CommitmentNotPresent {},
+
+    #[error("The revealed bin and salt do not match the committed hash")]
+    InvalidReveal {},
+
+    // Stake errors.
+    #[error("The stake Merkle root must be registered before the bid stage starts")]
+    StakeRootAfterBidStart {},
+
+    #[error("No stake Merkle root registered")]
+    StakeRootNotRegistered {},
+
+    #[error("Stake weight must be at least 1")]
+    InvalidStakeWeight {},
+
+    // Coupon errors.
+    #[error("No coupon Merkle root registered")]
+    CouponRootNotRegistered {},
+
+    #[error("Coupon discount must be between 1 and 10000 basis points")]
+    InvalidCoupon {},
+
+    #[error("The coupon of the player is already used")]
+    CouponAlreadyUsed {},
+
+    // Season pass errors.
+    #[error("Season pass must enter at least one round for a positive native price")]
+    InvalidSeasonPass {},
+
+    #[error("No season pass is sold")]
+    SeasonPassNotOffered {},
+
+    #[error("A season pass entry pays a single ticket")]
+    SeasonPassSingleTicket {},
+
+    // Loyalty errors.
+    #[error("Loyalty tiers need distinct positive points and discounts between 1 and 10000 basis points")]
+    InvalidLoyaltyTiers {},
+
+    // Bin multiplier errors.
+    #[error("Bin multipliers need distinct positive bins and multipliers between 0 and {max}")]
+    InvalidBinMultipliers { max: u64 },
+
+    #[error("The bin multipliers cannot change once the claim airdrop stage has started")]
+    BinMultipliersLocked {},
+
+    #[error("The bin multipliers have no effect when the round resolves to a single winning bin")]
+    BinMultipliersSingleBin {},
+
+    #[error("The contract holds {balance}{asset}, less than the {required}{asset} of the prizes of the round")]
+    PrizesNotFunded {
+        asset: String,
+        balance: Uint128,
+        required: Uint128,
+    },
+
+    #[error("The prizes paid to the winners exceed the funds escrowed for the round")]
+    PayoutsExceedEscrow {},
+
+    // Sponsorship errors.
+    #[error("Sponsorship must have a positive match ratio and cap")]
+    InvalidSponsorship {},
+
+    #[error("The prize pool must be funded with a single positive amount of the ticket asset or of the prize asset")]
+    InvalidPrizePoolFunds {},
+
+    #[error("Cannot be made more than one sponsorship per sponsor")]
+    SponsorshipAlreadyPresent {},
+
+    #[error("No sponsorship present for the sender")]
+    SponsorshipNotPresent {},
+
+    #[error("Cannot be made more than {limit} sponsorships per round")]
+    TooManySponsorships { limit: u32 },
+
+    // Treasury errors.
+    #[error("The {bucket} bucket holds {balance}{asset}, cannot pay {amount}{asset}")]
+    BucketOverdrawn {
+        bucket: String,
+        asset: String,
+        balance: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Only {available}{asset} left can be withdrawn")]
+    WithdrawExceedsLeftovers { asset: String, available: Uint128 },
+
     #[error("InsufficientFunds")]
     InsufficientFunds {},
     
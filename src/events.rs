@@ -0,0 +1,140 @@
+use cosmwasm_std::{Attribute, Event, Response, Uint128};
+
+// ======================================================================================
+// Attribute keys
+// ======================================================================================
+pub const ACTION: &str = "action";
+pub const PLAYER: &str = "player";
+pub const PAYER: &str = "payer";
+pub const RECIPIENT: &str = "recipient";
+pub const RELAYER: &str = "relayer";
+pub const BIN: &str = "bin";
+pub const NEW_BIN: &str = "new_bin";
+pub const TICKETS: &str = "tickets";
+pub const COMMITMENT: &str = "commitment";
+pub const STAGE_EXTENSION: &str = "stage_extension";
+pub const AMOUNT: &str = "amount";
+pub const STAGE: &str = "stage";
+pub const ROUND: &str = "round";
+pub const ADDRESS: &str = "address";
+pub const OWNER: &str = "owner";
+pub const PENDING_OWNER: &str = "pending_owner";
+pub const ASSET: &str = "asset";
+pub const ACTIONS: &str = "actions";
+pub const REFUNDED_BIDS: &str = "refunded_bids";
+pub const MERKLE_ROOT_STAKE: &str = "merkle_root_stake";
+pub const STAKE_WEIGHT: &str = "stake_weight";
+pub const MERKLE_ROOT_COUPON: &str = "merkle_root_coupon";
+pub const COUPON_DISCOUNT_BPS: &str = "coupon_discount_bps";
+pub const REFERRER: &str = "referrer";
+pub const TICKET_PRICE_PAYBACK: &str = "ticket_price_payback";
+pub const REMOVE_BID_PENALTY: &str = "remove_bid_penalty";
+pub const BID_CHANGE_FEE: &str = "bid_change_fee";
+pub const MERKLE_ROOT_AIRDROP: &str = "merkle_root_airdrop";
+pub const TOTAL_AMOUNT_AIRDROP: &str = "total_amount_airdrop";
+pub const MERKLE_ROOT_GAME: &str = "merkle_root_game";
+pub const OLD_MERKLE_ROOT_AIRDROP: &str = "old_merkle_root_airdrop";
+pub const OLD_MERKLE_ROOT_GAME: &str = "old_merkle_root_game";
+pub const WINNING_BIN: &str = "winning_bin";
+pub const PRICE: &str = "price";
+pub const JOB_ID: &str = "job_id";
+pub const RANDOMNESS: &str = "randomness";
+pub const AIRDROP_AMOUNT: &str = "airdrop_amount";
+pub const PRIZE_FROM_TICKETS: &str = "prize_from_tickets";
+pub const PRIZE_FROM_AIRDROP: &str = "prize_from_airdrop";
+pub const FIRST_BIDDER_BONUS: &str = "first_bidder_bonus";
+pub const DONATED_FROM_TICKETS: &str = "donated_from_tickets";
+pub const DONATED_FROM_AIRDROP: &str = "donated_from_airdrop";
+pub const SPONSOR: &str = "sponsor";
+pub const CONTRIBUTOR: &str = "contributor";
+pub const TOKEN: &str = "token";
+pub const MATCH_RATIO: &str = "match_ratio";
+pub const PRIZE_FROM_SPONSORS: &str = "prize_from_sponsors";
+pub const DONATED_FROM_SPONSORS: &str = "donated_from_sponsors";
+pub const PRIZE_TOKENS: &str = "prize_tokens";
+pub const PRIZE_DENOM: &str = "prize_denom";
+pub const MINTED: &str = "minted";
+pub const VESTING: &str = "vesting";
+pub const RELEASED: &str = "released";
+pub const STAKED: &str = "staked";
+pub const REENTRY: &str = "reentry";
+pub const ROLLOVER_FROM_TICKETS: &str = "rollover_from_tickets";
+pub const ROLLOVER_FROM_AIRDROP: &str = "rollover_from_airdrop";
+pub const ROLLOVER: &str = "rollover";
+pub const WINNERS: &str = "winners";
+pub const DUST_FROM_TICKETS: &str = "dust_from_tickets";
+pub const DUST_FROM_AIRDROP: &str = "dust_from_airdrop";
+pub const BOUNTY: &str = "bounty";
+pub const DUST_POLICY: &str = "dust_policy";
+pub const PROTOCOL_FEE: &str = "protocol_fee";
+pub const ADJACENT_PRIZE: &str = "adjacent_prize";
+pub const REFERRAL_FEE: &str = "referral_fee";
+pub const PRIZE_FROM_TOKENS: &str = "prize_from_tokens";
+pub const DONATED_FROM_TOKENS: &str = "donated_from_tokens";
+pub const NOTICES: &str = "notices";
+pub const REBATE: &str = "rebate";
+pub const DEPRECATED: &str = "deprecated";
+pub const BLOCKED: &str = "blocked";
+pub const UNBLOCKED: &str = "unblocked";
+pub const ACCEPTED_DENOM: &str = "accepted_denom";
+pub const REMOVED_DENOM: &str = "removed_denom";
+pub const LOYALTY_TIERS: &str = "loyalty_tiers";
+pub const LOYALTY_DISCOUNT_BPS: &str = "loyalty_discount_bps";
+pub const BIN_MULTIPLIERS: &str = "bin_multipliers";
+pub const SEASON_PASS_ENTRIES: &str = "season_pass_entries";
+pub const RECEIPT: &str = "receipt";
+pub const CHANNEL_ID: &str = "channel_id";
+
+// ======================================================================================
+// Builder
+// ======================================================================================
+/// Builder of the attributes describing an action of the game. Keys are taken from the
+/// constants of this module, so that indexers can rely on them.
+pub struct GameEvent {
+    action: String,
+    attributes: Vec<Attribute>,
+}
+
+impl GameEvent {
+    pub fn new(action: &str) -> Self {
+        GameEvent {
+            action: action.to_string(),
+            attributes: vec![],
+        }
+    }
+
+    pub fn add(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.attributes.push(Attribute::new(key, value));
+        self
+    }
+
+    pub fn player(self, player: impl Into<String>) -> Self {
+        self.add(PLAYER, player)
+    }
+
+    pub fn bin(self, bin: u8) -> Self {
+        self.add(BIN, bin.to_string())
+    }
+
+    pub fn amount(self, amount: Uint128) -> Self {
+        self.add(AMOUNT, amount)
+    }
+
+    pub fn stage(self, stage: &str) -> Self {
+        self.add(STAGE, stage)
+    }
+
+    pub fn round(self, round: u64) -> Self {
+        self.add(ROUND, round.to_string())
+    }
+
+    /// Adds the action to the response both as a structured event, named after the
+    /// action, and as the legacy flat attributes. The legacy attributes are kept until
+    /// indexers have moved to the events.
+    pub fn apply(self, res: Response) -> Response {
+        let event = Event::new(&self.action).add_attributes(self.attributes.clone());
+        res.add_attribute(ACTION, self.action)
+            .add_attributes(self.attributes)
+            .add_event(event)
+    }
+}
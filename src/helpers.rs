@@ -1,46 +1,179 @@
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult, WasmMsg, WasmQuery,
+    to_binary, Addr, Coin, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult, Uint128, WasmMsg,
+    WasmQuery,
 };
+use cw20::Cw20ExecuteMsg;
 
-use crate::msg::{CountResponse, ExecuteMsg, QueryMsg};
+use crate::msg::{
+    BidResponse, ConfigResponse, ExecuteMsg, GameStatusResponse, QueryMsg, ReceiveMsg, RoundResponse,
+    StagesResponse,
+};
 
-/// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
-/// for working with this.
+/// WasmGameContract is a wrapper around Addr that provides a lot of helpers
+/// for working with a deployed game, mirroring `Cw20Contract`. The messages and
+/// queries refer to the current round of the game.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct CwTemplateContract(pub Addr);
+pub struct WasmGameContract(pub Addr);
 
-impl CwTemplateContract {
+impl WasmGameContract {
     pub fn addr(&self) -> Addr {
         self.0.clone()
     }
 
     pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
+        self.call_with_funds(msg, vec![])
+    }
+
+    pub fn call_with_funds<T: Into<ExecuteMsg>>(&self, msg: T, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
         let msg = to_binary(&msg.into())?;
         Ok(WasmMsg::Execute {
             contract_addr: self.addr().into(),
             msg,
+            funds,
+        }
+        .into())
+    }
+
+    /// Bid on the bin, paying the tickets with the native funds.
+    pub fn bid(&self, bin: u8, tickets: Option<u32>, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        let msg = ExecuteMsg::Bid {
+            bin,
+            tickets,
+            recipient: None,
+            allowlist_proof: None,
+            coupon: None,
+            referrer: None,
+            prediction: None,
+            round: None,
+        };
+        self.call_with_funds(msg, funds)
+    }
+
+    /// Bid on the bin, paying the tickets with the amount of the cw20 ticket token.
+    pub fn bid_cw20(&self, token: &Addr, amount: Uint128, bin: u8, tickets: Option<u32>) -> StdResult<CosmosMsg> {
+        let msg = ReceiveMsg::Bid {
+            bin,
+            tickets,
+            allowlist_proof: None,
+            prediction: None,
+            round: None,
+        };
+        let send = Cw20ExecuteMsg::Send {
+            contract: self.addr().into(),
+            amount,
+            msg: to_binary(&msg)?,
+        };
+        Ok(WasmMsg::Execute {
+            contract_addr: token.into(),
+            msg: to_binary(&send)?,
             funds: vec![],
         }
         .into())
     }
 
-    /// Get Count
-    pub fn count<Q, T, CQ>(&self, querier: &Q) -> StdResult<CountResponse>
+    /// Move the bid to another bin, paying the fee of the change if any.
+    pub fn change_bid(&self, bin: u8, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call_with_funds(ExecuteMsg::ChangeBid { bin, round: None }, funds)
+    }
+
+    pub fn remove_bid(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemoveBid { round: None })
+    }
+
+    /// Claim the airdrop with the hex-encoded Merkle proofs of the airdrop and the game.
+    pub fn claim_airdrop(
+        &self,
+        amount: Uint128,
+        proof_airdrop: Vec<String>,
+        proof_game: Vec<String>,
+    ) -> StdResult<CosmosMsg> {
+        let msg = ExecuteMsg::ClaimAirdrop {
+            amount,
+            proof_airdrop,
+            proof_game,
+            recipient: None,
+            round: None,
+        };
+        self.call(msg)
+    }
+
+    /// Claim the prize, sent to the recipient if set.
+    pub fn claim_prize(&self, recipient: Option<String>) -> StdResult<CosmosMsg> {
+        let msg = ExecuteMsg::ClaimPrize {
+            recipient,
+            stake: None,
+            round: None,
+        };
+        self.call(msg)
+    }
+
+    fn query<Q, T, CQ>(&self, querier: &Q, msg: &QueryMsg) -> StdResult<T>
     where
         Q: Querier,
-        T: Into<String>,
+        T: DeserializeOwned,
         CQ: CustomQuery,
     {
-        let msg = QueryMsg::GetCount {};
         let query = WasmQuery::Smart {
             contract_addr: self.addr().into(),
-            msg: to_binary(&msg)?,
+            msg: to_binary(msg)?,
         }
         .into();
-        let res: CountResponse = QuerierWrapper::<CQ>::new(querier).query(&query)?;
-        Ok(res)
+        QuerierWrapper::<CQ>::new(querier).query(&query)
+    }
+
+    /// Get the configuration of the game. This is a good check that the address
+    /// is a game contract.
+    pub fn config<Q, CQ>(&self, querier: &Q) -> StdResult<ConfigResponse>
+    where
+        Q: Querier,
+        CQ: CustomQuery,
+    {
+        self.query::<_, _, CQ>(querier, &QueryMsg::Config {})
+    }
+
+    /// Get the current round of the game
+    pub fn round<Q, CQ>(&self, querier: &Q) -> StdResult<u64>
+    where
+        Q: Querier,
+        CQ: CustomQuery,
+    {
+        let res: RoundResponse = self.query::<_, _, CQ>(querier, &QueryMsg::Round {})?;
+        Ok(res.round)
+    }
+
+    /// Get the stages of the current round
+    pub fn stages<Q, CQ>(&self, querier: &Q) -> StdResult<StagesResponse>
+    where
+        Q: Querier,
+        CQ: CustomQuery,
+    {
+        self.query::<_, _, CQ>(querier, &QueryMsg::Stages { round: None })
+    }
+
+    /// Get the phase of the current round at the current block
+    pub fn game_status<Q, CQ>(&self, querier: &Q) -> StdResult<GameStatusResponse>
+    where
+        Q: Querier,
+        CQ: CustomQuery,
+    {
+        self.query::<_, _, CQ>(querier, &QueryMsg::GameStatus { round: None })
+    }
+
+    /// Get the bid of the given address in the current round
+    pub fn get_bid<Q, T, CQ>(&self, querier: &Q, address: T) -> StdResult<BidResponse>
+    where
+        Q: Querier,
+        T: Into<String>,
+        CQ: CustomQuery,
+    {
+        let msg = QueryMsg::Bid {
+            address: address.into(),
+            round: None,
+        };
+        self.query::<_, _, CQ>(querier, &msg)
     }
 }
@@ -0,0 +1,165 @@
+use cosmwasm_std::{to_binary, Binary, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ======================================================================================
+// Cross-chain bids
+// ======================================================================================
+// A sender contract on another chain places bids for its players with IBC packets. The
+// sender escrows the tickets on its chain and reads the acknowledgement of each packet
+// to keep or refund them. The entry points require the `ibc` feature.
+
+/// Version of the channels between the game and its sender.
+pub const IBC_VERSION: &str = "wasmgame-1";
+
+/// Packet sent by the sender contract to place a bid.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcBidPacket {
+    /// Address of the player on the sender chain, recorded as sent for the sender to pay
+    /// the prize.
+    pub player: String,
+    /// Bidding bin value.
+    pub bin: u8,
+    /// Number of tickets bought on the bin and escrowed by the sender, one if none set.
+    pub tickets: Option<u32>,
+    /// Proof that the player is in the allowlist, required if an allowlist is set.
+    pub allowlist_proof: Option<Vec<String>>,
+    /// Round of the game, the current one if none set.
+    pub round: Option<u64>,
+}
+
+/// Acknowledgement of a bid packet. On error, the sender refunds the escrowed tickets.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcBidAck {
+    Result(Binary),
+    Error(String),
+}
+
+/// Returns the acknowledgement of a bid placed.
+pub fn ack_success() -> StdResult<Binary> {
+    to_binary(&IbcBidAck::Result(Binary::from(b"1".to_vec())))
+}
+
+/// Returns the acknowledgement of a bid rejected with the error.
+pub fn ack_fail(err: String) -> StdResult<Binary> {
+    to_binary(&IbcBidAck::Error(err))
+}
+
+#[cfg(feature = "ibc")]
+pub use entry_points::*;
+
+#[cfg(feature = "ibc")]
+mod entry_points {
+    #[cfg(not(feature = "library"))]
+    use cosmwasm_std::entry_point;
+    use cosmwasm_std::{
+        from_slice, DepsMut, Env, IbcBasicResponse, IbcChannel, IbcChannelCloseMsg,
+        IbcChannelConnectMsg, IbcChannelOpenMsg, IbcOrder, IbcPacketAckMsg,
+        IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
+    };
+
+    use super::*;
+    use crate::contract::place_remote_bid;
+    use crate::error::ContractError;
+    use crate::state::CONFIG;
+
+    /// Checks that the channel is unordered, with the version of the game, and opened
+    /// with the sender port configured by the owner.
+    fn check_channel(deps: &DepsMut, channel: &IbcChannel, counterparty_version: Option<&str>) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidIbcChannel { reason: reason.to_string() };
+        if channel.order != IbcOrder::Unordered {
+            return Err(invalid("the channel must be unordered"));
+        }
+        if channel.version != IBC_VERSION || counterparty_version.is_some_and(|version| version != IBC_VERSION) {
+            return Err(invalid("the channel version must be wasmgame-1"));
+        }
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.ibc_bidder_port.as_deref() != Some(channel.counterparty_endpoint.port_id.as_str()) {
+            return Err(invalid("the counterparty port is not the bidder port"));
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn ibc_channel_open(deps: DepsMut, _env: Env, msg: IbcChannelOpenMsg) -> Result<(), ContractError> {
+        check_channel(&deps, msg.channel(), msg.counterparty_version())
+    }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn ibc_channel_connect(
+        deps: DepsMut,
+        _env: Env,
+        msg: IbcChannelConnectMsg,
+    ) -> Result<IbcBasicResponse, ContractError> {
+        let channel = msg.channel();
+        check_channel(&deps, channel, msg.counterparty_version())?;
+        Ok(IbcBasicResponse::new()
+            .add_attribute("action", "ibc_connect")
+            .add_attribute("channel_id", &channel.endpoint.channel_id))
+    }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn ibc_channel_close(
+        _deps: DepsMut,
+        _env: Env,
+        msg: IbcChannelCloseMsg,
+    ) -> Result<IbcBasicResponse, ContractError> {
+        Ok(IbcBasicResponse::new()
+            .add_attribute("action", "ibc_close")
+            .add_attribute("channel_id", &msg.channel().endpoint.channel_id))
+    }
+
+    /// Places the bid of the packet. A rejected bid is acknowledged with the error
+    /// instead of failing, so that the sender refunds the escrowed tickets. The bid writes
+    /// nothing before its last check, so a rejected bid leaves no state behind.
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn ibc_packet_receive(
+        deps: DepsMut,
+        env: Env,
+        msg: IbcPacketReceiveMsg,
+    ) -> Result<IbcReceiveResponse, ContractError> {
+        let res = from_slice::<IbcBidPacket>(&msg.packet.data)
+            .map_err(ContractError::from)
+            .and_then(|packet| {
+                place_remote_bid(
+                    deps,
+                    env,
+                    msg.packet.dest.channel_id.clone(),
+                    packet.player,
+                    packet.bin,
+                    packet.tickets,
+                    packet.allowlist_proof,
+                    packet.round,
+                )
+            });
+        let res = match res {
+            Ok(res) => IbcReceiveResponse::new()
+                .set_ack(ack_success()?)
+                .add_submessages(res.messages)
+                .add_attributes(res.attributes)
+                .add_events(res.events),
+            Err(err) => IbcReceiveResponse::new()
+                .set_ack(ack_fail(err.to_string())?)
+                .add_attribute("action", "ibc_bid")
+                .add_attribute("error", err.to_string()),
+        };
+        Ok(res)
+    }
+
+    /// The game sends no packets, so there is nothing to acknowledge.
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn ibc_packet_ack(_deps: DepsMut, _env: Env, _msg: IbcPacketAckMsg) -> Result<IbcBasicResponse, ContractError> {
+        Ok(IbcBasicResponse::new())
+    }
+
+    /// The game sends no packets, so none can time out.
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn ibc_packet_timeout(
+        _deps: DepsMut,
+        _env: Env,
+        _msg: IbcPacketTimeoutMsg,
+    ) -> Result<IbcBasicResponse, ContractError> {
+        Ok(IbcBasicResponse::new())
+    }
+}
@@ -1,25 +1,51 @@
 #![cfg(test)]
+#![allow(clippy::needless_return, clippy::useless_conversion, clippy::unnecessary_literal_unwrap, clippy::cloned_ref_to_slice_refs)]
 
 use std::borrow::BorrowMut;
 
-use cosmwasm_std::{from_slice, Addr, BlockInfo, Coin, CustomQuery, Empty, Event, Uint128};
-use cw20::{Cw20Coin, Cw20Contract};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Binary, BlockInfo, Coin, CustomQuery, Decimal, Deps,
+    DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult, Timestamp, Uint128,
+};
+use cw20::{Cw20Contract, Cw20ReceiveMsg};
 
 use anyhow::Result as AnyResult;
 
-use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_storage_plus::{Item, Map};
 use cw_utils::{Duration, Scheduled};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{bid_commitment, claim_authorization, execute, instantiate, place_remote_bid, query};
+use crate::cw721::{Cw721ExecuteMsg, Cw721QueryMsg, TokensResponse};
+use crate::events;
+use crate::helpers::WasmGameContract;
+use crate::ibc::IbcBidPacket;
+use crate::interface::{WasmGameQuerier, WasmGameQuery};
+use crate::nois::{NoisCallback, NoisProxyExecuteMsg};
+use crate::oracle::{OracleQueryMsg, PriceResponse};
 use crate::ContractError;
 
 use crate::msg::{
     BidResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MerkleRootsResponse,
-    QueryMsg, StagesResponse, GameAmountsResponse,
+    QueryMsg, StagesResponse, GameAmountsResponse, OutstandingResponse, ReceiveMsg,
+    RoundResponse, PlayerAction, BinCount, BinCountsResponse, LimitsResponse,
+    ClaimedAirdropsResponse, IsClaimedResponse, ClaimableAmountsResponse, SponsorPrize,
+    SponsorshipInfo, SponsorshipsResponse, NoticesResponse, GamePhase, GameStatusResponse,
+    BucketBalance, TreasuryResponse, ClaimStage, RebatesResponse, TicketPriceResponse,
+    PrizeTokenAmount, PrizeTokenInfo, VestingInfo, VestingResponse, StakingReceiveMsg, SudoMsg,
+    RoundStage, BlocklistResponse, TokenGate, Coupon, ReferralsResponse, LoyaltyPointsResponse,
+    LoyaltyTiersResponse, SeasonPassResponse, PriceOracle, Raffle, Resolution, BinMultipliersResponse,
+    BinBoundariesResponse, BinRange, OddsResponse, ContributorInfo, ContributorsResponse, IbcDenomsResponse,
+    MerkleRootKind, VerifyProofResponse, RemoteWinner, RemoteWinnersResponse,
+};
+use crate::merkle::HashAlgorithm;
+use crate::state::{AntiSnipe, DustPolicy, LoyaltyTier, Notice, PausedBy, PriceStep, SeasonPass, Stage};
+use crate::testing::{
+    contract_game, create_cw20, create_game, create_game_with_msg, game_instantiate_msg, mock_app, valid_stages,
 };
-use crate::state::Stage;
+use crate::treasury::Bucket;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -30,116 +56,128 @@ pub enum MyCustomQuery {
 
 impl CustomQuery for MyCustomQuery {}
 
-fn mock_app() -> App {
-    let mut app = App::default();
-    let current_block = app.block_info();
-    app.set_block(BlockInfo {
-        height: 199_999,
-        time: current_block.time,
-        chain_id: current_block.chain_id,
-    });
-    return app;
-}
-
-fn valid_stages() -> (Stage, Stage, Stage) {
-    let stage_bid = Stage {
-        start: Scheduled::AtHeight(200_000),
-        duration: Duration::Height(2),
-    };
-
-    let stage_claim_airdrop = Stage {
-        start: Scheduled::AtHeight(201_000),
-        duration: Duration::Height(2),
-    };
-
-    let stage_claim_prize = Stage {
-        start: Scheduled::AtHeight(202_000),
-        duration: Duration::Height(2),
-    };
-
-    return (stage_bid, stage_claim_airdrop, stage_claim_prize);
-}
-
 // ======================================================================================
 // Contracts
 // ======================================================================================
-/// Create the game contract.
-pub fn contract_game() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(execute, instantiate, query);
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum StakingExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+}
+
+/// Create a staking contract accepting the stakes sent with a `StakingReceiveMsg`.
+pub fn contract_staking() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |_: DepsMut, _: Env, info: MessageInfo, msg: StakingExecuteMsg| -> StdResult<Response> {
+            let StakingExecuteMsg::Receive(receive) = msg;
+            let StakingReceiveMsg::StakeFor { address } = from_binary(&receive.msg)?;
+            Ok(Response::new()
+                .add_attribute("token", info.sender)
+                .add_attribute("stake_for", address)
+                .add_attribute("amount", receive.amount))
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::new()) },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { to_binary(&Empty {}) },
+    );
     Box::new(contract)
 }
 
-/// Create the token contract.
-pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+/// Create a cw721 collection where each of the addresses it is instantiated with holds
+/// a token.
+pub fn contract_cw721() -> Box<dyn Contract<Empty>> {
+    const HOLDERS: Item<Vec<String>> = Item::new("holders");
+    const MINTED: Map<&str, String> = Map::new("minted");
     let contract = ContractWrapper::new(
-        cw20_base::contract::execute,
-        cw20_base::contract::instantiate,
-        cw20_base::contract::query,
+        |deps: DepsMut, _: Env, _: MessageInfo, msg: Cw721ExecuteMsg| -> StdResult<Response> {
+            match msg {
+                Cw721ExecuteMsg::Mint { token_id, owner, .. } => MINTED.save(deps.storage, &token_id, &owner)?,
+                Cw721ExecuteMsg::Burn { token_id } => MINTED.remove(deps.storage, &token_id),
+            }
+            Ok(Response::new())
+        },
+        |deps: DepsMut, _: Env, _: MessageInfo, holders: Vec<String>| -> StdResult<Response> {
+            HOLDERS.save(deps.storage, &holders)?;
+            Ok(Response::new())
+        },
+        |deps: Deps, _: Env, msg: Cw721QueryMsg| -> StdResult<Binary> {
+            let Cw721QueryMsg::Tokens { owner, .. } = msg;
+            let holders = HOLDERS.load(deps.storage)?;
+            let mut tokens: Vec<String> = holders
+                .iter()
+                .position(|holder| *holder == owner)
+                .map(|id| vec![id.to_string()])
+                .unwrap_or_default();
+            for minted in MINTED.range(deps.storage, None, None, Order::Ascending) {
+                let (token_id, token_owner) = minted?;
+                if token_owner == owner {
+                    tokens.push(token_id);
+                }
+            }
+            to_binary(&TokensResponse { tokens })
+        },
     );
     Box::new(contract)
 }
 
-/// Instantiate the game contract.
-pub fn create_game(
-    router: &mut App,
-    owner: &Addr,
-    ticket_price: Coin,
-    bins: u8,
-    stage_bid: Stage,
-    stage_claim_airdrop: Stage,
-    stage_claim_prize: Stage,
-    cw20_token: Option<String>,
-) -> AnyResult<Addr> {
-    let game_id = router.store_code(contract_game());
+/// Price oracle quoting a single price, set at instantiation and updated by any
+/// execution.
+pub fn contract_oracle() -> Box<dyn Contract<Empty>> {
+    const PRICE: Item<Decimal> = Item::new("price");
+    let contract = ContractWrapper::new(
+        |deps: DepsMut, _: Env, _: MessageInfo, price: Decimal| -> StdResult<Response> {
+            PRICE.save(deps.storage, &price)?;
+            Ok(Response::new())
+        },
+        |deps: DepsMut, _: Env, _: MessageInfo, price: Decimal| -> StdResult<Response> {
+            PRICE.save(deps.storage, &price)?;
+            Ok(Response::new())
+        },
+        |deps: Deps, _: Env, msg: OracleQueryMsg| -> StdResult<Binary> {
+            let OracleQueryMsg::Price { .. } = msg;
+            to_binary(&PriceResponse { price: PRICE.load(deps.storage)? })
+        },
+    );
+    Box::new(contract)
+}
 
-    let msg = InstantiateMsg {
-        owner: Some("owner0000".to_string()),
-        cw20_token_address: cw20_token.unwrap_or("random0000".to_string()),
-        ticket_price,
-        bins,
-        stage_bid,
-        stage_claim_airdrop,
-        stage_claim_prize,
-    };
-    router.instantiate_contract(
-        game_id, 
-        owner.clone(), 
-        &msg, 
-        &[], 
-        "game", 
-        None)
+/// Game whose sudo entry point places the bids of IBC packets received on the channel,
+/// which the app cannot relay.
+pub fn contract_game_with_remote_bids() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute, instantiate, query).with_sudo(
+        |deps: DepsMut, env: Env, (channel_id, packet): (String, IbcBidPacket)| -> Result<Response, ContractError> {
+            place_remote_bid(
+                deps,
+                env,
+                channel_id,
+                packet.player,
+                packet.bin,
+                packet.tickets,
+                packet.allowlist_proof,
+                packet.round,
+            )
+        },
+    );
+    Box::new(contract)
 }
 
-/// Instantiate the token contract.
-fn create_cw20(
-    router: &mut App,
-    owner: &Addr,
-    name: String,
-    symbol: String,
-    balance: Uint128,
-) -> Cw20Contract {
-    let cw20_id = router.store_code(contract_cw20());
-    let msg = cw20_base::msg::InstantiateMsg {
-        name,
-        symbol,
-        decimals: 6,
-        initial_balances: vec![Cw20Coin {
-            address: owner.to_string(),
-            amount: balance,
-        }],
-        mint: None,
-        marketing: None,
-    };
-    let addr = router
-        .instantiate_contract(
-            cw20_id, 
-            owner.clone(), 
-            &msg, 
-            &[], 
-            "TOKEN", 
-            None)
-        .unwrap();
-    Cw20Contract(addr)
+/// Nois proxy recording the randomness jobs requested, the callbacks being sent by the
+/// tests on its behalf.
+pub fn contract_nois_proxy() -> Box<dyn Contract<Empty>> {
+    const JOBS: Item<Vec<String>> = Item::new("jobs");
+    let contract = ContractWrapper::new(
+        |deps: DepsMut, _: Env, _: MessageInfo, msg: NoisProxyExecuteMsg| -> StdResult<Response> {
+            let NoisProxyExecuteMsg::GetNextRandomness { job_id } = msg;
+            let mut jobs = JOBS.may_load(deps.storage)?.unwrap_or_default();
+            jobs.push(job_id);
+            JOBS.save(deps.storage, &jobs)?;
+            Ok(Response::new())
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::new()) },
+        |deps: Deps, _: Env, _: Empty| -> StdResult<Binary> {
+            to_binary(&JOBS.may_load(deps.storage)?.unwrap_or_default())
+        },
+    );
+    Box::new(contract)
 }
 
 // ======================================================================================
@@ -148,14 +186,21 @@ fn create_cw20(
 fn get_stages(router: &App, contract_addr: &Addr) -> StagesResponse {
     router
         .wrap()
-        .query_wasm_smart(contract_addr, &QueryMsg::Stages {})
+        .query_wasm_smart(contract_addr, &QueryMsg::Stages { round: None })
         .unwrap()
 }
 
 fn get_bid(router: &App, contract_addr: &Addr, address: String) -> BidResponse {
     router
         .wrap()
-        .query_wasm_smart(contract_addr, &QueryMsg::Bid { address })
+        .query_wasm_smart(contract_addr, &QueryMsg::Bid { address, round: None })
+        .unwrap()
+}
+
+fn get_bin_counts(router: &App, contract_addr: &Addr) -> BinCountsResponse {
+    router
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::BinCounts { round: None })
         .unwrap()
 }
 
@@ -169,14 +214,21 @@ fn get_config(router: &App, contract_addr: &Addr) -> ConfigResponse {
 fn get_merkle_roots(router: &App, contract_addr: &Addr) -> MerkleRootsResponse {
     router
         .wrap()
-        .query_wasm_smart(contract_addr, &QueryMsg::MerkleRoots {})
+        .query_wasm_smart(contract_addr, &QueryMsg::MerkleRoots { round: None })
         .unwrap()
 }
 
 fn get_game_amount(router: &App, contract_addr: &Addr) -> GameAmountsResponse {
     router
         .wrap()
-        .query_wasm_smart(contract_addr, &QueryMsg::GameAmounts {})
+        .query_wasm_smart(contract_addr, &QueryMsg::GameAmounts { round: None })
+        .unwrap()
+}
+
+fn get_outstanding(router: &App, contract_addr: &Addr) -> OutstandingResponse {
+    router
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::Outstanding { round: None })
         .unwrap()
 }
 
@@ -311,7 +363,7 @@ fn valid_bid_no_change() {
     ).unwrap();
 
     // Cannot bid if bid stage not started.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
     let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
     let err = router
         .execute_contract(
@@ -330,7 +382,7 @@ fn valid_bid_no_change() {
 
     // Make a valid bid without a change.
     let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+    let res = router
         .execute_contract(
             owner.clone(),
             game_addr.clone(),
@@ -340,6 +392,16 @@ fn valid_bid_no_change() {
     let balance: Coin = bank_balance(&mut router, &owner, native_token_denom.to_string());
     assert_eq!(Uint128::new(999_990), balance.amount);
 
+    // Both the structured event and the legacy attributes are emitted.
+    let event_bid = Event::new("wasm-bid")
+        .add_attribute("_contract_addr", game_addr.to_string())
+        .add_attribute(events::PLAYER, owner.to_string())
+        .add_attribute(events::BIN, "1")
+        .add_attribute(events::STAGE, "bid")
+        .add_attribute(events::ROUND, "1");
+    assert!(res.has_event(&event_bid));
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::ACTION, "bid")));
+
     // Trigger CannotBidMoreThanOnce error.
     let err = router
         .execute_contract(
@@ -379,7 +441,7 @@ fn valid_bid_with_change() {
     router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
 
     // Check that the response has the correct trasnfer message
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
     let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(20)};
     let res = router
         .execute_contract(
@@ -427,7 +489,7 @@ fn invalid_bid() {
     router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
 
     // Trigger TicketPriceNotPaid error for insufficient funds.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
     let bid = Coin {denom: native_token_denom.into(), amount: Uint128::new(1)};
     let err = router
         .execute_contract(
@@ -440,7 +502,7 @@ fn invalid_bid() {
     assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
 
     // Trigger TicketPriceNotPaid error for wrong funds.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
     let bid = Coin {denom: "ubtc".into(), amount: Uint128::new(10)};
     let err = router
         .execute_contract(
@@ -453,6 +515,239 @@ fn invalid_bid() {
     assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
 }
 
+#[test]
+fn cw20_ticket_bid() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, _, bins, funds) = global_variables();
+
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &owner, funds).unwrap()
+    });
+
+    let ticket_token = create_cw20(
+        &mut router,
+        &owner,
+        "ticket".to_string(),
+        "TICKET".to_string(),
+        Uint128::new(1_000)
+    );
+    let other_token = create_cw20(
+        &mut router,
+        &owner,
+        "other".to_string(),
+        "OTHER".to_string(),
+        Uint128::new(1_000)
+    );
+
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let ticket_price = Coin {denom: ticket_token.addr().to_string(), amount: Uint128::new(10)};
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.ticket_cw20_address = Some(ticket_token.addr().to_string());
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+
+    set_height(&mut router, 200_001);
+
+    // Native funds cannot pay a cw20 ticket.
+    let bid = Coin {denom: native_token_denom, amount: Uint128::new(10)};
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+            &[bid],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::InvalidTicketAsset {}, err.downcast().unwrap());
+
+    // Only the ticket token is accepted.
+    let send_msg = cw20::Cw20ExecuteMsg::Send {
+        contract: game_addr.to_string(),
+        amount: Uint128::new(10),
+        msg: to_binary(&ReceiveMsg::Bid { bin: 1, tickets: None, allowlist_proof: None, prediction: None, round: None }).unwrap(),
+    };
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            other_token.addr(),
+            &send_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::InvalidTicketAsset {}, err.downcast().unwrap());
+
+    // Valid bid with change paid back in the ticket token.
+    let send_msg = cw20::Cw20ExecuteMsg::Send {
+        contract: game_addr.to_string(),
+        amount: Uint128::new(15),
+        msg: to_binary(&ReceiveMsg::Bid { bin: 1, tickets: None, allowlist_proof: None, prediction: None, round: None }).unwrap(),
+    };
+    router
+        .execute_contract(
+            owner.clone(),
+            ticket_token.addr(),
+            &send_msg,
+            &[],
+        ).unwrap();
+    let owner_balance = ticket_token
+        .balance::<App, Addr, MyCustomQuery>(&router, owner.clone())
+        .unwrap();
+    let bid = get_bid(&router, &game_addr, owner.to_string());
+
+    assert_eq!(owner_balance, Uint128::new(990));
+    assert_eq!(bid.bid, Some(1));
+
+    // Removing the bid pays back the ticket in the ticket token.
+    router
+        .execute_contract(
+            owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RemoveBid { round: None },
+            &[],
+        ).unwrap();
+    let owner_balance = ticket_token
+        .balance::<App, Addr, MyCustomQuery>(&router, owner)
+        .unwrap();
+
+    assert_eq!(owner_balance, Uint128::new(1_000));
+}
+
+#[test]
+fn bid_limits() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.max_per_bin = Some(0);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidBidLimit {}, err.downcast().unwrap());
+
+    msg.max_bids = Some(3);
+    msg.max_per_bin = Some(2);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    let limits: LimitsResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Limits {})
+        .unwrap();
+    assert_eq!((limits.max_bids, limits.max_per_bin), (Some(3), Some(2)));
+
+    // A full bin does not take more bids.
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    let err = place_bid(&mut router, &game_addr, &players[2], 1).unwrap_err();
+    assert_eq!(ContractError::BinFull { bin: 1, limit: 2 }, err.downcast().unwrap());
+    place_bid(&mut router, &game_addr, &players[2], 2).unwrap();
+
+    // The round does not take more bids than the maximum.
+    let err = place_bid(&mut router, &game_addr, &owner, 3).unwrap_err();
+    assert_eq!(ContractError::TooManyBids { limit: 3 }, err.downcast().unwrap());
+
+    // Bids can be moved to bins that are not full.
+    let change_bid_msg = |bin: u8| ExecuteMsg::ChangeBid { bin, round: None };
+    let err = router
+        .execute_contract(players[2].clone(), game_addr.clone(), &change_bid_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BinFull { bin: 1, limit: 2 }, err.downcast().unwrap());
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &change_bid_msg(1), &[])
+        .unwrap();
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &change_bid_msg(2), &[])
+        .unwrap();
+    router
+        .execute_contract(players[2].clone(), game_addr.clone(), &change_bid_msg(1), &[])
+        .unwrap();
+}
+
+#[test]
+fn bid_multiple() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let bid_multiple = |router: &mut App, player: &Addr, bins: Vec<u8>, amount: u128| {
+        router.execute_contract(
+            player.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::BidMultiple { bins, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(amount) }],
+        )
+    };
+
+    // The bins must be at least one, without repetitions, and each one is paid.
+    set_height(&mut router, 200_001);
+    let err = bid_multiple(&mut router, &players[0], vec![], 10).unwrap_err();
+    assert_eq!(ContractError::InvalidBins {}, err.downcast().unwrap());
+    let err = bid_multiple(&mut router, &players[0], vec![5, 5], 20).unwrap_err();
+    assert_eq!(ContractError::InvalidBins {}, err.downcast().unwrap());
+    let err = bid_multiple(&mut router, &players[0], vec![5, 1], 10).unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+    let err = bid_multiple(&mut router, &players[0], vec![5, 11], 20).unwrap_err();
+    assert_eq!(ContractError::BinDoesNotExist { bins: 10 }, err.downcast().unwrap());
+
+    // The change is sent back.
+    bid_multiple(&mut router, &players[0], vec![5, 1], 25).unwrap();
+    let balance = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    assert_eq!(balance.amount, Uint128::new(1_000_000 - 20));
+    let info = get_bid(&router, &game_addr, players[0].to_string());
+    assert_eq!(BidResponse { bid: Some(5), bins: vec![5, 1], tickets: 1, stake: Uint128::new(20), changes: 0 }, info);
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(20));
+
+    // A bid on several bins cannot be changed, but can be removed.
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ChangeBid { bin: 2, round: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::CannotChangeMultipleBins {}, err.downcast().unwrap());
+    let err = bid_multiple(&mut router, &players[0], vec![2], 10).unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+
+    bid_multiple(&mut router, &players[1], vec![4, 6, 7], 30).unwrap();
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    let balance = bank_balance(&mut router, &players[1], native_token_denom);
+    assert_eq!(balance.amount, Uint128::new(1_000_000));
+    let info = get_bid(&router, &game_addr, players[1].to_string());
+    assert_eq!(BidResponse { bid: None, bins: vec![], tickets: 0, stake: Uint128::zero(), changes: 0 }, info);
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(20));
+
+    // The bid wins with any of its bins, taking a single share of the prize.
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.winners_amount, Uint128::new(1));
+}
+
 #[test]
 fn change_bid() {
     let mut router = mock_app();
@@ -480,7 +775,7 @@ fn change_bid() {
     router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
 
     // Trigger BidNotPresent error.
-    let change_bid_msg = ExecuteMsg::ChangeBid { bin: 2 };
+    let change_bid_msg = ExecuteMsg::ChangeBid { bin: 2, round: None };
     let err = router
         .execute_contract(
             owner.clone(),
@@ -492,7 +787,7 @@ fn change_bid() {
     assert_eq!(ContractError::BidNotPresent {}, err.downcast().unwrap());
 
     // Check correctness on bid modification.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
     let bid = Coin {denom: native_token_denom.into(), amount: Uint128::new(10)};
     let _res = router
         .execute_contract(
@@ -503,9 +798,9 @@ fn change_bid() {
         ).unwrap();
     let info = get_bid(&router, &game_addr, owner.to_string());
 
-    assert_eq!(BidResponse {bid: Some(1)}, info);
+    assert_eq!(BidResponse { bid: Some(1), bins: vec![1], tickets: 1, stake: Uint128::new(10), changes: 0 }, info);
 
-    let change_bid_msg = ExecuteMsg::ChangeBid { bin: 2 };
+    let change_bid_msg = ExecuteMsg::ChangeBid { bin: 2, round: None };
     let _res = router
         .execute_contract(
             owner.clone(),
@@ -515,7 +810,7 @@ fn change_bid() {
         ).unwrap();
     let info = get_bid(&router, &game_addr, owner.to_string());
 
-    assert_eq!(BidResponse { bid: Some(2) }, info);
+    assert_eq!(BidResponse { bid: Some(2), bins: vec![2], tickets: 1, stake: Uint128::new(10), changes: 1 }, info);
 
 }
 
@@ -546,7 +841,7 @@ fn remove_bid() {
     router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
 
     // Trigger BidNotPresent error.
-    let remove_bid_msg = ExecuteMsg::RemoveBid {};
+    let remove_bid_msg = ExecuteMsg::RemoveBid { round: None };
     let err = router
         .execute_contract(
             owner.clone(),
@@ -558,7 +853,7 @@ fn remove_bid() {
     assert_eq!(ContractError::BidNotPresent {}, err.downcast().unwrap());
 
     // Check that bid is removed and funds returned
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
     let valid_bid_no_change = Coin {denom: native_token_denom.clone().into(), amount: Uint128::new(10)};
     let _res = router
         .execute_contract(
@@ -571,7 +866,7 @@ fn remove_bid() {
 
     assert_eq!(Uint128::new(999_990), balance.amount);
 
-    let remove_bid_msg = ExecuteMsg::RemoveBid {};
+    let remove_bid_msg = ExecuteMsg::RemoveBid { round: None };
     let _res = router
         .execute_contract(
             owner.clone(),
@@ -582,11 +877,11 @@ fn remove_bid() {
     let info = get_bid(&router, &game_addr, owner.to_string());
     let balance: Coin = bank_balance(&mut router, &owner, native_token_denom.to_string());
 
-    assert_eq!(BidResponse { bid: None }, info);
+    assert_eq!(BidResponse { bid: None, bins: vec![], tickets: 0, stake: Uint128::zero(), changes: 0 }, info);
     assert_eq!(Uint128::new(1_000_000), balance.amount);
 
     // Check that two consecutive remove bid is not possible.
-    let remove_bid_msg = ExecuteMsg::RemoveBid {};
+    let remove_bid_msg = ExecuteMsg::RemoveBid { round: None };
     let err = router
         .execute_contract(
             owner.clone(),
@@ -601,13 +896,10 @@ fn remove_bid() {
 
 }
 
-// ======================================================================================
-// Tests Merkle root
-// ======================================================================================
 #[test]
-fn register_merkle_root() {
+fn batch_actions() {
     let mut router = mock_app();
-    let (_, owner,ticket_price, bins, funds) = global_variables();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
 
     router.borrow_mut().init_modules(|router, _, storage| {
         router.bank.init_balance(storage, &owner, funds).unwrap()
@@ -620,278 +912,362 @@ fn register_merkle_root() {
         &owner,
         ticket_price,
         bins,
-        stage_bid.clone(),
-        stage_claim_airdrop.clone(),
-        stage_claim_prize.clone(),
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
         None,
     ).unwrap();
-    
-    // Check Merkle roots properly saved.
-    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
-        merkle_root_airdrop: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
-        total_amount_airdrop: None,
-        merkle_root_game: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d38".to_string(),
-        total_amount_game: None,
-    };
-    let _res = router
+
+    set_height(&mut router, 200_001);
+    let bid = Coin {denom: native_token_denom.clone(), amount: Uint128::new(10)};
+    router
         .execute_contract(
-            Addr::unchecked("owner0000"),
+            owner.clone(),
             game_addr.clone(),
-            &register_merkle_root_msg,
-            &[],
+            &ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+            std::slice::from_ref(&bid),
         ).unwrap();
 
-    let info = get_merkle_roots(&router, &game_addr);
-    assert_eq!(
-        info.merkle_root_airdrop,
-        "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string()
-    );
-    assert_eq!(
-        info.merkle_root_game,
-        "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d38".to_string()
-    );
-
-    // Only the game owner can register the roots.
+    // Empty batches are rejected.
     let err = router
         .execute_contract(
             owner.clone(),
             game_addr.clone(),
-            &register_merkle_root_msg,
+            &ExecuteMsg::BatchActions { actions: vec![] },
             &[],
         ).unwrap_err();
-
-    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
-
-
-}
-
-const TEST_DATA_AIRDROP: &[u8] = include_bytes!("../testdata/airdrop_test_data.json");
-const TEST_DATA_GAME: &[u8] = include_bytes!("../testdata/airdrop_game_test_data.json");
-
-#[derive(Deserialize, Debug)]
-struct Address {
-    account: String,
-    amount: Uint128,
-    proofs: Vec<String>,
-}
-
-#[derive(Deserialize, Debug)]
-struct Encoded {
-    root: String,
-    addresses: Vec<Address>
+    assert_eq!(ContractError::InvalidInput {}, err.downcast().unwrap());
+
+    // Remove the bid and place a new one: the refunded ticket and the funds not used
+    // are sent back with a single transfer.
+    let batch_msg = ExecuteMsg::BatchActions {
+        actions: vec![
+            PlayerAction::RemoveBid { round: None },
+            PlayerAction::Bid { bin: 2, tickets: None, round: None },
+        ],
+    };
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            game_addr.clone(),
+            &batch_msg,
+            &[Coin {denom: native_token_denom.clone(), amount: Uint128::new(15)}],
+        ).unwrap();
+    let transfers = res.events.iter().filter(|e| e.ty == "transfer").count();
+    assert_eq!(transfers, 1);
+    let balance = bank_balance(&mut router, &owner, native_token_denom.clone());
+    assert_eq!(Uint128::new(999_990), balance.amount);
+    assert_eq!(get_bid(&router, &game_addr, owner.to_string()).bid, Some(2));
+
+    // If an action fails no action is applied.
+    let batch_msg = ExecuteMsg::BatchActions {
+        actions: vec![
+            PlayerAction::ChangeBid { bin: 3, round: None },
+            PlayerAction::Bid { bin: 4, tickets: None, round: None },
+        ],
+    };
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            game_addr.clone(),
+            &batch_msg,
+            std::slice::from_ref(&bid),
+        ).unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+    assert_eq!(get_bid(&router, &game_addr, owner.to_string()).bid, Some(2));
 }
 
-// ======================================================================================
-// Claims
-// ======================================================================================
 #[test]
-fn claim_airdrop() {
+fn refund_bids() {
     let mut router = mock_app();
-    let (_, owner,ticket_price, bins, funds) = global_variables();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
 
-    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
-    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let players: Vec<Addr> = vec![owner.clone(), Addr::unchecked("addr0001"), Addr::unchecked("addr0002")];
+    for addr in players.iter() {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
 
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &owner, funds).unwrap()
-    });
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Create the game token contract.
-    let cw20_token = create_cw20(
-        &mut router,
-        &owner,
-        "token".to_string(),
-        "CWTOKEN".to_string(),
-        Uint128::new(1_000_000)
-    );
-
-    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
-
-    // Create the game contract.
-    let cw20_token_address = Some(cw20_token.addr().to_string()).unwrap();
-    let game_addr = create_game(
+    let game_addr = create_game(
         &mut router,
         &owner,
         ticket_price,
         bins,
-        stage_bid.clone(),
-        stage_claim_airdrop.clone(),
-        stage_claim_prize.clone(),
-        Some(cw20_token_address.clone()),
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
     ).unwrap();
 
-    // Check that the game has the correct cw20 token contract.
-    let info = get_config(&router, &game_addr);
-
-    assert_eq!(info.cw20_token_address, cw20_token_address);
+    let game_owner = Addr::unchecked("owner0000");
+    set_height(&mut router, 200_001);
+    for player in players.iter() {
+        place_bid(&mut router, &game_addr, player, 1).unwrap();
+    }
 
-    // Check initial token balance of the owner.
-    let owner_balance = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, owner.clone())
-        .unwrap();
+    let refund_msg = |addresses: Option<Vec<String>>, limit: Option<u32>| ExecuteMsg::RefundBids {
+        addresses,
+        limit,
+        round: None,
+    };
 
-    assert_eq!(owner_balance, Uint128::new(1_000_000));
+    // Bids cannot be refunded while the game can still be played.
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &refund_msg(None, None), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RoundNotAborted {}, err.downcast().unwrap());
 
-    // Register Merkle roots.
-    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
-        merkle_root_airdrop: test_data_airdrop.root,
-        total_amount_airdrop: Some(Uint128::new(1_000)),
-        merkle_root_game: test_data_game.root,
-        total_amount_game: Some(Uint128::new(1_000_000)),
-    };
-    let _res = router
+    // Claim airdrop stage started without Merkle roots: the roots can no longer be
+    // registered, and anyone can push the refunds.
+    set_height(&mut router, 201_000);
+    let err = router
         .execute_contract(
-            Addr::unchecked("owner0000"),
+            game_owner.clone(),
             game_addr.clone(),
-            &register_merkle_root_msg,
+            &ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: "00".repeat(32),
+                total_amount_airdrop: None,
+                expected_recipients: None,
+                round: None,
+            },
             &[],
-        ).unwrap();
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::RootRegistrationClosed {}, err.downcast().unwrap());
 
-    // Check that initially no token have been claimed.
-    let info = get_game_amount(&router, &game_addr);
-    assert_eq!(info.total_claimed_airdrop, Uint128::new(0));
-    assert_eq!(info.total_claimed_prize, Uint128::new(0));
-    assert_eq!(info.total_ticket_prize, Uint128::new(0));
-    assert_eq!(info.total_airdrop_amount, Uint128::new(1_000));
-    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_000_000));
+    // Refund the listed bidders, skipping addresses without a bid.
+    let addresses = vec![players[2].to_string(), "addr0003".to_string()];
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &refund_msg(Some(addresses), None), &[])
+        .unwrap();
+    let balance = bank_balance(&mut router, &players[2], native_token_denom.clone());
+    assert_eq!(Uint128::new(1_000_000), balance.amount);
+    assert_eq!(get_bid(&router, &game_addr, players[2].to_string()).bid, None);
 
-    // Transfer token to the game contract and verify the balance.
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.clone().into(),amount: Uint128::new(110)};
-    let _res = router
-        .execute_contract(
-            owner,
-            Addr::unchecked(cw20_token_address),
-            &send_token_msg,
-            &[],
-        ).unwrap();
-    let game_balance = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone())
+    // Refund the remaining bidders in address order.
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &refund_msg(None, Some(1)), &[])
         .unwrap();
+    assert_eq!(get_bid(&router, &game_addr, players[1].to_string()).bid, None);
+    assert_eq!(get_bid(&router, &game_addr, owner.to_string()).bid, Some(1));
 
-    assert_eq!(game_balance, Uint128::new(110));
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &refund_msg(None, Some(1)), &[])
+        .unwrap();
+    let balance = bank_balance(&mut router, &owner, native_token_denom.clone());
+    assert_eq!(Uint128::new(1_000_000), balance.amount);
 
-    // Claim not allowed if claiming stage not active.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[0].amount,
-        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
-        proof_game: test_data_game.addresses[0].proofs.clone()
-    };
-    let err = router
-        .execute_contract(
-            Addr::unchecked(game_addr.to_string()),
-            game_addr.clone(),
-            &claim_airdrop_msg,
-            &[],
-        ).unwrap_err();
+    // Refunding again does not pay anything.
+    let res = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &refund_msg(None, None), &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::REFUNDED_BIDS, "0")));
+    let balance = bank_balance(&mut router, &game_addr, native_token_denom);
+    assert_eq!(Uint128::zero(), balance.amount);
+}
 
-    assert_eq!(ContractError::StageNotStarted {stage_name: String::from("claim airdrop")},err.downcast().unwrap());
+#[test]
+fn cancel_game() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Trigger claiming airdrop stage.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 201_001,time: current_block.time,chain_id: current_block.chain_id});
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let game_owner = Addr::unchecked("owner0000");
 
-    // Cannot be claimed a different amount than the one in the Merkle tree.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: Uint128::new(1_000),
-        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
-        proof_game: test_data_game.addresses[0].proofs.clone()
-    };
+    // The Merkle roots of the round created by the setup are registered.
     let err = router
-        .execute_contract(
-            Addr::unchecked(test_data_airdrop.addresses[0].account.clone()),
-            game_addr.clone(),
-            &claim_airdrop_msg,
-            &[],
-        ).unwrap_err();
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::CancelGame { round: None }, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::MerkleRootsAlreadyRegistered {}, err.downcast().unwrap());
 
-    assert_eq!(ContractError::VerificationFailed { merkle_root: "airdrop".to_string() }, err.downcast().unwrap());
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let game_addr = create_game(
+        &mut router,
+        &game_owner,
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    ).unwrap();
 
-    // Claim the correct ammount and verify balances.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[0].amount,
-        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
-        proof_game: test_data_game.addresses[0].proofs.clone()
-    };
+    set_height(&mut router, 200_001);
+    for player in players.iter() {
+        place_bid(&mut router, &game_addr, player, 1).unwrap();
+    }
 
-    let _res = router
-        .execute_contract(
-            Addr::unchecked(test_data_airdrop.addresses[0].account.clone()),
-            game_addr.clone(),
-            &claim_airdrop_msg,
-            &[],
-        ).unwrap();
-    let claimer_balance = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, Addr::unchecked(test_data_airdrop.addresses[0].account.clone()))
+    // Just the owner can cancel the round.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &ExecuteMsg::CancelGame { round: None }, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::CancelGame { round: None }, &[])
         .unwrap();
-    let game_balance = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone())
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::CancelGame { round: None }, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RoundCancelled {}, err.downcast().unwrap());
+
+    let status: GameStatusResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::GameStatus { round: None })
         .unwrap();
+    assert_eq!(status.phase, GamePhase::Cancelled);
 
-    assert_eq!(claimer_balance, Uint128::new(100));
-    assert_eq!(game_balance, Uint128::new(10));
+    // No bid can be placed in a cancelled round, even during the bid stage.
+    let err = place_bid(&mut router, &game_addr, &owner, 1).unwrap_err();
+    assert_eq!(ContractError::RoundCancelled {}, err.downcast().unwrap());
 
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[0].amount,
-        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
-        proof_game: test_data_game.addresses[0].proofs.clone()
+    // Anyone can push the refunds, in batches.
+    let refund_msg = ExecuteMsg::RefundBids {
+        addresses: None,
+        limit: Some(2),
+        round: None,
     };
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &refund_msg, &[])
+        .unwrap();
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &refund_msg, &[])
+        .unwrap();
+    for player in players.iter() {
+        let balance = bank_balance(&mut router, player, native_token_denom.clone());
+        assert_eq!(Uint128::new(1_000_000), balance.amount);
+    }
+    let balance = bank_balance(&mut router, &game_addr, native_token_denom);
+    assert_eq!(Uint128::zero(), balance.amount);
+}
 
-    // Airdrop cannot be claimed more than once.
+#[test]
+fn min_participants() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.min_participants = Some(4);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    for player in players.iter() {
+        place_bid(&mut router, &game_addr, player, 1).unwrap();
+    }
+    let refund_ticket_msg = ExecuteMsg::RefundTicket { round: None };
+
+    // Tickets cannot be refunded while bids can still be placed.
     let err = router
-        .execute_contract(
-            Addr::unchecked(test_data_airdrop.addresses[0].account.clone()),
-            game_addr.clone(),
-            &claim_airdrop_msg,
-            &[],
-        ).unwrap_err();
+        .execute_contract(players[0].clone(), game_addr.clone(), &refund_ticket_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RoundNotAborted {}, err.downcast().unwrap());
 
-    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+    // The bid stage ended with fewer bidders than the minimum: no claim is allowed.
+    set_height(&mut router, 201_001);
+    let err = claim_airdrop_at(&mut router, &game_addr, 0).unwrap_err();
+    assert_eq!(
+        ContractError::NotEnoughParticipants { participants: 3, min_participants: 4 },
+        err.downcast().unwrap()
+    );
 
-    // Verify total claimed amount
-    let info = get_game_amount(&router, &game_addr);
+    // Every bidder gets back its ticket, once.
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &refund_ticket_msg, &[])
+        .unwrap();
+    let balance = bank_balance(&mut router, &players[0], native_token_denom);
+    assert_eq!(Uint128::new(1_000_000), balance.amount);
 
-    assert_eq!(info.total_claimed_airdrop, Uint128::new(100));
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &refund_ticket_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BidNotPresent {}, err.downcast().unwrap());
+
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.min_participants, Some(4));
+    assert_eq!(info.total_ticket_prize, Uint128::new(20));
 }
 
 #[test]
-fn claim_prize() {
+fn bin_counts() {
     let mut router = mock_app();
-    let (native_token_denom, owner,ticket_price, bins, funds) = global_variables();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
-    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let count = |bin: u8, count: u64| BinCount { bin, count };
 
-    let address_1 = Addr::unchecked(test_data_airdrop.addresses[0].account.to_string());
-    let address_2 = Addr::unchecked(test_data_airdrop.addresses[1].account.to_string());
-    let address_3 = Addr::unchecked(test_data_airdrop.addresses[2].account.to_string());
+    assert!(get_bin_counts(&router, &game_addr).bin_counts.is_empty());
 
-    // Assign native token to owner and the two addresses
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &owner, funds.clone()).unwrap()
-    });
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &address_1, funds.clone()).unwrap()
-    });
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &address_2, funds.clone()).unwrap()
-    });
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &address_3, funds.clone()).unwrap()
-    });
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    assert_eq!(get_bin_counts(&router, &game_addr).bin_counts, vec![count(1, 2), count(10, 1)]);
 
-    // Create the game token contract.
-    let cw20_token = create_cw20(
-        &mut router,
-        &owner,
-        "token".to_string(),
-        "CWTOKEN".to_string(),
-        Uint128::new(1_000_000_000)
+    router
+        .execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ChangeBid { bin: 3, round: None },
+            &[],
+        ).unwrap();
+    assert_eq!(
+        get_bin_counts(&router, &game_addr).bin_counts,
+        vec![count(1, 1), count(3, 1), count(10, 1)]
     );
 
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RemoveBid { round: None },
+            &[],
+        ).unwrap();
+    assert_eq!(get_bin_counts(&router, &game_addr).bin_counts, vec![count(3, 1), count(10, 1)]);
+}
+
+// ======================================================================================
+// Tests Merkle root
+// ======================================================================================
+#[test]
+fn register_merkle_root() {
+    let mut router = mock_app();
+    let (_, owner,ticket_price, bins, funds) = global_variables();
+
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &owner, funds).unwrap()
+    });
+
     let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Create the game contract.
-    let cw20_token_address = Some(cw20_token.addr().to_string()).unwrap();
     let game_addr = create_game(
         &mut router,
         &owner,
@@ -900,15 +1276,18 @@ fn claim_prize() {
         stage_bid.clone(),
         stage_claim_airdrop.clone(),
         stage_claim_prize.clone(),
-        Some(cw20_token_address.clone()),
+        None,
     ).unwrap();
-
-    // Register Merkle roots.
+    
+    // Check Merkle roots properly saved.
     let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
-        merkle_root_airdrop: test_data_airdrop.root,
-        total_amount_airdrop: Some(Uint128::new(1_000)),
-        merkle_root_game: test_data_game.root,
-        total_amount_game: Some(Uint128::new(1_000_000)),
+        merkle_root_airdrop: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
+        total_amount_airdrop: None,
+        merkle_root_game: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d38".to_string(),
+        total_amount_game: None,
+        expected_recipients: None,
+        prize_tokens: None,
+        round: None,
     };
     let _res = router
         .execute_contract(
@@ -918,27 +1297,231 @@ fn claim_prize() {
             &[],
         ).unwrap();
 
-    // Transfer token to: 
-    // The game contract
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.clone().into(),amount: Uint128::new(1_001_000)};
-    let _res = router
-        .execute_contract(
-            owner.clone(),
-            Addr::unchecked(cw20_token_address.clone()),
-            &send_token_msg,
+    let info = get_merkle_roots(&router, &game_addr);
+    assert_eq!(
+        info.merkle_root_airdrop,
+        "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string()
+    );
+    assert_eq!(
+        info.merkle_root_game,
+        Some("634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d38".to_string())
+    );
+
+    // Only the game owner can register the roots.
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            game_addr.clone(),
+            &register_merkle_root_msg,
             &[],
-        ).unwrap();
-    // The first address
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_1.clone().to_string(), amount: Uint128::new(1_000)};
-    let _res = router
+        ).unwrap_err();
+
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+
+}
+
+const TEST_DATA_AIRDROP: &[u8] = include_bytes!("../testdata/airdrop_test_data.json");
+const TEST_DATA_GAME: &[u8] = include_bytes!("../testdata/airdrop_game_test_data.json");
+const TEST_DATA_STAKE: &[u8] = include_bytes!("../testdata/airdrop_stake_test_data.json");
+
+#[derive(Deserialize, Debug)]
+struct Address {
+    account: String,
+    amount: Uint128,
+    proofs: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Encoded {
+    root: String,
+    addresses: Vec<Address>
+}
+
+/// Move the chain to the given height.
+fn set_height(router: &mut App, height: u64) {
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height, time: current_block.time, chain_id: current_block.chain_id});
+}
+
+/// Create a funded game with registered Merkle roots, where the owner and the three
+/// test data addresses hold native funds. Returns the game, the token and the players.
+fn setup_claim_game(router: &mut App, msg: InstantiateMsg) -> (Addr, Cw20Contract, Vec<Addr>) {
+    setup_claim_game_with_prize_tokens(router, msg, &[])
+}
+
+/// Same as `setup_claim_game`, adding to the game prize the given amounts of other cw20
+/// tokens held by the owner.
+fn setup_claim_game_with_prize_tokens(
+    router: &mut App,
+    msg: InstantiateMsg,
+    prize_tokens: &[(Cw20Contract, Uint128)],
+) -> (Addr, Cw20Contract, Vec<Addr>) {
+    let game_id = router.store_code(contract_game());
+    setup_claim_game_with_code(router, game_id, msg, prize_tokens)
+}
+
+/// Same as `setup_claim_game_with_prize_tokens`, instantiating the game from the code.
+fn setup_claim_game_with_code(
+    router: &mut App,
+    game_id: u64,
+    mut msg: InstantiateMsg,
+    prize_tokens: &[(Cw20Contract, Uint128)],
+) -> (Addr, Cw20Contract, Vec<Addr>) {
+    let (_, owner, _, _, funds) = global_variables();
+
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+
+    for addr in std::iter::once(&owner).chain(players.iter()) {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
+
+    let cw20_token = create_cw20(
+        router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+
+    msg.cw20_token_address = cw20_token.addr().to_string();
+    let game_addr = router.instantiate_contract(game_id, owner.clone(), &msg, &[], "game", None).unwrap();
+
+    // The prizes are funded before the roots are registered.
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.to_string(), amount: Uint128::new(1_100_000)};
+    router
         .execute_contract(
             owner.clone(),
-            Addr::unchecked(cw20_token_address.clone()),
+            cw20_token.addr(),
             &send_token_msg,
             &[],
         ).unwrap();
-    // The second address
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_2.clone().to_string(), amount: Uint128::new(100)};
+    for (token, amount) in prize_tokens {
+        let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: *amount };
+        router.execute_contract(owner.clone(), token.addr(), &send_token_msg, &[]).unwrap();
+    }
+    if let Some(prize_denom) = &msg.prize_denom {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, &game_addr, vec![Coin::new(1_000_000, prize_denom)]).unwrap()
+        });
+    }
+
+    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
+        merkle_root_airdrop: test_data_airdrop.root,
+        total_amount_airdrop: Some(Uint128::new(11_330)),
+        merkle_root_game: test_data_game.root,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        expected_recipients: Some(6),
+        prize_tokens: Some(
+            prize_tokens
+                .iter()
+                .map(|(token, amount)| PrizeTokenAmount { token: token.addr().to_string(), amount: *amount })
+                .collect(),
+        ),
+        round: None,
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &register_merkle_root_msg,
+            &[],
+        ).unwrap();
+
+    (game_addr, cw20_token, players)
+}
+
+/// Place a bid paying exactly the default ticket price.
+fn place_bid(router: &mut App, game_addr: &Addr, player: &Addr, bin: u8) -> AnyResult<AppResponse> {
+    let (native_token_denom, _, ticket_price, _, _) = global_variables();
+    router.execute_contract(
+        player.clone(),
+        game_addr.clone(),
+        &ExecuteMsg::Bid { bin, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+        &[Coin {denom: native_token_denom, amount: ticket_price.amount}],
+    )
+}
+
+/// Claim the airdrop of the test data address at the given index.
+fn claim_airdrop_at(router: &mut App, game_addr: &Addr, index: usize) -> AnyResult<AppResponse> {
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[index].amount,
+        proof_airdrop: test_data_airdrop.addresses[index].proofs.clone(),
+        proof_game: test_data_game.addresses[index].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    router.execute_contract(
+        Addr::unchecked(test_data_airdrop.addresses[index].account.clone()),
+        game_addr.clone(),
+        &claim_airdrop_msg,
+        &[],
+    )
+}
+
+// ======================================================================================
+// Claims
+// ======================================================================================
+#[test]
+fn claim_airdrop() {
+    let mut router = mock_app();
+    let (_, owner,ticket_price, bins, funds) = global_variables();
+
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &owner, funds).unwrap()
+    });
+
+    // Create the game token contract.
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(2_000_000)
+    );
+
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    // Create the game contract.
+    let cw20_token_address = Some(cw20_token.addr().to_string()).unwrap();
+    let game_addr = create_game(
+        &mut router,
+        &owner,
+        ticket_price,
+        bins,
+        stage_bid.clone(),
+        stage_claim_airdrop.clone(),
+        stage_claim_prize.clone(),
+        Some(cw20_token_address.clone()),
+    ).unwrap();
+
+    // Check that the game has the correct cw20 token contract.
+    let info = get_config(&router, &game_addr);
+
+    assert_eq!(info.cw20_token_address, cw20_token_address);
+
+    // Check initial token balance of the owner.
+    let owner_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, owner.clone())
+        .unwrap();
+
+    assert_eq!(owner_balance, Uint128::new(2_000_000));
+
+    // Fund the prizes before registering the roots and verify the balance.
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.clone().into(),amount: Uint128::new(1_001_000)};
     let _res = router
         .execute_contract(
             owner.clone(),
@@ -946,469 +1529,7089 @@ fn claim_prize() {
             &send_token_msg,
             &[],
         ).unwrap();
-
     let game_balance = cw20_token
         .balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone())
         .unwrap();
-    let address_1_balance = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_1.clone())
-        .unwrap();
-    let address_2_balance = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_2.clone())
-        .unwrap();
 
     assert_eq!(game_balance, Uint128::new(1_001_000));
-    assert_eq!(address_1_balance, Uint128::new(1_000));
-    assert_eq!(address_2_balance, Uint128::new(100));
 
-    // Trigger bid stage start.
+    // Register Merkle roots.
+    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
+        merkle_root_airdrop: test_data_airdrop.root,
+        total_amount_airdrop: Some(Uint128::new(1_000)),
+        merkle_root_game: test_data_game.root,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        expected_recipients: None,
+        prize_tokens: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &register_merkle_root_msg,
+            &[],
+        ).unwrap();
+
+    // Check that initially no token have been claimed.
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_claimed_airdrop, Uint128::new(0));
+    assert_eq!(info.total_claimed_prize, Uint128::new(0));
+    assert_eq!(info.total_ticket_prize, Uint128::new(0));
+    assert_eq!(info.total_airdrop_amount, Uint128::new(1_000));
+    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_000_000));
+
+    // Claim not allowed if claiming stage not active.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[0].amount,
+        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+        proof_game: test_data_game.addresses[0].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(
+            Addr::unchecked(game_addr.to_string()),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::StageNotStarted {stage_name: String::from("claim airdrop")},err.downcast().unwrap());
+
+    // Trigger claiming airdrop stage.
     let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
+    router.set_block(BlockInfo {height: 201_001,time: current_block.time,chain_id: current_block.chain_id});
+
+    // Cannot be claimed a different amount than the one in the Merkle tree.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: Uint128::new(1_000),
+        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+        proof_game: test_data_game.addresses[0].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(
+            Addr::unchecked(test_data_airdrop.addresses[0].account.clone()),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::VerificationFailed { merkle_root: "airdrop".to_string() }, err.downcast().unwrap());
+
+    // Claim the correct ammount and verify balances.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[0].amount,
+        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+        proof_game: test_data_game.addresses[0].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+
+    let _res = router
+        .execute_contract(
+            Addr::unchecked(test_data_airdrop.addresses[0].account.clone()),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+    let claimer_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, Addr::unchecked(test_data_airdrop.addresses[0].account.clone()))
+        .unwrap();
+    let game_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone())
+        .unwrap();
+
+    assert_eq!(claimer_balance, Uint128::new(100));
+    assert_eq!(game_balance, Uint128::new(1_000_900));
+
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[0].amount,
+        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+        proof_game: test_data_game.addresses[0].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+
+    // Airdrop cannot be claimed more than once.
+    let err = router
+        .execute_contract(
+            Addr::unchecked(test_data_airdrop.addresses[0].account.clone()),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+
+    // Verify total claimed amount
+    let info = get_game_amount(&router, &game_addr);
+
+    assert_eq!(info.total_claimed_airdrop, Uint128::new(100));
+}
+
+#[test]
+fn claim_prize() {
+    let mut router = mock_app();
+    let (native_token_denom, owner,ticket_price, bins, funds) = global_variables();
+
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+
+    let address_1 = Addr::unchecked(test_data_airdrop.addresses[0].account.to_string());
+    let address_2 = Addr::unchecked(test_data_airdrop.addresses[1].account.to_string());
+    let address_3 = Addr::unchecked(test_data_airdrop.addresses[2].account.to_string());
+
+    // Assign native token to owner and the two addresses
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &owner, funds.clone()).unwrap()
+    });
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &address_1, funds.clone()).unwrap()
+    });
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &address_2, funds.clone()).unwrap()
+    });
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &address_3, funds.clone()).unwrap()
+    });
+
+    // Create the game token contract.
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    // Create the game contract.
+    let cw20_token_address = Some(cw20_token.addr().to_string()).unwrap();
+    let game_addr = create_game(
+        &mut router,
+        &owner,
+        ticket_price,
+        bins,
+        stage_bid.clone(),
+        stage_claim_airdrop.clone(),
+        stage_claim_prize.clone(),
+        Some(cw20_token_address.clone()),
+    ).unwrap();
+
+    // Transfer token to the game contract, funding the prizes before the roots are
+    // registered.
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.clone().into(),amount: Uint128::new(1_011_330)};
+    let _res = router
+        .execute_contract(
+            owner.clone(),
+            Addr::unchecked(cw20_token_address.clone()),
+            &send_token_msg,
+            &[],
+        ).unwrap();
+
+    // Register Merkle roots.
+    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
+        merkle_root_airdrop: test_data_airdrop.root,
+        total_amount_airdrop: Some(Uint128::new(11_330)),
+        merkle_root_game: test_data_game.root,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        expected_recipients: None,
+        prize_tokens: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &register_merkle_root_msg,
+            &[],
+        ).unwrap();
+
+    // Transfer token to:
+    // The first address
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_1.clone().to_string(), amount: Uint128::new(1_000)};
+    let _res = router
+        .execute_contract(
+            owner.clone(),
+            Addr::unchecked(cw20_token_address.clone()),
+            &send_token_msg,
+            &[],
+        ).unwrap();
+    // The second address
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_2.clone().to_string(), amount: Uint128::new(100)};
+    let _res = router
+        .execute_contract(
+            owner.clone(),
+            Addr::unchecked(cw20_token_address.clone()),
+            &send_token_msg,
+            &[],
+        ).unwrap();
+
+    let game_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone())
+        .unwrap();
+    let address_1_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_1.clone())
+        .unwrap();
+    let address_2_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_2.clone())
+        .unwrap();
+
+    assert_eq!(game_balance, Uint128::new(1_011_330));
+    assert_eq!(address_1_balance, Uint128::new(1_000));
+    assert_eq!(address_2_balance, Uint128::new(100));
+
+    // Trigger bid stage start.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
+
+    // Address 1 winning bid.
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
+    let _res = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &bid_msg,
+            &[bid.clone()],
+        ).unwrap();
+
+    // Address 2 losing bid.
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
+    let _res = router
+        .execute_contract(
+            address_2.clone(),
+            game_addr.clone(),
+            &bid_msg,
+            &[bid.clone()],
+        ).unwrap();
+
+    // Address 3 winning bid.
+    let bid_msg = ExecuteMsg::Bid { bin: 10, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
+    let _res = router
+        .execute_contract(
+            address_3.clone(),
+            game_addr.clone(),
+            &bid_msg,
+            &[bid.clone()],
+        ).unwrap();
+
+    // Trigger claiming airdrop stage.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 201_001,time: current_block.time,chain_id: current_block.chain_id});
+
+    // Address 1 claim the correct ammount and verify balances and winners numbers.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[0].amount,
+        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+        proof_game: test_data_game.addresses[0].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+    let balance_address_1 = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_1.clone())
+        .unwrap();
+
+    assert_eq!(balance_address_1, Uint128::new(1100));
+
+    // Check that initially no token have been claimed.
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_claimed_airdrop, Uint128::new(100));
+    assert_eq!(info.total_claimed_prize, Uint128::new(0));
+    assert_eq!(info.total_ticket_prize, Uint128::new(30));
+    assert_eq!(info.winners_amount, Uint128::new(1));
+    assert_eq!(info.total_airdrop_amount, Uint128::new(11_330));
+    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_000_000));
+
+    // Address 2 claim the correct ammount and verify balances and winners numbers.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[1].amount,
+        proof_airdrop: test_data_airdrop.addresses[1].proofs.clone(),
+        proof_game: test_data_game.addresses[1].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            address_2.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+    let balance_address_2 = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_2.clone())
+        .unwrap();
+
+    assert_eq!(balance_address_2, Uint128::new(1110));
+
+    // Address 3 claim the correct ammount and verify balances and winners numbers.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[2].amount,
+        proof_airdrop: test_data_airdrop.addresses[2].proofs.clone(),
+        proof_game: test_data_game.addresses[2].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            address_3.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+    let balance_address_3 = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_3.clone())
+        .unwrap();
+    let info = get_game_amount(&router, &game_addr);
+
+    assert_eq!(balance_address_3, Uint128::new(10220));
+    assert_eq!(info.total_claimed_prize, Uint128::new(0));
+    assert_eq!(info.total_ticket_prize, Uint128::new(30));
+    assert_eq!(info.winners_amount, Uint128::new(2));
+
+    // Cannot claim prize if relative stage is not started
+    let claim_prize_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let err = router
+        .execute_contract(
+            address_2.clone(),
+            game_addr.clone(),
+            &claim_prize_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::StageNotStarted { stage_name: String::from("claim prize") }, err.downcast().unwrap());
+
+    // Trigger claim prize stage start.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 202_001, time: current_block.time, chain_id: current_block.chain_id});
+
+    // Cannot claim prize if not winning bid.
+    let claim_prize_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let err = router
+        .execute_contract(
+            address_2.clone(),
+            game_addr.clone(),
+            &claim_prize_msg,
+            &[],
+        ).unwrap_err();
+    let balance_address_2 = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_2.clone())
+        .unwrap();
+    let bank_balance_address_2: Coin = bank_balance(&mut router, &address_2, native_token_denom.clone().to_string());
+
+    assert_eq!(ContractError::NoteEligible {}, err.downcast().unwrap());
+    assert_eq!(balance_address_2, Uint128::new(1110));
+    assert_eq!(bank_balance_address_2.amount, Uint128::new(999_990));
+
+    // Can claim prize if winning bid.
+    let claim_prize_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let _res = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &claim_prize_msg,
+            &[],
+        ).unwrap();
+    let balance_address_1 = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, address_1.clone())
+        .unwrap();
+    let bank_balance_address_1: Coin = bank_balance(&mut router, &address_1, native_token_denom.clone().to_string());
+
+    assert_eq!(balance_address_1, Uint128::new(1100) + Uint128::new(500_000));
+    assert_eq!(bank_balance_address_1.amount, Uint128::new(999_990) + Uint128::new(15));
+
+    // Verify claimed amounts
+    let info = get_game_amount(&router, &game_addr);
+
+    assert_eq!(info.total_claimed_prize, Uint128::new(15));
+    assert_eq!(info.total_claimed_airdrop, Uint128::new(500_000) + Uint128::new(100) + Uint128::new(1010) + Uint128::new(10220));
+
+    // Claim more than once the prize is not allowed
+    let claim_prize_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let err = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &claim_prize_msg,
+            &[],
+        ).unwrap_err();
+    
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+}
+
+// ======================================================================================
+// Withdraws
+// ======================================================================================
+#[test]
+fn withdraw_airdrop_and_prize() {
+    let mut router = mock_app();
+    let (native_token_denom, owner,ticket_price, bins, funds) = global_variables();
+
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+
+    let address_1 = Addr::unchecked(test_data_airdrop.addresses[0].account.to_string());
+    let address_2 = Addr::unchecked(test_data_airdrop.addresses[1].account.to_string());
+    let address_3 = Addr::unchecked(test_data_airdrop.addresses[2].account.to_string());
+
+    // Assign native token to owner and the two addresses
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &owner, funds.clone()).unwrap()
+    });
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &address_1, funds.clone()).unwrap()
+    });
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &address_2, funds.clone()).unwrap()
+    });
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &address_3, funds.clone()).unwrap()
+    });
+
+    // Create the game token contract.
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    // Create the game contract.
+    let cw20_token_address = Some(cw20_token.addr().to_string()).unwrap();
+    let game_addr = create_game(
+        &mut router,
+        &owner,
+        ticket_price,
+        bins,
+        stage_bid.clone(),
+        stage_claim_airdrop.clone(),
+        stage_claim_prize.clone(),
+        Some(cw20_token_address.clone()),
+    ).unwrap();
+
+    // Transfer token to the game contract, funding the prizes before the roots are
+    // registered.
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.clone().into(),amount: Uint128::new(1_011_330)};
+    let _res = router
+        .execute_contract(
+            owner.clone(),
+            Addr::unchecked(cw20_token_address.clone()),
+            &send_token_msg,
+            &[],
+        ).unwrap();
+
+    // Register Merkle roots.
+    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
+        merkle_root_airdrop: test_data_airdrop.root,
+        total_amount_airdrop: Some(Uint128::new(11_330)),
+        merkle_root_game: test_data_game.root,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        expected_recipients: None,
+        prize_tokens: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &register_merkle_root_msg,
+            &[],
+        ).unwrap();
+
+    // Transfer token to:
+    // The first address
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_1.clone().to_string(), amount: Uint128::new(1_000)};
+    let _res = router
+        .execute_contract(
+            owner.clone(),
+            Addr::unchecked(cw20_token_address.clone()),
+            &send_token_msg,
+            &[],
+        ).unwrap();
+    // The second address
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_2.clone().to_string(), amount: Uint128::new(100)};
+    let _res = router
+        .execute_contract(
+            owner.clone(),
+            Addr::unchecked(cw20_token_address.clone()),
+            &send_token_msg,
+            &[],
+        ).unwrap();
+
+    // Trigger bid stage start.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
+
+    // Address 1 winning bid.
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
+    let _res = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &bid_msg,
+            &[bid.clone()],
+        ).unwrap();
+
+    // Address 2 losing bid.
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
+    let _res = router
+        .execute_contract(
+            address_2.clone(),
+            game_addr.clone(),
+            &bid_msg,
+            &[bid.clone()],
+        ).unwrap();
+
+    // Address 3 winning bid.
+    let bid_msg = ExecuteMsg::Bid { bin: 10, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
+    let _res = router
+        .execute_contract(
+            address_3.clone(),
+            game_addr.clone(),
+            &bid_msg,
+            &[bid.clone()],
+        ).unwrap();
+
+    // Trigger claiming airdrop stage.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 201_001,time: current_block.time,chain_id: current_block.chain_id});
+
+    // Address 1 claim the correct ammount and verify balances and winners numbers.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[0].amount,
+        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+        proof_game: test_data_game.addresses[0].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+
+    // Address 2 claim the correct ammount and verify balances and winners numbers.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[1].amount,
+        proof_airdrop: test_data_airdrop.addresses[1].proofs.clone(),
+        proof_game: test_data_game.addresses[1].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            address_2.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+
+    // Address 3 claim the correct ammount and verify balances and winners numbers.
+    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[2].amount,
+        proof_airdrop: test_data_airdrop.addresses[2].proofs.clone(),
+        proof_game: test_data_game.addresses[2].proofs.clone(),
+        recipient: None,
+        round: None,
+    };
+    let _res = router
+        .execute_contract(
+            address_3.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+
+    // Trigger claim prize stage start.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 202_001, time: current_block.time, chain_id: current_block.chain_id});
+
+    // Can claim prize if winning bid.
+    let claim_prize_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let _res = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &claim_prize_msg,
+            &[],
+        ).unwrap();
+
+    // Verify claimed amounts
+    let info = get_game_amount(&router, &game_addr);
+
+    assert_eq!(info.total_ticket_prize, Uint128::new(30));
+    assert_eq!(info.total_airdrop_amount, Uint128::new(11_330));
+    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1000000));
+    assert_eq!(info.total_claimed_airdrop, Uint128::new(511330));
+    assert_eq!(info.total_claimed_prize, Uint128::new(15));
+
+    let withdraw_address = Addr::unchecked("withdraw0000");
+
+    // Just the owner can withdraw.
+    let claim_airdrop_msg = ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone(), round: None };
+    let err = router
+        .execute_contract(
+            address_1.clone(),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    // Cannot withdraw if claim prize stage not ended.
+    let claim_airdrop_msg = ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone(), round: None };
+    let err = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::ClaimPrizeStageNotFinished {}, err.downcast().unwrap());
+
+    // Check withdrawing address empty
+    let balance_withdraw = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address.clone())
+        .unwrap();
+    let bank_balance_withdraw: Coin = bank_balance(&mut router, &withdraw_address, native_token_denom.clone().to_string());
+
+    assert_eq!(balance_withdraw, Uint128::new(0));
+    assert_eq!(bank_balance_withdraw.amount, Uint128::new(0));
+    
+    // Trigger claim prize stage end.
+    let current_block = router.block_info();
+    router.set_block(BlockInfo {height: 203_001, time: current_block.time, chain_id: current_block.chain_id});
+
+    // Check withdraw leftover airdrop.
+    let claim_airdrop_msg = ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone(), round: None };
+    let _res = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+    let balance_withdraw = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address.clone())
+        .unwrap();
+    
+    assert_eq!(balance_withdraw, Uint128::new(500_000));
+
+    // Check withdraw leftover prize.
+    let claim_airdrop_msg = ExecuteMsg::WithdrawPrize { address: withdraw_address.clone(), round: None };
+    let _res = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &claim_airdrop_msg,
+            &[],
+        ).unwrap();
+    let bank_balance_withdraw: Coin = bank_balance(&mut router, &withdraw_address, native_token_denom.clone().to_string());
+
+    assert_eq!(bank_balance_withdraw.amount, Uint128::new(15));
+}
+
+#[test]
+fn claim_prize_first_bidder_bonus() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let fee_collector = Addr::unchecked("collector");
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.first_bidder_bonus = Some(Uint128::new(4));
+
+    // The bonus is paid from the protocol fee.
+    let err = create_game_with_msg(&mut router, &Addr::unchecked("owner"), &msg).unwrap_err();
+    assert_eq!(ContractError::FirstBidderBonusWithoutFee {}, err.downcast().unwrap());
+
+    msg.protocol_fee_bps = Some(2_000);
+    msg.fee_collector = Some(fee_collector.to_string());
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // Address 1 places the earliest winning bid, even if address 3 comes first
+    // in the address order.
+    set_height(&mut router, 200_000);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    for index in [2, 1, 0] {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.first_bidder_bonus, Uint128::new(4));
+    assert_eq!(info.first_winner, Some(players[0].to_string()));
+
+    // The bonus is paid to the earliest winner out of the protocol fee of 6 when the
+    // round is finalized, the rest of the fee going to the fee collector.
+    set_height(&mut router, 201_003);
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::FinalizeGame { round: None }, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::PROTOCOL_FEE, "6")
+            .add_attribute(events::FIRST_BIDDER_BONUS, "4")
+    ));
+    let balance_address_1 = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    assert_eq!(balance_address_1.amount, Uint128::new(999_990) + Uint128::new(4));
+    let balance_collector = bank_balance(&mut router, &fee_collector, native_token_denom.clone());
+    assert_eq!(balance_collector.amount, Uint128::new(2));
+
+    // The winners share the tickets prize left after the protocol fee.
+    set_height(&mut router, 202_001);
+    for player in [&players[0], &players[2]] {
+        router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+                &[],
+            ).unwrap();
+    }
+    let balance_address_1 = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    let balance_address_3 = bank_balance(&mut router, &players[2], native_token_denom);
+
+    assert_eq!(balance_address_1.amount, Uint128::new(999_990) + Uint128::new(12 + 4));
+    assert_eq!(balance_address_3.amount, Uint128::new(999_990) + Uint128::new(12));
+
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_claimed_prize, Uint128::new(24));
+}
+
+#[test]
+fn capped_winners() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.max_winners = Some(0);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidMaxWinners {}, err.downcast().unwrap());
+
+    msg.max_winners = Some(1);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // Address 3 places the earliest winning bid, address 1 the latest one.
+    set_height(&mut router, 200_000);
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+
+    // Address 1 is left out of the winners once address 3 claims, whatever the order
+    // of the claims, and its ticket is removed from the prize.
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.max_winners, Some(1));
+    assert_eq!(info.winners_amount, Uint128::new(1));
+    assert_eq!(info.total_ticket_prize, Uint128::new(20));
+
+    let notices: NoticesResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Notices { address: players[0].to_string() })
+        .unwrap();
+    assert_eq!(notices.notices.last(), Some(&Notice::Consolation {
+        round: 1,
+        amount: Uint128::new(10),
+        closes_at: Scheduled::AtHeight(202_002),
+    }));
+
+    // The selected winner takes the whole prize, the other one gets back the ticket.
+    set_height(&mut router, 202_001);
+    for player in [&players[2], &players[0]] {
+        router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+                &[],
+            ).unwrap();
+    }
+    let balance_address_1 = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    let balance_address_3 = bank_balance(&mut router, &players[2], native_token_denom);
+
+    assert_eq!(balance_address_1.amount, Uint128::new(1_000_000));
+    assert_eq!(balance_address_3.amount, Uint128::new(999_990) + Uint128::new(20));
+
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+
+    let outstanding = get_outstanding(&router, &game_addr);
+    assert_eq!(outstanding.unclaimed_prize, Uint128::zero());
+}
+
+#[test]
+fn donate_prize() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let charity = Addr::unchecked("charity0000");
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.charity_address = Some(charity.to_string());
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    set_height(&mut router, 202_001);
+
+    // Donation share must be valid.
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::DonatePrize { share_bps: 10_001, round: None },
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::InvalidDonationShare {}, err.downcast().unwrap());
+
+    // Half of the prize goes to the charity.
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::DonatePrize { share_bps: 5_000, round: None },
+            &[],
+        ).unwrap();
+    let bank_balance_address_1 = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    let bank_balance_charity = bank_balance(&mut router, &charity, native_token_denom);
+    let balance_charity = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, charity.clone())
+        .unwrap();
+
+    assert_eq!(bank_balance_address_1.amount, Uint128::new(999_990) + Uint128::new(8));
+    assert_eq!(bank_balance_charity.amount, Uint128::new(7));
+    assert_eq!(balance_charity, Uint128::new(250_000));
+
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_donated_prize, Uint128::new(7));
+    assert_eq!(info.total_donated_airdrop, Uint128::new(250_000));
+    assert_eq!(info.total_claimed_prize, Uint128::new(15));
+
+    // Donating is a claim, it cannot be repeated.
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        ).unwrap_err();
+
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+}
+
+#[test]
+fn outstanding_claims() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    let info = get_outstanding(&router, &game_addr);
+    assert_eq!(info.expected_recipients, 6);
+    assert_eq!(info.airdrop_claims, 0);
+    assert_eq!(info.unclaimed_recipients, 6);
+    assert_eq!(info.unclaimed_airdrop, Uint128::new(1_011_330));
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        ).unwrap();
+
+    let info = get_outstanding(&router, &game_addr);
+    assert_eq!(info.airdrop_claims, 3);
+    assert_eq!(info.unclaimed_recipients, 3);
+    assert_eq!(info.unclaimed_winners, Uint128::new(1));
+    assert_eq!(info.unclaimed_airdrop, Uint128::new(500_000));
+    assert_eq!(info.unclaimed_prize, Uint128::new(15));
+}
+
+#[test]
+fn game_querier() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 1).unwrap();
+
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        ).unwrap();
+
+    let wrapper = router.wrap();
+    let game = WasmGameQuerier::new(&wrapper, game_addr);
+
+    assert_eq!(game.stages().unwrap().stage_bid.start, Scheduled::AtHeight(200_000));
+    assert_eq!(game.game_status().unwrap().phase, GamePhase::ClaimPrize);
+    assert_eq!(game.bid(&players[1]).unwrap(), Some(1));
+    assert_eq!(game.bid(&players[2]).unwrap(), None);
+    assert!(game.is_winner(&players[0]).unwrap());
+    assert!(!game.is_winner(&players[1]).unwrap());
+    assert!(game.airdrop_claimed(&players[1]).unwrap());
+    assert!(!game.airdrop_claimed(&players[2]).unwrap());
+    assert!(game.prize_claimed(&players[0]).unwrap());
+    assert!(!game.prize_claimed(&players[1]).unwrap());
+}
+
+#[test]
+fn game_contract_helpers() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    let game = WasmGameContract(game_addr.clone());
+    let ticket = vec![Coin { denom: native_token_denom, amount: ticket_price.amount }];
+
+    let config = game.config::<App, MyCustomQuery>(&router).unwrap();
+    assert_eq!(config.cw20_token_address, cw20_token.addr().to_string());
+    assert_eq!(game.round::<App, MyCustomQuery>(&router).unwrap(), 1);
+    let stages = game.stages::<App, MyCustomQuery>(&router).unwrap();
+    assert_eq!(stages.stage_bid.start, Scheduled::AtHeight(200_000));
+
+    // The messages are executed as built by a downstream contract.
+    set_height(&mut router, 200_001);
+    assert_eq!(game.game_status::<App, MyCustomQuery>(&router).unwrap().phase, GamePhase::Bidding);
+    router.execute(players[0].clone(), game.bid(1, None, ticket.clone()).unwrap()).unwrap();
+    router.execute(players[1].clone(), game.bid(3, None, ticket.clone()).unwrap()).unwrap();
+    router.execute(players[2].clone(), game.bid(5, None, ticket).unwrap()).unwrap();
+    router.execute(players[1].clone(), game.change_bid(1, vec![]).unwrap()).unwrap();
+    router.execute(players[2].clone(), game.remove_bid().unwrap()).unwrap();
+    let bid = game.get_bid::<App, _, MyCustomQuery>(&router, &players[1]).unwrap();
+    assert_eq!(bid.bid, Some(1));
+    let bid = game.get_bid::<App, _, MyCustomQuery>(&router, &players[2]).unwrap();
+    assert_eq!(bid.bid, None);
+
+    set_height(&mut router, 201_001);
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    for (index, player) in players.iter().take(2).enumerate() {
+        let msg = game
+            .claim_airdrop(
+                test_data_airdrop.addresses[index].amount,
+                test_data_airdrop.addresses[index].proofs.clone(),
+                test_data_game.addresses[index].proofs.clone(),
+            )
+            .unwrap();
+        router.execute(player.clone(), msg).unwrap();
+    }
+
+    set_height(&mut router, 202_001);
+    router.execute(players[0].clone(), game.claim_prize(None).unwrap()).unwrap();
+    let res = router
+        .wrap()
+        .query_wasm_smart::<IsClaimedResponse>(
+            &game_addr,
+            &QueryMsg::IsClaimed { address: players[0].to_string(), round: None },
+        )
+        .unwrap();
+    assert!(res.prize_claimed);
+}
+
+#[test]
+fn claim_rebates() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let game_owner = Addr::unchecked("owner0000");
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &game_owner, funds).unwrap()
+    });
+
+    let get_rebates = |router: &App| -> RebatesResponse {
+        router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::Rebates { round: None })
+            .unwrap()
+    };
+    let fund_rebates_msg = ExecuteMsg::FundRebates {
+        rebate: Uint128::new(5),
+        round: None,
+    };
+    let rebate_funds = |amount: u128| vec![Coin { denom: native_token_denom.clone(), amount: Uint128::new(amount) }];
+
+    // Just the owner can fund the pool, with a single native token.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &fund_rebates_msg, &rebate_funds(12))
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let mut two_denoms = rebate_funds(12);
+    two_denoms.push(Coin { denom: "ubtc".to_string(), amount: Uint128::new(1) });
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &fund_rebates_msg, &two_denoms)
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidRebateFunds {}, err.downcast().unwrap());
+
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &fund_rebates_msg, &rebate_funds(12))
+        .unwrap();
+    let err = router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &fund_rebates_msg,
+            &[Coin { denom: "ubtc".to_string(), amount: Uint128::new(10) }],
+        ).unwrap_err();
+    assert_eq!(ContractError::InvalidRebateFunds {}, err.downcast().unwrap());
+
+    // The claims are rebated while the pool holds the rebate.
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        let res = claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+        let rebated = res.has_event(&Event::new("wasm").add_attribute(events::REBATE, "5"));
+        assert_eq!(rebated, index < 2);
+    }
+    for (index, player) in players.iter().enumerate() {
+        let rebate = if index < 2 { 5 } else { 0 };
+        let balance = bank_balance(&mut router, player, native_token_denom.clone());
+        assert_eq!(balance.amount, Uint128::new(1_000_000 + rebate));
+    }
+    assert_eq!(get_rebates(&router), RebatesResponse {
+        rebate: Some(Coin { denom: native_token_denom.clone(), amount: Uint128::new(5) }),
+        pool: Uint128::new(2),
+        total_paid: Uint128::new(10),
+    });
+
+    // What is left of the pool is withdrawn with the airdrop.
+    set_height(&mut router, 202_003);
+    let withdraw_address = Addr::unchecked("withdraw0000");
+    router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone(), round: None },
+            &[],
+        ).unwrap();
+    let balance = bank_balance(&mut router, &withdraw_address, native_token_denom);
+    assert_eq!(balance.amount, Uint128::new(2));
+    assert_eq!(get_rebates(&router).pool, Uint128::zero());
+}
+
+#[test]
+fn claim_size_limit() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.max_claim_size = Some(100);
+    let (game_addr, _, _) = setup_claim_game(&mut router, msg);
+
+    let limits: LimitsResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Limits {})
+        .unwrap();
+    assert_eq!(limits.max_claim_size, 100);
+    assert_eq!(limits.max_refund_limit, 30);
+
+    set_height(&mut router, 201_001);
+    let err = claim_airdrop_at(&mut router, &game_addr, 0).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::ClaimTooLarge { limit: 100, .. }
+    ));
+
+    // The proofs are registered one hash at a time, then the claim is sent without them.
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let claimer = Addr::unchecked(test_data_airdrop.addresses[0].account.clone());
+    let proofs = test_data_airdrop.addresses[0].proofs.iter().map(|proof| (vec![proof.clone()], vec![]))
+        .chain(test_data_game.addresses[0].proofs.iter().map(|proof| (vec![], vec![proof.clone()])));
+    for (proof_airdrop, proof_game) in proofs {
+        let register_msg = ExecuteMsg::RegisterClaimProofs { proof_airdrop, proof_game, round: None };
+        router.execute_contract(claimer.clone(), game_addr.clone(), &register_msg, &[]).unwrap();
+    }
+    let claim_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[0].amount,
+        proof_airdrop: vec![],
+        proof_game: vec![],
+        recipient: None,
+        round: None,
+    };
+    router.execute_contract(claimer.clone(), game_addr.clone(), &claim_msg, &[]).unwrap();
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_claimed_airdrop, test_data_airdrop.addresses[0].amount);
+
+    // Wrong proofs registered are cleared before registering the right ones.
+    let claimer = Addr::unchecked(test_data_airdrop.addresses[1].account.clone());
+    let register_msg = ExecuteMsg::RegisterClaimProofs {
+        proof_airdrop: vec![test_data_airdrop.addresses[0].proofs[0].clone()],
+        proof_game: vec![],
+        round: None,
+    };
+    router.execute_contract(claimer.clone(), game_addr.clone(), &register_msg, &[]).unwrap();
+    let claim_msg = ExecuteMsg::ClaimAirdrop {
+        amount: test_data_airdrop.addresses[1].amount,
+        proof_airdrop: vec![],
+        proof_game: vec![],
+        recipient: None,
+        round: None,
+    };
+    let err = router.execute_contract(claimer.clone(), game_addr.clone(), &claim_msg, &[]).unwrap_err();
+    assert_eq!(
+        ContractError::VerificationFailed { merkle_root: "airdrop".to_string() },
+        err.downcast().unwrap()
+    );
+    let clear_msg = ExecuteMsg::ClearClaimProofs { round: None };
+    router.execute_contract(claimer.clone(), game_addr.clone(), &clear_msg, &[]).unwrap();
+    for proof in &test_data_airdrop.addresses[1].proofs {
+        let register_msg = ExecuteMsg::RegisterClaimProofs {
+            proof_airdrop: vec![proof.clone()],
+            proof_game: vec![],
+            round: None,
+        };
+        router.execute_contract(claimer.clone(), game_addr.clone(), &register_msg, &[]).unwrap();
+    }
+    router.execute_contract(claimer, game_addr.clone(), &claim_msg, &[]).unwrap();
+}
+
+#[test]
+fn claimed_airdrops() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    let mut claimed: Vec<String> = players.iter().map(|p| p.to_string()).collect();
+    claimed.sort();
+
+    let claimed_airdrops = |start_after: Option<String>| -> ClaimedAirdropsResponse {
+        router
+            .wrap()
+            .query_wasm_smart(
+                &game_addr,
+                &QueryMsg::ClaimedAirdrops { start_after, limit: Some(2), round: None },
+            )
+            .unwrap()
+    };
+
+    let page = claimed_airdrops(None);
+    assert_eq!(page.addresses, claimed[..2].to_vec());
+    let page = claimed_airdrops(Some(claimed[1].clone()));
+    assert_eq!(page.addresses, claimed[2..].to_vec());
+    let page = claimed_airdrops(Some(claimed[2].clone()));
+    assert!(page.addresses.is_empty());
+}
+
+#[test]
+fn is_claimed() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 1).unwrap();
+
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        ).unwrap();
+
+    let is_claimed = |address: &Addr| -> IsClaimedResponse {
+        router
+            .wrap()
+            .query_wasm_smart(
+                &game_addr,
+                &QueryMsg::IsClaimed { address: address.to_string(), round: None },
+            )
+            .unwrap()
+    };
+    let status = |airdrop_claimed, prize_eligible, prize_claimed| IsClaimedResponse {
+        airdrop_claimed,
+        prize_eligible,
+        prize_claimed,
+    };
+
+    assert_eq!(is_claimed(&players[0]), status(true, true, true));
+    assert_eq!(is_claimed(&players[1]), status(true, false, false));
+    assert_eq!(is_claimed(&players[2]), status(false, false, false));
+}
+
+#[test]
+fn verify_proof() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, _) = setup_claim_game(&mut router, msg);
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+
+    let verify_proof = |root_kind, address: &Address, amount_or_bin: String| {
+        router.wrap().query_wasm_smart::<VerifyProofResponse>(
+            &game_addr,
+            &QueryMsg::VerifyProof {
+                root_kind,
+                address: address.account.clone(),
+                amount_or_bin,
+                proof: address.proofs.clone(),
+                round: None,
+            },
+        )
+    };
+    let verified = |verified| Ok(VerifyProofResponse { verified });
+
+    // The proofs are checked before the claim stage, as the leaves of the claims.
+    let airdrop = &test_data_airdrop.addresses[0];
+    assert_eq!(verify_proof(MerkleRootKind::Airdrop, airdrop, airdrop.amount.to_string()), verified(true));
+    assert_eq!(verify_proof(MerkleRootKind::Airdrop, airdrop, format!("0{}", airdrop.amount)), verified(true));
+    assert_eq!(verify_proof(MerkleRootKind::Airdrop, airdrop, (airdrop.amount + Uint128::new(1)).to_string()), verified(false));
+    let game = &test_data_game.addresses[0];
+    assert_eq!(verify_proof(MerkleRootKind::Game, game, game.amount.to_string()), verified(true));
+    assert_eq!(verify_proof(MerkleRootKind::Game, &test_data_game.addresses[1], game.amount.to_string()), verified(false));
+
+    // The bin and the amount must be valid values of the claim messages.
+    verify_proof(MerkleRootKind::Game, game, "256".to_string()).unwrap_err();
+    verify_proof(MerkleRootKind::Airdrop, airdrop, "ten".to_string()).unwrap_err();
+
+    // No stake root is registered.
+    verify_proof(MerkleRootKind::Stake, airdrop, airdrop.amount.to_string()).unwrap_err();
+}
+
+#[test]
+fn keccak256_merkle_trees() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.cw20_token_address = cw20_token.addr().to_string();
+    msg.hash_algorithm = Some(HashAlgorithm::Keccak256);
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    assert_eq!(get_config(&router, &game_addr).hash_algorithm, HashAlgorithm::Keccak256);
+
+    // A two leaves airdrop tree, as generated by the Ethereum tooling.
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let claimers = [Addr::unchecked("wasm1claimer0"), Addr::unchecked("wasm1claimer1")];
+    let amount = Uint128::new(1_000);
+    let tree = |algorithm: HashAlgorithm| {
+        let leaves: Vec<[u8; 32]> = claimers
+            .iter()
+            .map(|claimer| algorithm.hash(format!("{}{}", claimer, amount).as_bytes()).unwrap())
+            .collect();
+        let mut pair = [leaves[0], leaves[1]];
+        pair.sort_unstable();
+        let root = hex::encode(algorithm.hash(&pair.concat()).unwrap());
+        (root, vec![vec![hex::encode(leaves[1])], vec![hex::encode(leaves[0])]])
+    };
+    let (merkle_root_airdrop, proofs) = tree(HashAlgorithm::Keccak256);
+
+    router
+        .execute_contract(
+            owner,
+            cw20_token.addr(),
+            &cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: amount * Uint128::new(2) + Uint128::new(1_000_000) },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterMerkleRoots {
+                merkle_root_airdrop,
+                total_amount_airdrop: Some(amount * Uint128::new(2)),
+                merkle_root_game: test_data_game.root,
+                total_amount_game: Some(Uint128::new(1_000_000)),
+                expected_recipients: Some(2),
+                prize_tokens: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let claim_airdrop_msg = |proof_airdrop: Vec<String>| ExecuteMsg::ClaimAirdrop {
+        amount,
+        proof_airdrop,
+        proof_game: vec![],
+        recipient: None,
+        round: None,
+    };
+    set_height(&mut router, 201_001);
+
+    // The proofs of the same tree hashed with sha256 are rejected.
+    let (_, sha256_proofs) = tree(HashAlgorithm::Sha256);
+    assert_ne!(sha256_proofs, proofs);
+    let err = router
+        .execute_contract(claimers[0].clone(), game_addr.clone(), &claim_airdrop_msg(sha256_proofs[0].clone()), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VerificationFailed { merkle_root: "airdrop".to_string() },
+        err.downcast().unwrap()
+    );
+
+    // The keccak256 proofs are verified, as by the VerifyProof query.
+    let verified: VerifyProofResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &game_addr,
+            &QueryMsg::VerifyProof {
+                root_kind: MerkleRootKind::Airdrop,
+                address: claimers[1].to_string(),
+                amount_or_bin: amount.to_string(),
+                proof: proofs[1].clone(),
+                round: None,
+            },
+        )
+        .unwrap();
+    assert!(verified.verified);
+    for (claimer, proof) in claimers.iter().zip(proofs) {
+        router.execute_contract(claimer.clone(), game_addr.clone(), &claim_airdrop_msg(proof), &[]).unwrap();
+        assert_eq!(cw20_token.balance::<App, Addr, MyCustomQuery>(&router, claimer.clone()).unwrap(), amount);
+    }
+}
+
+#[test]
+fn stake_weighted_prize() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let test_data_stake: Encoded = from_slice(TEST_DATA_STAKE).unwrap();
+
+    let register_msg = ExecuteMsg::RegisterStakeRoot {
+        merkle_root_stake: test_data_stake.root.clone(),
+        round: None,
+    };
+    router
+        .execute_contract(Addr::unchecked("owner0000"), game_addr.clone(), &register_msg, &[])
+        .unwrap();
+
+    set_height(&mut router, 200_001);
+
+    // The stake root cannot change once bids are open.
+    let err = router
+        .execute_contract(Addr::unchecked("owner0000"), game_addr.clone(), &register_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::StakeRootAfterBidStart {}, err.downcast().unwrap());
+
+    let prove_stake_msg = |index: usize, weight: u64| ExecuteMsg::ProveStake {
+        weight,
+        proof: test_data_stake.addresses[index].proofs.clone(),
+        round: None,
+    };
+
+    // A bid is required to prove the stake.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &prove_stake_msg(0, 3), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BidNotPresent {}, err.downcast().unwrap());
+
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    // The weight must match the snapshot.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &prove_stake_msg(0, 4), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VerificationFailed { merkle_root: "stake".to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &prove_stake_msg(0, 3), &[])
+        .unwrap();
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    // Winners split the prize 3:1.
+    set_height(&mut router, 202_001);
+    for (player, ticket_share, airdrop_share) in [(&players[0], "22", "750000"), (&players[2], "7", "250000")] {
+        let res = router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+                &[],
+            ).unwrap();
+        let event = Event::new("wasm")
+            .add_attribute(events::PRIZE_FROM_TICKETS, ticket_share)
+            .add_attribute(events::PRIZE_FROM_AIRDROP, airdrop_share);
+        assert!(res.has_event(&event));
+    }
+}
+
+#[test]
+fn bid_cost_overflow() {
+    let mut router = mock_app();
+    let (native_token_denom, _, _, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let ticket_price = Coin { denom: native_token_denom.clone(), amount: Uint128::MAX };
+    let msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // The cost of the tickets is checked before the funds sent.
+    set_height(&mut router, 200_001);
+    let bid_msg = ExecuteMsg::Bid { bin: 1, tickets: Some(2), recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None };
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr,
+            &bid_msg,
+            &[Coin { denom: native_token_denom, amount: Uint128::new(10) }],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::BidCostOverflow { tickets: 2, bins: 1 }, err.downcast().unwrap());
+}
+
+#[test]
+fn ticket_weighted_prize() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let bid = |router: &mut App, player: &Addr, bin: u8, tickets: Option<u32>, amount: u128| {
+        router.execute_contract(
+            player.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::Bid { bin, tickets, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(amount) }],
+        )
+    };
+
+    // At least one ticket is bought, and each one is paid.
+    set_height(&mut router, 200_001);
+    let err = bid(&mut router, &players[0], 2, Some(0), 10).unwrap_err();
+    assert_eq!(ContractError::InvalidTickets {}, err.downcast().unwrap());
+    let err = bid(&mut router, &players[0], 2, Some(3), 20).unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+
+    // The tickets follow the bid when it changes bin.
+    bid(&mut router, &players[0], 2, Some(3), 30).unwrap();
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ChangeBid { bin: 1, round: None },
+            &[],
+        )
+        .unwrap();
+    let info = get_bid(&router, &game_addr, players[0].to_string());
+    assert_eq!(BidResponse { bid: Some(1), bins: vec![1], tickets: 3, stake: Uint128::new(30), changes: 1 }, info);
+
+    // Removing the bid pays back every ticket.
+    bid(&mut router, &players[1], 1, Some(2), 20).unwrap();
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::TICKET_PRICE_PAYBACK, "20")));
+
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(50));
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    // Winners split the prize by their tickets, 3:1.
+    set_height(&mut router, 202_001);
+    for (player, ticket_share, airdrop_share) in [(&players[0], "37", "750000"), (&players[2], "12", "250000")] {
+        let res = router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+                &[],
+            ).unwrap();
+        let event = Event::new("wasm")
+            .add_attribute(events::PRIZE_FROM_TICKETS, ticket_share)
+            .add_attribute(events::PRIZE_FROM_AIRDROP, airdrop_share);
+        assert!(res.has_event(&event));
+    }
+}
+
+#[test]
+fn parimutuel_prize() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.parimutuel = Some(true);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    assert!(get_config(&router, &game_addr).parimutuel);
+    let bid = |router: &mut App, player: &Addr, bin: u8, amount: u128| {
+        router.execute_contract(
+            player.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::Bid { bin, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(amount) }],
+        )
+    };
+
+    // The stake cannot be lower than the ticket price, and is not sent back as change.
+    set_height(&mut router, 200_001);
+    let err = bid(&mut router, &players[0], 1, 5).unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+    let res = bid(&mut router, &players[0], 1, 60).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::AMOUNT, "60")));
+    let balance = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    assert_eq!(balance.amount, Uint128::new(1_000_000 - 60));
+    let info = get_bid(&router, &game_addr, players[0].to_string());
+    assert_eq!(info.stake, Uint128::new(60));
+
+    bid(&mut router, &players[1], 1, 10).unwrap();
+    bid(&mut router, &players[2], 10, 20).unwrap();
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(90));
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    // Winners split the pool by their stake, 3:1.
+    set_height(&mut router, 202_001);
+    for (player, ticket_share, airdrop_share) in [(&players[0], "67", "750000"), (&players[2], "22", "250000")] {
+        let res = router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+                &[],
+            ).unwrap();
+        let event = Event::new("wasm")
+            .add_attribute(events::PRIZE_FROM_TICKETS, ticket_share)
+            .add_attribute(events::PRIZE_FROM_AIRDROP, airdrop_share);
+        assert!(res.has_event(&event));
+    }
+}
+
+#[test]
+fn commit_reveal() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+
+    // The reveal stage cannot overlap the bid stage.
+    msg.stage_reveal = Some(Stage {
+        start: Scheduled::AtHeight(200_001),
+        duration: Duration::Height(2),
+    });
+    let err = create_game_with_msg(&mut router, &Addr::unchecked("owner"), &msg).unwrap_err();
+    assert_eq!(
+        ContractError::StagesOverlap { first: "bid".to_string(), second: "Reveal".to_string() },
+        err.downcast().unwrap()
+    );
+
+    let stage_reveal = Stage {
+        start: Scheduled::AtHeight(200_500),
+        duration: Duration::Height(2),
+    };
+    msg.stage_reveal = Some(stage_reveal.clone());
+    msg.max_per_bin = Some(1);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    assert_eq!(get_stages(&router, &game_addr).stage_reveal, Some(stage_reveal));
+
+    let commit = |router: &mut App, player: &Addr, bin: u8, salt: &str| {
+        let hash = bid_commitment(player, 1, &game_addr, bin, salt);
+        router.execute_contract(
+            player.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::CommitBid { hash, allowlist_proof: None, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(10) }],
+        )
+    };
+    let reveal = |router: &mut App, player: &Addr, bin: u8, salt: &str| {
+        router.execute_contract(
+            player.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RevealBid { bin, salt: salt.to_string(), round: None },
+            &[],
+        )
+    };
+
+    // Bids are committed during the bid stage, they cannot be placed in clear.
+    set_height(&mut router, 200_001);
+    let err = place_bid(&mut router, &game_addr, &players[0], 1).unwrap_err();
+    assert_eq!(ContractError::CommitRequired {}, err.downcast().unwrap());
+    commit(&mut router, &players[0], 1, "salt0").unwrap();
+    let err = commit(&mut router, &players[0], 2, "salt0").unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+    commit(&mut router, &players[1], 1, "0salt1").unwrap();
+    commit(&mut router, &players[2], 10, "salt2").unwrap();
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, None);
+
+    let err = reveal(&mut router, &players[0], 1, "salt0").unwrap_err();
+    assert_eq!(
+        ContractError::StageNotStarted { stage_name: "reveal".to_string() },
+        err.downcast().unwrap()
+    );
+
+    // Only the committed bin and salt are accepted.
+    set_height(&mut router, 200_501);
+    let status: GameStatusResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::GameStatus { round: None })
+        .unwrap();
+    assert_eq!(status.phase, GamePhase::Revealing);
+    let err = reveal(&mut router, &players[0], 2, "salt0").unwrap_err();
+    assert_eq!(ContractError::InvalidReveal {}, err.downcast().unwrap());
+    reveal(&mut router, &players[0], 1, "salt0").unwrap();
+    reveal(&mut router, &players[2], 10, "salt2").unwrap();
+    let err = reveal(&mut router, &players[2], 10, "salt2").unwrap_err();
+    assert_eq!(ContractError::CommitmentNotPresent {}, err.downcast().unwrap());
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, Some(1));
+
+    // A commitment on bin 1 cannot be opened on bin 10 by moving a digit of the salt,
+    // and the revealed bid is subject to the limit of its bin.
+    let err = reveal(&mut router, &players[1], 10, "salt1").unwrap_err();
+    assert_eq!(ContractError::InvalidReveal {}, err.downcast().unwrap());
+    let err = reveal(&mut router, &players[1], 1, "0salt1").unwrap_err();
+    assert_eq!(ContractError::BinFull { bin: 1, limit: 1 }, err.downcast().unwrap());
+
+    // The ticket of the bid not revealed is kept in the prize.
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_ticket_prize, Uint128::new(30));
+    assert_eq!(info.winners_amount, Uint128::new(2));
+
+    set_height(&mut router, 202_001);
+    for player in [&players[0], &players[2]] {
+        let res = router
+            .execute_contract(player.clone(), game_addr.clone(), &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None }, &[])
+            .unwrap();
+        assert!(res.has_event(&Event::new("wasm").add_attribute(events::PRIZE_FROM_TICKETS, "15")));
+    }
+}
+
+#[test]
+fn anti_snipe() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (mut stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    stage_bid.duration = Duration::Height(100);
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.anti_snipe = Some(AntiSnipe { window: 0, extension: 20, max_extension: 30 });
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidAntiSnipe {}, err.downcast().unwrap());
+
+    msg.anti_snipe = Some(AntiSnipe { window: 10, extension: 20, max_extension: 30 });
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let bid_stage_duration = |router: &App| get_stages(router, &game_addr).stage_bid.duration;
+
+    // Bids placed before the last blocks of the stage do not extend it.
+    set_height(&mut router, 200_050);
+    place_bid(&mut router, &game_addr, &owner, 3).unwrap();
+    assert_eq!(bid_stage_duration(&router), Duration::Height(100));
+
+    // Late bids extend the stage, up to the maximum extension.
+    set_height(&mut router, 200_095);
+    let res = place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::STAGE_EXTENSION, "20")));
+    assert_eq!(bid_stage_duration(&router), Duration::Height(120));
+
+    set_height(&mut router, 200_115);
+    let res = place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::STAGE_EXTENSION, "10")));
+    assert_eq!(bid_stage_duration(&router), Duration::Height(130));
+
+    set_height(&mut router, 200_125);
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    assert_eq!(bid_stage_duration(&router), Duration::Height(130));
+
+    set_height(&mut router, 200_130);
+    let err = place_bid(&mut router, &game_addr, &players[2], 10).unwrap_err();
+    assert_eq!(
+        ContractError::StageEnded { stage_name: "bid".to_string() },
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn price_schedule() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (mut stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    stage_bid.duration = Duration::Height(900);
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.price_schedule = Some(vec![
+        PriceStep { start: Scheduled::AtHeight(200_500), amount: Uint128::new(20) },
+        PriceStep { start: Scheduled::AtHeight(200_100), amount: Uint128::new(15) },
+    ]);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidPriceSchedule {}, err.downcast().unwrap());
+
+    msg.price_schedule = Some(vec![
+        PriceStep { start: Scheduled::AtHeight(200_100), amount: Uint128::new(15) },
+        PriceStep { start: Scheduled::AtHeight(200_500), amount: Uint128::new(20) },
+    ]);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let current_price = |router: &App| -> TicketPriceResponse {
+        router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::TicketPrice { round: None })
+            .unwrap()
+    };
+
+    // Before the first step, the ticket price of the round applies.
+    set_height(&mut router, 200_001);
+    assert_eq!(current_price(&router).ticket_price.amount, Uint128::new(10));
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+
+    // Once a step starts, its price applies.
+    set_height(&mut router, 200_200);
+    let res = current_price(&router);
+    assert_eq!(res.ticket_price.amount, Uint128::new(15));
+    assert_eq!(res.price_schedule.len(), 2);
+    let err = place_bid(&mut router, &game_addr, &players[1], 1).unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+    router
+        .execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(15) }],
+        )
+        .unwrap();
+
+    set_height(&mut router, 200_600);
+    assert_eq!(current_price(&router).ticket_price.amount, Uint128::new(20));
+
+    // Removing a bid pays back the price paid for it.
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    assert_eq!(
+        bank_balance(&mut router, &players[1], native_token_denom).amount,
+        Uint128::new(1_000_000)
+    );
+}
+
+#[test]
+fn remove_bid_penalty() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.remove_bid_penalty_bps = Some(10_001);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidRemoveBidPenalty {}, err.downcast().unwrap());
+
+    msg.remove_bid_penalty_bps = Some(3_000);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    assert_eq!(get_config(&router, &game_addr).remove_bid_penalty_bps, 3_000);
+
+    // The penalty is retained from the stake and stays in the ticket prize.
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::REMOVE_BID_PENALTY, "3")));
+    assert_eq!(
+        bank_balance(&mut router, &players[1], native_token_denom).amount,
+        Uint128::new(999_997)
+    );
+
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(23));
+}
+
+#[test]
+fn bid_change_limits() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.bid_change_fee = Some(Coin { denom: native_token_denom.clone(), amount: Uint128::zero() });
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidBidChangeFee {}, err.downcast().unwrap());
+
+    msg.bid_change_fee = Some(Coin { denom: native_token_denom.clone(), amount: Uint128::new(3) });
+    msg.max_bid_changes = Some(2);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let change_bid = |router: &mut App, bin: u8, fee: u128| {
+        router.execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ChangeBid { bin, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(fee) }],
+        )
+    };
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 2).unwrap();
+
+    // Each change pays the fee, the funds sent above it are sent back.
+    let err = change_bid(&mut router, 1, 2).unwrap_err();
+    assert_eq!(ContractError::BidChangeFeeNotPaid {}, err.downcast().unwrap());
+    let res = change_bid(&mut router, 1, 5).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::BID_CHANGE_FEE, "3")));
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        Uint128::new(999_987)
+    );
+
+    // A change in a batch pays the fee out of the funds sent with the batch.
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::BatchActions { actions: vec![PlayerAction::ChangeBid { bin: 3, round: None }] },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(10) }],
+        )
+        .unwrap();
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).changes, 2);
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        Uint128::new(999_984)
+    );
+
+    // The changes are limited.
+    let err = change_bid(&mut router, 1, 3).unwrap_err();
+    assert_eq!(ContractError::TooManyBidChanges { limit: 2 }, err.downcast().unwrap());
+
+    // The fees are withdrawn with the prize leftovers.
+    set_height(&mut router, 202_003);
+    let res = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::WithdrawPrize { address: owner.clone(), round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::BID_CHANGE_FEE, "6")));
+}
+
+#[test]
+fn bid_for_recipient() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // The sender pays the ticket and gets back the change, the bid is placed for the
+    // recipient.
+    set_height(&mut router, 200_001);
+    let res = router
+        .execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::Bid { bin: 1, tickets: None, recipient: Some(players[0].to_string()), allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(15) }],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::PLAYER, players[0].as_str())
+            .add_attribute(events::PAYER, players[1].as_str())
+    ));
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, Some(1));
+    assert_eq!(get_bid(&router, &game_addr, players[1].to_string()).bid, None);
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        Uint128::new(1_000_000)
+    );
+    assert_eq!(
+        bank_balance(&mut router, &players[1], native_token_denom.clone()).amount,
+        Uint128::new(999_990)
+    );
+
+    // The recipient cannot bid twice, the payer can still place its own bid.
+    let err = place_bid(&mut router, &game_addr, &players[0], 1).unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+
+    // The recipient is the winner of the bid.
+    set_height(&mut router, 201_001);
+    let res = claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::PLAYER, players[0].as_str())));
+    assert!(get_game_amount(&router, &game_addr).winners_amount > Uint128::zero());
+}
+
+#[test]
+fn gift_bid() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let gift_bid = |router: &mut App, recipient: &Addr| {
+        router.execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::GiftBid { recipient: recipient.to_string(), bin: 1, round: None },
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(10) }],
+        )
+    };
+
+    // The sender pays the ticket of the bid recorded for the recipient.
+    set_height(&mut router, 200_001);
+    let res = gift_bid(&mut router, &players[0]).unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::ACTION, "gift_bid")
+            .add_attribute(events::PLAYER, players[0].as_str())
+            .add_attribute(events::PAYER, players[1].as_str())
+    ));
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, Some(1));
+    assert_eq!(get_bid(&router, &game_addr, players[1].to_string()).bid, None);
+    assert_eq!(
+        bank_balance(&mut router, &players[1], native_token_denom.clone()).amount,
+        Uint128::new(999_990)
+    );
+
+    // A recipient with a bid cannot receive another one.
+    let err = gift_bid(&mut router, &players[0]).unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+}
+
+#[test]
+fn transfer_bid() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let transfer_bid = |router: &mut App, sender: &Addr, new_address: &Addr| {
+        router.execute_contract(
+            sender.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::TransferBid { new_address: new_address.to_string(), round: None },
+            &[],
+        )
+    };
+
+    set_height(&mut router, 200_001);
+    let err = transfer_bid(&mut router, &players[1], &players[0]).unwrap_err();
+    assert_eq!(ContractError::BidNotPresent {}, err.downcast().unwrap());
+
+    // The bid cannot be moved to an address with a bid.
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    let err = transfer_bid(&mut router, &players[1], &players[2]).unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+
+    // The bid is moved with its bin and tickets.
+    let res = transfer_bid(&mut router, &players[1], &players[0]).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::ADDRESS, players[0].as_str())));
+    assert_eq!(get_bid(&router, &game_addr, players[1].to_string()).bid, None);
+    let bid = get_bid(&router, &game_addr, players[0].to_string());
+    assert_eq!((bid.bid, bid.tickets, bid.stake), (Some(1), 1, Uint128::new(10)));
+
+    // The new address is the winner of the bid.
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 1).unwrap();
+    assert_eq!(get_game_amount(&router, &game_addr).first_winner, Some(players[0].to_string()));
+}
+
+#[test]
+fn claim_to_recipient() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    let cold_wallet = Addr::unchecked("cold0000");
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    // The claim is verified for the sender, the airdrop is sent to the recipient.
+    set_height(&mut router, 201_001);
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimAirdrop {
+                amount: test_data_airdrop.addresses[0].amount,
+                proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+                proof_game: test_data_game.addresses[0].proofs.clone(),
+                recipient: Some(cold_wallet.to_string()),
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::RECIPIENT, cold_wallet.as_str())));
+    let airdrop_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, cold_wallet.clone())
+        .unwrap();
+    assert_eq!(airdrop_balance, test_data_airdrop.addresses[0].amount);
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // The prize of the sender is sent to the recipient.
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: Some(cold_wallet.to_string()), stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(bank_balance(&mut router, &cold_wallet, native_token_denom).amount, Uint128::new(10));
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+}
+
+#[test]
+fn claim_all() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+
+    // The airdrop is claimed, the prize is left to its own stage.
+    set_height(&mut router, 201_001);
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimAll {
+                amount: test_data_airdrop.addresses[0].amount,
+                proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+                proof_game: test_data_game.addresses[0].proofs.clone(),
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::ACTION, "claim_airdrop")));
+    assert!(!res.has_event(&Event::new("wasm").add_attribute(events::ACTION, "claim_prize")));
+    let airdrop_balance = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, players[0].clone())
+        .unwrap();
+    assert_eq!(airdrop_balance, test_data_airdrop.addresses[0].amount);
+
+    let claimed: IsClaimedResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::IsClaimed { address: players[0].to_string(), round: None })
+        .unwrap();
+    assert!(claimed.airdrop_claimed && claimed.prize_eligible && !claimed.prize_claimed);
+}
+
+#[test]
+fn claim_airdrop_to() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+
+    // A second game, still open to the sponsors, receives the claimed airdrop.
+    let later_stage = |start: u64| Stage { start: Scheduled::AtHeight(start), duration: Duration::Height(2) };
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        later_stage(300_000),
+        later_stage(301_000),
+        later_stage(302_000),
+        None,
+    );
+    let target_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+
+    set_height(&mut router, 201_001);
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimAirdropTo {
+                contract: target_addr.to_string(),
+                msg: to_binary(&ReceiveMsg::Sponsor { ratio: Decimal::one(), round: None }).unwrap(),
+                amount: test_data_airdrop.addresses[0].amount,
+                proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
+                proof_game: test_data_game.addresses[0].proofs.clone(),
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::RECIPIENT, target_addr.as_str())));
+
+    // The airdrop sponsors the second game. The cw20 is sent by the game contract, that
+    // the target contract sees as the sender.
+    let sponsorships: SponsorshipsResponse = router
+        .wrap()
+        .query_wasm_smart(&target_addr, &QueryMsg::Sponsorships { round: None })
+        .unwrap();
+    assert_eq!(sponsorships.sponsorships.len(), 1);
+    assert_eq!(sponsorships.sponsorships[0].sponsor, game_addr.to_string());
+    assert_eq!(sponsorships.sponsorships[0].token, cw20_token.addr().to_string());
+    assert_eq!(sponsorships.sponsorships[0].cap, test_data_airdrop.addresses[0].amount);
+}
+
+#[test]
+fn claim_airdrop_for() {
+    use bech32::ToBase32;
+    use k256::ecdsa::signature::Signer;
+    use sha2::Digest;
+
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    // The claimer holds a secp256k1 key, and its address is derived from the public key.
+    let signing_key = k256::ecdsa::SigningKey::from_bytes(&[7u8; 32]).unwrap();
+    let pubkey = Binary::from(signing_key.verifying_key().to_bytes().to_vec());
+    let pubkey_hash = ripemd160::Ripemd160::digest(&sha2::Sha256::digest(pubkey.as_slice()));
+    let claimer = bech32::encode("wasm", pubkey_hash.to_base32(), bech32::Variant::Bech32).unwrap();
+    let amount = Uint128::new(1_000);
+
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.cw20_token_address = cw20_token.addr().to_string();
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+
+    // A single leaf airdrop tree: its root is the hash of the leaf.
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let merkle_root_airdrop = hex::encode(sha2::Sha256::digest(format!("{}{}", claimer, amount).as_bytes()));
+    router
+        .execute_contract(
+            owner,
+            cw20_token.addr(),
+            &cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: amount + Uint128::new(1_000_000) },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterMerkleRoots {
+                merkle_root_airdrop,
+                total_amount_airdrop: Some(amount),
+                merkle_root_game: test_data_game.root,
+                total_amount_game: Some(Uint128::new(1_000_000)),
+                expected_recipients: Some(1),
+                prize_tokens: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // The claimer signs the authorization, which binds the proofs, as ADR-36 arbitrary data.
+    let proof_game = vec![hex::encode(sha2::Sha256::digest(b"game proof"))];
+    let data = claim_authorization(&game_addr, 1, amount, &[], &proof_game);
+    let sign_doc = format!(
+        r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+        Binary::from(data.as_bytes()).to_base64(),
+        claimer
+    );
+    let signature: k256::ecdsa::Signature = signing_key.sign(sign_doc.as_bytes());
+    let signature = Binary::from(signature.as_ref());
+
+    let relayer = Addr::unchecked("relayer");
+    let claim_msg_with_proofs = |signature: Binary, pubkey: Binary, amount: Uint128, proof_game: Vec<String>| ExecuteMsg::ClaimAirdropFor {
+        claimer: claimer.clone(),
+        amount,
+        proof_airdrop: vec![],
+        proof_game,
+        signature,
+        pubkey,
+        round: None,
+    };
+    let claim_msg = |signature: Binary, pubkey: Binary, amount: Uint128| {
+        claim_msg_with_proofs(signature, pubkey, amount, proof_game.clone())
+    };
+    set_height(&mut router, 201_001);
+
+    // A relayer front-running the claim cannot drop the game proof of the claimer.
+    let err = router
+        .execute_contract(relayer.clone(), game_addr.clone(), &claim_msg_with_proofs(signature.clone(), pubkey.clone(), amount, vec![]), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidClaimSignature { claimer: claimer.clone() },
+        err.downcast().unwrap()
+    );
+
+    // The signature authorizes only the signed amount.
+    let err = router
+        .execute_contract(relayer.clone(), game_addr.clone(), &claim_msg(signature.clone(), pubkey.clone(), Uint128::new(999)), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidClaimSignature { claimer: claimer.clone() },
+        err.downcast().unwrap()
+    );
+
+    // The public key must be the one of the claimer.
+    let other_key = k256::ecdsa::SigningKey::from_bytes(&[8u8; 32]).unwrap();
+    let other_pubkey = Binary::from(other_key.verifying_key().to_bytes().to_vec());
+    let err = router
+        .execute_contract(relayer.clone(), game_addr.clone(), &claim_msg(signature.clone(), other_pubkey, amount), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidClaimSignature { claimer: claimer.clone() },
+        err.downcast().unwrap()
+    );
+
+    // The relayer submits the claim and the claimer receives the airdrop.
+    let res = router
+        .execute_contract(relayer.clone(), game_addr.clone(), &claim_msg(signature.clone(), pubkey.clone(), amount), &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::RELAYER, relayer.as_str())));
+    let balance = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, Addr::unchecked(&claimer)).unwrap();
+    assert_eq!(balance, amount);
+
+    // The signature cannot be replayed.
+    let err = router
+        .execute_contract(relayer, game_addr, &claim_msg(signature, pubkey, amount), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+}
+
+#[test]
+fn prize_tokens() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let token_a = create_cw20(&mut router, &owner, "token a".to_string(), "TOKENA".to_string(), Uint128::new(1_000));
+    let token_b = create_cw20(&mut router, &owner, "token b".to_string(), "TOKENB".to_string(), Uint128::new(1_000));
+    let (game_addr, cw20_token, players) = setup_claim_game_with_prize_tokens(
+        &mut router,
+        msg.clone(),
+        &[(token_a.clone(), Uint128::new(101)), (token_b.clone(), Uint128::new(40))],
+    );
+
+    // The airdrop token is already part of the prize.
+    let other_game = create_game_with_msg(&mut router, &owner, &InstantiateMsg {
+        cw20_token_address: cw20_token.addr().to_string(),
+        ..msg
+    }).unwrap();
+    let err = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            other_game,
+            &ExecuteMsg::RegisterMerkleRoots {
+                merkle_root_airdrop: "00".repeat(32),
+                total_amount_airdrop: None,
+                merkle_root_game: "00".repeat(32),
+                total_amount_game: None,
+                expected_recipients: None,
+                prize_tokens: Some(vec![PrizeTokenAmount { token: cw20_token.addr().to_string(), amount: Uint128::new(1) }]),
+                round: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidPrizeTokens {}, err.downcast().unwrap());
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // Each winner receives its share of every token of the basket, with one transfer each.
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    let paid = format!("50{},20{}", token_a.addr(), token_b.addr());
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::PRIZE_FROM_TOKENS, paid)));
+    assert_eq!(token_a.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(), Uint128::new(50));
+    assert_eq!(token_b.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(), Uint128::new(20));
+
+    router
+        .execute_contract(
+            players[2].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    let amounts = get_game_amount(&router, &game_addr);
+    assert_eq!(amounts.prize_tokens, vec![
+        PrizeTokenInfo { token: token_a.addr().to_string(), amount: Uint128::new(101), claimed: Uint128::new(100) },
+        PrizeTokenInfo { token: token_b.addr().to_string(), amount: Uint128::new(40), claimed: Uint128::new(40) },
+    ]);
+
+    // The dust left by the rounded down shares is withdrawn by the owner.
+    set_height(&mut router, 202_003);
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr,
+            &ExecuteMsg::WithdrawAirdrop { address: owner.clone(), round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(token_a.balance::<App, Addr, MyCustomQuery>(&router, owner).unwrap(), Uint128::new(900));
+}
+
+#[test]
+fn prize_denom() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+
+    // Without the tokenfactory feature the contract cannot create its own denom.
+    #[cfg(not(feature = "tokenfactory"))]
+    {
+        let (other_game, _, _) = setup_claim_game(&mut router, msg.clone());
+        let err = router
+            .execute_contract(
+                Addr::unchecked("owner0000"),
+                other_game,
+                &ExecuteMsg::CreatePrizeDenom { subdenom: "prize".to_string() },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(ContractError::TokenFactoryDisabled {}, err.downcast().unwrap());
+    }
+
+    // The prize is paid with a denom created elsewhere and sent to the contract.
+    let denom = "factory/owner0000/prize".to_string();
+    msg.prize_denom = Some(denom.clone());
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    assert_eq!(get_config(&router, &game_addr).prize_denom, Some(denom.clone()));
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::CreatePrizeDenom { subdenom: "prize".to_string() },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::PrizeDenomAlreadySet {}, err.downcast().unwrap());
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+    let airdrop = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+
+    // The winners receive the prize in the denom, the airdrop in the cw20 token.
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(bank_balance(&mut router, &players[0], denom.clone()).amount, Uint128::new(500_000));
+    assert_eq!(cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(), airdrop);
+
+    // The unclaimed prize is withdrawn in the denom.
+    set_height(&mut router, 202_003);
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr,
+            &ExecuteMsg::WithdrawAirdrop { address: owner.clone(), round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(bank_balance(&mut router, &owner, denom).amount, Uint128::new(500_000));
+}
+
+#[test]
+fn prize_vesting() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.prize_vesting = Some(Duration::Height(100));
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+    let airdrop = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+
+    // The claim records the share of the airdrop won instead of paying it.
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::VESTING, "500000")));
+    assert_eq!(cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(), airdrop);
+
+    let claim_vested = |router: &mut App| {
+        router.execute_contract(players[0].clone(), game_addr.clone(), &ExecuteMsg::ClaimVested {}, &[])
+    };
+    let err = claim_vested(&mut router).unwrap_err();
+    assert_eq!(ContractError::NothingVested {}, err.downcast().unwrap());
+
+    // The prize vests linearly, the vested part being released on request.
+    set_height(&mut router, 202_041);
+    let vesting: VestingResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Vesting { address: players[0].to_string() })
+        .unwrap();
+    assert_eq!(vesting.vestings, vec![VestingInfo {
+        round: 1,
+        asset: cw20_token.addr().to_string(),
+        amount: Uint128::new(500_000),
+        vested: Uint128::new(200_000),
+        released: Uint128::zero(),
+        remaining: Uint128::new(300_000),
+    }]);
+
+    claim_vested(&mut router).unwrap();
+    assert_eq!(
+        cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(),
+        airdrop + Uint128::new(200_000)
+    );
+
+    // Once fully released, the vesting is dropped.
+    set_height(&mut router, 202_200);
+    claim_vested(&mut router).unwrap();
+    assert_eq!(
+        cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(),
+        airdrop + Uint128::new(500_000)
+    );
+    let vesting: VestingResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Vesting { address: players[0].to_string() })
+        .unwrap();
+    assert!(vesting.vestings.is_empty());
+}
+
+#[test]
+fn stake_prize() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let staking_id = router.store_code(contract_staking());
+    let staking_addr = router
+        .instantiate_contract(staking_id, owner, &Empty {}, &[], "STAKING", None)
+        .unwrap();
+    let mut msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid.clone(),
+        stage_claim_airdrop.clone(),
+        stage_claim_prize.clone(),
+        None,
+    );
+    msg.staking_contract = Some(staking_addr.to_string());
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    let msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let (other_game, _, _) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    for game in [&game_addr, &other_game] {
+        place_bid(&mut router, game, &players[0], 1).unwrap();
+        place_bid(&mut router, game, &players[2], 10).unwrap();
+    }
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+    claim_airdrop_at(&mut router, &other_game, 0).unwrap();
+    let balance = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+
+    // The share of the airdrop is sent to the staking contract, staked for the winner.
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: Some(true), round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::STAKED, "500000")));
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("token", cw20_token.addr().as_str())
+            .add_attribute("stake_for", players[0].as_str())
+            .add_attribute("amount", "500000")
+    ));
+    assert_eq!(cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap(), balance);
+    assert_eq!(
+        cw20_token.balance::<App, Addr, MyCustomQuery>(&router, staking_addr).unwrap(),
+        Uint128::new(500_000)
+    );
+
+    // Without a staking contract the prize cannot be staked.
+    let err = router
+        .execute_contract(
+            players[0].clone(),
+            other_game,
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: Some(true), round: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::PrizeNotStakeable {}, err.downcast().unwrap());
+}
+
+#[test]
+fn claim_prize_and_reenter() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // Without a next round there is no bid to enter.
+    let reenter_msg = ExecuteMsg::ClaimPrizeAndReenter { bin: 3 };
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &reenter_msg, &[])
+        .unwrap_err();
+
+    // The bid stage of the next round is open with the claim prize stage.
+    let stage = |start: u64| Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::StartNewRound {
+                ticket_price: Coin { denom: native_token_denom.clone(), amount: Uint128::new(4) },
+                bins,
+                stage_bid: stage(202_000),
+                stage_claim_airdrop: stage(211_000),
+                stage_claim_prize: stage(212_000),
+                first_bidder_bonus: None,
+                max_winners: None,
+                min_participants: None,
+                price_schedule: None,
+                stage_reveal: None,
+            },
+            &[],
+        ).unwrap();
+
+    // A ticket of the next round is paid with the tickets prize, the rest is sent.
+    set_height(&mut router, 202_001);
+    let bank_balance_before = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    let balance_before = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+    let res = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &reenter_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::PRIZE_FROM_TICKETS, "6")
+            .add_attribute(events::PRIZE_FROM_AIRDROP, "500000")
+    ));
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::REENTRY, "4")));
+    assert!(res.has_event(&Event::new("wasm-reenter").add_attribute(events::ROUND, "2")));
+    let bank_balance_after = bank_balance(&mut router, &players[0], native_token_denom.clone());
+    let balance_after = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+    assert_eq!(bank_balance_after.amount - bank_balance_before.amount, Uint128::new(6));
+    assert_eq!(balance_after - balance_before, Uint128::new(500_000));
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, Some(3));
+    let treasury: TreasuryResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Treasury { round: None })
+        .unwrap();
+    let ticket_pot = BucketBalance {
+        bucket: Bucket::TicketPot,
+        asset: native_token_denom,
+        amount: Uint128::new(4),
+    };
+    assert_eq!(treasury.buckets, vec![ticket_pot]);
+
+    // The prize is claimed once.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &reenter_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+}
+
+#[test]
+fn jackpot_rollover() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(ticket_price.clone(), bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.rollover_grace_period = Some(Duration::Height(100));
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg.clone());
+    let (other_game, other_token, _) = setup_claim_game(&mut router, msg);
+    let treasury = |router: &App, game_addr: &Addr| -> Vec<BucketBalance> {
+        let res: TreasuryResponse = router
+            .wrap()
+            .query_wasm_smart(game_addr, &QueryMsg::Treasury { round: None })
+            .unwrap();
+        res.buckets
+    };
+    let balance = |bucket: Bucket, asset: &str, amount: u128| BucketBalance {
+        bucket,
+        asset: asset.to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    // Nobody claims the airdrop, so the bids do not win.
+    set_height(&mut router, 200_001);
+    for game in [&game_addr, &other_game] {
+        place_bid(&mut router, game, &players[0], 1).unwrap();
+        place_bid(&mut router, game, &players[2], 10).unwrap();
+    }
+
+    // The jackpot is rolled over once the claim airdrop stage is over.
+    set_height(&mut router, 201_001);
+    let roll_over_msg = ExecuteMsg::RollOverJackpot { round: None };
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &roll_over_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::JackpotNotRollable {}, err.downcast().unwrap());
+
+    set_height(&mut router, 201_003);
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &roll_over_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::ROLLOVER_FROM_TICKETS, "20")
+            .add_attribute(events::ROLLOVER_FROM_AIRDROP, "1000000")
+    ));
+    let token = cw20_token.addr().to_string();
+    assert_eq!(
+        treasury(&router, &game_addr),
+        vec![
+            balance(Bucket::AirdropReserve, &token, 11_330),
+            balance(Bucket::Rollover, &token, 1_000_000),
+            balance(Bucket::Rollover, &native_token_denom, 20),
+        ]
+    );
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &roll_over_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::JackpotNotRollable {}, err.downcast().unwrap());
+
+    // Without a new round, the rollover is withdrawn after the grace period.
+    let withdraw_address = Addr::unchecked("withdraw0000");
+    let withdraw_msg = ExecuteMsg::WithdrawRollover { address: withdraw_address.clone() };
+    let err = router
+        .execute_contract(Addr::unchecked("owner0000"), game_addr.clone(), &withdraw_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RolloverLocked {}, err.downcast().unwrap());
+
+    set_height(&mut router, 201_103);
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &withdraw_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router
+        .execute_contract(Addr::unchecked("owner0000"), game_addr.clone(), &withdraw_msg, &[])
+        .unwrap();
+    assert_eq!(bank_balance(&mut router, &withdraw_address, native_token_denom.clone()).amount, Uint128::new(20));
+    assert_eq!(
+        cw20_token.balance::<App, Addr, MyCustomQuery>(&router, withdraw_address).unwrap(),
+        Uint128::new(1_000_000)
+    );
+    assert_eq!(treasury(&router, &game_addr), vec![balance(Bucket::AirdropReserve, &token, 11_330)]);
+    let err = router
+        .execute_contract(Addr::unchecked("owner0000"), game_addr, &withdraw_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RolloverLocked {}, err.downcast().unwrap());
+
+    // A new round started after the claim airdrop stage takes the jackpot.
+    let stage = |start: u64| Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            other_game.clone(),
+            &ExecuteMsg::StartNewRound {
+                ticket_price,
+                bins,
+                stage_bid: stage(210_000),
+                stage_claim_airdrop: stage(211_000),
+                stage_claim_prize: stage(212_000),
+                first_bidder_bonus: None,
+                max_winners: None,
+                min_participants: None,
+                price_schedule: None,
+                stage_reveal: None,
+            },
+            &[],
+        ).unwrap();
+    let other_token = other_token.addr().to_string();
+    assert_eq!(
+        treasury(&router, &other_game),
+        vec![
+            balance(Bucket::TicketPot, &native_token_denom, 20),
+            balance(Bucket::GameReserve, &other_token, 1_000_000),
+        ]
+    );
+}
+
+#[test]
+fn claim_prize_without_winners() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+
+    // Nobody claims the airdrop, so there is no prize to share.
+    set_height(&mut router, 202_001);
+    for msg in [
+        ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+        ExecuteMsg::DonatePrize { share_bps: 5_000, round: None },
+    ] {
+        let err = router
+            .execute_contract(players[0].clone(), game_addr.clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(ContractError::NoWinners {}, err.downcast().unwrap());
+    }
+
+    // The jackpot goes to the rollover instead.
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr,
+            &ExecuteMsg::RollOverJackpot { round: None },
+            &[],
+        )
+        .unwrap();
+}
+
+#[test]
+fn finalize_game() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.first_bidder_bonus = Some(Uint128::new(1));
+    msg.protocol_fee_bps = Some(1_000);
+    msg.fee_collector = Some("collector".to_string());
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // The two winners split 27 of the tickets prize, after the protocol fee which pays
+    // the bonus of the first one.
+    set_height(&mut router, 200_000);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    let finalize_msg = ExecuteMsg::FinalizeGame { round: None };
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &finalize_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::ClaimAirdropStageNotFinished {}, err.downcast().unwrap());
+
+    // Anyone can finalize the round once, the remainder is left as dust.
+    set_height(&mut router, 201_003);
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &finalize_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::WINNERS, "2")
+            .add_attribute(events::DUST_FROM_TICKETS, "1")
+            .add_attribute(events::DUST_FROM_AIRDROP, "0")
+    ));
+    assert!(get_game_amount(&router, &game_addr).finalized);
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &finalize_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::AlreadyFinalized {}, err.downcast().unwrap());
+
+    let treasury: TreasuryResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Treasury { round: None })
+        .unwrap();
+    assert!(treasury.buckets.contains(&BucketBalance {
+        bucket: Bucket::Dust,
+        asset: native_token_denom,
+        amount: Uint128::new(1),
+    }));
+
+    // The claims pay the saved shares.
+    let claimable: ClaimableAmountsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &game_addr,
+            &QueryMsg::ClaimableAmounts { address: players[0].to_string(), round: None },
+        )
+        .unwrap();
+    assert_eq!(claimable.ticket_prize, Uint128::new(13));
+
+    set_height(&mut router, 202_001);
+    for (player, prize) in [(&players[0], "13"), (&players[2], "13")] {
+        let res = router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+                &[],
+            )
+            .unwrap();
+        assert!(res.has_event(&Event::new("wasm").add_attribute(events::PRIZE_FROM_TICKETS, prize)));
+    }
+}
+
+#[test]
+fn crank_bounty() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.crank_bounty = Some(Uint128::zero());
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidCrankBounty {}, err.downcast().unwrap());
+
+    msg.crank_bounty = Some(Uint128::new(3));
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg.clone());
+    msg.min_participants = Some(5);
+    let (aborted_game, _, _) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    for game in [&game_addr, &aborted_game] {
+        for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+            place_bid(&mut router, game, player, bin).unwrap();
+        }
+    }
+    router
+        .execute_contract(
+            players[0].clone(),
+            aborted_game.clone(),
+            &ExecuteMsg::FundPrizePool { round: None },
+            &[Coin::new(5, native_token_denom.clone())],
+        )
+        .unwrap();
+
+    // The bounty does not open the refunds of the aborted round to anyone.
+    set_height(&mut router, 200_003);
+    let refund_msg = ExecuteMsg::RefundBids { addresses: None, limit: Some(2), round: None };
+    let err = router
+        .execute_contract(players[1].clone(), aborted_game.clone(), &refund_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    // One bounty is paid per call, from the donation, and the bids are refunded in full.
+    let game_owner = Addr::unchecked("owner0000");
+    let owner_before = bank_balance(&mut router, &game_owner, native_token_denom.clone()).amount;
+    let balances_before: Vec<Uint128> = players[..3]
+        .iter()
+        .map(|player| bank_balance(&mut router, player, native_token_denom.clone()).amount)
+        .collect();
+    let res = router.execute_contract(game_owner.clone(), aborted_game.clone(), &refund_msg, &[]).unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::REFUNDED_BIDS, "2")
+            .add_attribute(events::BOUNTY, "3")
+    ));
+
+    // The bounty is capped by what is left of the donation.
+    let res = router.execute_contract(game_owner.clone(), aborted_game, &refund_msg, &[]).unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::REFUNDED_BIDS, "1")
+            .add_attribute(events::BOUNTY, "2")
+    ));
+    let owner_after = bank_balance(&mut router, &game_owner, native_token_denom.clone()).amount;
+    assert_eq!(owner_after - owner_before, Uint128::new(3 + 2));
+    for (player, balance_before) in players[..3].iter().zip(balances_before) {
+        let balance_after = bank_balance(&mut router, player, native_token_denom.clone()).amount;
+        assert_eq!(balance_after - balance_before, Uint128::new(10));
+    }
+
+    // The bounty of the finalization is taken from the tickets prize before sharing it.
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    set_height(&mut router, 201_003);
+    let balance_before = bank_balance(&mut router, &players[1], native_token_denom.clone()).amount;
+    let res = router
+        .execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::FinalizeGame { round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::DUST_FROM_TICKETS, "1")
+            .add_attribute(events::BOUNTY, "3")
+    ));
+    let balance_after = bank_balance(&mut router, &players[1], native_token_denom).amount;
+    assert_eq!(balance_after - balance_before, Uint128::new(3));
+
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr,
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::PRIZE_FROM_TICKETS, "13")));
+}
+
+#[test]
+fn dust_policy() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.protocol_fee_bps = Some(1_000);
+    msg.fee_collector = Some("collector".to_string());
+    msg.dust_policy = Some(DustPolicy::LastClaimer);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg.clone());
+    msg.dust_policy = Some(DustPolicy::Burn);
+    let (burn_game, _, _) = setup_claim_game(&mut router, msg);
+
+    // The two winners split 27 of the tickets prize, after the protocol fee, leaving 1
+    // of dust.
+    for game in [&game_addr, &burn_game] {
+        set_height(&mut router, 200_000);
+        place_bid(&mut router, game, &players[0], 1).unwrap();
+        set_height(&mut router, 200_001);
+        place_bid(&mut router, game, &players[1], 1).unwrap();
+        place_bid(&mut router, game, &players[2], 10).unwrap();
+        set_height(&mut router, 201_001);
+        claim_airdrop_at(&mut router, game, 0).unwrap();
+        claim_airdrop_at(&mut router, game, 2).unwrap();
+    }
+
+    // The dust is disposed of with the last claim: paid to the last claimer or burned.
+    set_height(&mut router, 202_001);
+    let claim_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    for (game, dust_policy) in [(&game_addr, "last_claimer"), (&burn_game, "burn")] {
+        let res = router.execute_contract(players[0].clone(), game.clone(), &claim_msg, &[]).unwrap();
+        assert!(!res.has_event(&Event::new("wasm").add_attribute(events::DUST_POLICY, dust_policy)));
+
+        let balance_before = bank_balance(&mut router, &players[2], native_token_denom.clone()).amount;
+        let res = router.execute_contract(players[2].clone(), game.clone(), &claim_msg, &[]).unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute(events::DUST_POLICY, dust_policy)
+                .add_attribute(events::DUST_FROM_TICKETS, "1")
+                .add_attribute(events::DUST_FROM_AIRDROP, "0")
+        ));
+        let balance_after = bank_balance(&mut router, &players[2], native_token_denom.clone()).amount;
+        let paid = if game == &game_addr { 14 } else { 13 };
+        assert_eq!(balance_after - balance_before, Uint128::new(paid));
+
+        // The prizes reconcile with the claims and the dust, and nothing is left.
+        let game_amounts = get_game_amount(&router, game);
+        assert_eq!(game_amounts.total_dust_prize, Uint128::new(1));
+        assert_eq!(game_amounts.total_dust_airdrop, Uint128::zero());
+        assert_eq!(
+            game_amounts.total_ticket_prize,
+            game_amounts.total_claimed_prize + game_amounts.total_dust_prize
+        );
+        let treasury: TreasuryResponse = router
+            .wrap()
+            .query_wasm_smart(game, &QueryMsg::Treasury { round: None })
+            .unwrap();
+        assert!(!treasury.buckets.iter().any(|b| b.bucket == Bucket::Dust && !b.amount.is_zero()));
+    }
+}
+
+#[test]
+fn protocol_fee() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let fee_collector = Addr::unchecked("collector");
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.protocol_fee_bps = Some(2_001);
+    msg.fee_collector = Some(fee_collector.to_string());
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidProtocolFee { max: 2_000 }, err.downcast().unwrap());
+
+    msg.protocol_fee_bps = Some(1_000);
+    msg.fee_collector = None;
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::FeeCollectorRequired {}, err.downcast().unwrap());
+
+    msg.fee_collector = Some(fee_collector.to_string());
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.protocol_fee_bps, 1_000);
+    assert_eq!(config.fee_collector, Some(fee_collector.to_string()));
+
+    set_height(&mut router, 200_001);
+    for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // The first claim finalizes the round, sending 10% of the tickets prize to the fee
+    // collector before the winners split the rest.
+    set_height(&mut router, 202_001);
+    let claim_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let res = router.execute_contract(players[0].clone(), game_addr.clone(), &claim_msg, &[]).unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::PRIZE_FROM_TICKETS, "13")
+            .add_attribute(events::PROTOCOL_FEE, "3")
+    ));
+    assert_eq!(bank_balance(&mut router, &fee_collector, native_token_denom).amount, Uint128::new(3));
+    let game_amounts = get_game_amount(&router, &game_addr);
+    assert_eq!(game_amounts.total_ticket_prize, Uint128::new(27));
+    assert_eq!(game_amounts.total_dust_prize, Uint128::new(1));
+
+    // The fee is taken once.
+    let res = router.execute_contract(players[2].clone(), game_addr.clone(), &claim_msg, &[]).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::PRIZE_FROM_TICKETS, "13")));
+    assert!(!res.has_event(&Event::new("wasm").add_attribute(events::PROTOCOL_FEE, "3")));
+}
+
+#[test]
+fn revenue_split() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let destinations = [Addr::unchecked("treasury"), Addr::unchecked("devfund"), Addr::unchecked("charity")];
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.protocol_fee_bps = Some(1_000);
+    for split in [
+        vec![(destinations[0].to_string(), Decimal::percent(50)), (destinations[1].to_string(), Decimal::percent(40))],
+        vec![(destinations[0].to_string(), Decimal::percent(50)), (destinations[0].to_string(), Decimal::percent(50))],
+        vec![(destinations[0].to_string(), Decimal::one()), (destinations[1].to_string(), Decimal::zero())],
+    ] {
+        msg.revenue_split = Some(split);
+        let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+        assert_eq!(ContractError::InvalidRevenueSplit {}, err.downcast().unwrap());
+    }
+
+    // The split replaces the fee collector.
+    msg.revenue_split = Some(vec![
+        (destinations[0].to_string(), Decimal::percent(50)),
+        (destinations[1].to_string(), Decimal::percent(30)),
+        (destinations[2].to_string(), Decimal::percent(20)),
+    ]);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // The protocol fee of 3 is split, the last destination taking the remainder.
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    let balances: Vec<u128> = destinations
+        .iter()
+        .map(|d| bank_balance(&mut router, d, native_token_denom.clone()).amount.u128())
+        .collect();
+    assert_eq!(balances, vec![1, 0, 2]);
+
+    // So are the withdraws, the address given being ignored: 13 left unclaimed and 1 of
+    // dust.
+    set_height(&mut router, 202_003);
+    let game_owner = Addr::unchecked("owner0000");
+    router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr,
+            &ExecuteMsg::WithdrawPrize { address: game_owner, round: None },
+            &[],
+        )
+        .unwrap();
+    let balances: Vec<u128> = destinations
+        .iter()
+        .map(|d| bank_balance(&mut router, d, native_token_denom.clone()).amount.u128())
+        .collect();
+    assert_eq!(balances, vec![1 + 7, 4, 2 + 3]);
+}
+
+#[test]
+fn burn_unclaimed() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let game_owner = Addr::unchecked("owner0000");
+    let burn_msg = ExecuteMsg::BurnUnclaimed { round: None };
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    let err = router.execute_contract(owner.clone(), game_addr, &burn_msg, &[]).unwrap_err();
+    assert_eq!(ContractError::BurnUnclaimedDisabled {}, err.downcast().unwrap());
+
+    msg.burn_unclaimed = Some(true);
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    assert!(get_config(&router, &game_addr).burn_unclaimed);
+
+    set_height(&mut router, 200_001);
+    for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    let err = router.execute_contract(players[1].clone(), game_addr.clone(), &burn_msg, &[]).unwrap_err();
+    assert_eq!(ContractError::ClaimPrizeStageNotFinished {}, err.downcast().unwrap());
+
+    // The owner cannot withdraw the unclaimed tokens, left to be burned.
+    set_height(&mut router, 203_001);
+    let withdraw_address = Addr::unchecked("withdraw0000");
+    router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone(), round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address)
+        .unwrap()
+        .is_zero());
+
+    // Anyone can burn them, once: the airdrop not claimed and the prize of the winner
+    // who did not claim it.
+    let balance = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone()).unwrap();
+    let supply = cw20_token.meta::<App, MyCustomQuery>(&router).unwrap().total_supply;
+    let res = router.execute_contract(players[1].clone(), game_addr.clone(), &burn_msg, &[]).unwrap();
+    let burned = Uint128::new(501_010);
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::AMOUNT, burned)));
+    assert_eq!(cw20_token.balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone()).unwrap(), balance - burned);
+    assert_eq!(cw20_token.meta::<App, MyCustomQuery>(&router).unwrap().total_supply, supply - burned);
+
+    let res = router.execute_contract(players[1].clone(), game_addr, &burn_msg, &[]).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::AMOUNT, "0")));
+}
+
+#[test]
+fn redistribute_unclaimed() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let redistribute_msg = ExecuteMsg::RedistributeUnclaimed { round: None };
+    let claim_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.claim_deadline = Some(Duration::Height(0));
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidClaimDeadline {}, err.downcast().unwrap());
+
+    msg.claim_deadline = Some(Duration::Height(1));
+    msg.crank_bounty = Some(Uint128::new(3));
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg.clone());
+    let (unclaimed_game, _, _) = setup_claim_game(&mut router, msg);
+    for game in [&game_addr, &unclaimed_game] {
+        set_height(&mut router, 200_001);
+        for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+            place_bid(&mut router, game, player, bin).unwrap();
+        }
+        set_height(&mut router, 201_001);
+        claim_airdrop_at(&mut router, game, 0).unwrap();
+        claim_airdrop_at(&mut router, game, 2).unwrap();
+    }
+
+    set_height(&mut router, 202_000);
+    router.execute_contract(players[0].clone(), game_addr.clone(), &claim_msg, &[]).unwrap();
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &redistribute_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::ClaimDeadlineNotReached {}, err.downcast().unwrap());
+
+    // Past the deadline, the prize not claimed goes to the winner who claimed.
+    set_height(&mut router, 202_001);
+    let err = router.execute_contract(players[2].clone(), game_addr.clone(), &claim_msg, &[]).unwrap_err();
+    assert_eq!(ContractError::ClaimDeadlinePassed {}, err.downcast().unwrap());
+
+    // The sender is paid one bounty from the tickets prize not claimed.
+    let balance_before = bank_balance(&mut router, &players[0], native_token_denom.clone()).amount;
+    let sender_before = bank_balance(&mut router, &players[1], native_token_denom.clone()).amount;
+    let cw20_before = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &redistribute_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::WINNERS, "1")
+            .add_attribute(events::PRIZE_FROM_TICKETS, "12")
+            .add_attribute(events::PRIZE_FROM_AIRDROP, "500000")
+            .add_attribute(events::BOUNTY, "3")
+    ));
+    let balance_after = bank_balance(&mut router, &players[0], native_token_denom.clone()).amount;
+    let sender_after = bank_balance(&mut router, &players[1], native_token_denom).amount;
+    let cw20_after = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+    assert_eq!(balance_after - balance_before, Uint128::new(12));
+    assert_eq!(sender_after - sender_before, Uint128::new(3));
+    assert_eq!(cw20_after - cw20_before, Uint128::new(500_000));
+    let game_amounts = get_game_amount(&router, &game_addr);
+    assert_eq!(game_amounts.total_claimed_prize, Uint128::new(27));
+
+    let err = router
+        .execute_contract(players[1].clone(), game_addr, &redistribute_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::AlreadyRedistributed {}, err.downcast().unwrap());
+
+    // Without claims, the prizes are rolled over.
+    let res = router
+        .execute_contract(players[1].clone(), unclaimed_game.clone(), &redistribute_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::ROLLOVER_FROM_TICKETS, "27")
+            .add_attribute(events::ROLLOVER_FROM_AIRDROP, "1000000")
+            .add_attribute(events::BOUNTY, "3")
+    ));
+}
+
+#[test]
+fn redistribute_unclaimed_sponsors_and_tokens() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let redistribute_msg = ExecuteMsg::RedistributeUnclaimed { round: None };
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.claim_deadline = Some(Duration::Height(1));
+    let token_a = create_cw20(&mut router, &owner, "token a".to_string(), "TOKENA".to_string(), Uint128::new(1_000));
+    let (game_addr, _, players) =
+        setup_claim_game_with_prize_tokens(&mut router, msg.clone(), &[(token_a.clone(), Uint128::new(101))]);
+    let (unclaimed_game, _, _) =
+        setup_claim_game_with_prize_tokens(&mut router, msg, &[(token_a.clone(), Uint128::new(101))]);
+
+    // Each game is sponsored 1:1, up to 50 tokens.
+    let sponsor = Addr::unchecked("sponsor0000");
+    let sponsor_token = create_cw20(&mut router, &sponsor, "sponsor".to_string(), "SPONSOR".to_string(), Uint128::new(1_000));
+    for game in [&game_addr, &unclaimed_game] {
+        let sponsor_msg = cw20::Cw20ExecuteMsg::Send {
+            contract: game.to_string(),
+            amount: Uint128::new(50),
+            msg: to_binary(&ReceiveMsg::Sponsor { ratio: Decimal::one(), round: None }).unwrap(),
+        };
+        router.execute_contract(sponsor.clone(), sponsor_token.addr(), &sponsor_msg, &[]).unwrap();
+    }
+    for game in [&game_addr, &unclaimed_game] {
+        set_height(&mut router, 200_001);
+        for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+            place_bid(&mut router, game, player, bin).unwrap();
+        }
+        set_height(&mut router, 201_001);
+        claim_airdrop_at(&mut router, game, 0).unwrap();
+        claim_airdrop_at(&mut router, game, 2).unwrap();
+    }
+
+    // The match of the 30 tickets sold and the 101 tokens are shared by the two winners.
+    set_height(&mut router, 202_000);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+
+    // Past the deadline, the match and the tokens not claimed go to the winner who claimed.
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &redistribute_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute(events::PRIZE_FROM_SPONSORS, format!("15{}", sponsor_token.addr()))
+            .add_attribute(events::PRIZE_FROM_TOKENS, format!("50{}", token_a.addr()))
+    ));
+    let sponsor_prize = sponsor_token.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+    let token_prize = token_a.balance::<App, Addr, MyCustomQuery>(&router, players[0].clone()).unwrap();
+    assert_eq!(sponsor_prize, Uint128::new(30));
+    assert_eq!(token_prize, Uint128::new(100));
+    let sponsorships: SponsorshipsResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Sponsorships { round: None })
+        .unwrap();
+    assert_eq!(sponsorships.sponsorships[0].claimed, Uint128::new(30));
+
+    // Without claims, the match is left to the sponsor.
+    router
+        .execute_contract(players[1].clone(), unclaimed_game.clone(), &redistribute_msg, &[])
+        .unwrap();
+    set_height(&mut router, 202_003);
+    for game in [&game_addr, &unclaimed_game] {
+        router
+            .execute_contract(sponsor.clone(), game.clone(), &ExecuteMsg::ReclaimSponsorship { round: None }, &[])
+            .unwrap();
+    }
+    let sponsor_balance = sponsor_token.balance::<App, Addr, MyCustomQuery>(&router, sponsor).unwrap();
+    assert_eq!(sponsor_balance, Uint128::new(1_000 - 30));
+}
+
+#[test]
+fn withdraw() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let game_owner = Addr::unchecked("owner0000");
+    let withdraw_address = Addr::unchecked("withdraw0000");
+
+    let msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    set_height(&mut router, 200_001);
+    for (player, bin) in [(&players[0], 1), (&players[1], 1), (&players[2], 10)] {
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+
+    let withdraw_msg = |asset: &str, amount: Option<u128>| ExecuteMsg::Withdraw {
+        asset: asset.to_string(),
+        amount: amount.map(Uint128::new),
+        recipient: withdraw_address.to_string(),
+        round: None,
+    };
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &withdraw_msg(&native_token_denom, None), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::ClaimPrizeStageNotFinished {}, err.downcast().unwrap());
+
+    set_height(&mut router, 203_001);
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &withdraw_msg(&native_token_denom, None), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    // The prize left can be withdrawn in parts, up to what is left.
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &withdraw_msg(&native_token_denom, Some(5)), &[])
+        .unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &withdraw_msg(&native_token_denom, Some(11)), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WithdrawExceedsLeftovers { asset: native_token_denom.clone(), available: Uint128::new(10) },
+        err.downcast().unwrap()
+    );
+    let res = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &withdraw_msg(&native_token_denom, None), &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::AMOUNT, "10")));
+    assert_eq!(bank_balance(&mut router, &withdraw_address, native_token_denom).amount, Uint128::new(15));
+
+    // The cw20 pool holds the airdrop not claimed and the prize of the winner who did not
+    // claim it.
+    let token = cw20_token.addr().to_string();
+    router
+        .execute_contract(game_owner, game_addr.clone(), &withdraw_msg(&token, None), &[])
+        .unwrap();
+    let balance_withdraw = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address)
+        .unwrap();
+    assert_eq!(balance_withdraw, Uint128::new(501_010));
+    let treasury: TreasuryResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Treasury { round: None })
+        .unwrap();
+    assert!(treasury.buckets.is_empty());
+}
+
+#[test]
+fn sudo_governance() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (claimed_game_addr, _, _) = setup_claim_game(&mut router, msg.clone());
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    for (player, bin) in players.iter().zip([1, 1, 10]) {
+        place_bid(&mut router, &claimed_game_addr, player, bin).unwrap();
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+
+    // While paused, every message but the refunds is rejected.
+    router.wasm_sudo(game_addr.clone(), &SudoMsg::Pause {}).unwrap();
+    let err = router.wasm_sudo(game_addr.clone(), &SudoMsg::Pause {}).unwrap_err();
+    assert_eq!(ContractError::ContractPaused {}, err.downcast().unwrap());
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.paused, Some(PausedBy::Governance));
+
+    let err = place_bid(&mut router, &game_addr, &players[0], 2).unwrap_err();
+    assert_eq!(ContractError::ContractPaused {}, err.downcast().unwrap());
+    let refund_ticket_msg = ExecuteMsg::RefundTicket { round: None };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &refund_ticket_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RoundNotAborted {}, err.downcast().unwrap());
+
+    // The end of a stage can be moved, but not before its start or over the next stage.
+    let adjust = |stage, end| SudoMsg::AdjustStageEnd { stage, end, round: None };
+    let err = router
+        .wasm_sudo(game_addr.clone(), &adjust(RoundStage::Bid, Scheduled::AtHeight(200_000)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStageEnd { stage_name: String::from("bid") },
+        err.downcast().unwrap()
+    );
+    let end = Scheduled::AtTime(Timestamp::from_seconds(201_100));
+    let err = router
+        .wasm_sudo(game_addr.clone(), &adjust(RoundStage::ClaimAirdrop, end))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStageEnd { stage_name: String::from("claim airdrop") },
+        err.downcast().unwrap()
+    );
+    let err = router
+        .wasm_sudo(game_addr.clone(), &adjust(RoundStage::Bid, Scheduled::AtHeight(201_001)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StagesOverlap { first: String::from("bid"), second: String::from("claim airdrop") },
+        err.downcast().unwrap()
+    );
+    let err = router
+        .wasm_sudo(game_addr.clone(), &adjust(RoundStage::Reveal, Scheduled::AtHeight(200_500)))
+        .unwrap_err();
+    assert_eq!(ContractError::RevealStageNotSet {}, err.downcast().unwrap());
+
+    router
+        .wasm_sudo(game_addr.clone(), &adjust(RoundStage::Bid, Scheduled::AtHeight(200_500)))
+        .unwrap();
+    let stages: StagesResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Stages { round: None })
+        .unwrap();
+    assert_eq!(stages.stage_bid.duration, Duration::Height(500));
+
+    router.wasm_sudo(game_addr.clone(), &SudoMsg::Unpause {}).unwrap();
+    let err = router.wasm_sudo(game_addr.clone(), &SudoMsg::Unpause {}).unwrap_err();
+    assert_eq!(ContractError::ContractNotPaused {}, err.downcast().unwrap());
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.paused, None);
+
+    // The refunds can be enabled even with the Merkle roots registered.
+    router
+        .wasm_sudo(game_addr.clone(), &SudoMsg::ForceRefunds { round: None })
+        .unwrap();
+    let err = router
+        .wasm_sudo(game_addr.clone(), &SudoMsg::ForceRefunds { round: None })
+        .unwrap_err();
+    assert_eq!(ContractError::RoundCancelled {}, err.downcast().unwrap());
+    let status: GameStatusResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::GameStatus { round: None })
+        .unwrap();
+    assert_eq!(status.phase, GamePhase::Cancelled);
+
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &refund_ticket_msg, &[])
+        .unwrap();
+    let balance = bank_balance(&mut router, &players[0], native_token_denom);
+    assert_eq!(Uint128::new(1_000_000) - ticket_price.amount, balance.amount);
+
+    // No airdrop can be claimed in the cancelled round.
+    set_height(&mut router, 201_001);
+    let err = claim_airdrop_at(&mut router, &game_addr, 0).unwrap_err();
+    assert_eq!(ContractError::RoundCancelled {}, err.downcast().unwrap());
+
+    // The refunds cannot be enabled once the airdrop has been claimed.
+    claim_airdrop_at(&mut router, &claimed_game_addr, 0).unwrap();
+    let err = router
+        .wasm_sudo(claimed_game_addr.clone(), &SudoMsg::ForceRefunds { round: None })
+        .unwrap_err();
+    assert_eq!(ContractError::ClaimsStarted {}, err.downcast().unwrap());
+}
+
+#[test]
+fn pause() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let game_owner = Addr::unchecked("owner0000");
+
+    set_height(&mut router, 200_001);
+    for (player, bin) in players.iter().zip([1, 1, 10]) {
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+
+    // Just the owner can pause the contract.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &ExecuteMsg::Pause {}, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::Pause {}, &[])
+        .unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::Pause {}, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::ContractPaused {}, err.downcast().unwrap());
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.paused, Some(PausedBy::Owner));
+
+    // The claims of the registered Merkle roots are stopped.
+    set_height(&mut router, 201_001);
+    let err = claim_airdrop_at(&mut router, &game_addr, 0).unwrap_err();
+    assert_eq!(ContractError::ContractPaused {}, err.downcast().unwrap());
+
+    // The governance can take over the pause, which the owner can no longer lift.
+    router.wasm_sudo(game_addr.clone(), &SudoMsg::Pause {}).unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::Unpause {}, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router.wasm_sudo(game_addr.clone(), &SudoMsg::Unpause {}).unwrap();
+
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::Pause {}, &[])
+        .unwrap();
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &ExecuteMsg::Unpause {}, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::Unpause {}, &[])
+        .unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &ExecuteMsg::Unpause {}, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::ContractNotPaused {}, err.downcast().unwrap());
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.paused, None);
+
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+}
+
+#[test]
+fn blocklist() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let game_owner = Addr::unchecked("owner0000");
+    let update_blocklist = |add: Vec<&Addr>, remove: Vec<&Addr>| ExecuteMsg::UpdateBlocklist {
+        add: add.into_iter().map(Addr::to_string).collect(),
+        remove: remove.into_iter().map(Addr::to_string).collect(),
+    };
+
+    // Just the owner can update the blocklist.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &update_blocklist(vec![&players[2]], vec![]), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![&players[0], &players[2]], vec![]), &[])
+        .unwrap();
+
+    let blocklist: BlocklistResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Blocklist { start_after: None, limit: Some(1) })
+        .unwrap();
+    let mut blocked = [players[0].to_string(), players[2].to_string()];
+    blocked.sort();
+    assert_eq!(blocklist.addresses, blocked[..1]);
+    let blocklist: BlocklistResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &game_addr,
+            &QueryMsg::Blocklist { start_after: Some(blocked[0].clone()), limit: None },
+        )
+        .unwrap();
+    assert_eq!(blocklist.addresses, blocked[1..]);
+
+    // The blocked addresses cannot bid.
+    set_height(&mut router, 200_001);
+    let err = place_bid(&mut router, &game_addr, &players[2], 10).unwrap_err();
+    assert_eq!(
+        ContractError::AddressBlocked { address: players[2].to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![], vec![&players[0], &players[2]]), &[])
+        .unwrap();
+    for (player, bin) in players.iter().zip([1, 1, 10]) {
+        place_bid(&mut router, &game_addr, player, bin).unwrap();
+    }
+
+    // Nor claim the airdrop or the prize.
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![&players[0]], vec![]), &[])
+        .unwrap();
+    set_height(&mut router, 201_001);
+    let err = claim_airdrop_at(&mut router, &game_addr, 0).unwrap_err();
+    assert_eq!(
+        ContractError::AddressBlocked { address: players[0].to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![], vec![&players[0]]), &[])
+        .unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![&players[0]], vec![]), &[])
+        .unwrap();
+    set_height(&mut router, 202_001);
+    let claim_prize_msg = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &claim_prize_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AddressBlocked { address: players[0].to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(players[2].clone(), game_addr.clone(), &claim_prize_msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn allowlist() {
+    use sha2::Digest;
+
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+
+    // The allowlist holds the first two players: the proof of each is the leaf of the other.
+    let leaves: Vec<Vec<u8>> = players[..2]
+        .iter()
+        .map(|p| sha2::Sha256::digest(p.as_bytes()).to_vec())
+        .collect();
+    let mut sorted_leaves = leaves.clone();
+    sorted_leaves.sort_unstable();
+    let merkle_root_allowlist = hex::encode(sha2::Sha256::digest(&sorted_leaves.concat()));
+    let proofs = [vec![hex::encode(&leaves[1])], vec![hex::encode(&leaves[0])]];
+
+    let mut msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.merkle_root_allowlist = Some(merkle_root_allowlist.clone());
+    let (game_addr, _, _) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.merkle_root_allowlist, Some(merkle_root_allowlist));
+
+    set_height(&mut router, 200_001);
+    let funds = [Coin { denom: native_token_denom, amount: ticket_price.amount }];
+    let bid_msg = |recipient: Option<&Addr>, allowlist_proof: Option<&Vec<String>>| ExecuteMsg::Bid {
+        bin: 1,
+        tickets: None,
+        recipient: recipient.map(Addr::to_string),
+        allowlist_proof: allowlist_proof.cloned(),
+        coupon: None,
+        referrer: None,
+        prediction: None,
+        round: None,
+    };
+
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(None, None), &funds)
+        .unwrap_err();
+    assert_eq!(ContractError::AllowlistProofRequired {}, err.downcast().unwrap());
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(None, Some(&proofs[1])), &funds)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VerificationFailed { merkle_root: "allowlist".to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(None, Some(&proofs[0])), &funds)
+        .unwrap();
+
+    // The proof is checked against the player, not the payer.
+    let err = router
+        .execute_contract(players[2].clone(), game_addr.clone(), &bid_msg(None, Some(&proofs[1])), &funds)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VerificationFailed { merkle_root: "allowlist".to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(players[2].clone(), game_addr.clone(), &bid_msg(Some(&players[1]), Some(&proofs[1])), &funds)
+        .unwrap();
+
+    // The bids without a proof are rejected.
+    let err = router
+        .execute_contract(
+            players[2].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::BidMultiple { bins: vec![1, 2], round: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::AllowlistProofRequired {}, err.downcast().unwrap());
+}
+
+#[test]
+fn token_gate() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let gate_token = create_cw20(
+        &mut router,
+        &owner,
+        "gate".to_string(),
+        "GATE".to_string(),
+        Uint128::new(1_000),
+    );
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+
+    // The minimum balance must be positive.
+    msg.bid_token_gate = Some(TokenGate {
+        token: gate_token.addr().to_string(),
+        min_balance: Uint128::zero(),
+    });
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidTokenGate {}, err.downcast().unwrap());
+
+    msg.bid_token_gate = Some(TokenGate {
+        token: gate_token.addr().to_string(),
+        min_balance: Uint128::new(100),
+    });
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.bid_token_gate.unwrap().min_balance, Uint128::new(100));
+
+    for (player, amount) in players.iter().zip([100, 99]) {
+        let transfer_msg = cw20::Cw20ExecuteMsg::Transfer {
+            recipient: player.to_string(),
+            amount: Uint128::new(amount),
+        };
+        router
+            .execute_contract(owner.clone(), gate_token.addr(), &transfer_msg, &[])
+            .unwrap();
+    }
+
+    // The balance of the player is checked when the bid is placed.
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    for player in &players[1..] {
+        let err = place_bid(&mut router, &game_addr, player, 1).unwrap_err();
+        assert_eq!(
+            ContractError::TokenGateNotMet {
+                token: gate_token.addr().to_string(),
+                min_balance: Uint128::new(100),
+            },
+            err.downcast().unwrap()
+        );
+    }
+    let transfer_msg = cw20::Cw20ExecuteMsg::Transfer {
+        recipient: players[1].to_string(),
+        amount: Uint128::new(1),
+    };
+    router
+        .execute_contract(owner.clone(), gate_token.addr(), &transfer_msg, &[])
+        .unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+}
+
+#[test]
+fn nft_gate() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+
+    // The first player holds a token of the collection.
+    let cw721_id = router.store_code(contract_cw721());
+    let collection = router
+        .instantiate_contract(cw721_id, owner.clone(), &vec![players[0].to_string()], &[], "NFT", None)
+        .unwrap();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.bid_nft_gate = Some(collection.to_string());
+    let (game_addr, _, _) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.bid_nft_gate, Some(collection.to_string()));
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    let err = place_bid(&mut router, &game_addr, &players[1], 1).unwrap_err();
+    assert_eq!(
+        ContractError::NftGateNotMet { collection: collection.to_string() },
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn coupons() {
+    use sha2::Digest;
+
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let game_owner = Addr::unchecked("owner0000");
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // The coupons discount the tickets of the first player by 50% and of the second by
+    // 25%: the proof of each is the leaf of the other.
+    let discounts = [5_000u16, 2_500];
+    let leaves: Vec<Vec<u8>> = players[..2]
+        .iter()
+        .zip(discounts)
+        .map(|(p, discount_bps)| sha2::Sha256::digest(format!("{}{}", p, discount_bps).as_bytes()).to_vec())
+        .collect();
+    let mut sorted_leaves = leaves.clone();
+    sorted_leaves.sort_unstable();
+    let merkle_root_coupon = hex::encode(sha2::Sha256::digest(&sorted_leaves.concat()));
+    let coupons = [
+        Coupon { discount_bps: discounts[0], proof: vec![hex::encode(&leaves[1])] },
+        Coupon { discount_bps: discounts[1], proof: vec![hex::encode(&leaves[0])] },
+    ];
+    let bid_msg = |coupon: &Coupon| ExecuteMsg::Bid {
+        bin: 1,
+        tickets: None,
+        recipient: None,
+        allowlist_proof: None,
+        coupon: Some(coupon.clone()),
+        referrer: None,
+        prediction: None,
+        round: None,
+    };
+    let funds = |amount: u128| [Coin { denom: native_token_denom.clone(), amount: Uint128::new(amount) }];
+
+    set_height(&mut router, 200_001);
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(&coupons[0]), &funds(5))
+        .unwrap_err();
+    assert_eq!(ContractError::CouponRootNotRegistered {}, err.downcast().unwrap());
+
+    // Just the owner can register the coupon Merkle root.
+    let register_msg = ExecuteMsg::RegisterCouponRoot {
+        merkle_root_coupon: merkle_root_coupon.clone(),
+        round: None,
+    };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &register_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router
+        .execute_contract(game_owner, game_addr.clone(), &register_msg, &[])
+        .unwrap();
+
+    let invalid_coupon = Coupon { discount_bps: 0, proof: coupons[0].proof.clone() };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(&invalid_coupon), &funds(10))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidCoupon {}, err.downcast().unwrap());
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(&coupons[1]), &funds(10))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VerificationFailed { merkle_root: "coupon".to_string() },
+        err.downcast().unwrap()
+    );
+
+    // The discounted price is paid and the change is sent back.
+    let balance = bank_balance(&mut router, &players[0], native_token_denom.clone()).amount;
+    let res = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(&coupons[0]), &funds(10))
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-bid").add_attribute(events::COUPON_DISCOUNT_BPS, "5000")));
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        balance - Uint128::new(5)
+    );
+
+    // The discount is rounded down.
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &bid_msg(&coupons[1]), &funds(7))
+        .unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &bid_msg(&coupons[1]), &funds(8))
+        .unwrap();
+
+    // A coupon is used once per round.
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    let err = router
+        .execute_contract(players[0].clone(), game_addr, &bid_msg(&coupons[0]), &funds(10))
+        .unwrap_err();
+    assert_eq!(ContractError::CouponAlreadyUsed {}, err.downcast().unwrap());
+}
+
+#[test]
+fn referrals() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let fee_collector = Addr::unchecked("collector");
+    let referrers = [Addr::unchecked("referrer"), Addr::unchecked("friend")];
+
+    let mut msg = game_instantiate_msg(ticket_price.clone(), bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.protocol_fee_bps = Some(2_000);
+    msg.fee_collector = Some(fee_collector.to_string());
+    msg.referral_fee_bps = Some(10_001);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidReferralFee {}, err.downcast().unwrap());
+
+    msg.referral_fee_bps = Some(5_000);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.referral_fee_bps, 5_000);
+
+    set_height(&mut router, 200_001);
+    let funds = [Coin { denom: native_token_denom.clone(), amount: ticket_price.amount }];
+    let bid_msg = |bin: u8, referrer: &Addr| ExecuteMsg::Bid {
+        bin,
+        tickets: None,
+        recipient: None,
+        allowlist_proof: None,
+        coupon: None,
+        referrer: Some(referrer.to_string()),
+        prediction: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(1, &players[0]), &funds)
+        .unwrap_err();
+    assert_eq!(ContractError::SelfReferral {}, err.downcast().unwrap());
+    let res = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(1, &referrers[0]), &funds)
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-bid").add_attribute(events::REFERRER, referrers[0].as_str())));
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &bid_msg(1, &referrers[0]), &funds)
+        .unwrap();
+    router
+        .execute_contract(players[2].clone(), game_addr.clone(), &bid_msg(10, &referrers[1]), &funds)
+        .unwrap();
+
+    // The removed bids are no longer counted as referrals.
+    let query_referrals = |router: &App, address: &Addr| -> u64 {
+        let res: ReferralsResponse = router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::Referrals { address: address.to_string() })
+            .unwrap();
+        res.referrals
+    };
+    assert_eq!(query_referrals(&router, &referrers[0]), 2);
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    assert_eq!(query_referrals(&router, &referrers[0]), 1);
+    assert_eq!(query_referrals(&router, &referrers[1]), 1);
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // Of the protocol fee of 4 taken on the 20 of tickets, each referrer gets half of the
+    // fee taken on the stake of its bid, and the fee collector the rest.
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(owner, game_addr.clone(), &ExecuteMsg::FinalizeGame { round: None }, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-finalize_game")
+            .add_attribute(events::PROTOCOL_FEE, "4")
+            .add_attribute(events::REFERRAL_FEE, "2")
+    ));
+    for referrer in &referrers {
+        assert_eq!(bank_balance(&mut router, referrer, native_token_denom.clone()).amount, Uint128::new(1));
+    }
+    assert_eq!(bank_balance(&mut router, &fee_collector, native_token_denom).amount, Uint128::new(2));
+}
+
+#[test]
+fn loyalty() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let game_owner = Addr::unchecked("owner0000");
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // Just the owner can set the loyalty tiers, with distinct points and valid discounts.
+    let tier = |min_points: u64, discount_bps: u16| LoyaltyTier { min_points, discount_bps };
+    let set_tiers = |tiers: Vec<LoyaltyTier>| ExecuteMsg::SetLoyaltyTiers { tiers };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &set_tiers(vec![tier(1, 2_000)]), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    for tiers in [vec![tier(0, 2_000)], vec![tier(1, 0)], vec![tier(1, 10_001)], vec![tier(1, 2_000), tier(1, 5_000)]] {
+        let err = router
+            .execute_contract(game_owner.clone(), game_addr.clone(), &set_tiers(tiers), &[])
+            .unwrap_err();
+        assert_eq!(ContractError::InvalidLoyaltyTiers {}, err.downcast().unwrap());
+    }
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &set_tiers(vec![tier(2, 5_000), tier(1, 2_000)]), &[])
+        .unwrap();
+    let res: LoyaltyTiersResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::LoyaltyTiers {})
+        .unwrap();
+    assert_eq!(res.tiers, vec![tier(1, 2_000), tier(2, 5_000)]);
+
+    // A point is earned for each round played, the removed bids earning none.
+    let query_points = |router: &App, player: &Addr| -> LoyaltyPointsResponse {
+        router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::LoyaltyPoints { address: player.to_string() })
+            .unwrap()
+    };
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    assert_eq!(query_points(&router, &players[0]), LoyaltyPointsResponse { points: 1, discount_bps: 2_000 });
+    assert_eq!(query_points(&router, &players[1]), LoyaltyPointsResponse { points: 0, discount_bps: 0 });
+
+    let stage = |start: u64| Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
+    };
+    router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::StartNewRound {
+                ticket_price: ticket_price.clone(),
+                bins,
+                stage_bid: stage(202_000),
+                stage_claim_airdrop: stage(211_000),
+                stage_claim_prize: stage(212_000),
+                first_bidder_bonus: None,
+                max_winners: None,
+                min_participants: None,
+                price_schedule: None,
+                stage_reveal: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // The points of the previous rounds discount the tickets of the next ones.
+    set_height(&mut router, 202_001);
+    let balance = bank_balance(&mut router, &players[0], native_token_denom.clone()).amount;
+    let res = place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    assert!(res.has_event(&Event::new("wasm-bid").add_attribute(events::LOYALTY_DISCOUNT_BPS, "2000")));
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        balance - Uint128::new(8)
+    );
+    assert_eq!(query_points(&router, &players[0]), LoyaltyPointsResponse { points: 2, discount_bps: 5_000 });
+
+    let funds = [Coin { denom: native_token_denom, amount: Uint128::new(8) }];
+    let bid_msg = ExecuteMsg::Bid {
+        bin: 1,
+        tickets: None,
+        recipient: None,
+        allowlist_proof: None,
+        coupon: None,
+        referrer: None,
+        prediction: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(players[1].clone(), game_addr, &bid_msg, &funds)
+        .unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+}
+
+#[test]
+fn season_pass() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let price = Coin { denom: native_token_denom.clone(), amount: Uint128::new(25) };
+    msg.season_pass = Some(SeasonPass { rounds: 0, price: price.clone() });
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidSeasonPass {}, err.downcast().unwrap());
+
+    // The pass pays up front 3 rounds, the change being sent back.
+    msg.season_pass = Some(SeasonPass { rounds: 3, price });
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let balance = bank_balance(&mut router, &players[0], native_token_denom.clone()).amount;
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::BuySeasonPass {},
+            &[Coin { denom: native_token_denom.clone(), amount: Uint128::new(30) }],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-buy_season_pass").add_attribute(events::SEASON_PASS_ENTRIES, "3")));
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        balance - Uint128::new(25)
+    );
+    let query_season_pass = |router: &App, player: &Addr| -> SeasonPassResponse {
+        router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::SeasonPass { address: player.to_string() })
+            .unwrap()
+    };
+    assert_eq!(
+        query_season_pass(&router, &players[0]),
+        SeasonPassResponse { entries: 3, amount: Uint128::new(25) }
+    );
+
+    // A bid without funds uses an entry, staking its part of the price of the pass.
+    set_height(&mut router, 200_001);
+    let bid_msg = |tickets: Option<u32>| ExecuteMsg::Bid {
+        bin: 1,
+        tickets,
+        recipient: None,
+        allowlist_proof: None,
+        coupon: None,
+        referrer: None,
+        prediction: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(Some(2)), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::SeasonPassSingleTicket {}, err.downcast().unwrap());
+    let res = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &bid_msg(None), &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-bid").add_attribute(events::SEASON_PASS_ENTRIES, "2")));
+    assert_eq!(
+        query_season_pass(&router, &players[0]),
+        SeasonPassResponse { entries: 2, amount: Uint128::new(17) }
+    );
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(8));
+
+    // Without a pass the ticket must be paid.
+    let err = router
+        .execute_contract(players[1].clone(), game_addr, &bid_msg(None), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::TicketPriceNotPaid {}, err.downcast().unwrap());
+}
+
+#[test]
+fn bid_receipts() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+
+    let cw721_id = router.store_code(contract_cw721());
+    let collection = router
+        .instantiate_contract(cw721_id, owner, &Vec::<String>::new(), &[], "Receipts", None)
+        .unwrap();
+    let tokens = |router: &App, owner: &Addr| -> Vec<String> {
+        let res: TokensResponse = router
+            .wrap()
+            .query_wasm_smart(
+                &collection,
+                &Cw721QueryMsg::Tokens { owner: owner.to_string(), start_after: None, limit: None },
+            )
+            .unwrap();
+        res.tokens
+    };
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.bid_receipts = Some(collection.to_string());
+    let (game_addr, _, _) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.bid_receipts, Some(collection.to_string()));
+
+    // Each bid mints a receipt to the player.
+    set_height(&mut router, 200_001);
+    let res = place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-bid").add_attribute(events::RECEIPT, format!("1-{}", players[0]))
+    ));
+    place_bid(&mut router, &game_addr, &players[1], 2).unwrap();
+    assert_eq!(tokens(&router, &players[0]), vec![format!("1-{}", players[0])]);
+    assert_eq!(tokens(&router, &players[1]), vec![format!("1-{}", players[1])]);
+
+    // The receipt is reissued when the bid is transferred.
+    let friend = Addr::unchecked("friend");
+    router
+        .execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::TransferBid { new_address: friend.to_string(), round: None },
+            &[],
+        )
+        .unwrap();
+    assert!(tokens(&router, &players[1]).is_empty());
+    assert_eq!(tokens(&router, &friend), vec![format!("1-{}", friend)]);
+
+    // And burned when the bid is removed.
+    router
+        .execute_contract(players[0].clone(), game_addr, &ExecuteMsg::RemoveBid { round: None }, &[])
+        .unwrap();
+    assert!(tokens(&router, &players[0]).is_empty());
+}
+
+#[test]
+fn winner_badges() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let cw721_id = router.store_code(contract_cw721());
+    let collection = router
+        .instantiate_contract(cw721_id, owner, &Vec::<String>::new(), &[], "Badges", None)
+        .unwrap();
+    let tokens = |router: &App, owner: &Addr| -> Vec<String> {
+        let res: TokensResponse = router
+            .wrap()
+            .query_wasm_smart(
+                &collection,
+                &Cw721QueryMsg::Tokens { owner: owner.to_string(), start_after: None, limit: None },
+            )
+            .unwrap();
+        res.tokens
+    };
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.winner_badges = Some(collection.to_string());
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let config = get_config(&router, &game_addr);
+    assert_eq!(config.winner_badges, Some(collection.to_string()));
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // The badge is minted to the winner, even when the prize is sent to a recipient.
+    set_height(&mut router, 202_001);
+    let cold_wallet = Addr::unchecked("cold0000");
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: Some(cold_wallet.to_string()), stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(tokens(&router, &players[0]), vec![format!("badge-1-{}", players[0])]);
+    assert!(tokens(&router, &cold_wallet).is_empty());
+
+    // Each winner gets a badge, the players who did not bid get none.
+    router
+        .execute_contract(
+            players[2].clone(),
+            game_addr,
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(tokens(&router, &players[2]), vec![format!("badge-1-{}", players[2])]);
+    assert!(tokens(&router, &players[1]).is_empty());
+}
+
+#[test]
+fn separate_roots() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+    for addr in std::iter::once(&owner).chain(players.iter()) {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    let game_owner = Addr::unchecked("owner0000");
+
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(1_100_000) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+
+    // The airdrop root is registered before the game starts.
+    let res = router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: test_data_airdrop.root.clone(),
+                total_amount_airdrop: Some(Uint128::new(11_330)),
+                expected_recipients: Some(6),
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-register_airdrop_root").add_attribute(events::MERKLE_ROOT_AIRDROP, &test_data_airdrop.root)
+    ));
+    let roots = get_merkle_roots(&router, &game_addr);
+    assert_eq!(roots.merkle_root_airdrop, test_data_airdrop.root);
+    assert_eq!(roots.merkle_root_game, None);
+
+    // The game root waits for the end of the bid stage.
+    let register_game_root = ExecuteMsg::RegisterGameRoot {
+        merkle_root_game: test_data_game.root.clone(),
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        prize_tokens: None,
+        round: None,
+    };
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &register_game_root, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BidStageNotFinished {}, err.downcast().unwrap());
+
+    set_height(&mut router, 200_002);
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &register_game_root, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &register_game_root, &[])
+        .unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &register_game_root, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::MerkleRootsAlreadyRegistered {}, err.downcast().unwrap());
+    assert_eq!(
+        get_merkle_roots(&router, &game_addr).merkle_root_game,
+        Some(test_data_game.root)
+    );
+
+    // The claims work as with the roots registered at once.
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_airdrop_amount, Uint128::new(11_330));
+    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_000_000));
+    assert_eq!(info.winners_amount, Uint128::new(1));
+}
+
+#[test]
+fn rotate_merkle_roots() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let game_owner = Addr::unchecked("owner0000");
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let wrong_root = "00".repeat(32);
+    let rotate = |merkle_root_game: &str| ExecuteMsg::RotateMerkleRoots {
+        merkle_root_airdrop: None,
+        merkle_root_game: Some(merkle_root_game.to_string()),
+        round: None,
+    };
+
+    // Only the owner can rotate the roots.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &rotate(&wrong_root), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    // A wrong root is fixed before the claims open, the event showing both roots.
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &rotate(&wrong_root), &[])
+        .unwrap();
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    let res = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &rotate(&test_data_game.root), &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-rotate_merkle_roots")
+            .add_attribute(events::OLD_MERKLE_ROOT_GAME, &wrong_root)
+            .add_attribute(events::MERKLE_ROOT_GAME, &test_data_game.root)
+    ));
+    assert_eq!(get_merkle_roots(&router, &game_addr).merkle_root_game, Some(test_data_game.root.clone()));
+
+    // Once the claims are open the roots are final.
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    let err = router
+        .execute_contract(game_owner, game_addr, &rotate(&wrong_root), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::RootRotationClosed {}, err.downcast().unwrap());
+}
+
+#[test]
+fn winning_bin_on_chain() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+    for addr in std::iter::once(&owner).chain(players.iter()) {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let attestor = Addr::unchecked("attestor0000");
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    msg.attestor = Some(attestor.to_string());
+    msg.resolution = Some(Resolution::OwnerSetsBin {});
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    assert_eq!(get_config(&router, &game_addr).attestor, Some(attestor.to_string()));
+    let game_owner = Addr::unchecked("owner0000");
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(1_100_000) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: test_data_airdrop.root.clone(),
+                total_amount_airdrop: Some(Uint128::new(11_330)),
+                expected_recipients: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 3).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 1).unwrap();
+
+    // The attestor sets the winning bin once the bids are final.
+    let set_winning_bin = ExecuteMsg::SetWinningBin {
+        bin: 1,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        prize_tokens: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(attestor.clone(), game_addr.clone(), &set_winning_bin, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BidStageNotFinished {}, err.downcast().unwrap());
+    set_height(&mut router, 200_002);
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &set_winning_bin, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    let res = router
+        .execute_contract(attestor, game_addr.clone(), &set_winning_bin, &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-set_winning_bin").add_attribute(events::WINNING_BIN, "1")));
+    assert_eq!(get_merkle_roots(&router, &game_addr).winning_bin, Some(1));
+
+    // The winning bin is set once, and no game root can be registered.
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &set_winning_bin, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::GameAlreadyResolved {}, err.downcast().unwrap());
+    let err = router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::RegisterGameRoot {
+                merkle_root_game: "00".repeat(32),
+                total_amount_game: None,
+                prize_tokens: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ResolutionNotAllowed { resolution: "owner_sets_bin".to_string() },
+        err.downcast().unwrap()
+    );
+
+    // The bids are checked against the winning bin, without game proofs.
+    set_height(&mut router, 201_001);
+    for (index, player) in players.iter().enumerate() {
+        router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimAirdrop {
+                    amount: test_data_airdrop.addresses[index].amount,
+                    proof_airdrop: test_data_airdrop.addresses[index].proofs.clone(),
+                    proof_game: vec![],
+                    recipient: None,
+                    round: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    assert_eq!(get_game_amount(&router, &game_addr).winners_amount, Uint128::new(2));
+
+    // The winners share the prize.
+    set_height(&mut router, 202_001);
+    let claim_prize = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    router.execute_contract(players[0].clone(), game_addr.clone(), &claim_prize, &[]).unwrap();
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom).amount,
+        Uint128::new(1_000_005)
+    );
+    let err = router
+        .execute_contract(players[1].clone(), game_addr, &claim_prize, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::NoteEligible {}, err.downcast().unwrap());
+}
+
+#[test]
+fn oracle_resolution() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+    for addr in std::iter::once(&owner).chain(players.iter()) {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let oracle_id = router.store_code(contract_oracle());
+    let oracle_addr = router
+        .instantiate_contract(oracle_id, owner.clone(), &Decimal::percent(320), &[], "oracle", None)
+        .unwrap();
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    let price_oracle = PriceOracle {
+        contract: oracle_addr.to_string(),
+        asset: "ATOM".to_string(),
+        min_price: Decimal::one(),
+        bin_width: Decimal::zero(),
+        total_amount_game: Some(Uint128::new(1_000_000)),
+    };
+    msg.resolution = Some(Resolution::Oracle(price_oracle.clone()));
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidPriceOracle {}, err.downcast().unwrap());
+    let price_oracle = PriceOracle { bin_width: Decimal::percent(50), ..price_oracle };
+    msg.resolution = Some(Resolution::Oracle(price_oracle.clone()));
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    assert_eq!(get_config(&router, &game_addr).resolution, Resolution::Oracle(price_oracle));
+    let game_owner = Addr::unchecked("owner0000");
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(1_100_000) };
+    router.execute_contract(owner.clone(), cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: test_data_airdrop.root.clone(),
+                total_amount_airdrop: Some(Uint128::new(11_330)),
+                expected_recipients: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 5).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 3).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 5).unwrap();
+
+    // The round is resolved from the oracle once the bids are final.
+    let resolve_game = ExecuteMsg::ResolveGame { round: None };
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &resolve_game, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BidStageNotFinished {}, err.downcast().unwrap());
+    set_height(&mut router, 200_002);
+
+    // The owner can no longer set the result.
+    let err = router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::SetWinningBin { bin: 3, total_amount_game: None, prize_tokens: None, round: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ResolutionNotAllowed { resolution: "oracle".to_string() },
+        err.downcast().unwrap()
+    );
+    let err = router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::RegisterGameRoot {
+                merkle_root_game: "00".repeat(32),
+                total_amount_game: None,
+                prize_tokens: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ResolutionNotAllowed { resolution: "oracle".to_string() },
+        err.downcast().unwrap()
+    );
+
+    // Anyone resolves the round: 3.2 falls in the fifth bin, from 3 to 3.5.
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &resolve_game, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-resolve_game")
+            .add_attribute(events::PRICE, "3.2")
+            .add_attribute(events::WINNING_BIN, "5")
+    ));
+    assert_eq!(get_merkle_roots(&router, &game_addr).winning_bin, Some(5));
+    assert_eq!(get_game_amount(&router, &game_addr).total_airdrop_game_amount, Uint128::new(1_000_000));
+
+    // The price observed later does not change the result.
+    router
+        .execute_contract(owner, oracle_addr, &Decimal::percent(120), &[])
+        .unwrap();
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &resolve_game, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::GameAlreadyResolved {}, err.downcast().unwrap());
+
+    set_height(&mut router, 201_001);
+    for (index, player) in players.iter().enumerate() {
+        router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimAirdrop {
+                    amount: test_data_airdrop.addresses[index].amount,
+                    proof_airdrop: test_data_airdrop.addresses[index].proofs.clone(),
+                    proof_game: vec![],
+                    recipient: None,
+                    round: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    assert_eq!(get_game_amount(&router, &game_addr).winners_amount, Uint128::new(2));
+}
+
+#[test]
+fn raffle_mode() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+    for addr in std::iter::once(&owner).chain(players.iter()) {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let proxy_id = router.store_code(contract_nois_proxy());
+    let proxy_addr = router
+        .instantiate_contract(proxy_id, owner.clone(), &Empty {}, &[], "nois-proxy", None)
+        .unwrap();
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    let raffle = Raffle {
+        nois_proxy: proxy_addr.to_string(),
+        total_amount_game: Some(Uint128::new(1_000_000)),
+    };
+    msg.resolution = Some(Resolution::Randomness(raffle.clone()));
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    assert_eq!(get_config(&router, &game_addr).resolution, Resolution::Randomness(raffle));
+    let game_owner = Addr::unchecked("owner0000");
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(1_100_000) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: test_data_airdrop.root.clone(),
+                total_amount_airdrop: Some(Uint128::new(11_330)),
+                expected_recipients: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 8).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 3).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 8).unwrap();
+
+    // The randomness is requested once the bids are final, paying the fee of the proxy.
+    let fee = Coin { denom: native_token_denom.clone(), amount: Uint128::new(50) };
+    let request_randomness = ExecuteMsg::RequestRandomness { round: None };
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &request_randomness, std::slice::from_ref(&fee))
+        .unwrap_err();
+    assert_eq!(ContractError::BidStageNotFinished {}, err.downcast().unwrap());
+    set_height(&mut router, 200_002);
+    let err = router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::SetWinningBin { bin: 3, total_amount_game: None, prize_tokens: None, round: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ResolutionNotAllowed { resolution: "randomness".to_string() },
+        err.downcast().unwrap()
+    );
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &request_randomness, std::slice::from_ref(&fee))
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-request_randomness").add_attribute(events::JOB_ID, "round-1")));
+    let jobs: Vec<String> = router.wrap().query_wasm_smart(&proxy_addr, &Empty {}).unwrap();
+    assert_eq!(jobs, vec!["round-1".to_string()]);
+    assert_eq!(bank_balance(&mut router, &proxy_addr, native_token_denom), fee);
+    assert_eq!(get_merkle_roots(&router, &game_addr).randomness_job, Some("round-1".to_string()));
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &request_randomness, &[fee])
+        .unwrap_err();
+    assert_eq!(ContractError::RandomnessAlreadyRequested {}, err.downcast().unwrap());
+
+    // Just the proxy delivers the randomness of the pending jobs.
+    let nois_receive = |job_id: &str| ExecuteMsg::NoisReceive {
+        callback: NoisCallback {
+            job_id: job_id.to_string(),
+            published: Timestamp::from_seconds(1_700_000_000),
+            randomness: format!("{:016x}{}", 7u64, "ab".repeat(24)),
+        },
+    };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &nois_receive("round-1"), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    let err = router
+        .execute_contract(proxy_addr.clone(), game_addr.clone(), &nois_receive("round-2"), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnknownRandomnessJob { job_id: "round-2".to_string() },
+        err.downcast().unwrap()
+    );
+    let res = router
+        .execute_contract(proxy_addr.clone(), game_addr.clone(), &nois_receive("round-1"), &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-nois_receive")
+            .add_attribute(events::JOB_ID, "round-1")
+            .add_attribute(events::RANDOMNESS, format!("{:016x}{}", 7u64, "ab".repeat(24)))
+            .add_attribute(events::WINNING_BIN, "8")
+    ));
+    assert_eq!(get_merkle_roots(&router, &game_addr).winning_bin, Some(8));
+    assert_eq!(get_game_amount(&router, &game_addr).total_airdrop_game_amount, Uint128::new(1_000_000));
+    let err = router
+        .execute_contract(proxy_addr, game_addr.clone(), &nois_receive("round-1"), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnknownRandomnessJob { job_id: "round-1".to_string() },
+        err.downcast().unwrap()
+    );
+
+    // The bids on the drawn bin win.
+    set_height(&mut router, 201_001);
+    for (index, player) in players.iter().enumerate() {
+        router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimAirdrop {
+                    amount: test_data_airdrop.addresses[index].amount,
+                    proof_airdrop: test_data_airdrop.addresses[index].proofs.clone(),
+                    proof_game: vec![],
+                    recipient: None,
+                    round: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    assert_eq!(get_game_amount(&router, &game_addr).winners_amount, Uint128::new(2));
+}
+
+#[test]
+fn resolution_strategies() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    // The games are resolved with the game root by default.
+    assert_eq!(get_config(&router, &game_addr).resolution, Resolution::MerkleRoot {});
+    let game_owner = Addr::unchecked("owner0000");
+    let set_winning_bin = ExecuteMsg::SetWinningBin {
+        bin: 1,
+        total_amount_game: None,
+        prize_tokens: None,
+        round: None,
+    };
+    let resolve_game = ExecuteMsg::ResolveGame { round: None };
+    let request_randomness = ExecuteMsg::RequestRandomness { round: None };
+    let nois_receive = ExecuteMsg::NoisReceive {
+        callback: NoisCallback {
+            job_id: "round-1".to_string(),
+            published: Timestamp::from_seconds(1_700_000_000),
+            randomness: "00".repeat(32),
+        },
+    };
+    for (sender, msg) in [
+        (&game_owner, &set_winning_bin),
+        (&players[0], &resolve_game),
+        (&players[0], &request_randomness),
+        (&players[0], &nois_receive),
+    ] {
+        let err = router
+            .execute_contract(sender.clone(), game_addr.clone(), msg, &[])
+            .unwrap_err();
+        assert_eq!(
+            ContractError::ResolutionNotAllowed { resolution: "merkle_root".to_string() },
+            err.downcast().unwrap()
+        );
+    }
+
+    // The winners prove their bins against the game root.
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 2).unwrap();
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 1).unwrap();
+    assert_eq!(get_game_amount(&router, &game_addr).winners_amount, Uint128::new(1));
+}
+
+#[test]
+fn adjacent_tier() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let players: Vec<Addr> = test_data_airdrop.addresses
+        .iter()
+        .map(|a| Addr::unchecked(a.account.clone()))
+        .collect();
+    for addr in std::iter::once(&owner).chain(players.iter()) {
+        router.borrow_mut().init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds.clone()).unwrap()
+        });
+    }
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    msg.adjacent_tier_bps = Some(2_000);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidAdjacentTier {}, err.downcast().unwrap());
+    msg.resolution = Some(Resolution::OwnerSetsBin {});
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    assert_eq!(get_config(&router, &game_addr).adjacent_tier_bps, 2_000);
+    let game_owner = Addr::unchecked("owner0000");
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(11_330) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    router
+        .execute_contract(
+            game_owner.clone(),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterAirdropRoot {
+                merkle_root_airdrop: test_data_airdrop.root.clone(),
+                total_amount_airdrop: Some(Uint128::new(11_330)),
+                expected_recipients: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // The tickets prize is 30: the bidders on the bins 4 and 6, adjacent to the winning
+    // bin 5, share 20% of it.
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 5).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 4).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 6).unwrap();
+    set_height(&mut router, 200_002);
+    router
+        .execute_contract(
+            game_owner,
+            game_addr.clone(),
+            &ExecuteMsg::SetWinningBin { bin: 5, total_amount_game: None, prize_tokens: None, round: None },
+            &[],
+        )
+        .unwrap();
+
+    set_height(&mut router, 201_001);
+    for (index, player) in players.iter().enumerate() {
+        router
+            .execute_contract(
+                player.clone(),
+                game_addr.clone(),
+                &ExecuteMsg::ClaimAirdrop {
+                    amount: test_data_airdrop.addresses[index].amount,
+                    proof_airdrop: test_data_airdrop.addresses[index].proofs.clone(),
+                    proof_game: vec![],
+                    recipient: None,
+                    round: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    assert_eq!(get_game_amount(&router, &game_addr).winners_amount, Uint128::new(1));
+
+    set_height(&mut router, 202_001);
+    let claim_prize = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    let res = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &claim_prize, &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-claim_adjacent_prize").add_attribute(events::AMOUNT, "3")));
+    assert_eq!(
+        bank_balance(&mut router, &players[1], native_token_denom.clone()).amount,
+        Uint128::new(999_993)
+    );
+    let err = router
+        .execute_contract(players[1].clone(), game_addr.clone(), &claim_prize, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+
+    // The winner shares the rest of the tickets prize.
+    router.execute_contract(players[0].clone(), game_addr.clone(), &claim_prize, &[]).unwrap();
+    assert_eq!(
+        bank_balance(&mut router, &players[0], native_token_denom.clone()).amount,
+        Uint128::new(1_000_014)
+    );
+    router.execute_contract(players[2].clone(), game_addr, &claim_prize, &[]).unwrap();
+    assert_eq!(
+        bank_balance(&mut router, &players[2], native_token_denom).amount,
+        Uint128::new(999_993)
+    );
+}
+
+#[test]
+fn bin_multipliers() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg.clone());
+    let game_owner = Addr::unchecked("owner0000");
+    let set_multipliers = |multipliers: Vec<(u8, Decimal)>| ExecuteMsg::SetBinMultipliers { multipliers };
+
+    // The multipliers have no effect with a single winning bin.
+    msg.resolution = Some(Resolution::OwnerSetsBin {});
+    let single_bin_game = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), single_bin_game, &set_multipliers(vec![(3, Decimal::percent(200))]), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BinMultipliersSingleBin {}, err.downcast().unwrap());
+
+    // Just the owner sets valid multipliers.
+    let multipliers = vec![(10, Decimal::permille(2_500)), (3, Decimal::percent(200))];
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &set_multipliers(multipliers.clone()), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    for invalid in [
+        vec![(0, Decimal::one())],
+        vec![(1, Decimal::zero())],
+        vec![(1, Decimal::percent(10_001))],
+        vec![(1, Decimal::one()), (1, Decimal::percent(200))],
+    ] {
+        let err = router
+            .execute_contract(game_owner.clone(), game_addr.clone(), &set_multipliers(invalid), &[])
+            .unwrap_err();
+        assert_eq!(ContractError::InvalidBinMultipliers { max: 100 }, err.downcast().unwrap());
+    }
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &set_multipliers(multipliers), &[])
+        .unwrap();
+    let res: BinMultipliersResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::BinMultipliers { round: Some(1) })
+        .unwrap();
+    assert_eq!(
+        res.multipliers,
+        vec![(3, Decimal::percent(200)), (10, Decimal::permille(2_500))]
+    );
+
+    // The winners on the bins 1, 3 and 10 share the tickets prize of 30 with the weights
+    // 1, 2 and 2.5.
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 3).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    set_height(&mut router, 201_001);
+    let err = router
+        .execute_contract(game_owner, game_addr.clone(), &set_multipliers(vec![]), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::BinMultipliersLocked {}, err.downcast().unwrap());
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    set_height(&mut router, 202_001);
+    let claim_prize = ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None };
+    for (player, balance) in players.iter().zip([999_995, 1_000_000, 1_000_003]) {
+        router.execute_contract(player.clone(), game_addr.clone(), &claim_prize, &[]).unwrap();
+        assert_eq!(
+            bank_balance(&mut router, player, native_token_denom.clone()).amount,
+            Uint128::new(balance)
+        );
+    }
+}
+
+#[test]
+fn prediction_bids() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let player = Addr::unchecked("player");
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &player, funds).unwrap()
+    });
+    let mut msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let game_without_boundaries = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+
+    // The 10 bins are delimited by 9 ascending boundaries.
+    for invalid in [vec![100, 200], vec![100, 300, 200, 400, 500, 600, 700, 800, 900]] {
+        msg.bin_boundaries = Some(invalid.into_iter().map(Uint128::new).collect());
+        let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+        assert_eq!(ContractError::InvalidBinBoundaries { expected: 9 }, err.downcast().unwrap());
+    }
+    let bin_boundaries: Vec<Uint128> = (1..10).map(|bin| Uint128::new(bin * 100)).collect();
+    msg.bin_boundaries = Some(bin_boundaries.clone());
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    assert_eq!(get_config(&router, &game_addr).bin_boundaries, Some(bin_boundaries));
+
+    let res: BinBoundariesResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::BinBoundaries {})
+        .unwrap();
+    assert_eq!(res.ranges.len(), 10);
+    assert_eq!(res.ranges[0], BinRange { bin: 1, min: None, max: Some(Uint128::new(100)) });
+    assert_eq!(res.ranges[1], BinRange { bin: 2, min: Some(Uint128::new(100)), max: Some(Uint128::new(200)) });
+    assert_eq!(res.ranges[9], BinRange { bin: 10, min: Some(Uint128::new(900)), max: None });
+    let res: BinBoundariesResponse = router
+        .wrap()
+        .query_wasm_smart(&game_without_boundaries, &QueryMsg::BinBoundaries {})
+        .unwrap();
+    assert!(res.ranges.is_empty());
+
+    // The prediction places the bid on the bin covering it.
+    set_height(&mut router, 200_001);
+    let bid_msg = |bin: u8, prediction: u128| ExecuteMsg::Bid {
+        bin,
+        tickets: None,
+        recipient: None,
+        allowlist_proof: None,
+        coupon: None,
+        referrer: None,
+        prediction: Some(Uint128::new(prediction)),
+        round: None,
+    };
+    let ticket = [Coin { denom: native_token_denom, amount: ticket_price.amount }];
+    let err = router
+        .execute_contract(player.clone(), game_without_boundaries, &bid_msg(0, 150), &ticket)
+        .unwrap_err();
+    assert_eq!(ContractError::NoBinBoundaries {}, err.downcast().unwrap());
+    let err = router
+        .execute_contract(player.clone(), game_addr.clone(), &bid_msg(3, 150), &ticket)
+        .unwrap_err();
+    assert_eq!(ContractError::BinAndPrediction {}, err.downcast().unwrap());
+    router
+        .execute_contract(player.clone(), game_addr.clone(), &bid_msg(0, 200), &ticket)
+        .unwrap();
+    assert_eq!(get_bid(&router, &game_addr, player.to_string()).bid, Some(3));
+}
+
+#[test]
+fn odds() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.protocol_fee_bps = Some(1_000);
+    msg.fee_collector = Some("fee_collector".to_string());
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let odds = |router: &App, bin: u8| -> StdResult<OddsResponse> {
+        router.wrap().query_wasm_smart(&game_addr, &QueryMsg::Odds { bin, round: None })
+    };
+    assert!(odds(&router, 0).is_err());
+    assert!(odds(&router, 11).is_err());
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    // The tickets prize of 30 is shared after the protocol fee of 10%.
+    let expected = OddsResponse {
+        bin: 1,
+        bin_bids: 2,
+        total_bids: 3,
+        ticket_payout: Uint128::new(13),
+        airdrop_payout: Uint128::new(500_000),
+    };
+    assert_eq!(odds(&router, 1).unwrap(), expected);
+    let expected = OddsResponse {
+        bin: 5,
+        bin_bids: 0,
+        total_bids: 3,
+        ticket_payout: Uint128::new(27),
+        airdrop_payout: Uint128::new(1_000_000),
+    };
+    assert_eq!(odds(&router, 5).unwrap(), expected);
+}
+
+#[test]
+fn airdrop_total_cap() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+
+    // The tree allows claims of 11330 tokens, but only 1110 are registered while the
+    // contract holds more.
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(11_330) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::RegisterMerkleRoots {
+                merkle_root_airdrop: test_data_airdrop.root,
+                total_amount_airdrop: Some(Uint128::new(1_110)),
+                merkle_root_game: test_data_game.root,
+                total_amount_game: None,
+                expected_recipients: None,
+                prize_tokens: None,
+                round: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 1).unwrap();
+    let err = claim_airdrop_at(&mut router, &game_addr, 2).unwrap_err();
+    assert_eq!(
+        ContractError::AirdropTotalExceeded {
+            total: Uint128::new(1_110),
+            claimed: Uint128::new(1_110),
+            amount: Uint128::new(10_220),
+        },
+        err.downcast().unwrap()
+    );
+    assert_eq!(get_game_amount(&router, &game_addr).total_claimed_airdrop, Uint128::new(1_110));
+}
+
+#[test]
+fn prize_funding() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    let register_merkle_roots = ExecuteMsg::RegisterMerkleRoots {
+        merkle_root_airdrop: test_data_airdrop.root.clone(),
+        total_amount_airdrop: Some(Uint128::new(11_330)),
+        merkle_root_game: test_data_game.root,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        expected_recipients: None,
+        prize_tokens: None,
+        round: None,
+    };
+    let register = |router: &mut App| {
+        router.execute_contract(Addr::unchecked("owner0000"), game_addr.clone(), &register_merkle_roots, &[])
+    };
+
+    // The roots cannot be registered before the airdrop and the prize are sent.
+    let err = register(&mut router).unwrap_err();
+    assert_eq!(
+        ContractError::PrizesNotFunded {
+            asset: cw20_token.addr().to_string(),
+            balance: Uint128::zero(),
+            required: Uint128::new(1_011_330),
+        },
+        err.downcast().unwrap()
+    );
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(11_330) };
+    router.execute_contract(owner.clone(), cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    let err = register(&mut router).unwrap_err();
+    assert_eq!(
+        ContractError::PrizesNotFunded {
+            asset: cw20_token.addr().to_string(),
+            balance: Uint128::new(11_330),
+            required: Uint128::new(1_011_330),
+        },
+        err.downcast().unwrap()
+    );
+
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(1_000_000) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    register(&mut router).unwrap();
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_airdrop_amount, Uint128::new(11_330));
+    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_000_000));
+}
+
+#[test]
+fn prize_allowance() {
+    let mut router = mock_app();
+    let (_, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
+    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    let game_owner = Addr::unchecked("owner0000");
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_owner.to_string(), amount: Uint128::new(1_000_000) };
+    router.execute_contract(owner.clone(), cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: game_addr.to_string(), amount: Uint128::new(11_330) };
+    router.execute_contract(owner, cw20_token.addr(), &send_token_msg, &[]).unwrap();
+    let increase_allowance = |amount: u128| cw20::Cw20ExecuteMsg::IncreaseAllowance {
+        spender: game_addr.to_string(),
+        amount: Uint128::new(amount),
+        expires: None,
+    };
+    let register_merkle_roots = ExecuteMsg::RegisterMerkleRoots {
+        merkle_root_airdrop: test_data_airdrop.root.clone(),
+        total_amount_airdrop: Some(Uint128::new(11_330)),
+        merkle_root_game: test_data_game.root,
+        total_amount_game: Some(Uint128::new(1_000_000)),
+        expected_recipients: None,
+        prize_tokens: None,
+        round: None,
+    };
+
+    // The allowance of the owner must cover the tokens missing to the contract.
+    router.execute_contract(game_owner.clone(), cw20_token.addr(), &increase_allowance(999_999), &[]).unwrap();
+    let err = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &register_merkle_roots, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PrizesNotFunded {
+            asset: cw20_token.addr().to_string(),
+            balance: Uint128::new(11_330),
+            required: Uint128::new(1_011_330),
+        },
+        err.downcast().unwrap()
+    );
+
+    // The registration pulls the exact amount missing.
+    router.execute_contract(game_owner.clone(), cw20_token.addr(), &increase_allowance(1), &[]).unwrap();
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &register_merkle_roots, &[])
+        .unwrap();
+    let game_balance = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, game_addr.clone()).unwrap();
+    assert_eq!(game_balance, Uint128::new(1_011_330));
+    let owner_balance = cw20_token.balance::<App, Addr, MyCustomQuery>(&router, game_owner).unwrap();
+    assert_eq!(owner_balance, Uint128::zero());
+    assert_eq!(get_game_amount(&router, &game_addr).total_airdrop_game_amount, Uint128::new(1_000_000));
+}
+
+#[test]
+fn prize_pool_donations() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    let fund_prize_pool = ExecuteMsg::FundPrizePool { round: None };
+
+    // Only the ticket asset and the prize asset can be donated.
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &fund_prize_pool, &[Coin::new(50, "ubtc")])
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidPrizePoolFunds {}, err.downcast().unwrap());
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &fund_prize_pool, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidPrizePoolFunds {}, err.downcast().unwrap());
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    let res = router
+        .execute_contract(
+            players[1].clone(),
+            game_addr.clone(),
+            &fund_prize_pool,
+            &[Coin::new(50, native_token_denom.clone())],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm-fund_prize_pool")
+            .add_attribute(events::CONTRIBUTOR, players[1].as_str())
+            .add_attribute(events::ASSET, native_token_denom.as_str())
+            .add_attribute(events::AMOUNT, "50")
+    ));
+    router
+        .execute_contract(players[1].clone(), game_addr.clone(), &fund_prize_pool, &[Coin::new(25, native_token_denom.clone())])
+        .unwrap();
+    let send_msg = cw20::Cw20ExecuteMsg::Send {
+        contract: game_addr.to_string(),
+        amount: Uint128::new(1_000),
+        msg: to_binary(&ReceiveMsg::FundPrizePool { round: None }).unwrap(),
+    };
+    router.execute_contract(owner.clone(), cw20_token.addr(), &send_msg, &[]).unwrap();
+
+    // The donations are added to the prizes registered.
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_ticket_prize, Uint128::new(85));
+    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_001_000));
+
+    let contributors = |router: &App, start_after: Option<String>| -> ContributorsResponse {
+        router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::Contributors { start_after, limit: Some(1), round: None })
+            .unwrap()
+    };
+    let expected = [
+        ContributorInfo { address: owner.to_string(), ticket_prize: Uint128::zero(), game_prize: Uint128::new(1_000) },
+        ContributorInfo { address: players[1].to_string(), ticket_prize: Uint128::new(75), game_prize: Uint128::zero() },
+    ];
+    let first = contributors(&router, None).contributors;
+    assert_eq!(first, vec![expected[0].clone()]);
+    let second = contributors(&router, Some(first[0].address.clone())).contributors;
+    assert_eq!(second, vec![expected[1].clone()]);
+    assert!(contributors(&router, Some(second[0].address.clone())).contributors.is_empty());
+
+    // The prizes are final once the bid stage has ended.
+    set_height(&mut router, 200_002);
+    let err = router
+        .execute_contract(players[1].clone(), game_addr, &fund_prize_pool, &[Coin::new(50, native_token_denom)])
+        .unwrap_err();
+    assert_eq!(ContractError::StageEnded { stage_name: String::from("bid") }, err.downcast().unwrap());
+}
+
+#[test]
+fn ibc_ticket_denoms() {
+    let mut router = mock_app();
+    let (_, owner, _, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    let atom = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_string();
+    let osmo = "ibc/ED07A3391A112B175915CD8FAF43A2DA8E4790EDE12566649D0C2F97716B8518".to_string();
+    let player = Addr::unchecked("player0000");
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &player, vec![Coin::new(1_000, atom.clone())]).unwrap()
+    });
+    let cw20_token = create_cw20(
+        &mut router,
+        &owner,
+        "token".to_string(),
+        "CWTOKEN".to_string(),
+        Uint128::new(1_000_000_000)
+    );
+    let mut msg = game_instantiate_msg(
+        Coin::new(10, atom.clone()),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        Some(cw20_token.addr().to_string()),
+    );
+
+    // A ticket with an IBC denom must be accepted by the owner.
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::IbcDenomNotAccepted { denom: atom.clone() }, err.downcast().unwrap());
+    msg.ibc_denoms = Some(vec!["ibc/atom".to_string()]);
+    let err = create_game_with_msg(&mut router, &owner, &msg).unwrap_err();
+    assert_eq!(ContractError::InvalidIbcDenom { denom: "ibc/atom".to_string() }, err.downcast().unwrap());
+    msg.ibc_denoms = Some(vec![atom.clone()]);
+    let game_addr = create_game_with_msg(&mut router, &owner, &msg).unwrap();
+    let game_owner = Addr::unchecked("owner0000");
+
+    let update_ibc_denoms = |add: Vec<String>, remove: Vec<String>| ExecuteMsg::UpdateIbcDenoms { add, remove };
+    let err = router
+        .execute_contract(player.clone(), game_addr.clone(), &update_ibc_denoms(vec![osmo.clone()], vec![]), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    let res = router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_ibc_denoms(vec![osmo.clone()], vec![]), &[])
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-update_ibc_denoms").add_attribute(events::ACCEPTED_DENOM, &osmo)));
+    let ibc_denoms = |router: &App, start_after: Option<String>| -> Vec<String> {
+        let res: IbcDenomsResponse = router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::IbcDenoms { start_after, limit: None })
+            .unwrap();
+        res.denoms
+    };
+    assert_eq!(ibc_denoms(&router, None), vec![atom.clone(), osmo.clone()]);
+    assert_eq!(ibc_denoms(&router, Some(atom.clone())), vec![osmo.clone()]);
+
+    // The bids are paid with the accepted denom, until the owner removes it.
+    set_height(&mut router, 200_001);
+    let bid = |bin: u8| ExecuteMsg::Bid {
+        bin,
+        tickets: None,
+        recipient: None,
+        allowlist_proof: None,
+        coupon: None,
+        referrer: None,
+        prediction: None,
+        round: None,
+    };
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_ibc_denoms(vec![], vec![atom.clone()]), &[])
+        .unwrap();
+    assert_eq!(ibc_denoms(&router, None), vec![osmo]);
+    let err = router
+        .execute_contract(player.clone(), game_addr.clone(), &bid(1), &[Coin::new(10, atom.clone())])
+        .unwrap_err();
+    assert_eq!(ContractError::IbcDenomNotAccepted { denom: atom.clone() }, err.downcast().unwrap());
+    router
+        .execute_contract(game_owner, game_addr.clone(), &update_ibc_denoms(vec![atom.clone()], vec![]), &[])
+        .unwrap();
+    router
+        .execute_contract(player.clone(), game_addr.clone(), &bid(1), &[Coin::new(10, atom.clone())])
+        .unwrap();
+    assert_eq!(get_bid(&router, &game_addr, player.to_string()).bid, Some(1));
+    assert_eq!(bank_balance(&mut router, &game_addr, atom).amount, Uint128::new(10));
+}
+
+#[test]
+fn remote_winners() {
+    let mut router = mock_app();
+    let (native_token_denom, owner, ticket_price, bins, funds) = global_variables();
+    let (mut stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+    stage_bid.duration = Duration::Height(100);
+    let player = Addr::unchecked("player");
+    router.borrow_mut().init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &player, funds).unwrap()
+    });
+
+    let mut msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    msg.ibc_bidder_port = Some("wasm.sender".to_string());
+    msg.max_bids = Some(1);
+    msg.max_per_bin = Some(1);
+    msg.anti_snipe = Some(AntiSnipe { window: 10, extension: 20, max_extension: 30 });
+    let game_id = router.store_code(contract_game_with_remote_bids());
+    let packet = |channel_id: &str, player: &str, bin: u8, tickets: u32| {
+        let packet = IbcBidPacket {
+            player: player.to_string(),
+            bin,
+            tickets: Some(tickets),
+            allowlist_proof: None,
+            round: None,
+        };
+        (channel_id.to_string(), packet)
+    };
+
+    let merkle_root_game = router.instantiate_contract(game_id, owner.clone(), &msg, &[], "game", None).unwrap();
+    msg.resolution = Some(Resolution::OwnerSetsBin {});
+    let game_addr = router.instantiate_contract(game_id, owner, &msg, &[], "game", None).unwrap();
+
+    // The winners of the remote bids must be known on chain.
+    set_height(&mut router, 200_050);
+    let err = router
+        .wasm_sudo(merkle_root_game, &packet("channel-1", "osmo1remote", 3, 1))
+        .unwrap_err();
+    assert_eq!(ContractError::RemoteBidsNotAllowed {}, err.downcast().unwrap());
+    place_bid(&mut router, &game_addr, &player, 3).unwrap();
+
+    // The remote players are keyed by channel, and their bids count toward neither the
+    // limits of the local bids nor the extension of the bid stage.
+    set_height(&mut router, 200_095);
+    let err = router
+        .wasm_sudo(game_addr.clone(), &packet("channel-1", "osmo1remote", 3, 101))
+        .unwrap_err();
+    assert_eq!(ContractError::TooManyRemoteTickets { max: 100 }, err.downcast().unwrap());
+    router.wasm_sudo(game_addr.clone(), &packet("channel-1", "osmo1remote", 3, 100)).unwrap();
+    router.wasm_sudo(game_addr.clone(), &packet("channel-1", "osmo1other", 5, 1)).unwrap();
+    router.wasm_sudo(game_addr.clone(), &packet("channel-2", "osmo1remote", 3, 1)).unwrap();
+    let err = router
+        .wasm_sudo(game_addr.clone(), &packet("channel-1", "osmo1remote", 4, 1))
+        .unwrap_err();
+    assert_eq!(ContractError::CannotBidMoreThanOnce {}, err.downcast().unwrap());
+    assert_eq!(get_stages(&router, &game_addr).stage_bid.duration, Duration::Height(100));
+    assert_eq!(get_bin_counts(&router, &game_addr).bin_counts, vec![BinCount { bin: 3, count: 1 }]);
+    assert_eq!(
+        bank_balance(&mut router, &game_addr, native_token_denom).amount,
+        Uint128::new(10)
+    );
+
+    // The remote winners are listed once the winning bin is set.
+    let remote_winners = |router: &App, channel_id: &str| -> Vec<RemoteWinner> {
+        let query = QueryMsg::RemoteWinners {
+            channel_id: channel_id.to_string(),
+            start_after: None,
+            limit: None,
+            round: None,
+        };
+        let res: RemoteWinnersResponse = router.wrap().query_wasm_smart(&game_addr, &query).unwrap();
+        res.winners
+    };
+    assert_eq!(remote_winners(&router, "channel-1"), vec![]);
+    set_height(&mut router, 200_100);
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &ExecuteMsg::SetWinningBin { bin: 3, total_amount_game: None, prize_tokens: None, round: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        remote_winners(&router, "channel-1"),
+        vec![RemoteWinner { player: "osmo1remote".to_string(), bin: 3, tickets: 100 }]
+    );
+    assert_eq!(
+        remote_winners(&router, "channel-2"),
+        vec![RemoteWinner { player: "osmo1remote".to_string(), bin: 3, tickets: 1 }]
+    );
+    assert_eq!(remote_winners(&router, "channel-3"), vec![]);
+}
+
+#[test]
+fn claimable_amounts() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    let claimable = |router: &App, address: &Addr| -> ClaimableAmountsResponse {
+        router
+            .wrap()
+            .query_wasm_smart(
+                &game_addr,
+                &QueryMsg::ClaimableAmounts { address: address.to_string(), round: None },
+            )
+            .unwrap()
+    };
+    let nothing = ClaimableAmountsResponse {
+        ticket_prize: Uint128::zero(),
+        airdrop_prize: Uint128::zero(),
+        sponsor_prizes: vec![],
+        token_prizes: vec![],
+    };
+
+    // Winners share the prize evenly, the others have nothing to claim.
+    let preview = claimable(&router, &players[0]);
+    assert_eq!(preview, claimable(&router, &players[2]));
+    assert_eq!(claimable(&router, &players[1]), nothing);
+
+    // The preview matches the amounts paid by the claim.
+    set_height(&mut router, 202_001);
+    let res = router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
+        ).unwrap();
+    let event = Event::new("wasm")
+        .add_attribute(events::PRIZE_FROM_TICKETS, preview.ticket_prize)
+        .add_attribute(events::PRIZE_FROM_AIRDROP, preview.airdrop_prize);
+    assert!(res.has_event(&event));
+    assert_eq!(claimable(&router, &players[0]), nothing);
+}
+
+#[test]
+fn sponsorship_matching() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    let sponsor = Addr::unchecked("sponsor0000");
+    let sponsor_token = create_cw20(
+        &mut router,
+        &sponsor,
+        "sponsor".to_string(),
+        "SPONSOR".to_string(),
+        Uint128::new(1_000),
+    );
+    let sponsor_msg = |ratio: Decimal| cw20::Cw20ExecuteMsg::Send {
+        contract: game_addr.to_string(),
+        amount: Uint128::new(50),
+        msg: to_binary(&ReceiveMsg::Sponsor { ratio, round: None }).unwrap(),
+    };
+
+    let err = router
+        .execute_contract(sponsor.clone(), sponsor_token.addr(), &sponsor_msg(Decimal::zero()), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidSponsorship {}, err.downcast().unwrap());
+
+    // The sponsor matches the ticket sales 1:1, up to 50 tokens.
+    router
+        .execute_contract(sponsor.clone(), sponsor_token.addr(), &sponsor_msg(Decimal::one()), &[])
+        .unwrap();
+    let err = router
+        .execute_contract(sponsor.clone(), sponsor_token.addr(), &sponsor_msg(Decimal::one()), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::SponsorshipAlreadyPresent {}, err.downcast().unwrap());
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    let sponsorships: SponsorshipsResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Sponsorships { round: None })
+        .unwrap();
+    assert_eq!(sponsorships.sponsorships, vec![SponsorshipInfo {
+        sponsor: sponsor.to_string(),
+        token: sponsor_token.addr().to_string(),
+        ratio: Decimal::one(),
+        cap: Uint128::new(50),
+        matched: Uint128::new(30),
+        claimed: Uint128::zero(),
+    }]);
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    // The match is shared by the winners as the rest of the prize.
+    let claimable: ClaimableAmountsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &game_addr,
+            &QueryMsg::ClaimableAmounts { address: players[0].to_string(), round: None },
+        )
+        .unwrap();
+    assert_eq!(claimable.sponsor_prizes, vec![SponsorPrize {
+        sponsor: sponsor.to_string(),
+        token: sponsor_token.addr().to_string(),
+        amount: Uint128::new(15),
+    }]);
 
-    // Address 1 winning bid.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
-    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+    set_height(&mut router, 202_001);
+    let err = router
         .execute_contract(
-            address_1.clone(),
+            sponsor.clone(),
             game_addr.clone(),
-            &bid_msg,
-            &[bid.clone()],
-        ).unwrap();
+            &ExecuteMsg::ReclaimSponsorship { round: None },
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::ClaimPrizeStageNotFinished {}, err.downcast().unwrap());
 
-    // Address 2 losing bid.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
-    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+    router
         .execute_contract(
-            address_2.clone(),
+            players[0].clone(),
             game_addr.clone(),
-            &bid_msg,
-            &[bid.clone()],
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
         ).unwrap();
+    let winner_balance = sponsor_token
+        .balance::<App, Addr, MyCustomQuery>(&router, players[0].clone())
+        .unwrap();
+    assert_eq!(winner_balance, Uint128::new(15));
 
-    // Address 3 winning bid.
-    let bid_msg = ExecuteMsg::Bid { bin: 10 };
-    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+    // The sponsor gets back the unmatched and the unclaimed amounts.
+    set_height(&mut router, 202_003);
+    router
         .execute_contract(
-            address_3.clone(),
+            sponsor.clone(),
             game_addr.clone(),
-            &bid_msg,
-            &[bid.clone()],
+            &ExecuteMsg::ReclaimSponsorship { round: None },
+            &[],
         ).unwrap();
+    let sponsor_balance = sponsor_token
+        .balance::<App, Addr, MyCustomQuery>(&router, sponsor.clone())
+        .unwrap();
+    assert_eq!(sponsor_balance, Uint128::new(985));
 
-    // Trigger claiming airdrop stage.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 201_001,time: current_block.time,chain_id: current_block.chain_id});
-
-    // Address 1 claim the correct ammount and verify balances and winners numbers.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[0].amount,
-        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
-        proof_game: test_data_game.addresses[0].proofs.clone()
-    };
-    let _res = router
+    let err = router
         .execute_contract(
-            address_1.clone(),
-            game_addr.clone(),
-            &claim_airdrop_msg,
+            sponsor,
+            game_addr,
+            &ExecuteMsg::ReclaimSponsorship { round: None },
             &[],
-        ).unwrap();
-    let balance_address_1 = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_1.clone())
-        .unwrap();
+        ).unwrap_err();
+    assert_eq!(ContractError::SponsorshipNotPresent {}, err.downcast().unwrap());
+}
 
-    assert_eq!(balance_address_1, Uint128::new(1100));
+#[test]
+fn notices() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Check that initially no token have been claimed.
-    let info = get_game_amount(&router, &game_addr);
-    assert_eq!(info.total_claimed_airdrop, Uint128::new(100));
-    assert_eq!(info.total_claimed_prize, Uint128::new(0));
-    assert_eq!(info.total_ticket_prize, Uint128::new(30));
-    assert_eq!(info.winners_amount, Uint128::new(1));
-    assert_eq!(info.total_airdrop_amount, Uint128::new(1_000));
-    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1_000_000));
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    let notices = |router: &App, address: &Addr| -> Vec<Notice> {
+        let res: NoticesResponse = router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::Notices { address: address.to_string() })
+            .unwrap();
+        res.notices
+    };
 
-    // Address 2 claim the correct ammount and verify balances and winners numbers.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[1].amount,
-        proof_airdrop: test_data_airdrop.addresses[1].proofs.clone(),
-        proof_game: test_data_game.addresses[1].proofs.clone()
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 1).unwrap();
+
+    // Every bidder is reminded of the claim airdrop window, winners of the prize one.
+    let claim_airdrop_window = Notice::ClaimAirdropWindow {
+        round: 1,
+        closes_at: Scheduled::AtHeight(201_002),
     };
-    let _res = router
-        .execute_contract(
-            address_2.clone(),
-            game_addr.clone(),
-            &claim_airdrop_msg,
-            &[],
-        ).unwrap();
-    let balance_address_2 = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_2.clone())
+    assert_eq!(notices(&router, &players[0]), vec![
+        claim_airdrop_window.clone(),
+        Notice::Won { round: 1, closes_at: Scheduled::AtHeight(202_002) },
+    ]);
+    assert_eq!(notices(&router, &players[1]), vec![claim_airdrop_window.clone()]);
+
+    let res = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &ExecuteMsg::AckNotices {}, &[])
         .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute(events::NOTICES, "2")));
+    assert!(notices(&router, &players[0]).is_empty());
+    assert_eq!(notices(&router, &players[1]), vec![claim_airdrop_window]);
+}
 
-    assert_eq!(balance_address_2, Uint128::new(1110));
+#[test]
+fn game_status() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Address 3 claim the correct ammount and verify balances and winners numbers.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[2].amount,
-        proof_airdrop: test_data_airdrop.addresses[2].proofs.clone(),
-        proof_game: test_data_game.addresses[2].proofs.clone()
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, _) = setup_claim_game(&mut router, msg);
+
+    let status = |router: &App| -> GameStatusResponse {
+        router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::GameStatus { round: None })
+            .unwrap()
+    };
+    let expected = |phase: GamePhase, phase_end: Option<u64>, blocks_remaining: Option<u64>| {
+        GameStatusResponse {
+            round: 1,
+            phase,
+            phase_end: phase_end.map(Scheduled::AtHeight),
+            blocks_remaining,
+            seconds_remaining: None,
+        }
     };
-    let _res = router
-        .execute_contract(
-            address_3.clone(),
-            game_addr.clone(),
-            &claim_airdrop_msg,
-            &[],
-        ).unwrap();
-    let balance_address_3 = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_3.clone())
-        .unwrap();
-    let info = get_game_amount(&router, &game_addr);
 
-    assert_eq!(balance_address_3, Uint128::new(10220));
-    assert_eq!(info.total_claimed_prize, Uint128::new(0));
-    assert_eq!(info.total_ticket_prize, Uint128::new(30));
-    assert_eq!(info.winners_amount, Uint128::new(2));
+    assert_eq!(status(&router), expected(GamePhase::Pending, Some(200_000), Some(1)));
+    set_height(&mut router, 200_001);
+    assert_eq!(status(&router), expected(GamePhase::Bidding, Some(200_002), Some(1)));
+
+    // Between two stages the game waits for the next one.
+    set_height(&mut router, 200_500);
+    assert_eq!(status(&router), expected(GamePhase::Pending, Some(201_000), Some(500)));
+    set_height(&mut router, 201_000);
+    assert_eq!(status(&router), expected(GamePhase::ClaimAirdrop, Some(201_002), Some(2)));
+    set_height(&mut router, 202_001);
+    assert_eq!(status(&router), expected(GamePhase::ClaimPrize, Some(202_002), Some(1)));
+    set_height(&mut router, 202_002);
+    assert_eq!(status(&router), expected(GamePhase::Ended, None, None));
+}
 
-    // Cannot claim prize if relative stage is not started
-    let claim_prize_msg = ExecuteMsg::ClaimPrize {};
+#[test]
+fn update_stages() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    let stage = |start: u64| Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
+    };
+    let update_stages_msg = |bid: u64, claim_airdrop: u64, claim_prize: u64| ExecuteMsg::UpdateStages {
+        stage_bid: stage(bid),
+        stage_claim_airdrop: stage(claim_airdrop),
+        stage_claim_prize: stage(claim_prize),
+    };
+
+    // Just the owner can update the stages.
     let err = router
         .execute_contract(
-            address_2.clone(),
+            players[0].clone(),
             game_addr.clone(),
-            &claim_prize_msg,
+            &update_stages_msg(300_000, 301_000, 302_000),
             &[],
         ).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
 
-    assert_eq!(ContractError::StageNotStarted { stage_name: String::from("claim prize") }, err.downcast().unwrap());
-
-    // Trigger claim prize stage start.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 202_001, time: current_block.time, chain_id: current_block.chain_id});
-
-    // Cannot claim prize if not winning bid.
-    let claim_prize_msg = ExecuteMsg::ClaimPrize {};
+    // The new stages are validated as the ones of a new round.
     let err = router
         .execute_contract(
-            address_2.clone(),
+            Addr::unchecked("owner0000"),
             game_addr.clone(),
-            &claim_prize_msg,
+            &update_stages_msg(300_000, 300_001, 302_000),
             &[],
         ).unwrap_err();
-    let balance_address_2 = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_2.clone())
-        .unwrap();
-    let bank_balance_address_2: Coin = bank_balance(&mut router, &address_2, native_token_denom.clone().to_string());
-
-    assert_eq!(ContractError::NoteEligible {}, err.downcast().unwrap());
-    assert_eq!(balance_address_2, Uint128::new(1110));
-    assert_eq!(bank_balance_address_2.amount, Uint128::new(999_990));
+    let first = String::from("bid");
+    let second = String::from("Claim airdrop");
+    assert_eq!(ContractError::StagesOverlap { first, second }, err.downcast().unwrap());
 
-    // Can claim prize if winning bid.
-    let claim_prize_msg = ExecuteMsg::ClaimPrize {};
-    let _res = router
+    router
         .execute_contract(
-            address_1.clone(),
+            Addr::unchecked("owner0000"),
             game_addr.clone(),
-            &claim_prize_msg,
+            &update_stages_msg(300_000, 301_000, 302_000),
             &[],
         ).unwrap();
-    let balance_address_1 = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, address_1.clone())
-        .unwrap();
-    let bank_balance_address_1: Coin = bank_balance(&mut router, &address_1, native_token_denom.clone().to_string());
-
-    assert_eq!(balance_address_1, Uint128::new(1100) + Uint128::new(500_000));
-    assert_eq!(bank_balance_address_1.amount, Uint128::new(999_990) + Uint128::new(15));
-
-    // Verify claimed amounts
-    let info = get_game_amount(&router, &game_addr);
-
-    assert_eq!(info.total_claimed_prize, Uint128::new(15));
-    assert_eq!(info.total_claimed_airdrop, Uint128::new(500_000) + Uint128::new(100) + Uint128::new(1010) + Uint128::new(10220));
+    let stages = get_stages(&router, &game_addr);
+    assert_eq!(stages.stage_bid, stage(300_000));
+    assert_eq!(stages.stage_claim_airdrop, stage(301_000));
+    assert_eq!(stages.stage_claim_prize, stage(302_000));
+
+    // The old bid stage is no longer open.
+    set_height(&mut router, 200_001);
+    let err = place_bid(&mut router, &game_addr, &players[0], 1).unwrap_err();
+    assert_eq!(
+        ContractError::StageNotStarted { stage_name: String::from("bid") },
+        err.downcast().unwrap()
+    );
 
-    // Claim more than once the prize is not allowed
-    let claim_prize_msg = ExecuteMsg::ClaimPrize {};
+    // Once the bid stage starts the stages are final.
+    set_height(&mut router, 300_000);
     let err = router
         .execute_contract(
-            address_1.clone(),
+            Addr::unchecked("owner0000"),
             game_addr.clone(),
-            &claim_prize_msg,
+            &update_stages_msg(400_000, 401_000, 402_000),
             &[],
         ).unwrap_err();
-    
-    assert_eq!(ContractError::AlreadyClaimed {}, err.downcast().unwrap());
+    assert_eq!(
+        ContractError::StageStarted { stage_name: String::from("bid") },
+        err.downcast().unwrap()
+    );
 }
 
-// ======================================================================================
-// Withdraws
-// ======================================================================================
 #[test]
-fn withdraw_airdrop_and_prize() {
+fn extend_stage() {
     let mut router = mock_app();
-    let (native_token_denom, owner,ticket_price, bins, funds) = global_variables();
-
-    let test_data_airdrop: Encoded = from_slice(TEST_DATA_AIRDROP).unwrap();
-    let test_data_game: Encoded = from_slice(TEST_DATA_GAME).unwrap();
-
-    let address_1 = Addr::unchecked(test_data_airdrop.addresses[0].account.to_string());
-    let address_2 = Addr::unchecked(test_data_airdrop.addresses[1].account.to_string());
-    let address_3 = Addr::unchecked(test_data_airdrop.addresses[2].account.to_string());
-
-    // Assign native token to owner and the two addresses
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &owner, funds.clone()).unwrap()
-    });
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &address_1, funds.clone()).unwrap()
-    });
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &address_2, funds.clone()).unwrap()
-    });
-    router.borrow_mut().init_modules(|router, _, storage| {
-        router.bank.init_balance(storage, &address_3, funds.clone()).unwrap()
-    });
-
-    // Create the game token contract.
-    let cw20_token = create_cw20(
-        &mut router,
-        &owner,
-        "token".to_string(),
-        "CWTOKEN".to_string(),
-        Uint128::new(1_000_000_000)
-    );
-
+    let (_, _, ticket_price, bins, _) = global_variables();
     let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Create the game contract.
-    let cw20_token_address = Some(cw20_token.addr().to_string()).unwrap();
-    let game_addr = create_game(
-        &mut router,
-        &owner,
+    let msg = game_instantiate_msg(
         ticket_price,
         bins,
-        stage_bid.clone(),
-        stage_claim_airdrop.clone(),
-        stage_claim_prize.clone(),
-        Some(cw20_token_address.clone()),
-    ).unwrap();
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
 
-    // Register Merkle roots.
-    let register_merkle_root_msg = ExecuteMsg::RegisterMerkleRoots {
-        merkle_root_airdrop: test_data_airdrop.root,
-        total_amount_airdrop: Some(Uint128::new(1_000)),
-        merkle_root_game: test_data_game.root,
-        total_amount_game: Some(Uint128::new(1_000_000)),
+    let extend_stage_msg = |stage: ClaimStage, extra_duration: Duration| ExecuteMsg::ExtendStage {
+        stage,
+        extra_duration,
+        round: None,
     };
-    let _res = router
+    let owner = Addr::unchecked("owner0000");
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+
+    // Just an active stage can be extended.
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            game_addr.clone(),
+            &extend_stage_msg(ClaimStage::ClaimAirdrop, Duration::Height(10)),
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::StageNotStarted { stage_name: String::from("claim airdrop") },
+        err.downcast().unwrap()
+    );
+
+    // Just the owner can extend the stages, by a non zero duration.
+    set_height(&mut router, 201_001);
+    let err = router
         .execute_contract(
-            Addr::unchecked("owner0000"),
+            players[0].clone(),
             game_addr.clone(),
-            &register_merkle_root_msg,
+            &extend_stage_msg(ClaimStage::ClaimAirdrop, Duration::Height(10)),
             &[],
-        ).unwrap();
+        ).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
 
-    // Transfer token to: 
-    // The game contract
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: game_addr.clone().into(),amount: Uint128::new(1_001_000)};
-    let _res = router
+    let err = router
         .execute_contract(
             owner.clone(),
-            Addr::unchecked(cw20_token_address.clone()),
-            &send_token_msg,
+            game_addr.clone(),
+            &extend_stage_msg(ClaimStage::ClaimAirdrop, Duration::Height(0)),
             &[],
-        ).unwrap();
-    // The first address
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_1.clone().to_string(), amount: Uint128::new(1_000)};
-    let _res = router
+        ).unwrap_err();
+    assert_eq!(ContractError::InvalidExtraDuration {}, err.downcast().unwrap());
+
+    // The claim airdrop stage cannot overlap the claim prize stage.
+    let err = router
         .execute_contract(
             owner.clone(),
-            Addr::unchecked(cw20_token_address.clone()),
-            &send_token_msg,
+            game_addr.clone(),
+            &extend_stage_msg(ClaimStage::ClaimAirdrop, Duration::Height(999)),
             &[],
-        ).unwrap();
-    // The second address
-    let send_token_msg = cw20::Cw20ExecuteMsg::Transfer {recipient: address_2.clone().to_string(), amount: Uint128::new(100)};
-    let _res = router
+        ).unwrap_err();
+    let first = String::from("claim aidrop");
+    let second = String::from("Claim prize");
+    assert_eq!(ContractError::StagesOverlap { first, second }, err.downcast().unwrap());
+
+    router
         .execute_contract(
             owner.clone(),
-            Addr::unchecked(cw20_token_address.clone()),
-            &send_token_msg,
+            game_addr.clone(),
+            &extend_stage_msg(ClaimStage::ClaimAirdrop, Duration::Height(998)),
             &[],
         ).unwrap();
+    let stages = get_stages(&router, &game_addr);
+    assert_eq!(stages.stage_claim_airdrop.duration, Duration::Height(1_000));
 
-    // Trigger bid stage start.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 200_001, time: current_block.time, chain_id: current_block.chain_id});
+    // The airdrop can be claimed after the original end of the stage.
+    set_height(&mut router, 201_999);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
 
-    // Address 1 winning bid.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
-    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+    // The extra duration has to be of the same kind as the stage duration.
+    set_height(&mut router, 202_001);
+    router
         .execute_contract(
-            address_1.clone(),
+            owner.clone(),
             game_addr.clone(),
-            &bid_msg,
-            &[bid.clone()],
-        ).unwrap();
-
-    // Address 2 losing bid.
-    let bid_msg = ExecuteMsg::Bid { bin: 1 };
-    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+            &extend_stage_msg(ClaimStage::ClaimPrize, Duration::Time(60)),
+            &[],
+        ).unwrap_err();
+    router
         .execute_contract(
-            address_2.clone(),
+            owner,
             game_addr.clone(),
-            &bid_msg,
-            &[bid.clone()],
+            &extend_stage_msg(ClaimStage::ClaimPrize, Duration::Height(10)),
+            &[],
         ).unwrap();
+    let stages = get_stages(&router, &game_addr);
+    assert_eq!(stages.stage_claim_prize.duration, Duration::Height(12));
 
-    // Address 3 winning bid.
-    let bid_msg = ExecuteMsg::Bid { bin: 10 };
-    let bid = Coin {denom: native_token_denom.clone().into(),amount: Uint128::new(10)};
-    let _res = router
+    set_height(&mut router, 202_005);
+    router
         .execute_contract(
-            address_3.clone(),
+            players[0].clone(),
             game_addr.clone(),
-            &bid_msg,
-            &[bid.clone()],
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
+            &[],
         ).unwrap();
+}
 
-    // Trigger claiming airdrop stage.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 201_001,time: current_block.time,chain_id: current_block.chain_id});
+#[test]
+fn treasury_buckets() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Address 1 claim the correct ammount and verify balances and winners numbers.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[0].amount,
-        proof_airdrop: test_data_airdrop.addresses[0].proofs.clone(),
-        proof_game: test_data_game.addresses[0].proofs.clone()
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, cw20_token, players) = setup_claim_game(&mut router, msg);
+    let token = cw20_token.addr().to_string();
+    let treasury = |router: &App, round: Option<u64>| -> Vec<BucketBalance> {
+        let res: TreasuryResponse = router
+            .wrap()
+            .query_wasm_smart(&game_addr, &QueryMsg::Treasury { round })
+            .unwrap();
+        res.buckets
     };
-    let _res = router
+    let balance = |bucket: Bucket, asset: &str, amount: u128| BucketBalance {
+        bucket,
+        asset: asset.to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    // The airdrop amounts can be earmarked only once.
+    let err = router
         .execute_contract(
-            address_1.clone(),
+            Addr::unchecked("owner0000"),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &ExecuteMsg::RegisterMerkleRoots {
+                merkle_root_airdrop: get_merkle_roots(&router, &game_addr).merkle_root_airdrop,
+                total_amount_airdrop: Some(Uint128::new(1)),
+                merkle_root_game: get_merkle_roots(&router, &game_addr).merkle_root_game.unwrap(),
+                total_amount_game: None,
+                expected_recipients: None,
+                prize_tokens: None,
+                round: None,
+            },
             &[],
-        ).unwrap();
+        ).unwrap_err();
+    assert_eq!(ContractError::MerkleRootsAlreadyRegistered {}, err.downcast().unwrap());
 
-    // Address 2 claim the correct ammount and verify balances and winners numbers.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[1].amount,
-        proof_airdrop: test_data_airdrop.addresses[1].proofs.clone(),
-        proof_game: test_data_game.addresses[1].proofs.clone()
-    };
-    let _res = router
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+
+    assert_eq!(
+        treasury(&router, None),
+        vec![
+            balance(Bucket::TicketPot, &native_token_denom, 30),
+            balance(Bucket::AirdropReserve, &token, 11_330),
+            balance(Bucket::GameReserve, &token, 1_000_000),
+        ]
+    );
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    // Without a charity, the donated half of the prize is kept for the next game.
+    set_height(&mut router, 202_001);
+    router
         .execute_contract(
-            address_2.clone(),
+            players[0].clone(),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &ExecuteMsg::DonatePrize { share_bps: 5_000, round: None },
             &[],
         ).unwrap();
 
-    // Address 3 claim the correct ammount and verify balances and winners numbers.
-    let claim_airdrop_msg = ExecuteMsg::ClaimAirdrop {
-        amount: test_data_airdrop.addresses[2].amount,
-        proof_airdrop: test_data_airdrop.addresses[2].proofs.clone(),
-        proof_game: test_data_game.addresses[2].proofs.clone()
+    assert_eq!(
+        treasury(&router, None),
+        vec![
+            balance(Bucket::TicketPot, &native_token_denom, 15),
+            balance(Bucket::GameReserve, &token, 500_000),
+            balance(Bucket::Rollover, &token, 250_000),
+            balance(Bucket::Rollover, &native_token_denom, 7),
+        ]
+    );
+
+    // The leftovers are withdrawn once, the rollover is not withdrawn.
+    set_height(&mut router, 203_001);
+    let withdraw_address = Addr::unchecked("withdraw0000");
+    for _ in 0..2 {
+        for msg in [
+            ExecuteMsg::WithdrawPrize { address: withdraw_address.clone(), round: None },
+            ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone(), round: None },
+        ] {
+            router
+                .execute_contract(Addr::unchecked("owner0000"), game_addr.clone(), &msg, &[])
+                .unwrap();
+        }
+    }
+    let bank_balance_withdraw = bank_balance(&mut router, &withdraw_address, native_token_denom.clone());
+    let balance_withdraw = cw20_token
+        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address)
+        .unwrap();
+    assert_eq!(bank_balance_withdraw.amount, Uint128::new(15));
+    assert_eq!(balance_withdraw, Uint128::new(500_000));
+    assert_eq!(
+        treasury(&router, None),
+        vec![
+            balance(Bucket::Rollover, &token, 250_000),
+            balance(Bucket::Rollover, &native_token_denom, 7),
+        ]
+    );
+
+    // The rollover is added to the prizes of the next round.
+    let stage = |start: u64| Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
     };
-    let _res = router
+    router
         .execute_contract(
-            address_3.clone(),
+            Addr::unchecked("owner0000"),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &ExecuteMsg::StartNewRound {
+                ticket_price,
+                bins,
+                stage_bid: stage(210_000),
+                stage_claim_airdrop: stage(211_000),
+                stage_claim_prize: stage(212_000),
+                first_bidder_bonus: None,
+                max_winners: None,
+                min_participants: None,
+                price_schedule: None,
+                stage_reveal: None,
+            },
             &[],
         ).unwrap();
 
-    // Trigger claim prize stage start.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 202_001, time: current_block.time, chain_id: current_block.chain_id});
+    assert_eq!(
+        treasury(&router, None),
+        vec![
+            balance(Bucket::TicketPot, &native_token_denom, 7),
+            balance(Bucket::GameReserve, &token, 250_000),
+        ]
+    );
+    assert_eq!(treasury(&router, Some(1)), vec![]);
+}
 
-    // Can claim prize if winning bid.
-    let claim_prize_msg = ExecuteMsg::ClaimPrize {};
-    let _res = router
-        .execute_contract(
-            address_1.clone(),
-            game_addr.clone(),
-            &claim_prize_msg,
-            &[],
-        ).unwrap();
+// ======================================================================================
+// Rounds
+// ======================================================================================
+#[test]
+fn start_new_round() {
+    let mut router = mock_app();
+    let (_, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
 
-    // Verify claimed amounts
-    let info = get_game_amount(&router, &game_addr);
+    let msg = game_instantiate_msg(
+        ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        None,
+    );
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
 
-    assert_eq!(info.total_ticket_prize, Uint128::new(30));
-    assert_eq!(info.total_airdrop_amount, Uint128::new(1000));
-    assert_eq!(info.total_airdrop_game_amount, Uint128::new(1000000));
-    assert_eq!(info.total_claimed_airdrop, Uint128::new(511330));
-    assert_eq!(info.total_claimed_prize, Uint128::new(15));
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
 
-    let withdraw_address = Addr::unchecked("withdraw0000");
+    let round: RoundResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Round {})
+        .unwrap();
+    assert_eq!(round.round, 1);
 
-    // Just the owner can withdraw.
-    let claim_airdrop_msg = ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone() };
+    let stage = |start: u64| Stage {
+        start: Scheduled::AtHeight(start),
+        duration: Duration::Height(2),
+    };
+    let new_round_msg = |stage_bid: Stage| ExecuteMsg::StartNewRound {
+        ticket_price: ticket_price.clone(),
+        bins,
+        stage_bid,
+        stage_claim_airdrop: stage(211_000),
+        stage_claim_prize: stage(212_000),
+        first_bidder_bonus: None,
+        max_winners: None,
+        min_participants: None,
+        price_schedule: None,
+        stage_reveal: None,
+    };
+
+    // Just the owner can start a new round.
     let err = router
         .execute_contract(
-            address_1.clone(),
+            players[0].clone(),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &new_round_msg(stage(210_000)),
             &[],
         ).unwrap_err();
-
     assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
 
-    // Cannot withdraw if claim prize stage not ended.
-    let claim_airdrop_msg = ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone() };
+    // The new bid stage cannot overlap the previous one.
     let err = router
         .execute_contract(
             Addr::unchecked("owner0000"),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &new_round_msg(stage(200_001)),
             &[],
         ).unwrap_err();
+    let first = String::from("previous round bid");
+    let second = String::from("Bid");
+    assert_eq!(ContractError::StagesOverlap { first, second }, err.downcast().unwrap());
 
-    assert_eq!(ContractError::ClaimPrizeStageNotFinished {}, err.downcast().unwrap());
+    router
+        .execute_contract(
+            Addr::unchecked("owner0000"),
+            game_addr.clone(),
+            &new_round_msg(stage(210_000)),
+            &[],
+        ).unwrap();
 
-    // Check withdrawing address empty
-    let balance_withdraw = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address.clone())
+    let round: RoundResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Round {})
         .unwrap();
-    let bank_balance_withdraw: Coin = bank_balance(&mut router, &withdraw_address, native_token_denom.clone().to_string());
+    assert_eq!(round.round, 2);
+    assert_eq!(get_stages(&router, &game_addr).stage_bid.start, Scheduled::AtHeight(210_000));
 
-    assert_eq!(balance_withdraw, Uint128::new(0));
-    assert_eq!(bank_balance_withdraw.amount, Uint128::new(0));
-    
-    // Trigger claim prize stage end.
-    let current_block = router.block_info();
-    router.set_block(BlockInfo {height: 203_001, time: current_block.time, chain_id: current_block.chain_id});
+    // Bids of the previous round are kept, the new round starts empty.
+    let bid: BidResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &game_addr,
+            &QueryMsg::Bid { address: players[0].to_string(), round: Some(1) },
+        )
+        .unwrap();
+    assert_eq!(bid.bid, Some(1));
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, None);
 
-    // Check withdraw leftover airdrop.
-    let claim_airdrop_msg = ExecuteMsg::WithdrawAirdrop { address: withdraw_address.clone() };
-    let _res = router
+    set_height(&mut router, 210_001);
+    place_bid(&mut router, &game_addr, &players[0], 5).unwrap();
+    assert_eq!(get_bid(&router, &game_addr, players[0].to_string()).bid, Some(5));
+
+    // Rounds not started yet cannot be used.
+    router
         .execute_contract(
-            Addr::unchecked("owner0000"),
+            players[1].clone(),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &ExecuteMsg::Bid { bin: 1, tickets: None, recipient: None, allowlist_proof: None, coupon: None, referrer: None, prediction: None, round: Some(3) },
+            &[ticket_price],
+        ).unwrap_err();
+}
+
+#[test]
+fn game_amounts_from_treasury() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    assert_eq!(get_game_amount(&router, &game_addr).total_ticket_prize, Uint128::new(30));
+
+    set_height(&mut router, 201_001);
+    for index in 0..3 {
+        claim_airdrop_at(&mut router, &game_addr, index).unwrap();
+    }
+
+    // Without a charity, the donation is kept for the next game: it leaves the prize of
+    // the round without being claimed.
+    set_height(&mut router, 202_001);
+    router
+        .execute_contract(
+            players[0].clone(),
+            game_addr.clone(),
+            &ExecuteMsg::DonatePrize { share_bps: 5_000, round: None },
             &[],
         ).unwrap();
-    let balance_withdraw = cw20_token
-        .balance::<App, Addr, MyCustomQuery>(&router, withdraw_address.clone())
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_donated_prize, Uint128::new(7));
+    assert_eq!(info.total_claimed_prize, Uint128::new(8));
+    assert_eq!(info.total_ticket_prize, Uint128::new(23));
+
+    // The amounts reported match the buckets of the treasury.
+    let treasury: TreasuryResponse = router
+        .wrap()
+        .query_wasm_smart(&game_addr, &QueryMsg::Treasury { round: None })
         .unwrap();
-    
-    assert_eq!(balance_withdraw, Uint128::new(489670));
+    let balance = |bucket: Bucket| {
+        treasury
+            .buckets
+            .iter()
+            .find(|b| b.bucket == bucket && b.asset == native_token_denom)
+            .map_or(Uint128::zero(), |b| b.amount)
+    };
+    assert_eq!(balance(Bucket::Rollover), Uint128::new(7));
+    assert_eq!(balance(Bucket::TicketPot), info.total_ticket_prize - info.total_claimed_prize);
 
-    // Check withdraw leftover prize.
-    let claim_airdrop_msg = ExecuteMsg::WithdrawPrize { address: withdraw_address.clone() };
-    let _res = router
+    // Once every winner has claimed, the prize is what was claimed and the dust.
+    router
         .execute_contract(
-            Addr::unchecked("owner0000"),
+            players[2].clone(),
             game_addr.clone(),
-            &claim_airdrop_msg,
+            &ExecuteMsg::ClaimPrize { recipient: None, stake: None, round: None },
             &[],
         ).unwrap();
-    let bank_balance_withdraw: Coin = bank_balance(&mut router, &withdraw_address, native_token_denom.clone().to_string());
+    let info = get_game_amount(&router, &game_addr);
+    assert_eq!(info.total_claimed_prize, Uint128::new(23));
+    assert_eq!(info.total_ticket_prize, info.total_claimed_prize + info.total_dust_prize);
+}
 
-    assert_eq!(bank_balance_withdraw.amount, Uint128::new(15));
-}
\ No newline at end of file
+#[test]
+fn blocked_consolation_claimer() {
+    let mut router = mock_app();
+    let (native_token_denom, _, ticket_price, bins, _) = global_variables();
+    let (stage_bid, stage_claim_airdrop, stage_claim_prize) = valid_stages();
+
+    let mut msg = game_instantiate_msg(ticket_price, bins, stage_bid, stage_claim_airdrop, stage_claim_prize, None);
+    msg.max_winners = Some(1);
+    let (game_addr, _, players) = setup_claim_game(&mut router, msg);
+    let game_owner = Addr::unchecked("owner0000");
+    let update_blocklist = |add: Vec<&Addr>, remove: Vec<&Addr>| ExecuteMsg::UpdateBlocklist {
+        add: add.into_iter().map(Addr::to_string).collect(),
+        remove: remove.into_iter().map(Addr::to_string).collect(),
+    };
+
+    // Address 1 is left out of the winners and gets a consolation.
+    set_height(&mut router, 200_000);
+    place_bid(&mut router, &game_addr, &players[2], 10).unwrap();
+    set_height(&mut router, 200_001);
+    place_bid(&mut router, &game_addr, &players[0], 1).unwrap();
+    place_bid(&mut router, &game_addr, &players[1], 1).unwrap();
+    set_height(&mut router, 201_001);
+    claim_airdrop_at(&mut router, &game_addr, 0).unwrap();
+    claim_airdrop_at(&mut router, &game_addr, 2).unwrap();
+
+    // Neither a blocked claimer nor a blocked recipient can take the consolation.
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![&players[0], &players[1]], vec![]), &[])
+        .unwrap();
+    set_height(&mut router, 202_001);
+    let claim_msg = |recipient: Option<&Addr>| ExecuteMsg::ClaimPrize {
+        recipient: recipient.map(Addr::to_string),
+        stake: None,
+        round: None,
+    };
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &claim_msg(None), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AddressBlocked { address: players[0].to_string() },
+        err.downcast().unwrap()
+    );
+    router
+        .execute_contract(game_owner.clone(), game_addr.clone(), &update_blocklist(vec![], vec![&players[0]]), &[])
+        .unwrap();
+    let err = router
+        .execute_contract(players[0].clone(), game_addr.clone(), &claim_msg(Some(&players[1])), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AddressBlocked { address: players[1].to_string() },
+        err.downcast().unwrap()
+    );
+
+    // Once unblocked, the ticket is paid back.
+    router
+        .execute_contract(players[0].clone(), game_addr.clone(), &claim_msg(None), &[])
+        .unwrap();
+    let balance = bank_balance(&mut router, &players[0], native_token_denom);
+    assert_eq!(balance.amount, Uint128::new(1_000_000));
+}
@@ -0,0 +1,91 @@
+use cosmwasm_std::{Addr, CustomQuery, QuerierWrapper, StdResult};
+
+use crate::msg::{BidResponse, GameStatusResponse, QueryMsg, RoundResponse, StagesResponse};
+use crate::state::{CLAIM_AIRDROP, CLAIM_PRIZE};
+
+/// Minimal query interface of the game, for contracts that integrate with it
+/// (e.g. a rewards booster checking if an address won). Queries refer to the current
+/// round of the game.
+pub trait WasmGameQuery {
+    /// Returns the current round of the game.
+    fn round(&self) -> StdResult<u64>;
+
+    /// Returns the stages of the game.
+    fn stages(&self) -> StdResult<StagesResponse>;
+
+    /// Returns the phase of the game at the current block.
+    fn game_status(&self) -> StdResult<GameStatusResponse>;
+
+    /// Returns the bin chosen by the address, if any.
+    fn bid(&self, address: &Addr) -> StdResult<Option<u8>>;
+
+    /// Returns true if the address has been verified as a winner of the game.
+    fn is_winner(&self, address: &Addr) -> StdResult<bool>;
+
+    /// Returns true if the address has claimed the airdrop.
+    fn airdrop_claimed(&self, address: &Addr) -> StdResult<bool>;
+
+    /// Returns true if the address has claimed the game prize.
+    fn prize_claimed(&self, address: &Addr) -> StdResult<bool>;
+}
+
+/// Client to query a deployed game contract.
+pub struct WasmGameQuerier<'a, C: CustomQuery> {
+    querier: &'a QuerierWrapper<'a, C>,
+    contract: Addr,
+}
+
+impl<'a, C: CustomQuery> WasmGameQuerier<'a, C> {
+    pub fn new(querier: &'a QuerierWrapper<'a, C>, contract: Addr) -> Self {
+        Self { querier, contract }
+    }
+}
+
+impl<'a, C: CustomQuery> WasmGameQuery for WasmGameQuerier<'a, C> {
+    fn round(&self) -> StdResult<u64> {
+        let res: RoundResponse = self
+            .querier
+            .query_wasm_smart(&self.contract, &QueryMsg::Round {})?;
+        Ok(res.round)
+    }
+
+    fn stages(&self) -> StdResult<StagesResponse> {
+        self.querier
+            .query_wasm_smart(&self.contract, &QueryMsg::Stages { round: None })
+    }
+
+    fn game_status(&self) -> StdResult<GameStatusResponse> {
+        self.querier
+            .query_wasm_smart(&self.contract, &QueryMsg::GameStatus { round: None })
+    }
+
+    fn bid(&self, address: &Addr) -> StdResult<Option<u8>> {
+        let res: BidResponse = self.querier.query_wasm_smart(
+            &self.contract,
+            &QueryMsg::Bid {
+                address: address.to_string(),
+                round: None,
+            },
+        )?;
+        Ok(res.bid)
+    }
+
+    fn is_winner(&self, address: &Addr) -> StdResult<bool> {
+        // Winners are saved with an unclaimed prize when they claim the airdrop.
+        let key = (self.round()?, address);
+        let claim = CLAIM_PRIZE.query(self.querier, self.contract.clone(), key)?;
+        Ok(claim.is_some())
+    }
+
+    fn airdrop_claimed(&self, address: &Addr) -> StdResult<bool> {
+        let key = (self.round()?, address);
+        let claim = CLAIM_AIRDROP.query(self.querier, self.contract.clone(), key)?;
+        Ok(claim.unwrap_or(false))
+    }
+
+    fn prize_claimed(&self, address: &Addr) -> StdResult<bool> {
+        let key = (self.round()?, address);
+        let claim = CLAIM_PRIZE.query(self.querier, self.contract.clone(), key)?;
+        Ok(claim.unwrap_or(false))
+    }
+}
@@ -1,6 +1,20 @@
+pub mod compat;
 pub mod contract;
+pub mod cw721;
 mod error;
+pub mod events;
+pub mod helpers;
+pub mod ibc;
+pub mod interface;
+pub mod merkle;
 pub mod msg;
+pub mod nois;
+pub mod oracle;
+pub mod shares;
 pub mod state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+pub mod tokenfactory;
+pub mod treasury;
 mod integration_tests;
 pub use crate::error::ContractError;
@@ -0,0 +1,100 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::convert::TryInto;
+
+use crate::ContractError;
+
+// ======================================================================================
+// Merkle proofs
+// ======================================================================================
+// The leaves are the hash of a string, such as the address followed by the amount, and
+// each pair of hashes is sorted before being hashed, so that the proofs do not need to
+// tell the side of the siblings.
+
+/// Hash function of the Merkle trees of the game, selected at instantiation.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    /// Keccak-256, as the trees of the Ethereum airdrop tooling.
+    Keccak256,
+}
+
+impl HashAlgorithm {
+    /// Returns the hash of the data.
+    pub fn hash(&self, data: &[u8]) -> Result<[u8; 32], ContractError> {
+        let hash = match self {
+            HashAlgorithm::Sha256 => sha2::Sha256::digest(data).to_vec(),
+            HashAlgorithm::Keccak256 => sha3::Keccak256::digest(data).to_vec(),
+        };
+        hash.as_slice().try_into().map_err(|_| ContractError::WrongLength {})
+    }
+}
+
+/// Returns true if the hex encoded proof links the leaf to the hex encoded Merkle root.
+pub fn merkle_root_matches(
+    algorithm: HashAlgorithm,
+    leaf: &str,
+    proof: Vec<String>,
+    merkle_root: &str,
+) -> Result<bool, ContractError> {
+    let hash = algorithm.hash(leaf.as_bytes())?;
+
+    let hash = proof.into_iter().try_fold(hash, |hash, p| {
+        let mut proof_buf = [0; 32];
+        hex::decode_to_slice(p, &mut proof_buf)?;
+        let mut hashes = [hash, proof_buf];
+        hashes.sort_unstable();
+        algorithm.hash(&hashes.concat())
+    })?;
+
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(merkle_root, &mut root_buf)?;
+    Ok(root_buf == hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the root of the tree of two leaves and the proof of the first one.
+    fn two_leaves_tree(algorithm: HashAlgorithm, leaf: &str, other: &str) -> (String, Vec<String>) {
+        let mut hashes = [algorithm.hash(leaf.as_bytes()).unwrap(), algorithm.hash(other.as_bytes()).unwrap()];
+        let proof = vec![hex::encode(hashes[1])];
+        hashes.sort_unstable();
+        (hex::encode(algorithm.hash(&hashes.concat()).unwrap()), proof)
+    }
+
+    #[test]
+    fn hashes_with_the_algorithm() {
+        assert_eq!(
+            hex::encode(HashAlgorithm::Sha256.hash(b"").unwrap()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(HashAlgorithm::Keccak256.hash(b"").unwrap()),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(HashAlgorithm::default(), HashAlgorithm::Sha256);
+    }
+
+    #[test]
+    fn verifies_the_proofs_of_the_algorithm() {
+        for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Keccak256] {
+            let (root, proof) = two_leaves_tree(algorithm, "wasm1a10", "wasm1b3");
+            assert!(merkle_root_matches(algorithm, "wasm1a10", proof.clone(), &root).unwrap());
+            assert!(!merkle_root_matches(algorithm, "wasm1a11", proof, &root).unwrap());
+        }
+
+        // A tree of one algorithm is not verified with the other one.
+        let (root, proof) = two_leaves_tree(HashAlgorithm::Keccak256, "wasm1a10", "wasm1b3");
+        assert!(!merkle_root_matches(HashAlgorithm::Sha256, "wasm1a10", proof.clone(), &root).unwrap());
+
+        // The proofs and the root must be hex encoded hashes.
+        let err = merkle_root_matches(HashAlgorithm::Keccak256, "wasm1a10", vec!["zz".to_string()], &root);
+        assert!(matches!(err, Err(ContractError::Hex(_))));
+        assert!(merkle_root_matches(HashAlgorithm::Keccak256, "wasm1a10", proof, "00").is_err());
+    }
+}
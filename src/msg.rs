@@ -1,8 +1,13 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::Stage;
-use cosmwasm_std::{Addr, Uint128, Coin};
+use crate::state::{AntiSnipe, DustPolicy, LoyaltyTier, Notice, PausedBy, PriceStep, SeasonPass, Stage};
+use crate::merkle::HashAlgorithm;
+use crate::nois::NoisCallback;
+use crate::treasury::Bucket;
+use cosmwasm_std::{Addr, Binary, Uint128, Coin, Decimal};
+use cw_utils::{Duration, Expiration, Scheduled};
+use cw20::Cw20ReceiveMsg;
 
 // ======================================================================================
 // Entrypoints data structures
@@ -15,44 +20,480 @@ pub struct InstantiateMsg {
     pub cw20_token_address: String,
     /// Price of the ticket to bid.
     pub ticket_price: Coin,
+    /// Steps changing the ticket price as the bid stage progresses, ordered by start.
+    /// The ticket price applies until the first step starts.
+    pub price_schedule: Option<Vec<PriceStep>>,
     /// The winning probability is associasted to the number of bins.
     pub bins: u8,
     /// Info related to the bidding stage.
     pub stage_bid: Stage,
+    /// Info related to the reveal stage. If set, bids are committed as hashes during
+    /// the bidding stage and revealed during this stage.
+    pub stage_reveal: Option<Stage>,
     /// Info related to the airdrop claiming stage.
     pub stage_claim_airdrop: Stage,
     /// Info related to the prize claiming stage.
     pub stage_claim_prize: Stage,
+    /// Bonus taken from the protocol fee and paid to the earliest bid on the winning
+    /// bin when the round is finalized. Ties on the bid height are broken by address
+    /// order. The bonus is capped by the protocol fee of the round, so a non-zero bonus
+    /// requires `protocol_fee_bps` to be set, otherwise the message fails with
+    /// `FirstBidderBonusWithoutFee`.
+    pub first_bidder_bonus: Option<Uint128>,
+    /// Maximum number of winners sharing the prize, the earliest winning bids. The
+    /// other winners get back their ticket. If none set, the winners are not capped.
+    pub max_winners: Option<u32>,
+    /// Minimum number of bidders for the game to be played. If the bid stage ends with
+    /// fewer bidders, the claims are disabled and the bidders get back their ticket.
+    pub min_participants: Option<u32>,
+    /// Address receiving the donated prizes. If none set, donations are kept in the
+    /// contract for the next game.
+    pub charity_address: Option<String>,
+    /// Expected bech32 prefix (e.g. "juno") of the externally supplied addresses.
+    /// If none set, addresses are not checked against a prefix.
+    pub address_prefix: Option<String>,
+    /// Token used to pay the tickets, by sending it to the contract with a
+    /// `ReceiveMsg::Bid`. If none set, tickets are paid with the native denom of
+    /// the ticket price.
+    pub ticket_cw20_address: Option<String>,
+    /// Maximum size, in bytes, of the JSON encoded proofs sent to claim the airdrop.
+    /// Default to 4096 bytes if none set.
+    pub max_claim_size: Option<u32>,
+    /// Maximum number of bids of each round. If none set, the bids are not limited.
+    pub max_bids: Option<u32>,
+    /// Maximum number of bids on a single bin, to keep the bins balanced. If none set,
+    /// the bins are not limited.
+    pub max_per_bin: Option<u32>,
+    /// If true, bidders can stake any amount above the tickets price and the winners
+    /// split the prize proportionally to their stake. Default to false if none set.
+    pub parimutuel: Option<bool>,
+    /// Extension of the bid stages scheduled at a height when bids arrive in their
+    /// last blocks. If none set, the bid stages are never extended.
+    pub anti_snipe: Option<AntiSnipe>,
+    /// Part of the stake, in basis points, retained in the tickets prize when a bid is
+    /// removed, so that bids cannot be probed for free. Default to 0 if none set.
+    pub remove_bid_penalty_bps: Option<u16>,
+    /// Maximum number of times a bid can be changed. If none set, the changes are not
+    /// limited.
+    pub max_bid_changes: Option<u32>,
+    /// Native fee paid with each `ChangeBid`. If none set, the changes are free.
+    pub bid_change_fee: Option<Coin>,
+    /// Native denom, such as a TokenFactory denom, paying the prize of the winners
+    /// instead of the cw20 token. If none set, the prize is paid with the cw20 token.
+    pub prize_denom: Option<String>,
+    /// Duration over which the share of the airdrop won vests linearly from the claim of
+    /// the prize, released with `ClaimVested`. If none set, it is paid with the claim.
+    pub prize_vesting: Option<Duration>,
+    /// Staking contract the winners can deliver the cw20 part of their prize to, with a
+    /// `StakingReceiveMsg`, by claiming it with `stake`.
+    pub staking_contract: Option<String>,
+    /// Grace period after a jackpot is rolled over, without a new round started, after
+    /// which the owner can withdraw the rollover. If none set, the rollover is kept for
+    /// the next round.
+    pub rollover_grace_period: Option<Duration>,
+    /// Bounty, in the ticket asset, paid once per call from the tickets prize to whoever
+    /// calls `FinalizeGame`, `RedistributeUnclaimed` or `RefundBids`. The bounty of the
+    /// refunds is only paid from the part of the tickets prize not owed back to the
+    /// bidders. If none set, no bounty is paid.
+    pub crank_bounty: Option<Uint128>,
+    /// Destination of the dust left by rounding down the shares of the tickets prize and
+    /// of the airdrop, disposed of with the last prize claim. If none set, the dust is
+    /// withdrawn by the owner with the leftovers.
+    pub dust_policy: Option<DustPolicy>,
+    /// Part of the tickets prize, in basis points, sent to the `fee_collector` when the
+    /// round is finalized, before the prize is shared. Default to 0 if none set.
+    pub protocol_fee_bps: Option<u16>,
+    /// Address receiving the protocol fee, required when the fee is set without a
+    /// `revenue_split`.
+    pub fee_collector: Option<String>,
+    /// Destinations among which the withdraws of the owner and the protocol fee are
+    /// split, with their shares summing to 1. If none set, the withdraws are sent to the
+    /// given address and the protocol fee to the `fee_collector`.
+    pub revenue_split: Option<Vec<(String, Decimal)>>,
+    /// If true, the unclaimed airdrop and airdrop prize are burned with `BurnUnclaimed`
+    /// once the claim prize stage has ended, instead of being withdrawn by the owner.
+    /// Default to false if none set.
+    pub burn_unclaimed: Option<bool>,
+    /// Time from the start of the claim prize stage after which the winners can no
+    /// longer claim the prize, and the prizes not claimed are redistributed with
+    /// `RedistributeUnclaimed`. If none set, the winners can claim until the stage ends.
+    pub claim_deadline: Option<Duration>,
+    /// Hex encoded Merkle root of the addresses allowed to bid, for example the ones
+    /// eligible for the airdrop. If none set, anyone can bid.
+    pub merkle_root_allowlist: Option<String>,
+    /// Minimum balance of a cw20 token the players must hold to bid. If none set, the
+    /// bids are not gated.
+    pub bid_token_gate: Option<TokenGate>,
+    /// Address of a cw721 collection the players must hold a token of to bid. If none
+    /// set, the bids are not gated.
+    pub bid_nft_gate: Option<String>,
+    /// Part of the protocol fee, in basis points, paid to the referrers of the bids when
+    /// the round is finalized, in proportion to the stakes of the bids they referred.
+    /// Default to 0 if none set.
+    pub referral_fee_bps: Option<u16>,
+    /// Season pass sold with `BuySeasonPass`, paying up front the entries of a number of
+    /// rounds, in a native denom. If none set, no season pass is sold.
+    pub season_pass: Option<SeasonPass>,
+    /// Address of a cw721 collection, with the game as minter, receiving a receipt NFT
+    /// of each bid, burned when the bid is removed. If none set, no receipt is minted.
+    pub bid_receipts: Option<String>,
+    /// Address of a non transferable cw721 collection, with the game as minter,
+    /// receiving a badge recording the round and the winning bin of each prize claimed
+    /// with `ClaimPrize`. If none set, no badge is minted.
+    pub winner_badges: Option<String>,
+    /// Address allowed, besides the owner, to set the winning bin of the rounds with
+    /// `SetWinningBin`. If none set, only the owner can.
+    pub attestor: Option<String>,
+    /// Mechanism setting the result of the rounds, against which the claims are verified.
+    /// Default to `MerkleRoot` if none set.
+    pub resolution: Option<Resolution>,
+    /// Part of the tickets prize, in basis points, shared among the bidders on the bins
+    /// adjacent to the winning bin, as a consolation tier. Requires a resolution setting
+    /// the winning bin on chain. Default to 0 if none set.
+    pub adjacent_tier_bps: Option<u16>,
+    /// Ascending boundaries of the bins, one less than the bins, letting the bids carry
+    /// a prediction mapped to a bin: the first bin covers the predictions below the first
+    /// boundary, each next bin the predictions from its boundary. Every round must then
+    /// have this number of bins. If none set, the bids are placed on the bins only.
+    pub bin_boundaries: Option<Vec<Uint128>>,
+    /// IBC voucher denoms, `ibc/` followed by the hex hash of the denom trace, accepted
+    /// as ticket. A native ticket with an IBC denom must be in this list.
+    pub ibc_denoms: Option<Vec<String>>,
+    /// Port of the sender contract, on another chain, allowed to open a channel with the
+    /// game and place bids with IBC packets, the tickets being escrowed by the sender.
+    /// If none set, no channel can be opened.
+    pub ibc_bidder_port: Option<String>,
+    /// Hash function of the Merkle trees verified by the game, `keccak256` letting the
+    /// trees of the Ethereum airdrop tooling be reused. Default to `sha256` if none set.
+    pub hash_algorithm: Option<HashAlgorithm>,
+}
+
+/// Mechanism setting the result of the rounds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// The owner registers the game root with `RegisterGameRoot`, and the winners prove
+    /// their bins with `proof_game`.
+    MerkleRoot {},
+    /// The owner, or the attestor, sets the winning bin with `SetWinningBin`.
+    OwnerSetsBin {},
+    /// The winning bin is derived from the price of an asset with `ResolveGame`.
+    Oracle(PriceOracle),
+    /// The winning bin is drawn from the randomness of a nois-proxy requested with
+    /// `RequestRandomness`.
+    Randomness(Raffle),
+}
+
+/// Minimum balance of a cw20 token required to bid.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenGate {
+    /// Address of the cw20 token.
+    pub token: String,
+    pub min_balance: Uint128,
+}
+
+/// Price oracle and price ranges of the bins resolving the rounds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceOracle {
+    /// Address of the oracle contract.
+    pub contract: String,
+    pub asset: String,
+    /// Lowest price of the first bin, the lower prices falling in it as well.
+    pub min_price: Decimal,
+    /// Price range covered by each bin, the higher prices falling in the last one.
+    pub bin_width: Decimal,
+    /// Amount earmarked for the winners of each round resolved.
+    pub total_amount_game: Option<Uint128>,
+}
+
+/// Nois proxy and prize of the `Randomness` resolution.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Raffle {
+    /// Address of the nois-proxy contract.
+    pub nois_proxy: String,
+    /// Amount earmarked for the winners of each round drawn.
+    pub total_amount_game: Option<Uint128>,
+}
+
+/// Discount coupon of a player, proven against the coupon Merkle root of the round.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Coupon {
+    /// Discount on the tickets, in basis points.
+    pub discount_bps: u16,
+    /// Proof is hex-encoded merkle proof.
+    pub proof: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Update current contract configuration.
+    /// Update current contract configuration. Deprecated: use `UpdateOwnership`.
     UpdateConfig {
-        /// NewOwner if non sent, contract gets locked. Recipients can receive airdrops
-        /// but owner cannot register new stages.
+        /// Owner proposed as with `transfer_ownership`, without expiry, who has to accept
+        /// it. If none sent, the ownership is renounced and the contract gets locked.
         new_owner: Option<String>,
     },
+    /// Transfer the ownership of the contract in two steps: the owner proposes a new
+    /// owner, who accepts it. The owner can renounce it, locking the contract: recipients
+    /// can receive airdrops but nobody can register new stages.
+    UpdateOwnership(OwnershipAction),
+    /// Create the TokenFactory denom `factory/{contract}/{subdenom}` and make it the
+    /// prize of the winners, minted when the Merkle roots are registered (only owner).
+    /// Requires the `tokenfactory` feature.
+    CreatePrizeDenom {
+        subdenom: String,
+    },
+    /// Start a new round of the game, which becomes the current one.
+    StartNewRound {
+        /// Price of the ticket to bid.
+        ticket_price: Coin,
+        /// Steps changing the ticket price as the bid stage progresses.
+        price_schedule: Option<Vec<PriceStep>>,
+        /// The winning probability is associasted to the number of bins.
+        bins: u8,
+        /// Info related to the bidding stage.
+        stage_bid: Stage,
+        /// Info related to the reveal stage, for a round with committed bids.
+        stage_reveal: Option<Stage>,
+        /// Info related to the airdrop claiming stage.
+        stage_claim_airdrop: Stage,
+        /// Info related to the prize claiming stage.
+        stage_claim_prize: Stage,
+        /// Bonus taken from the protocol fee and paid to the earliest bid on the
+        /// winning bin. Capped by the protocol fee, a non-zero bonus fails with
+        /// `FirstBidderBonusWithoutFee` when no protocol fee is set.
+        first_bidder_bonus: Option<Uint128>,
+        /// Maximum number of winners sharing the prize.
+        max_winners: Option<u32>,
+        /// Minimum number of bidders for the round to be played.
+        min_participants: Option<u32>,
+    },
+    /// Replace the stages of the current round (only owner, before the bid stage
+    /// starts).
+    UpdateStages {
+        stage_bid: Stage,
+        stage_claim_airdrop: Stage,
+        stage_claim_prize: Stage,
+    },
+    /// Extend the duration of an active claim stage (only owner).
+    ExtendStage {
+        stage: ClaimStage,
+        /// Duration added to the stage, of the same kind as the stage duration.
+        extra_duration: Duration,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
     /// Place a bid.
     Bid {
-        /// bidding bin value
+        /// bidding bin value, 0 if a prediction is set
+        bin: u8,
+        /// Number of tickets bought on the bin, one if none set. Each ticket is worth a
+        /// share of the prize.
+        tickets: Option<u32>,
+        /// Address the bid is placed for, the sender if none set. The sender pays the
+        /// tickets and gets back the change.
+        recipient: Option<String>,
+        /// Proof that the player is in the allowlist, required if an allowlist is set.
+        allowlist_proof: Option<Vec<String>>,
+        /// Coupon discounting the tickets of the player, used once per round.
+        coupon: Option<Coupon>,
+        /// Address that referred the player, paid a cut of the protocol fee taken on
+        /// the stake of the bid.
+        referrer: Option<String>,
+        /// Predicted value, placing the bid on the bin covering it, if bin boundaries
+        /// are set.
+        prediction: Option<Uint128>,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Gift a bid to the recipient, that must not have a bid yet, paying its ticket.
+    GiftBid {
+        recipient: String,
+        bin: u8,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Place a bid on several bins, paying a ticket for each bin. A winning bin gives
+    /// a single share of the prize.
+    BidMultiple {
+        bins: Vec<u8>,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Commit a bid, paying the ticket, in a round with a reveal stage. The hash is
+    /// the hex encoded SHA-256 hash of the address, the round and the game address,
+    /// each followed by `|`, then of the bin as a single byte and a secret salt.
+    CommitBid {
+        hash: String,
+        /// Proof that the sender is in the allowlist, required if an allowlist is set.
+        allowlist_proof: Option<Vec<String>>,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Reveal the bin of a committed bid during the reveal stage. Bids not revealed
+    /// lose their ticket, which is kept in the prize.
+    RevealBid {
         bin: u8,
+        salt: String,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
     },
-    /// Change the value of a previously placed bid.
+    /// Place a bid paying the ticket with the cw20 token configured for tickets.
+    Receive(Cw20ReceiveMsg),
+    /// Perform several player actions atomically. The funds sent pay the tickets of
+    /// the bids in order and what is left is sent back.
+    BatchActions {
+        actions: Vec<PlayerAction>,
+    },
+    /// Change the value of a previously placed bid, paying the bid change fee if any.
     ChangeBid {
         /// input a value to change a previous bid
         bin: u8,
+        round: Option<u64>,
+    },
+    /// Move a previously placed bid to a new address without a bid, keeping its bins,
+    /// tickets and height.
+    TransferBid {
+        new_address: String,
+        round: Option<u64>,
     },
     /// Remove a previously placed bid.
-    RemoveBid {},
-    /// Register Merkle root in the contract.
+    RemoveBid {
+        round: Option<u64>,
+    },
+    /// Register the Merkle root of the (address, stake weight) pairs used to boost
+    /// the prize share of stakers (only owner, before the bid stage starts).
+    RegisterStakeRoot {
+        merkle_root_stake: String,
+        round: Option<u64>,
+    },
+    /// Register the Merkle root of the (address, discount) pairs of the ticket coupons
+    /// (only owner).
+    RegisterCouponRoot {
+        merkle_root_coupon: String,
+        round: Option<u64>,
+    },
+    /// Prove the stake weight of the sender, which multiplies its prize share weight.
+    /// Allowed during the bid stage once a bid is placed.
+    ProveStake {
+        weight: u64,
+        /// Proof is hex-encoded merkle proof.
+        proof: Vec<String>,
+        round: Option<u64>,
+    },
+    /// Pause the contract (only owner): every message is rejected, except the refunds of
+    /// the tickets, until unpaused.
+    Pause {},
+    /// Resume the contract paused by the owner (only owner).
+    Unpause {},
+    /// Add and remove addresses of the blocklist (only owner). The blocked addresses
+    /// can neither bid nor claim.
+    UpdateBlocklist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Add and remove IBC voucher denoms accepted as ticket (only owner). The bids paid
+    /// with an IBC denom no longer accepted are rejected.
+    UpdateIbcDenoms {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Buy a season pass, adding its entries to the ones left to the sender. A `Bid`
+    /// sent without funds by an address with entries left uses one of them in place of
+    /// the ticket.
+    BuySeasonPass {},
+    /// Replace the loyalty tiers (only owner). The players get the discount of the
+    /// highest tier reached by the loyalty points earned in the previous rounds.
+    SetLoyaltyTiers {
+        tiers: Vec<LoyaltyTier>,
+    },
+    /// Replace the payout multipliers of the bins for the current round (only owner),
+    /// before its claim airdrop stage, with the Merkle root resolution only. The prize
+    /// share weight of a winner is multiplied by the multiplier of the winning bin, one
+    /// for the bins without a multiplier.
+    SetBinMultipliers {
+        multipliers: Vec<(u8, Decimal)>,
+    },
+    /// Cancel the round before the Merkle roots are registered (only owner). The bids
+    /// of a cancelled round can only be refunded.
+    CancelGame {
+        round: Option<u64>,
+    },
+    /// Send back the tickets of the bids of an aborted round (only owner, anyone if
+    /// the round has been cancelled). If no addresses are given, the bids are refunded
+    /// in address order; refunded bids are removed, so calling it again continues with
+    /// the remaining ones. The sender is paid the crank bounty, up to the tickets prize
+    /// not owed back to the bidders.
+    RefundBids {
+        addresses: Option<Vec<String>>,
+        /// Maximum number of bids refunded by the call.
+        limit: Option<u32>,
+        round: Option<u64>,
+    },
+    /// Send back the ticket of the sender's bid in an aborted round, such as a round
+    /// ended with fewer bidders than the minimum.
+    RefundTicket {
+        round: Option<u64>,
+    },
+    /// Register the airdrop and game Merkle roots at once. Deprecated: use
+    /// `RegisterAirdropRoot` and `RegisterGameRoot`.
     RegisterMerkleRoots {
         /// MerkleRoot is hex-encoded merkle root.
         merkle_root_airdrop: String,
         total_amount_airdrop: Option<Uint128>,
         merkle_root_game: String,
-        total_amount_game: Option<Uint128>
+        total_amount_game: Option<Uint128>,
+        /// Number of addresses in the airdrop snapshot.
+        expected_recipients: Option<u64>,
+        /// Other cw20 tokens shared among the winners together with the game amount.
+        prize_tokens: Option<Vec<PrizeTokenAmount>>,
+        round: Option<u64>,
+    },
+    /// Register the Merkle root of the airdrop, which can be done before the game starts.
+    RegisterAirdropRoot {
+        /// MerkleRoot is hex-encoded merkle root.
+        merkle_root_airdrop: String,
+        total_amount_airdrop: Option<Uint128>,
+        /// Number of addresses in the airdrop snapshot.
+        expected_recipients: Option<u64>,
+        round: Option<u64>,
+    },
+    /// Register the Merkle root of the game result, once the bids are final.
+    RegisterGameRoot {
+        /// MerkleRoot is hex-encoded merkle root.
+        merkle_root_game: String,
+        total_amount_game: Option<Uint128>,
+        /// Other cw20 tokens shared among the winners together with the game amount.
+        prize_tokens: Option<Vec<PrizeTokenAmount>>,
+        round: Option<u64>,
+    },
+    /// Set the winning bin of the game, once the bids are final, in place of the game
+    /// root: the winners claiming the airdrop are checked against their bids, without
+    /// `proof_game`.
+    SetWinningBin {
+        bin: u8,
+        total_amount_game: Option<Uint128>,
+        /// Other cw20 tokens shared among the winners together with the game amount.
+        prize_tokens: Option<Vec<PrizeTokenAmount>>,
+        round: Option<u64>,
+    },
+    /// Set the winning bin of the game from the price observed on the price oracle of the
+    /// `Oracle` resolution, once the bids are final and before the claim airdrop stage
+    /// starts. Anyone can resolve.
+    ResolveGame { round: Option<u64> },
+    /// Request the randomness drawing the winning bin of the game with the `Randomness`
+    /// resolution, once the bids are final, paying the fee of the nois-proxy with the funds
+    /// sent. Anyone can request it, once per round.
+    RequestRandomness { round: Option<u64> },
+    /// Callback of the nois-proxy delivering the randomness requested, drawing the winning
+    /// bin of the round before the claim airdrop stage starts.
+    NoisReceive { callback: NoisCallback },
+    /// Replace the registered Merkle roots set, to fix a wrong root before the claims
+    /// open. The earmarked amounts are kept.
+    RotateMerkleRoots {
+        merkle_root_airdrop: Option<String>,
+        merkle_root_game: Option<String>,
+        round: Option<u64>,
     },
     // Claim does not check if contract has enough funds, owner must ensure it.
     /// Claim airdrop bin.
@@ -60,52 +501,750 @@ pub enum ExecuteMsg {
         amount: Uint128,
         /// Proof is hex-encoded merkle proof.
         proof_airdrop: Vec<String>,
-        proof_game: Vec<String>
+        proof_game: Vec<String>,
+        /// Address receiving the airdrop, the sender if none set. The claim is still
+        /// verified for the sender.
+        recipient: Option<String>,
+        round: Option<u64>,
+    },
+    /// Claim the airdrop sending it, with a cw20 `Send`, to a contract that handles it
+    /// with the given message.
+    ClaimAirdropTo {
+        contract: String,
+        msg: Binary,
+        amount: Uint128,
+        proof_airdrop: Vec<String>,
+        proof_game: Vec<String>,
+        round: Option<u64>,
+    },
+    /// Claim the airdrop on behalf of the claimer, who authorized it by signing, as
+    /// ADR-36 arbitrary data, the text returned by `claim_authorization`.
+    ClaimAirdropFor {
+        claimer: String,
+        amount: Uint128,
+        proof_airdrop: Vec<String>,
+        proof_game: Vec<String>,
+        /// Secp256k1 signature of the ADR-36 sign doc, 64 bytes.
+        signature: Binary,
+        /// Compressed secp256k1 public key of the claimer.
+        pubkey: Binary,
+        round: Option<u64>,
+    },
+    /// Register a part of the proofs of the airdrop claim of the sender, appended to the
+    /// parts already registered, for the proofs exceeding `max_claim_size`. A claim sent
+    /// with empty proofs is verified with the registered ones.
+    RegisterClaimProofs {
+        proof_airdrop: Vec<String>,
+        proof_game: Vec<String>,
+        round: Option<u64>,
+    },
+    /// Remove the proofs registered by the sender.
+    ClearClaimProofs {
+        round: Option<u64>,
+    },
+    ClaimPrize {
+        /// Address receiving the prize, the sender if none set.
+        recipient: Option<String>,
+        /// If true, the cw20 part of the prize is staked for the recipient in the
+        /// staking contract. Default to false if none set.
+        stake: Option<bool>,
+        round: Option<u64>,
+    },
+    /// Claim the airdrop and, if the claim prize stage is open and the sender is a
+    /// winner, the prize in the same transaction.
+    ClaimAll {
+        amount: Uint128,
+        proof_airdrop: Vec<String>,
+        proof_game: Vec<String>,
+        round: Option<u64>,
     },
-    ClaimPrize {},
-    // Withdraw the remaining Airdrop tokens after expire time (only owner)
+    /// Claim the prize donating a share of it, expressed in basis points.
+    DonatePrize {
+        share_bps: u16,
+        round: Option<u64>,
+    },
+    /// Claim the prize of the previous round, turning the part of the tickets prize
+    /// worth a ticket of the current round into a bid on the bin. The rest of the prize
+    /// is paid to the sender.
+    ClaimPrizeAndReenter {
+        bin: u8,
+    },
+    /// Fix the prize of each winner once the claim airdrop stage is over, leaving the
+    /// remainders of the rounded down shares as dust. Anyone can finalize the round, and
+    /// is paid the crank bounty from the tickets prize; the first prize claim finalizes
+    /// it otherwise.
+    FinalizeGame {
+        round: Option<u64>,
+    },
+    /// Share the prizes not claimed by the claim deadline, sponsor matches and prize tokens
+    /// included, among the winners who claimed, proportionally to their weight, or roll
+    /// them over if nobody claimed, leaving the matches to the sponsors. Anyone can
+    /// redistribute them, once, until the claim prize stage ends, and is paid the crank
+    /// bounty from the tickets prize not claimed.
+    RedistributeUnclaimed {
+        round: Option<u64>,
+    },
+    /// Move the prizes of a round whose claim airdrop stage ended without winners to the
+    /// rollover, for the next round started. Anyone can roll over the jackpot.
+    RollOverJackpot {
+        round: Option<u64>,
+    },
+    /// Release the vested part of the prizes of the sender, of any round.
+    ClaimVested {},
+    /// Fund the rebate pool of the round with the sent native token (only owner). Each
+    /// airdrop claim is paid `rebate` from the pool, while the pool holds it. Allowed
+    /// until the end of the claim airdrop stage.
+    FundRebates {
+        rebate: Uint128,
+        round: Option<u64>,
+    },
+    /// Donate the sent native token to the prize pool of the round: the ticket asset is
+    /// added to the tickets prize, the prize denom to the game prize. Anyone can donate,
+    /// until the end of the bid stage.
+    FundPrizePool {
+        round: Option<u64>,
+    },
+    /// Send back to the sponsor the part of the sponsorship not paid to the winners,
+    /// once the claim prize stage has ended or the round has been aborted.
+    ReclaimSponsorship {
+        round: Option<u64>,
+    },
+    /// Clear the notices in the inbox of the sender.
+    AckNotices {},
+    /// Withdraw, once the claim prize stage has ended, an amount of an asset left in the
+    /// round, or all of it if no amount is given (only owner). The asset is the native
+    /// denom or the cw20 address. The prizes kept for vesting, the sponsorships and the
+    /// rollover cannot be withdrawn.
+    Withdraw {
+        asset: String,
+        amount: Option<Uint128>,
+        recipient: String,
+        round: Option<u64>,
+    },
+    // Withdraw the remaining Airdrop tokens after expire time (only owner). Deprecated,
+    // replaced by `Withdraw`.
     WithdrawAirdrop {
         address: Addr,
+        round: Option<u64>,
     },
-    // Withdraw the remaining Prize tokens after expire time (only owner)
+    // Withdraw the remaining Prize tokens after expire time (only owner). Deprecated,
+    // replaced by `Withdraw`.
     WithdrawPrize {
         address: Addr,
+        round: Option<u64>,
+    },
+    // Withdraw the rollover once the grace period of the last jackpot rolled over has
+    // expired (only owner)
+    WithdrawRollover {
+        address: Addr,
     },
+    /// Burn the unclaimed airdrop and airdrop prize once the claim prize stage has ended,
+    /// when `burn_unclaimed` is set. Anyone can burn them.
+    BurnUnclaimed {
+        round: Option<u64>,
+    },
+}
+
+/// Message embedded in the cw20 `Send` delivering a prize to the staking contract, which
+/// stakes the tokens received for the address.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingReceiveMsg {
+    StakeFor { address: String },
+}
+
+/// Claim stages that can be extended with `ExecuteMsg::ExtendStage`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimStage {
+    ClaimAirdrop,
+    ClaimPrize,
+}
+
+/// Stages of a round that can be adjusted with `SudoMsg::AdjustStageEnd`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundStage {
+    Bid,
+    Reveal,
+    ClaimAirdrop,
+    ClaimPrize,
+}
+
+/// Merkle roots of a round that can be checked with `QueryMsg::VerifyProof`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleRootKind {
+    /// Root of the airdrop, with the amount of the address.
+    Airdrop,
+    /// Root of the game, with the bin of the address.
+    Game,
+    /// Root of the stake weights, with the weight of the address.
+    Stake,
+    /// Root of the ticket coupons, with the discount in basis points of the address.
+    Coupon,
 }
 
+/// Actions a player can perform within `ExecuteMsg::BatchActions`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerAction {
+    Bid {
+        bin: u8,
+        tickets: Option<u32>,
+        round: Option<u64>,
+    },
+    ChangeBid {
+        bin: u8,
+        round: Option<u64>,
+    },
+    RemoveBid {
+        round: Option<u64>,
+    },
+    ProveStake {
+        weight: u64,
+        proof: Vec<String>,
+        round: Option<u64>,
+    },
+    ClaimAirdrop {
+        amount: Uint128,
+        proof_airdrop: Vec<String>,
+        proof_game: Vec<String>,
+        round: Option<u64>,
+    },
+    ClaimPrize {
+        round: Option<u64>,
+    },
+    DonatePrize {
+        share_bps: u16,
+        round: Option<u64>,
+    },
+}
+
+/// Messages embedded in the cw20 `Send` used to pay the tickets.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// Place a bid.
+    Bid {
+        /// bidding bin value, 0 if a prediction is set
+        bin: u8,
+        /// Number of tickets bought on the bin, one if none set. Each ticket is worth a
+        /// share of the prize.
+        tickets: Option<u32>,
+        /// Proof that the sender is in the allowlist, required if an allowlist is set.
+        allowlist_proof: Option<Vec<String>>,
+        /// Predicted value, placing the bid on the bin covering it, if bin boundaries
+        /// are set.
+        prediction: Option<Uint128>,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Commit to match the ticket sales of the round with the sent token, up to the
+    /// amount sent. Allowed until the end of the bid stage.
+    Sponsor {
+        /// Amount of the sent token matched for each unit of the ticket asset sold.
+        ratio: Decimal,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Donate the sent token to the prize pool of the round: the cw20 ticket is added to
+    /// the tickets prize, the airdrop token to the game prize. Allowed until the end of
+    /// the bid stage.
+    FundPrizePool {
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+}
+
+/// Actions of the two steps transfer of the ownership, as in cw-ownable.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnershipAction {
+    /// Propose a new owner, who can accept until the expiry if any (only owner). A new
+    /// proposal replaces the pending one.
+    TransferOwnership {
+        new_owner: String,
+        expiry: Option<Expiration>,
+    },
+    /// Accept the pending proposal (only the proposed owner).
+    AcceptOwnership,
+    /// Give up the ownership, dropping the pending proposal (only owner).
+    RenounceOwnership,
+}
+
+/// Queries related to a round of the game refer to the current one if `round` is
+/// none set.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
-    Stages {},
-    Bid { address: String },
-    MerkleRoots {},
-    GameAmounts {},
+    Ownership {},
+    Limits {},
+    Round {},
+    Stages { round: Option<u64> },
+    GameStatus { round: Option<u64> },
+    Bid { address: String, round: Option<u64> },
+    BinCounts { round: Option<u64> },
+    /// Bids on the bin and in the round, with the prizes projected for each winner if
+    /// the bin wins.
+    Odds { bin: u8, round: Option<u64> },
+    MerkleRoots { round: Option<u64> },
+    GameAmounts { round: Option<u64> },
+    Outstanding { round: Option<u64> },
+    Treasury { round: Option<u64> },
+    ClaimedAirdrops {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        round: Option<u64>,
+    },
+    IsClaimed { address: String, round: Option<u64> },
+    ClaimableAmounts { address: String, round: Option<u64> },
+    Sponsorships { round: Option<u64> },
+    /// Donations to the prize pool of the round, paginated by contributor.
+    Contributors {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        round: Option<u64>,
+    },
+    Rebates { round: Option<u64> },
+    TicketPrice { round: Option<u64> },
+    Notices { address: String },
+    /// Prizes of the address vesting, of any round.
+    Vesting { address: String },
+    /// Blocked addresses, in ascending order.
+    Blocklist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// IBC voucher denoms accepted as ticket, in ascending order.
+    IbcDenoms {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Players of the channel whose bids, placed with IBC packets, are on the winning bin
+    /// of the round, in ascending order, paid by the sender.
+    RemoteWinners {
+        channel_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        round: Option<u64>,
+    },
+    /// Number of bids referred by the address, of any round.
+    Referrals { address: String },
+    /// Loyalty points of the address, with the discount they give on the tickets.
+    LoyaltyPoints { address: String },
+    /// Loyalty tiers, by ascending points.
+    LoyaltyTiers {},
+    /// Payout multipliers of the bins of the round, by ascending bin.
+    BinMultipliers { round: Option<u64> },
+    /// Range of predictions covered by each bin, if bin boundaries are set.
+    BinBoundaries {},
+    /// Entries left of the season passes of the address.
+    SeasonPass { address: String },
+    /// Check the proof of the address against the Merkle root of the round as the claims
+    /// do, without claiming, so that a claim can be checked before paying its gas.
+    VerifyProof {
+        root_kind: MerkleRootKind,
+        address: String,
+        /// Amount, bin, weight or discount of the leaf, depending on the root.
+        amount_or_bin: String,
+        proof: Vec<String>,
+        round: Option<u64>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
 
+/// Messages of the chain governance, through the `sudo` entry point, to intervene when
+/// the owner key is lost or compromised.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    /// Pause the contract: every message is rejected, except the refunds of the tickets.
+    Pause {},
+    /// Resume the paused contract.
+    Unpause {},
+    /// Move the end of a stage, keeping its start. The stage cannot overlap the next one.
+    AdjustStageEnd {
+        stage: RoundStage,
+        /// New end of the stage, of the same kind as the stage start.
+        end: Scheduled,
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+    /// Enable the refunds of the tickets of the round, as if cancelled by the owner.
+    /// Rejected once the claims have started.
+    ForceRefunds {
+        /// Round of the game, the current one if none set.
+        round: Option<u64>,
+    },
+}
+
 // ======================================================================================
 // Responses data structures
 // ======================================================================================
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipResponse {
+    pub owner: Option<String>,
+    /// Owner proposed by the owner, until accepted.
+    pub pending_owner: Option<String>,
+    pub pending_expiry: Option<Expiration>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ConfigResponse {
     pub owner: Option<String>,
     pub cw20_token_address: String,
+    pub charity_address: Option<String>,
+    pub address_prefix: Option<String>,
+    pub ticket_cw20_address: Option<String>,
+    pub parimutuel: bool,
+    pub anti_snipe: Option<AntiSnipe>,
+    pub remove_bid_penalty_bps: u16,
+    pub max_bid_changes: Option<u32>,
+    pub bid_change_fee: Option<Coin>,
+    pub prize_denom: Option<String>,
+    pub mint_prize: bool,
+    pub prize_vesting: Option<Duration>,
+    pub staking_contract: Option<String>,
+    pub rollover_grace_period: Option<Duration>,
+    pub crank_bounty: Option<Uint128>,
+    pub dust_policy: Option<DustPolicy>,
+    pub protocol_fee_bps: u16,
+    pub fee_collector: Option<String>,
+    pub revenue_split: Option<Vec<(String, Decimal)>>,
+    pub burn_unclaimed: bool,
+    pub claim_deadline: Option<Duration>,
+    pub merkle_root_allowlist: Option<String>,
+    pub bid_token_gate: Option<TokenGate>,
+    pub bid_nft_gate: Option<String>,
+    pub referral_fee_bps: u16,
+    pub season_pass: Option<SeasonPass>,
+    pub bid_receipts: Option<String>,
+    pub winner_badges: Option<String>,
+    pub attestor: Option<String>,
+    pub resolution: Resolution,
+    pub adjacent_tier_bps: u16,
+    pub bin_boundaries: Option<Vec<Uint128>>,
+    pub ibc_bidder_port: Option<String>,
+    pub hash_algorithm: HashAlgorithm,
+    /// Authority that paused the contract, if paused.
+    pub paused: Option<PausedBy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LimitsResponse {
+    /// Maximum size, in bytes, of the JSON encoded proofs sent to claim the airdrop.
+    pub max_claim_size: u32,
+    /// Number of bids refunded by `RefundBids` if no limit is given.
+    pub default_refund_limit: u32,
+    /// Maximum number of bids refunded by a single `RefundBids`.
+    pub max_refund_limit: u32,
+    /// Maximum number of sponsorships of a round.
+    pub max_sponsorships: u32,
+    /// Maximum number of notices kept in the inbox of an address.
+    pub max_notices: u32,
+    /// Maximum number of bids of a round, if any.
+    pub max_bids: Option<u32>,
+    /// Maximum number of bids on a single bin, if any.
+    pub max_per_bin: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundResponse {
+    /// Identifier of the current round.
+    pub round: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StagesResponse {
     pub stage_bid: Stage,
+    pub stage_reveal: Option<Stage>,
     pub stage_claim_airdrop: Stage,
     pub stage_claim_prize: Stage,
 }
 
+/// Phase of the game, derived from the stages.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GamePhase {
+    /// No stage in progress, waiting for the next one to start.
+    Pending,
+    Bidding,
+    Revealing,
+    ClaimAirdrop,
+    ClaimPrize,
+    /// All the stages have ended.
+    Ended,
+    /// The round has been cancelled, the bids can only be refunded.
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameStatusResponse {
+    pub round: u64,
+    pub phase: GamePhase,
+    /// End of the phase, none set if the game has ended or has been cancelled.
+    pub phase_end: Option<Scheduled>,
+    /// Blocks left before the end of the phase, if it ends at a height.
+    pub blocks_remaining: Option<u64>,
+    /// Seconds left before the end of the phase, if it ends at a time.
+    pub seconds_remaining: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BidResponse {
     pub bid: Option<u8>,
+    /// Every bin of the bid, more than one for a bid placed with `BidMultiple`.
+    pub bins: Vec<u8>,
+    /// Number of tickets bought on each bin of the bid, zero if there is no bid.
+    pub tickets: u32,
+    /// Amount paid for the bid, zero if there is no bid.
+    pub stake: Uint128,
+    /// Number of times the bid has been changed.
+    pub changes: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimedAirdropsResponse {
+    /// Addresses that claimed the airdrop, in ascending order.
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsClaimedResponse {
+    pub airdrop_claimed: bool,
+    /// True if the address has been verified as a winner when claiming the airdrop.
+    pub prize_eligible: bool,
+    pub prize_claimed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableAmountsResponse {
+    /// Share of the tickets prize, including the first bidder bonus.
+    pub ticket_prize: Uint128,
+    /// Share of the airdrop reserved to the winners.
+    pub airdrop_prize: Uint128,
+    /// Share of the ticket sales matched by each sponsorship.
+    pub sponsor_prizes: Vec<SponsorPrize>,
+    /// Share of each token of the basket of the game prize.
+    pub token_prizes: Vec<PrizeTokenAmount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SponsorPrize {
+    pub sponsor: String,
+    /// Cw20 token of the sponsorship.
+    pub token: String,
+    pub amount: Uint128,
+}
+
+/// Cw20 token of the basket of the game prize, with the amount shared among the winners.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeTokenAmount {
+    pub token: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeTokenInfo {
+    pub token: String,
+    /// Amount of the token shared among the winners.
+    pub amount: Uint128,
+    /// Amount of the token paid to the winners or donated to the charity.
+    pub claimed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SponsorshipInfo {
+    pub sponsor: String,
+    /// Cw20 token used to match the ticket sales.
+    pub token: String,
+    /// Amount of the token matched for each unit of the ticket asset sold.
+    pub ratio: Decimal,
+    /// Maximum amount of the token matched.
+    pub cap: Uint128,
+    /// Amount matched by the current ticket sales.
+    pub matched: Uint128,
+    /// Amount of the match paid to the winners or donated to the charity.
+    pub claimed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SponsorshipsResponse {
+    /// Sponsorships of the round, in ascending order of sponsor.
+    pub sponsorships: Vec<SponsorshipInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContributorInfo {
+    pub address: String,
+    /// Amount of the ticket asset donated to the tickets prize.
+    pub ticket_prize: Uint128,
+    /// Amount of the prize asset donated to the game prize.
+    pub game_prize: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContributorsResponse {
+    /// Contributors of the round, in ascending order of address.
+    pub contributors: Vec<ContributorInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RebatesResponse {
+    /// Rebate paid with each airdrop claim, none set if the pool has not been funded.
+    pub rebate: Option<Coin>,
+    /// Amount left in the rebate pool.
+    pub pool: Uint128,
+    /// Total amount of the rebates paid.
+    pub total_paid: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketPriceResponse {
+    /// Price of a ticket bought at the current block.
+    pub ticket_price: Coin,
+    /// Steps changing the ticket price during the bid stage, empty if the price is fixed.
+    pub price_schedule: Vec<PriceStep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingInfo {
+    pub round: u64,
+    /// Asset of the prize, a cw20 address or a native denom.
+    pub asset: String,
+    pub amount: Uint128,
+    /// Part of the amount vested so far, released or not.
+    pub vested: Uint128,
+    pub released: Uint128,
+    /// Part of the amount still to vest.
+    pub remaining: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingResponse {
+    pub vestings: Vec<VestingInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlocklistResponse {
+    /// Blocked addresses, in ascending order.
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcDenomsResponse {
+    /// IBC voucher denoms accepted as ticket, in ascending order.
+    pub denoms: Vec<String>,
+}
+
+/// Winner whose bid was placed with an IBC packet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoteWinner {
+    /// Address of the player on the sender chain, as sent in the packet.
+    pub player: String,
+    pub bin: u8,
+    /// Number of tickets escrowed by the sender for the bid.
+    pub tickets: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoteWinnersResponse {
+    /// Remote winners, in ascending order of player.
+    pub winners: Vec<RemoteWinner>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralsResponse {
+    /// Number of bids referred by the address and not removed.
+    pub referrals: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoyaltyPointsResponse {
+    /// Loyalty points, one for each round played.
+    pub points: u64,
+    /// Discount on the tickets of the next rounds, in basis points.
+    pub discount_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoyaltyTiersResponse {
+    /// Loyalty tiers, by ascending points.
+    pub tiers: Vec<LoyaltyTier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BinMultipliersResponse {
+    /// Payout multipliers, as (bin, multiplier), by ascending bin.
+    pub multipliers: Vec<(u8, Decimal)>,
+}
+
+/// Range of predictions covered by a bin.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BinRange {
+    pub bin: u8,
+    /// Lowest prediction of the bin, none for the first bin.
+    pub min: Option<Uint128>,
+    /// Prediction from which the next bin starts, none for the last bin.
+    pub max: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BinBoundariesResponse {
+    /// Ranges of the bins, by ascending bin. Empty if no bin boundaries are set.
+    pub ranges: Vec<BinRange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonPassResponse {
+    /// Entries left, each paying the ticket of a round.
+    pub entries: u32,
+    /// Amount paid for the entries left, staked on the bids as they are used.
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyProofResponse {
+    /// True if the proof links the leaf of the address to the Merkle root.
+    pub verified: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NoticesResponse {
+    /// Notices not acknowledged yet, oldest first.
+    pub notices: Vec<Notice>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BinCount {
+    pub bin: u8,
+    /// Number of bids placed on the bin.
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BinCountsResponse {
+    /// Bins with at least a bid, in ascending order.
+    pub bin_counts: Vec<BinCount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OddsResponse {
+    pub bin: u8,
+    /// Number of bids placed on the bin.
+    pub bin_bids: u64,
+    /// Number of bids placed on every bin.
+    pub total_bids: u64,
+    /// Share of the tickets prize, after the protocol fee, of each bid on the bin if it
+    /// wins, the whole prize if the bin has no bid yet.
+    pub ticket_payout: Uint128,
+    /// Share of the airdrop prize of each bid on the bin if it wins.
+    pub airdrop_payout: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -113,16 +1252,69 @@ pub struct MerkleRootsResponse {
     /// MerkleRoot is hex-encoded merkle root.
     pub merkle_root_airdrop: String,
     pub total_amount: Uint128,
-    pub merkle_root_game: String
+    /// None until the game root is registered.
+    pub merkle_root_game: Option<String>,
+    /// Winning bin set in place of the game root, if any.
+    pub winning_bin: Option<u8>,
+    /// Id of the randomness job drawing the winning bin with the `Randomness` resolution,
+    /// if requested.
+    pub randomness_job: Option<String>,
 
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GameAmountsResponse {
+    /// Tickets prize of the round, read from the treasury: the ticket pot and, once the
+    /// round is finalized, the prizes paid from it and the dust. The donations kept for
+    /// the next game leave it.
     pub total_ticket_prize: Uint128,
     pub total_airdrop_amount: Uint128,
     pub total_airdrop_game_amount: Uint128,
     pub winners_amount: Uint128,
+    /// Amounts paid from the reserves of the round, the donations sent to the charity
+    /// included.
     pub total_claimed_airdrop: Uint128,
     pub total_claimed_prize: Uint128,
+    pub first_bidder_bonus: Uint128,
+    pub first_winner: Option<String>,
+    pub max_winners: Option<u32>,
+    pub min_participants: Option<u32>,
+    pub total_donated_prize: Uint128,
+    pub total_donated_airdrop: Uint128,
+    /// Tokens of the basket of the game prize besides the airdrop token.
+    pub prize_tokens: Vec<PrizeTokenInfo>,
+    /// Whether the prize of each winner has been fixed.
+    pub finalized: bool,
+    /// Remainders of the rounded down shares of the tickets prize and of the airdrop, held
+    /// or disposed of as set by the dust policy. The prizes are the sum of the amounts
+    /// claimed, still to claim and the dust.
+    pub total_dust_prize: Uint128,
+    pub total_dust_airdrop: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OutstandingResponse {
+    /// Number of addresses in the airdrop snapshot.
+    pub expected_recipients: u64,
+    pub airdrop_claims: u64,
+    pub unclaimed_recipients: u64,
+    pub unclaimed_winners: Uint128,
+    pub unclaimed_airdrop: Uint128,
+    pub unclaimed_prize: Uint128,
+}
+
+/// Balance of an asset in an earmarked bucket of the treasury.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BucketBalance {
+    pub bucket: Bucket,
+    /// Native denom or cw20 address of the asset.
+    pub asset: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TreasuryResponse {
+    /// Non empty balances of the round, followed by the rollover kept for the next
+    /// round.
+    pub buckets: Vec<BucketBalance>,
 }
@@ -0,0 +1,79 @@
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, StdError, StdResult, Timestamp, WasmMsg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ======================================================================================
+// Nois randomness
+// ======================================================================================
+// With the randomness resolution, the winning bin of a round is drawn from the randomness
+// published by the Nois network, requested to a nois-proxy contract and delivered by its
+// callback.
+
+/// Subset of the execute messages of the nois-proxy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NoisProxyExecuteMsg {
+    /// Requests the next randomness published, delivered with the job id to the
+    /// `NoisReceive` handler of the sender.
+    GetNextRandomness { job_id: String },
+}
+
+/// Randomness delivered by the nois-proxy for a job.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NoisCallback {
+    pub job_id: String,
+    /// Publication time of the randomness.
+    pub published: Timestamp,
+    /// Hex-encoded 32 bytes of randomness.
+    pub randomness: String,
+}
+
+/// Returns the job id of the randomness of the round.
+pub fn job_id(round: u64) -> String {
+    format!("round-{}", round)
+}
+
+/// Returns the message requesting the randomness of the job, paying the fee of the
+/// proxy with the funds.
+pub fn randomness_request_msg(proxy: &Addr, job_id: String, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: proxy.to_string(),
+        msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness { job_id })?,
+        funds,
+    }
+    .into())
+}
+
+/// Returns the bin, from 1 to `bins`, drawn from the randomness. The modulo bias is
+/// negligible over 64 bits of randomness.
+pub fn random_bin(randomness: &str, bins: u8) -> StdResult<u8> {
+    let mut randomness_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(randomness, &mut randomness_buf)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let mut head: [u8; 8] = [0; 8];
+    head.copy_from_slice(&randomness_buf[..8]);
+    let draw = u64::from_be_bytes(head) % u64::from(bins.max(1));
+    Ok(draw as u8 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_bin_draws_from_the_randomness() {
+        let zero = "00".repeat(32);
+        assert_eq!(random_bin(&zero, 10).unwrap(), 1);
+        let seven = format!("{:016x}{}", 7u64, "ff".repeat(24));
+        assert_eq!(random_bin(&seven, 10).unwrap(), 8);
+        assert_eq!(random_bin(&seven, 5).unwrap(), 3);
+        assert_eq!(random_bin(&"ff".repeat(32), 255).unwrap(), (u64::MAX % 255) as u8 + 1);
+        assert_eq!(random_bin(&"ff".repeat(32), 1).unwrap(), 1);
+    }
+
+    #[test]
+    fn random_bin_requires_32_bytes() {
+        assert!(random_bin(&"00".repeat(31), 10).is_err());
+        assert!(random_bin("not hex", 10).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ======================================================================================
+// Price oracle
+// ======================================================================================
+// The winning bin of a round can be derived from the price of an asset observed on a
+// price oracle, each bin covering a fixed price range from a minimum price.
+
+/// Subset of the query messages of the price oracle.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    /// Current price of the asset.
+    Price { asset: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    pub price: Decimal,
+}
+
+/// Returns the current price of the asset on the oracle.
+pub fn query_price(querier: &QuerierWrapper, oracle: &Addr, asset: &str) -> StdResult<Decimal> {
+    let msg = OracleQueryMsg::Price { asset: asset.to_string() };
+    let res: PriceResponse = querier.query_wasm_smart(oracle, &msg)?;
+    Ok(res.price)
+}
+
+/// Returns the bin, from 1 to `bins`, of the price: the first bin covers the prices below
+/// `min_price + bin_width`, the last one every price above the previous bins.
+pub fn price_bin(price: Decimal, min_price: Decimal, bin_width: Decimal, bins: u8) -> u8 {
+    if price <= min_price || bin_width.is_zero() {
+        return 1;
+    }
+    // Both prices have the same decimal places, so are compared in atomics.
+    let index = (price.atomics() - min_price.atomics()) / bin_width.atomics();
+    if index >= Uint128::from(bins) {
+        return bins;
+    }
+    index.u128() as u8 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn bin(price: &str) -> u8 {
+        let min_price = Decimal::from_str("1.5").unwrap();
+        let bin_width = Decimal::from_str("0.25").unwrap();
+        price_bin(Decimal::from_str(price).unwrap(), min_price, bin_width, 10)
+    }
+
+    #[test]
+    fn price_bin_maps_the_price_ranges() {
+        assert_eq!(bin("1.5"), 1);
+        assert_eq!(bin("1.6"), 1);
+        assert_eq!(bin("1.75"), 2);
+        assert_eq!(bin("1.999999"), 2);
+        assert_eq!(bin("2"), 3);
+        assert_eq!(bin("3.74"), 9);
+        assert_eq!(bin("3.75"), 10);
+    }
+
+    #[test]
+    fn price_bin_caps_the_prices_out_of_range() {
+        assert_eq!(bin("0"), 1);
+        assert_eq!(bin("1.49"), 1);
+        assert_eq!(bin("4"), 10);
+        assert_eq!(bin("1000000"), 10);
+        assert_eq!(price_bin(Decimal::MAX, Decimal::zero(), Decimal::percent(1), 255), 255);
+    }
+}
@@ -0,0 +1,99 @@
+use cosmwasm_std::{Decimal256, DivideByZeroError, StdError, StdResult, Uint128, Uint256};
+use std::convert::TryFrom;
+
+// ======================================================================================
+// Share math
+// ======================================================================================
+// Prizes are split in `Decimal256`, keeping the fractional part of every share, and are
+// converted to a payout only once, with the rounding policy of `to_payout`.
+
+/// Basis points representing the whole of an amount.
+pub const MAX_BPS: u16 = 10_000;
+
+/// Returns the part of the amount proportional to the weight over the total weight.
+pub fn weighted_amount(
+    amount: Uint128,
+    weight: Uint128,
+    total_weight: Uint128,
+) -> StdResult<Decimal256> {
+    if total_weight.is_zero() {
+        return Err(StdError::divide_by_zero(DivideByZeroError::new(amount)));
+    }
+    // The product of two Uint128 always fits in a Uint256.
+    let numerator = Uint256::from(amount) * Uint256::from(weight);
+    Decimal256::checked_from_ratio(numerator, total_weight)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+/// Returns the part of the amount expressed in basis points.
+pub fn bps_amount(amount: Uint128, bps: u16) -> StdResult<Decimal256> {
+    weighted_amount(amount, Uint128::from(bps), Uint128::from(MAX_BPS))
+}
+
+/// Converts an amount to a payout. The fractional part is rounded down, so that the
+/// payouts of a prize never exceed it; the remainder is left in the contract.
+pub fn to_payout(amount: Decimal256) -> StdResult<Uint128> {
+    let units = amount.atomics() / Uint256::from(10u128.pow(amount.decimal_places()));
+    Uint128::try_from(units).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payout(amount: u128, weight: u128, total_weight: u128) -> u128 {
+        let amount = weighted_amount(amount.into(), weight.into(), total_weight.into()).unwrap();
+        to_payout(amount).unwrap().u128()
+    }
+
+    #[test]
+    fn weighted_amount_rounds_down() {
+        assert_eq!(payout(30, 1, 3), 10);
+        assert_eq!(payout(10, 1, 3), 3);
+        assert_eq!(payout(10, 2, 3), 6);
+        assert_eq!(payout(1, 1, 2), 0);
+        assert_eq!(payout(0, 1, 2), 0);
+        assert_eq!(payout(10, 0, 2), 0);
+
+        // Shares of the same prize never exceed it.
+        let paid: u128 = (0..7).map(|_| payout(100, 1, 7)).sum();
+        assert_eq!(paid, 98);
+    }
+
+    #[test]
+    fn weighted_amount_keeps_precision_on_large_amounts() {
+        let max = Uint128::MAX.u128();
+        assert_eq!(payout(max, 1, 1), max);
+        assert_eq!(payout(max, max, max), max);
+        assert_eq!(payout(max, 1, 3), max / 3);
+        assert_eq!(payout(max, max - 1, max), max - 1);
+        assert_eq!(payout(max, 1, max), 1);
+        assert_eq!(payout(1, 1, max), 0);
+    }
+
+    #[test]
+    fn weighted_amount_checks_the_weights() {
+        let err = weighted_amount(Uint128::new(10), Uint128::new(1), Uint128::zero()).unwrap_err();
+        assert!(matches!(err, StdError::DivideByZero { .. }));
+
+        // A weight larger than the total gives more than the amount.
+        assert_eq!(payout(10, 3, 2), 15);
+        let amount = weighted_amount(Uint128::MAX, Uint128::new(2), Uint128::new(1)).unwrap();
+        assert!(to_payout(amount).is_err());
+        assert!(weighted_amount(Uint128::MAX, Uint128::MAX, Uint128::new(1)).is_err());
+    }
+
+    #[test]
+    fn bps_amount_rounds_down() {
+        let bps_payout = |amount: u128, bps: u16| {
+            to_payout(bps_amount(amount.into(), bps).unwrap()).unwrap().u128()
+        };
+        assert_eq!(bps_payout(1_000, 0), 0);
+        assert_eq!(bps_payout(1_000, 2_500), 250);
+        assert_eq!(bps_payout(1_000, MAX_BPS), 1_000);
+        assert_eq!(bps_payout(3, 5_000), 1);
+        assert_eq!(bps_payout(9_999, 1), 0);
+        assert_eq!(bps_payout(Uint128::MAX.u128(), MAX_BPS), Uint128::MAX.u128());
+        assert_eq!(bps_payout(Uint128::MAX.u128(), 1), Uint128::MAX.u128() / 10_000);
+    }
+}
@@ -1,15 +1,204 @@
-use cosmwasm_std::{Addr, Uint128, Coin};
+use cosmwasm_std::{Addr, BlockInfo, Uint128, Coin, Decimal, StdError, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
-use cw_utils::{Duration, Scheduled};
+use cw_utils::{Duration, Expiration, Scheduled};
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::merkle::HashAlgorithm;
+
 /// Struct to manage the contract configuration.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     /// Owner If None set, contract is frozen.
     pub owner: Option<Addr>,
     pub cw20_token_address: Addr,
+    /// Address receiving the donated prizes. If None set, donations are kept in the
+    /// contract for the next game.
+    pub charity_address: Option<Addr>,
+    /// Expected bech32 prefix of the externally supplied addresses.
+    pub address_prefix: Option<String>,
+    /// Token used to pay the tickets. If None set, tickets are paid with the native
+    /// denom of the ticket price.
+    pub ticket_cw20_address: Option<Addr>,
+    /// Maximum size, in bytes, of the proofs sent to claim the airdrop.
+    pub max_claim_size: u32,
+    /// Maximum number of bids of a round. If None set, the bids are not limited.
+    pub max_bids: Option<u32>,
+    /// Maximum number of bids on a single bin. If None set, the bins are not limited.
+    pub max_per_bin: Option<u32>,
+    /// Whether bidders stake any amount above the tickets price, the winners splitting
+    /// the prize proportionally to their stake.
+    pub parimutuel: bool,
+    /// Extension of the bid stage when bids arrive close to its end. If None set, the
+    /// bid stage is never extended.
+    pub anti_snipe: Option<AntiSnipe>,
+    /// Part of the stake, in basis points, retained in the tickets prize when a bid is
+    /// removed.
+    pub remove_bid_penalty_bps: u16,
+    /// Maximum number of times a bid can be changed. If None set, the changes are not
+    /// limited.
+    pub max_bid_changes: Option<u32>,
+    /// Native fee paid for each change of a bid. If None set, the changes are free.
+    pub bid_change_fee: Option<Coin>,
+    /// Native denom paying the prize of the winners. If None set, the prize is paid with
+    /// the cw20 token.
+    pub prize_denom: Option<String>,
+    /// Whether the prize denom is a TokenFactory denom of the contract, minted when the
+    /// Merkle roots are registered.
+    pub mint_prize: bool,
+    /// Duration over which the share of the airdrop won vests. If None set, it is paid
+    /// with the claim of the prize.
+    pub prize_vesting: Option<Duration>,
+    /// Staking contract receiving the cw20 prizes claimed with `stake`.
+    pub staking_contract: Option<Addr>,
+    /// Grace period after which the owner can withdraw a rollover left unused. If None
+    /// set, the rollover is kept for the next round.
+    pub rollover_grace_period: Option<Duration>,
+    /// Bounty paid from the tickets to the callers of the permissionless cranks. If None
+    /// set, no bounty is paid.
+    pub crank_bounty: Option<Uint128>,
+    /// Destination of the dust of the tickets prize and of the airdrop. If None set, the
+    /// dust is withdrawn by the owner with the leftovers.
+    pub dust_policy: Option<DustPolicy>,
+    /// Part of the tickets prize, in basis points, taken as protocol fee when the round
+    /// is finalized.
+    pub protocol_fee_bps: u16,
+    /// Address receiving the protocol fee.
+    pub fee_collector: Option<Addr>,
+    /// Destinations, with their shares summing to 1, among which the withdraws and the
+    /// protocol fee are split. If None set, they are sent to a single address.
+    pub revenue_split: Option<Vec<(Addr, Decimal)>>,
+    /// If true, the unclaimed airdrop is burned with `BurnUnclaimed` instead of being
+    /// withdrawn by the owner.
+    pub burn_unclaimed: bool,
+    /// Time from the start of the claim prize stage after which the prizes not claimed
+    /// are redistributed. If None set, the winners can claim until the stage ends.
+    pub claim_deadline: Option<Duration>,
+    /// Merkle root of the addresses allowed to bid. If None set, anyone can bid.
+    pub merkle_root_allowlist: Option<String>,
+    /// Cw20 token and minimum balance of it the players must hold to bid. If None set,
+    /// the bids are not gated.
+    pub bid_token_gate: Option<(Addr, Uint128)>,
+    /// Cw721 collection the players must hold a token of to bid. If None set, the bids
+    /// are not gated.
+    pub bid_nft_gate: Option<Addr>,
+    /// Part of the protocol fee, in basis points, paid to the referrers of the bids.
+    pub referral_fee_bps: u16,
+    /// Season pass sold to pay up front the entries of several rounds. If None set, no
+    /// season pass is sold.
+    pub season_pass: Option<SeasonPass>,
+    /// Cw721 collection, minted by the game, receiving a receipt of each bid. If None
+    /// set, no receipt is minted.
+    pub bid_receipts: Option<Addr>,
+    /// Non transferable cw721 collection, minted by the game, receiving a badge of each
+    /// prize claimed. If None set, no badge is minted.
+    pub winner_badges: Option<Addr>,
+    /// Address allowed, besides the owner, to set the winning bin of the rounds.
+    pub attestor: Option<Addr>,
+    /// Mechanism setting the result of the rounds. The games instantiated before it was
+    /// configurable are resolved with the game root.
+    #[serde(default)]
+    pub resolution: GameResolution,
+    /// Part of the tickets prize, in basis points, shared among the bidders on the bins
+    /// adjacent to the winning bin.
+    pub adjacent_tier_bps: u16,
+    /// Ascending boundaries of the bins, mapping the predictions of the bids to bins.
+    pub bin_boundaries: Option<Vec<Uint128>>,
+    /// Port of the sender contract, on another chain, allowed to open a channel placing
+    /// bids with IBC packets.
+    pub ibc_bidder_port: Option<String>,
+    /// Hash function of the Merkle trees. The games instantiated before it was
+    /// configurable use sha256.
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+}
+
+/// Mechanism setting the result of the rounds, with the validated addresses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameResolution {
+    MerkleRoot {},
+    OwnerSetsBin {},
+    Oracle(OracleResolution),
+    Randomness(RaffleResolution),
+}
+
+impl Default for GameResolution {
+    fn default() -> Self {
+        GameResolution::MerkleRoot {}
+    }
+}
+
+impl GameResolution {
+    /// Name of the mechanism, as in `InstantiateMsg`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameResolution::MerkleRoot {} => "merkle_root",
+            GameResolution::OwnerSetsBin {} => "owner_sets_bin",
+            GameResolution::Oracle(_) => "oracle",
+            GameResolution::Randomness(_) => "randomness",
+        }
+    }
+}
+
+/// Price oracle and price ranges of the bins deriving the winning bin of the rounds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OracleResolution {
+    pub oracle: Addr,
+    pub asset: String,
+    /// Lowest price of the first bin.
+    pub min_price: Decimal,
+    /// Price range covered by each bin.
+    pub bin_width: Decimal,
+    /// Amount earmarked for the winners of each round resolved.
+    pub total_amount_game: Option<Uint128>,
+}
+
+/// Nois proxy and prize of the rounds whose winning bin is drawn at random.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RaffleResolution {
+    pub nois_proxy: Addr,
+    /// Amount earmarked for the winners of each round drawn.
+    pub total_amount_game: Option<Uint128>,
+}
+
+/// Struct to manage the extension of the bid stages scheduled at a block height, all
+/// the values being numbers of blocks.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AntiSnipe {
+    /// Bids placed within this number of blocks from the end of the stage extend it.
+    pub window: u64,
+    /// Extension of the stage end for each bid placed within the window.
+    pub extension: u64,
+    /// Maximum total extension of the bid stage of a round.
+    pub max_extension: u64,
+}
+
+/// Season pass paying up front the entries of a number of rounds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonPass {
+    /// Number of rounds entered with the pass.
+    pub rounds: u32,
+    /// Price of the pass, in a native denom.
+    pub price: Coin,
+}
+
+/// Entries left of the season passes of an address, with the amount paid for them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonPassBalance {
+    pub entries: u32,
+    pub amount: Uint128,
+}
+
+/// Discount on the tickets of the players that earned at least a number of loyalty
+/// points.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoyaltyTier {
+    /// Loyalty points required for the discount.
+    pub min_points: u64,
+    /// Discount on the tickets, in basis points.
+    pub discount_bps: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,56 +210,477 @@ pub struct Stage {
     pub duration: Duration,
 }
 
+/// Struct to track the bid of an address.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bid {
+    /// Bin of the bid, the first one for a bid placed on several bins.
+    pub bin: u8,
+    /// Number of tickets bought on each bin of the bid, each one worth a share of the
+    /// prize.
+    pub tickets: u32,
+    /// Amount paid for the bid, above the tickets price only in parimutuel mode.
+    pub stake: Uint128,
+    /// Number of times the bid has been changed.
+    #[serde(default)]
+    pub changes: u32,
+}
+
+/// Destination of the dust left by rounding down the shares of the winners, disposed of
+/// when the last winner claims the prize.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DustPolicy {
+    /// Paid to the last winner claiming the prize.
+    LastClaimer,
+    /// Sent to the owner.
+    Owner,
+    /// Burned.
+    Burn,
+}
+
+impl DustPolicy {
+    /// Returns the name of the policy, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DustPolicy::LastClaimer => "last_claimer",
+            DustPolicy::Owner => "owner",
+            DustPolicy::Burn => "burn",
+        }
+    }
+}
+
+/// Struct to manage a step of the ticket price schedule of a round.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceStep {
+    /// Event from which the price applies.
+    pub start: Scheduled,
+    /// Price of the ticket, in the denom of the ticket price of the round.
+    pub amount: Uint128,
+}
+
+/// Struct to track a bid committed as a hash, to be revealed in the reveal stage.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Commitment {
+    /// Hex encoded SHA-256 hash of the address, the bin and the salt.
+    pub hash: String,
+    /// Block height at which the bid has been committed.
+    pub height: u64,
+    /// Amount paid for the bid.
+    pub stake: Uint128,
+}
+
+/// Struct to store the prize of a winner, fixed when the round is finalized.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FinalShare {
+    /// Share of the tickets prize.
+    pub ticket_prize: Uint128,
+    /// Share of the airdrop reserved to the winners.
+    pub airdrop_prize: Uint128,
+}
+
+/// Struct to track the earliest bid placed on the winning bin.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FirstWinner {
+    /// Address of the winner.
+    pub address: Addr,
+    /// Block height at which the winning bid has been placed.
+    pub height: u64,
+}
+
+/// Struct to track the commitment of a sponsor to match the ticket sales of a round.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sponsorship {
+    /// Cw20 token used to match the ticket sales.
+    pub token: Addr,
+    /// Amount of the token matched for each unit of the ticket asset sold.
+    pub ratio: Decimal,
+    /// Maximum amount of the token matched, deposited by the sponsor.
+    pub cap: Uint128,
+    /// Amount of the match paid to the winners or donated to the charity.
+    pub claimed: Uint128,
+    /// Amount matched by the tickets prize, fixed when the round is finalized.
+    pub final_match: Option<Uint128>,
+}
+
+/// Struct to track the donations of a contributor to the prize pool of a round.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Contribution {
+    /// Amount of the ticket asset added to the tickets prize.
+    pub ticket_prize: Uint128,
+    /// Amount of the prize asset added to the game prize.
+    pub game_prize: Uint128,
+}
+
+/// Cw20 token of the basket of the game prize, besides the airdrop token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeToken {
+    pub token: Addr,
+    /// Amount of the token shared among the winners.
+    pub amount: Uint128,
+    /// Amount of the token paid to the winners or donated to the charity.
+    pub claimed: Uint128,
+}
+
+impl Sponsorship {
+    /// Returns the amount matched for the given ticket sales, or the one fixed when the
+    /// round was finalized.
+    pub fn matched(&self, ticket_sales: Uint128) -> Uint128 {
+        self.final_match
+            .unwrap_or_else(|| (ticket_sales * self.ratio).min(self.cap))
+    }
+}
+
+/// Prize of a winner vesting linearly from the claim of the prize.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vesting {
+    pub round: u64,
+    /// Asset of the prize, as recorded in the treasury.
+    pub asset: String,
+    pub amount: Uint128,
+    pub released: Uint128,
+    /// Height, or time in seconds, at which the vesting starts, depending on the duration.
+    pub start: u64,
+    pub duration: Duration,
+}
+
+impl Vesting {
+    /// Returns the part of the amount vested at the given block.
+    pub fn vested(&self, block: &BlockInfo) -> Uint128 {
+        let (elapsed, total) = match self.duration {
+            Duration::Height(height) => (block.height.saturating_sub(self.start), height),
+            Duration::Time(time) => (block.time.seconds().saturating_sub(self.start), time),
+        };
+        if elapsed >= total {
+            self.amount
+        } else {
+            self.amount.multiply_ratio(elapsed, total)
+        }
+    }
+}
+
+/// Personalized notice recorded in the inbox of a player.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Notice {
+    /// A bid has been placed: the airdrop, which verifies the bid, can be claimed until
+    /// the end of the claim airdrop stage.
+    ClaimAirdropWindow { round: u64, closes_at: Scheduled },
+    /// The bid won: the prize can be claimed until the end of the claim prize stage.
+    Won { round: u64, closes_at: Scheduled },
+    /// The ticket of the bid has been refunded.
+    Refunded { round: u64, amount: Uint128 },
+    /// The bid won but has been left out by the cap on the winners: the ticket can be
+    /// claimed back, as a consolation, until the end of the claim prize stage.
+    Consolation { round: u64, amount: Uint128, closes_at: Scheduled },
+}
+
+/// Item of the game state saved once per round, behaving like an `Item` keyed by the
+/// round identifier.
+pub struct RoundItem<'a, T>(Map<'a, u64, T>);
+
+impl<'a, T> RoundItem<'a, T> {
+    pub const fn new(namespace: &'a str) -> Self {
+        RoundItem(Map::new(namespace))
+    }
+}
+
+impl<'a, T> RoundItem<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn save(&self, store: &mut dyn Storage, round: u64, data: &T) -> StdResult<()> {
+        self.0.save(store, round, data)
+    }
+
+    pub fn remove(&self, store: &mut dyn Storage, round: u64) {
+        self.0.remove(store, round)
+    }
+
+    pub fn load(&self, store: &dyn Storage, round: u64) -> StdResult<T> {
+        self.0.load(store, round)
+    }
+
+    pub fn may_load(&self, store: &dyn Storage, round: u64) -> StdResult<Option<T>> {
+        self.0.may_load(store, round)
+    }
+
+    pub fn has(&self, store: &dyn Storage, round: u64) -> bool {
+        self.0.has(store, round)
+    }
+
+    /// Loads the data of the round, applies the action and saves the result. Fails if
+    /// no data is saved for the round.
+    pub fn update<A, E>(&self, store: &mut dyn Storage, round: u64, action: A) -> Result<T, E>
+    where
+        A: FnOnce(T) -> Result<T, E>,
+        E: From<StdError>,
+    {
+        let input = self.load(store, round)?;
+        let output = action(input)?;
+        self.save(store, round, &output)?;
+        Ok(output)
+    }
+}
+
 /// Storage to manage contract configuration.
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Storage for the identifier of the current round of the game.
+pub const CURRENT_ROUND: Item<u64> = Item::new("current_round");
+
 /// Storage for the bid stage info.
-pub const STAGE_BID: Item<Stage> = Item::new("stage_bid");
+pub const STAGE_BID: RoundItem<Stage> = RoundItem::new("stage_bid");
+
+/// Storage to save the number of blocks the bid stage has been extended by late bids.
+pub const BID_STAGE_EXTENSION: RoundItem<u64> = RoundItem::new("bid_stage_extension");
+
+/// Storage for the reveal stage info, set for the rounds whose bids are committed.
+pub const STAGE_REVEAL: RoundItem<Stage> = RoundItem::new("stage_reveal");
 
 /// Storage for the airdrop stage info.
-pub const STAGE_CLAIM_AIRDROP: Item<Stage> = Item::new("stage_claim_airdrop");
+pub const STAGE_CLAIM_AIRDROP: RoundItem<Stage> = RoundItem::new("stage_claim_airdrop");
 
 /// Storage for the claiming prize stage info.
-pub const STAGE_CLAIM_PRIZE: Item<Stage> = Item::new("stage_claim_prize");
+pub const STAGE_CLAIM_PRIZE: RoundItem<Stage> = RoundItem::new("stage_claim_prize");
+
+/// Storage to save the game ticket price.
+pub const TICKET_PRICE: RoundItem<Coin> = RoundItem::new("ticket_price");
 
-/// Storage to save the first game ticket price.
-pub const TICKET_PRICE: Item<Coin> = Item::new("ticket_price");
+/// Storage to save the steps changing the ticket price during the bid stage, ordered
+/// by start.
+pub const PRICE_SCHEDULE: RoundItem<Vec<PriceStep>> = RoundItem::new("price_schedule");
 
 /// Storage to save the number of allowed bins for the game.
-pub const BINS: Item<u8> = Item::new("bins");
+pub const BINS: RoundItem<u8> = RoundItem::new("bins");
 
 /// Storage to manage the bid of each address.
-pub const BIDS: Map<&Addr, u8> = Map::new("bids");
+pub const BIDS: Map<(u64, &Addr), Bid> = Map::new("bids");
+
+/// Storage for the bins of a bid placed on several bins, besides the one saved in
+/// `BIDS`.
+pub const EXTRA_BINS: Map<(u64, &Addr), Vec<u8>> = Map::new("extra_bins");
+
+/// Storage for the bids committed and not yet revealed.
+pub const COMMITMENTS: Map<(u64, &Addr), Commitment> = Map::new("commitments");
+
+/// Storage to save the number of bids placed on each bin.
+pub const BIN_COUNTS: Map<(u64, u8), u64> = Map::new("bin_counts");
+
+/// Storage to save the prize share weight proven by each bidder with the stake Merkle
+/// root. Bidders without a proven weight have a weight of one.
+pub const BID_WEIGHTS: Map<(u64, &Addr), u64> = Map::new("bid_weights");
+
+/// Storage to save the block height at which each bid has been placed.
+pub const BID_HEIGHTS: Map<(u64, &Addr), u64> = Map::new("bid_heights");
+
+/// Storage to save the bonus, taken from the tickets prize, granted to the earliest
+/// bid on the winning bin.
+pub const FIRST_BIDDER_BONUS: RoundItem<Uint128> = RoundItem::new("first_bidder_bonus");
+
+/// Storage to save the maximum number of winners sharing the prize. If none set, every
+/// verified winner shares the prize.
+pub const MAX_WINNERS: RoundItem<u32> = RoundItem::new("max_winners");
+
+/// Storage for the minimum number of bidders of the round, saved only if set.
+pub const MIN_PARTICIPANTS: RoundItem<u32> = RoundItem::new("min_participants");
+
+/// Storage for the winners sharing the prize of a round with capped winners, keyed by
+/// bid height and address, with their prize share weight.
+pub const SELECTED_WINNERS: Map<(u64, u64, &Addr), Uint128> = Map::new("selected_winners");
+
+/// Storage to save if a winner left out by the cap has claimed the consolation or not.
+pub const CONSOLATIONS: Map<(u64, &Addr), bool> = Map::new("consolations");
+
+/// Storage to save if a bidder on a bin adjacent to the winning bin has claimed its
+/// share of the adjacent tier or not.
+pub const ADJACENT_BIDDERS: Map<(u64, &Addr), bool> = Map::new("adjacent_bidders");
+
+/// Storage for the sum of the weights of the bidders on the bins adjacent to the winning
+/// bin.
+pub const ADJACENT_WEIGHT: RoundItem<Uint128> = RoundItem::new("adjacent_weight");
+
+/// Storage for the part of the tickets prize shared by the adjacent tier, set when the
+/// round is finalized.
+pub const ADJACENT_PRIZE: RoundItem<Uint128> = RoundItem::new("adjacent_prize");
+
+/// Storage for the earliest winning bid verified during the airdrop claim.
+pub const FIRST_WINNER: RoundItem<FirstWinner> = RoundItem::new("first_winner");
+
+/// Storage for the native rebate paid with each airdrop claim, saved once the rebate
+/// pool is funded.
+pub const REBATE: RoundItem<Coin> = RoundItem::new("rebate");
+
+/// Storage for the total amount of the rebates paid.
+pub const REBATES_PAID: RoundItem<Uint128> = RoundItem::new("rebates_paid");
+
+/// Storage for the cancellation of the round by the owner, saved only if cancelled.
+pub const CANCELLED: RoundItem<bool> = RoundItem::new("cancelled");
+
+/// Storage for the rollover of the prizes of a round ended without winners, saved only
+/// if rolled over.
+pub const ROLLED_OVER: RoundItem<bool> = RoundItem::new("rolled_over");
+
+/// Storage for the redistribution of the prizes not claimed by the claim deadline, saved
+/// only if redistributed.
+pub const REDISTRIBUTED: RoundItem<bool> = RoundItem::new("redistributed");
+
+/// Owner proposed by the owner, who becomes the owner by accepting the proposal.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwner {
+    pub address: Addr,
+    pub expiry: Option<Expiration>,
+}
+
+/// Storage for the pending transfer of the ownership, removed when accepted or when the
+/// ownership is renounced.
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+
+/// Authority that paused the contract.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PausedBy {
+    Owner,
+    /// The chain governance, through the `sudo` entry point. Only the governance can
+    /// lift its pause.
+    Governance,
+}
+
+/// Storage for the pause of the contract, removed when unpaused.
+pub const PAUSED: Item<PausedBy> = Item::new("paused");
+
+/// Storage for the end of the grace period of the last jackpot rolled over, after which
+/// the owner can withdraw the rollover. Removed when a new round starts.
+pub const ROLLOVER_EXPIRATION: Item<Expiration> = Item::new("rollover_expiration");
 
 /// Storage for the Merkle root of the airdrop.
-pub const MERKLE_ROOT_AIRDROP: Item<String> = Item::new("merkle_root_airdrop");
+pub const MERKLE_ROOT_AIRDROP: RoundItem<String> = RoundItem::new("merkle_root_airdrop");
+
+/// Storage for the Merkle root of the stake weights of the players.
+pub const MERKLE_ROOT_STAKE: RoundItem<String> = RoundItem::new("merkle_root_stake");
+
+/// Storage for the Merkle root of the ticket coupons.
+pub const MERKLE_ROOT_COUPON: RoundItem<String> = RoundItem::new("merkle_root_coupon");
+
+/// Storage for the players that used their ticket coupon.
+pub const COUPONS_USED: Map<(u64, &Addr), bool> = Map::new("coupons_used");
+
+/// Storage for the referrer of the bid of each player.
+pub const REFERRERS: Map<(u64, &Addr), Addr> = Map::new("referrers");
+
+/// Storage for the number of bids referred by each address, of any round.
+pub const REFERRALS: Map<&Addr, u64> = Map::new("referrals");
 
 /// Storage for the Merkle root of the game.
-pub const MERKLE_ROOT_GAME: Item<String> = Item::new("merkle_root_game");
+pub const MERKLE_ROOT_GAME: RoundItem<String> = RoundItem::new("merkle_root_game");
+
+/// Storage for the winning bin of the game, set on chain in place of the game root.
+pub const WINNING_BIN: RoundItem<u8> = RoundItem::new("winning_bin");
+
+/// Storage for the id of the randomness job requested for the round, with the randomness
+/// resolution.
+pub const RANDOMNESS_JOB: RoundItem<String> = RoundItem::new("randomness_job");
+
+/// Storage for the round of the randomness jobs waiting for the callback of the proxy.
+pub const PENDING_JOBS: Map<&str, u64> = Map::new("pending_jobs");
 
-/// Storage for the amount of airdropped tokens claimed.
-/// This variable will consider:
-/// - Amount from simple airdrop.
-/// - Amount airdropped to winners of the first game.
-pub const CLAIMED_AIRDROP_AMOUNT: Item<Uint128> = Item::new("claimed_amount");
+/// Storage for the amount of the prize coming from the tickets donated by winners.
+pub const DONATED_PRIZE_AMOUNT: RoundItem<Uint128> = RoundItem::new("donated_prize");
 
-/// Storage for the amount of the prize coming from the tickets claimed.
-pub const CLAIMED_PRIZE_AMOUNT: Item<Uint128> = Item::new("claimed_prize");
+/// Storage for the amount of the prize coming from the airdrop donated by winners.
+pub const DONATED_AIRDROP_AMOUNT: RoundItem<Uint128> = RoundItem::new("donated_airdrop");
 
-/// Storage to save the number of winning addresses.
-pub const WINNERS: Item<Uint128> = Item::new("winners");
+/// Number of addresses in the airdrop snapshot, as declared by the owner.
+pub const EXPECTED_RECIPIENTS: RoundItem<u64> = RoundItem::new("expected_recipients");
 
-/// Storage to keep track of the total prize from game tickets.
-pub const TOTAL_TICKET_PRIZE: Item<Uint128> = Item::new("total_ticket_prize");
+/// Storage to save the number of airdrop claims made.
+pub const AIRDROP_CLAIMS: RoundItem<u64> = RoundItem::new("airdrop_claims");
+
+/// Storage to save the number of prize claims made.
+pub const PRIZE_CLAIMS: RoundItem<Uint128> = RoundItem::new("prize_claims");
+
+/// Storage to save the number of winning addresses. Winners are added by the airdrop
+/// claims only, so the number is final once the claim airdrop stage ends.
+pub const WINNERS: RoundItem<Uint128> = RoundItem::new("winners");
+
+/// Storage for the number of winners of the round, saved when the round is finalized.
+pub const FINALIZED_WINNERS: RoundItem<Uint128> = RoundItem::new("finalized_winners");
+
+/// Storage for the prize of each winner of the round, saved when the round is finalized.
+pub const FINAL_SHARES: Map<(u64, &Addr), FinalShare> = Map::new("final_shares");
+
+/// Storage to save the sum of the prize share weights of the winning addresses.
+pub const WINNERS_WEIGHT: RoundItem<Uint128> = RoundItem::new("winners_weight");
 
 /// Total amount of tokens for the plain airdrop.
-pub const TOTAL_AIRDROP_AMOUNT: Item<Uint128> = Item::new("total_amount_airdrop");
+pub const TOTAL_AIRDROP_AMOUNT: RoundItem<Uint128> = RoundItem::new("total_amount_airdrop");
 
 /// Total amount of tokens for the airdrop of the game winners.
-pub const TOTAL_AIRDROP_GAME_AMOUNT: Item<Uint128> = Item::new("total_amount_game");
+pub const TOTAL_AIRDROP_GAME_AMOUNT: RoundItem<Uint128> = RoundItem::new("total_amount_game");
+
+/// Tokens of the basket of the game prize of each round, besides the airdrop token.
+pub const PRIZE_TOKENS: RoundItem<Vec<PrizeToken>> = RoundItem::new("prize_tokens");
+
+/// Storage for the sponsorships of each round, keyed by sponsor.
+pub const SPONSORSHIPS: Map<(u64, &Addr), Sponsorship> = Map::new("sponsorships");
+
+/// Storage for the donations to the prize pool of each round, keyed by contributor.
+pub const CONTRIBUTIONS: Map<(u64, &Addr), Contribution> = Map::new("contributions");
+
+/// Storage for the inbox of the notices of each address, oldest first.
+pub const NOTICES: Map<&Addr, Vec<Notice>> = Map::new("notices");
+
+/// Storage for the prizes of each address vesting, of any round.
+pub const VESTINGS: Map<&Addr, Vec<Vesting>> = Map::new("vestings");
 
 /// Storage to save if an address has claimed the airdrop or not.
-pub const CLAIM_AIRDROP: Map<&Addr, bool> = Map::new("CLAIM_AIRDROP_PREFIX");
+pub const CLAIM_AIRDROP: Map<(u64, &Addr), bool> = Map::new("CLAIM_AIRDROP_PREFIX");
+
+/// Struct to store the proofs of an airdrop claim registered in parts.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ClaimProofs {
+    pub proof_airdrop: Vec<String>,
+    pub proof_game: Vec<String>,
+}
+
+/// Bid placed with an IBC packet, whose tickets are escrowed by the sender on its chain.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoteBid {
+    pub bin: u8,
+    pub tickets: u32,
+}
+
+/// Storage for the bids placed with IBC packets, by round, channel and player. The player
+/// is an address of the sender chain, not validated on this one.
+pub const REMOTE_BIDS: Map<(u64, &str, &str), RemoteBid> = Map::new("remote_bids_by_channel");
+
+/// Storage for the proofs registered by each address ahead of its airdrop claim.
+pub const CLAIM_PROOFS: Map<(u64, &Addr), ClaimProofs> = Map::new("claim_proofs");
 
 /// Storage to save if a winning address has claimed the prize or not.
-pub const CLAIM_PRIZE: Map<&Addr, bool> = Map::new("claim_prize");
\ No newline at end of file
+pub const CLAIM_PRIZE: Map<(u64, &Addr), bool> = Map::new("claim_prize");
+
+/// Storage for the bin with which each winner won the round.
+pub const WINNING_BINS: Map<(u64, &Addr), u8> = Map::new("winning_bins");
+
+/// Storage for the addresses blocked by the owner, which can neither bid nor claim.
+pub const BLOCKLIST: Map<&Addr, bool> = Map::new("blocklist");
+
+/// Storage for the IBC voucher denoms accepted by the owner as ticket.
+pub const IBC_DENOMS: Map<&str, bool> = Map::new("ibc_denoms");
+
+/// Storage for the loyalty points of each address, one for each round it played.
+pub const LOYALTY_POINTS: Map<&Addr, u64> = Map::new("loyalty_points");
+
+/// Storage for the loyalty tiers set by the owner, by ascending points.
+pub const LOYALTY_TIERS: Item<Vec<LoyaltyTier>> = Item::new("loyalty_tiers");
+
+/// Storage for the payout multipliers set by the owner for the bins of each round, by
+/// round and bin. A bin without a multiplier has a multiplier of one.
+pub const BIN_MULTIPLIERS: Map<(u64, u8), Decimal> = Map::new("bin_multipliers_by_round");
+
+/// Storage for the prize share weight of each winner, when its bid weight is multiplied
+/// by the multiplier of the winning bin.
+pub const WINNER_WEIGHTS: Map<(u64, &Addr), Uint128> = Map::new("winner_weights");
+
+/// Storage for the entries left of the season passes of each address.
+pub const SEASON_PASSES: Map<&Addr, SeasonPassBalance> = Map::new("season_passes");
\ No newline at end of file
@@ -0,0 +1,186 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{Addr, BlockInfo, Coin, Empty, Uint128};
+use cw20::{Cw20Coin, Cw20Contract};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::{Duration, Scheduled};
+
+use crate::contract::{execute, instantiate, query, sudo};
+use crate::msg::InstantiateMsg;
+use crate::state::Stage;
+
+// ======================================================================================
+// Test harness
+// ======================================================================================
+// cw-multi-test fixtures of the game, exported with the `test-utils` feature so that
+// the crates integrating with the game can test against it.
+
+/// Create the app at the height before the bid stage of `valid_stages` starts.
+#[allow(clippy::needless_return)]
+pub fn mock_app() -> App {
+    let mut app = App::default();
+    let current_block = app.block_info();
+    app.set_block(BlockInfo {
+        height: 199_999,
+        time: current_block.time,
+        chain_id: current_block.chain_id,
+    });
+    return app;
+}
+
+/// Build the bid, claim airdrop and claim prize stages of a game, starting at the
+/// heights 200_000, 201_000 and 202_000 and lasting two blocks each.
+#[allow(clippy::needless_return)]
+pub fn valid_stages() -> (Stage, Stage, Stage) {
+    let stage_bid = Stage {
+        start: Scheduled::AtHeight(200_000),
+        duration: Duration::Height(2),
+    };
+
+    let stage_claim_airdrop = Stage {
+        start: Scheduled::AtHeight(201_000),
+        duration: Duration::Height(2),
+    };
+
+    let stage_claim_prize = Stage {
+        start: Scheduled::AtHeight(202_000),
+        duration: Duration::Height(2),
+    };
+
+    return (stage_bid, stage_claim_airdrop, stage_claim_prize);
+}
+
+/// Create the game contract.
+pub fn contract_game() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute, instantiate, query).with_sudo(sudo);
+    Box::new(contract)
+}
+
+/// Create the token contract.
+pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+/// Build the game instantiation message with all the optional features disabled.
+pub fn game_instantiate_msg(
+    ticket_price: Coin,
+    bins: u8,
+    stage_bid: Stage,
+    stage_claim_airdrop: Stage,
+    stage_claim_prize: Stage,
+    cw20_token: Option<String>,
+) -> InstantiateMsg {
+    InstantiateMsg {
+        owner: Some("owner0000".to_string()),
+        cw20_token_address: cw20_token.unwrap_or("random0000".to_string()),
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        first_bidder_bonus: None,
+        max_winners: None,
+        min_participants: None,
+        price_schedule: None,
+        stage_reveal: None,
+        charity_address: None,
+        address_prefix: None,
+        ticket_cw20_address: None,
+        max_claim_size: None,
+        max_bids: None,
+        max_per_bin: None,
+        parimutuel: None,
+        anti_snipe: None,
+        remove_bid_penalty_bps: None,
+        max_bid_changes: None,
+        bid_change_fee: None,
+        prize_denom: None,
+        prize_vesting: None,
+        staking_contract: None,
+        rollover_grace_period: None,
+        crank_bounty: None,
+        dust_policy: None,
+        protocol_fee_bps: None,
+        fee_collector: None,
+        revenue_split: None,
+        burn_unclaimed: None,
+        claim_deadline: None,
+        merkle_root_allowlist: None,
+        bid_token_gate: None,
+        bid_nft_gate: None,
+        referral_fee_bps: None,
+        season_pass: None,
+        bid_receipts: None,
+        winner_badges: None,
+        attestor: None,
+        resolution: None,
+        adjacent_tier_bps: None,
+        bin_boundaries: None,
+        ibc_denoms: None,
+        ibc_bidder_port: None,
+        hash_algorithm: None,
+    }
+}
+
+/// Instantiate the game contract from a custom instantiation message.
+pub fn create_game_with_msg(
+    router: &mut App,
+    owner: &Addr,
+    msg: &InstantiateMsg,
+) -> AnyResult<Addr> {
+    let game_id = router.store_code(contract_game());
+    router.instantiate_contract(game_id, owner.clone(), msg, &[], "game", None)
+}
+
+/// Instantiate the game contract.
+#[allow(clippy::too_many_arguments)]
+pub fn create_game(
+    router: &mut App,
+    owner: &Addr,
+    ticket_price: Coin,
+    bins: u8,
+    stage_bid: Stage,
+    stage_claim_airdrop: Stage,
+    stage_claim_prize: Stage,
+    cw20_token: Option<String>,
+) -> AnyResult<Addr> {
+    let msg = game_instantiate_msg(
+        ticket_price,
+        bins,
+        stage_bid,
+        stage_claim_airdrop,
+        stage_claim_prize,
+        cw20_token,
+    );
+    create_game_with_msg(router, owner, &msg)
+}
+
+/// Instantiate the token contract.
+pub fn create_cw20(
+    router: &mut App,
+    owner: &Addr,
+    name: String,
+    symbol: String,
+    balance: Uint128,
+) -> Cw20Contract {
+    let cw20_id = router.store_code(contract_cw20());
+    let msg = cw20_base::msg::InstantiateMsg {
+        name,
+        symbol,
+        decimals: 6,
+        initial_balances: vec![Cw20Coin {
+            address: owner.to_string(),
+            amount: balance,
+        }],
+        mint: None,
+        marketing: None,
+    };
+    let addr = router
+        .instantiate_contract(cw20_id, owner.clone(), &msg, &[], "TOKEN", None)
+        .unwrap();
+    Cw20Contract(addr)
+}
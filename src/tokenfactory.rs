@@ -0,0 +1,119 @@
+use cosmwasm_std::{Addr, Coin, CosmosMsg};
+
+use crate::error::ContractError;
+
+// ======================================================================================
+// TokenFactory messages
+// ======================================================================================
+/// Returns the denom created by the contract for the subdenom.
+pub fn factory_denom(contract: &Addr, subdenom: &str) -> String {
+    format!("factory/{}/{}", contract, subdenom)
+}
+
+/// Returns the message creating the subdenom of the contract. Requires the
+/// `tokenfactory` feature.
+pub fn create_denom_msg(sender: &Addr, subdenom: &str) -> Result<CosmosMsg, ContractError> {
+    #[cfg(feature = "tokenfactory")]
+    {
+        let msg = proto::MsgCreateDenom {
+            sender: sender.to_string(),
+            subdenom: subdenom.to_string(),
+        };
+        Ok(proto::stargate_msg(proto::MSG_CREATE_DENOM, &msg))
+    }
+    #[cfg(not(feature = "tokenfactory"))]
+    {
+        let _ = (sender, subdenom);
+        Err(ContractError::TokenFactoryDisabled {})
+    }
+}
+
+/// Returns the message minting the amount of a denom created by the contract to the
+/// contract itself. Requires the `tokenfactory` feature.
+pub fn mint_msg(sender: &Addr, amount: Coin) -> Result<CosmosMsg, ContractError> {
+    #[cfg(feature = "tokenfactory")]
+    {
+        let msg = proto::MsgMint {
+            sender: sender.to_string(),
+            amount: Some(proto::Coin {
+                denom: amount.denom,
+                amount: amount.amount.to_string(),
+            }),
+        };
+        Ok(proto::stargate_msg(proto::MSG_MINT, &msg))
+    }
+    #[cfg(not(feature = "tokenfactory"))]
+    {
+        let _ = (sender, amount);
+        Err(ContractError::TokenFactoryDisabled {})
+    }
+}
+
+/// Protobuf encoding of the TokenFactory messages, as defined by the Osmosis module and
+/// its forks.
+#[cfg(feature = "tokenfactory")]
+mod proto {
+    use cosmwasm_std::{Binary, CosmosMsg};
+
+    pub const MSG_CREATE_DENOM: &str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
+    pub const MSG_MINT: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgCreateDenom {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(string, tag = "2")]
+        pub subdenom: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgMint {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(message, optional, tag = "2")]
+        pub amount: Option<Coin>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Coin {
+        #[prost(string, tag = "1")]
+        pub denom: String,
+        #[prost(string, tag = "2")]
+        pub amount: String,
+    }
+
+    pub fn stargate_msg(type_url: &str, msg: &impl prost::Message) -> CosmosMsg {
+        CosmosMsg::Stargate {
+            type_url: type_url.to_string(),
+            value: Binary::from(msg.encode_to_vec()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tokenfactory"))]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Binary};
+
+    #[test]
+    fn messages_are_protobuf_encoded() {
+        let contract = Addr::unchecked("game");
+        let msg = create_denom_msg(&contract, "prize").unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Stargate {
+                type_url: "/osmosis.tokenfactory.v1beta1.MsgCreateDenom".to_string(),
+                value: Binary::from(b"\x0a\x04game\x12\x05prize".to_vec()),
+            }
+        );
+
+        let msg = mint_msg(&contract, coin(7, factory_denom(&contract, "p"))).unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Stargate {
+                type_url: "/osmosis.tokenfactory.v1beta1.MsgMint".to_string(),
+                value: Binary::from(b"\x0a\x04game\x12\x13\x0a\x0efactory/game/p\x12\x017".to_vec()),
+            }
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use cosmwasm_std::{Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+// ======================================================================================
+// Buckets
+// ======================================================================================
+/// Earmarked bucket of the funds held by the contract. Every amount received is
+/// credited to a bucket and every amount paid is debited from one, so that the funds
+/// earmarked for a purpose cannot be paid for another.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Bucket {
+    /// Tickets paid by the players, shared by the winners.
+    TicketPot,
+    /// Tickets given back to the winners left out by the cap on the winners, and the part
+    /// of the tickets prize shared by the bidders on the bins adjacent to the winning bin.
+    Consolations,
+    /// Tokens of the plain airdrop.
+    AirdropReserve,
+    /// Tokens of the airdrop reserved to the winners of the game.
+    GameReserve,
+    /// Fees charged by the contract.
+    Fees,
+    /// Tokens deposited by the sponsors to match the ticket sales.
+    Sponsorships,
+    /// Native tokens funded by the owner to rebate the fees of the airdrop claims.
+    Rebates,
+    /// Donations kept for the next game. The rollover is not tied to a round: it is
+    /// added to the prizes of the next round started.
+    Rollover,
+    /// Remainders of the prizes left by rounding down the shares of the winners.
+    Dust,
+    /// Prizes claimed by the winners, released as they vest.
+    Vesting,
+    /// Season passes paid up front, staked on the bids as their entries are used. The
+    /// season passes are not tied to a round.
+    SeasonPasses,
+}
+
+impl Bucket {
+    const ALL: [Bucket; 11] = [
+        Bucket::TicketPot,
+        Bucket::Consolations,
+        Bucket::AirdropReserve,
+        Bucket::GameReserve,
+        Bucket::Fees,
+        Bucket::Sponsorships,
+        Bucket::Rebates,
+        Bucket::Rollover,
+        Bucket::Dust,
+        Bucket::Vesting,
+        Bucket::SeasonPasses,
+    ];
+
+    fn key(&self) -> &'static str {
+        match self {
+            Bucket::TicketPot => "ticket_pot",
+            Bucket::Consolations => "consolations",
+            Bucket::AirdropReserve => "airdrop_reserve",
+            Bucket::GameReserve => "game_reserve",
+            Bucket::Fees => "fees",
+            Bucket::Sponsorships => "sponsorships",
+            Bucket::Rebates => "rebates",
+            Bucket::Rollover => "rollover",
+            Bucket::Dust => "dust",
+            Bucket::Vesting => "vesting",
+            Bucket::SeasonPasses => "season_passes",
+        }
+    }
+
+    fn from_key(key: &str) -> StdResult<Self> {
+        Bucket::ALL
+            .iter()
+            .copied()
+            .find(|bucket| bucket.key() == key)
+            .ok_or_else(|| StdError::parse_err("Bucket", key))
+    }
+
+    /// Round under which the balance of the bucket is saved.
+    fn round(&self, round: u64) -> u64 {
+        match self {
+            Bucket::Rollover | Bucket::SeasonPasses => SHARED_ROUND,
+            _ => round,
+        }
+    }
+}
+
+/// Rounds start from one, the buckets shared by the rounds are saved under round zero.
+const SHARED_ROUND: u64 = 0;
+
+/// Storage for the balances of the buckets, keyed by round, bucket and asset. Assets are
+/// identified by the native denom or by the cw20 address.
+const BALANCES: Map<(u64, &str, &str), Uint128> = Map::new("treasury_balances");
+
+/// Storage for the amounts paid as prizes out of the buckets, keyed as the balances. The
+/// amounts claimed are read from here rather than tracked aside from the balances.
+const PAID: Map<(u64, &str, &str), Uint128> = Map::new("treasury_paid");
+
+// ======================================================================================
+// Debit and credit
+// ======================================================================================
+/// Returns the balance of an asset in a bucket of the round.
+pub fn balance(storage: &dyn Storage, round: u64, bucket: Bucket, asset: &str) -> StdResult<Uint128> {
+    let key = (bucket.round(round), bucket.key(), asset);
+    Ok(BALANCES.may_load(storage, key)?.unwrap_or_default())
+}
+
+/// Adds an amount of an asset to a bucket of the round.
+pub fn credit(
+    storage: &mut dyn Storage,
+    round: u64,
+    bucket: Bucket,
+    asset: &str,
+    amount: Uint128,
+) -> StdResult<()> {
+    let new_balance = balance(storage, round, bucket, asset)?.checked_add(amount)?;
+    save_balance(storage, round, bucket, asset, new_balance)
+}
+
+/// Removes an amount of an asset from a bucket of the round. Fails if the bucket does
+/// not hold the amount.
+pub fn debit(
+    storage: &mut dyn Storage,
+    round: u64,
+    bucket: Bucket,
+    asset: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let balance = balance(storage, round, bucket, asset)?;
+    let new_balance = balance
+        .checked_sub(amount)
+        .map_err(|_| ContractError::BucketOverdrawn {
+            bucket: bucket.key().to_string(),
+            asset: asset.to_string(),
+            balance,
+            amount,
+        })?;
+    Ok(save_balance(storage, round, bucket, asset, new_balance)?)
+}
+
+/// Removes an amount of an asset paid as a prize from a bucket of the round, recording it
+/// as paid. Fails if the bucket does not hold the amount.
+pub fn pay(
+    storage: &mut dyn Storage,
+    round: u64,
+    bucket: Bucket,
+    asset: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    debit(storage, round, bucket, asset, amount)?;
+    if !amount.is_zero() {
+        let key = (bucket.round(round), bucket.key(), asset);
+        let paid = paid(storage, round, bucket, asset)?.checked_add(amount).map_err(StdError::from)?;
+        PAID.save(storage, key, &paid)?;
+    }
+    Ok(())
+}
+
+/// Returns the amount of an asset paid as prizes out of a bucket of the round.
+pub fn paid(storage: &dyn Storage, round: u64, bucket: Bucket, asset: &str) -> StdResult<Uint128> {
+    let key = (bucket.round(round), bucket.key(), asset);
+    Ok(PAID.may_load(storage, key)?.unwrap_or_default())
+}
+
+/// Removes the whole balance of an asset from a bucket of the round, returning it.
+pub fn drain(storage: &mut dyn Storage, round: u64, bucket: Bucket, asset: &str) -> StdResult<Uint128> {
+    let balance = balance(storage, round, bucket, asset)?;
+    save_balance(storage, round, bucket, asset, Uint128::zero())?;
+    Ok(balance)
+}
+
+/// Moves an amount of an asset between two buckets of the round.
+pub fn transfer(
+    storage: &mut dyn Storage,
+    round: u64,
+    from: Bucket,
+    to: Bucket,
+    asset: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    debit(storage, round, from, asset, amount)?;
+    Ok(credit(storage, round, to, asset, amount)?)
+}
+
+/// Returns the non empty balances of the round, ordered by bucket and asset, followed
+/// by the ones of the buckets shared by the rounds, as (bucket, asset, amount).
+pub fn balances(storage: &dyn Storage, round: u64) -> StdResult<Vec<(Bucket, String, Uint128)>> {
+    let mut balances = vec![];
+    for round in [round, SHARED_ROUND] {
+        let mut round_balances = BALANCES
+            .sub_prefix(round)
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| {
+                let ((bucket, asset), amount) = item?;
+                Ok((Bucket::from_key(&bucket)?, asset, amount))
+            })
+            .filter(|item| !matches!(item, Ok((bucket, _, _)) if bucket.round(round) != round))
+            .collect::<StdResult<Vec<_>>>()?;
+        round_balances.sort();
+        balances.append(&mut round_balances);
+    }
+    Ok(balances)
+}
+
+/// Returns the non empty balances of the rollover, ordered by asset, as (asset, amount).
+pub fn rollover(storage: &dyn Storage) -> StdResult<Vec<(String, Uint128)>> {
+    BALANCES
+        .prefix((SHARED_ROUND, Bucket::Rollover.key()))
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn save_balance(
+    storage: &mut dyn Storage,
+    round: u64,
+    bucket: Bucket,
+    asset: &str,
+    balance: Uint128,
+) -> StdResult<()> {
+    let key = (bucket.round(round), bucket.key(), asset);
+    if balance.is_zero() {
+        BALANCES.remove(storage, key);
+        Ok(())
+    } else {
+        BALANCES.save(storage, key, &balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn debit_and_credit() {
+        let mut storage = MockStorage::new();
+        credit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(30)).unwrap();
+        debit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(10)).unwrap();
+        assert_eq!(balance(&storage, 1, Bucket::TicketPot, "ujuno").unwrap(), Uint128::new(20));
+
+        // Buckets are separated by round, bucket and asset.
+        assert!(balance(&storage, 2, Bucket::TicketPot, "ujuno").unwrap().is_zero());
+        assert!(balance(&storage, 1, Bucket::Dust, "ujuno").unwrap().is_zero());
+        assert!(balance(&storage, 1, Bucket::TicketPot, "ubtc").unwrap().is_zero());
+
+        // A bucket cannot pay more than it holds.
+        let err = debit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(21)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BucketOverdrawn {
+                bucket: "ticket_pot".to_string(),
+                asset: "ujuno".to_string(),
+                balance: Uint128::new(20),
+                amount: Uint128::new(21),
+            }
+        );
+        let err = debit(&mut storage, 1, Bucket::Dust, "ujuno", Uint128::new(1)).unwrap_err();
+        assert!(matches!(err, ContractError::BucketOverdrawn { .. }));
+
+        credit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::MAX - Uint128::new(20)).unwrap();
+        assert!(credit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(1)).is_err());
+    }
+
+    #[test]
+    fn transfer_and_drain() {
+        let mut storage = MockStorage::new();
+        credit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(30)).unwrap();
+        transfer(&mut storage, 1, Bucket::TicketPot, Bucket::Dust, "ujuno", Uint128::new(5)).unwrap();
+        assert!(transfer(&mut storage, 1, Bucket::Dust, Bucket::TicketPot, "ujuno", Uint128::new(6)).is_err());
+
+        assert_eq!(drain(&mut storage, 1, Bucket::TicketPot, "ujuno").unwrap(), Uint128::new(25));
+        assert_eq!(drain(&mut storage, 1, Bucket::TicketPot, "ujuno").unwrap(), Uint128::zero());
+        assert_eq!(
+            balances(&storage, 1).unwrap(),
+            vec![(Bucket::Dust, "ujuno".to_string(), Uint128::new(5))]
+        );
+    }
+
+    #[test]
+    fn pay_records_the_paid_amounts() {
+        let mut storage = MockStorage::new();
+        credit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(30)).unwrap();
+        pay(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(10)).unwrap();
+        pay(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(5)).unwrap();
+        debit(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(5)).unwrap();
+        assert_eq!(balance(&storage, 1, Bucket::TicketPot, "ujuno").unwrap(), Uint128::new(10));
+        assert_eq!(paid(&storage, 1, Bucket::TicketPot, "ujuno").unwrap(), Uint128::new(15));
+        assert!(paid(&storage, 2, Bucket::TicketPot, "ujuno").unwrap().is_zero());
+
+        // Nothing is recorded if the bucket cannot pay.
+        assert!(pay(&mut storage, 1, Bucket::TicketPot, "ujuno", Uint128::new(11)).is_err());
+        assert_eq!(paid(&storage, 1, Bucket::TicketPot, "ujuno").unwrap(), Uint128::new(15));
+    }
+
+    #[test]
+    fn rollover_is_shared_by_the_rounds() {
+        let mut storage = MockStorage::new();
+        credit(&mut storage, 1, Bucket::Rollover, "ujuno", Uint128::new(7)).unwrap();
+        credit(&mut storage, 1, Bucket::GameReserve, "token", Uint128::new(3)).unwrap();
+        assert_eq!(balance(&storage, 2, Bucket::Rollover, "ujuno").unwrap(), Uint128::new(7));
+
+        assert_eq!(
+            balances(&storage, 2).unwrap(),
+            vec![(Bucket::Rollover, "ujuno".to_string(), Uint128::new(7))]
+        );
+        credit(&mut storage, 2, Bucket::Rollover, "token", Uint128::new(2)).unwrap();
+        assert_eq!(
+            rollover(&storage).unwrap(),
+            vec![("token".to_string(), Uint128::new(2)), ("ujuno".to_string(), Uint128::new(7))]
+        );
+        drain(&mut storage, 1, Bucket::Rollover, "token").unwrap();
+        debit(&mut storage, 2, Bucket::Rollover, "ujuno", Uint128::new(7)).unwrap();
+        assert_eq!(
+            balances(&storage, 1).unwrap(),
+            vec![(Bucket::GameReserve, "token".to_string(), Uint128::new(3))]
+        );
+    }
+
+    #[test]
+    fn season_passes_are_shared_by_the_rounds() {
+        let mut storage = MockStorage::new();
+        credit(&mut storage, 1, Bucket::SeasonPasses, "ujuno", Uint128::new(30)).unwrap();
+        transfer(&mut storage, 2, Bucket::SeasonPasses, Bucket::TicketPot, "ujuno", Uint128::new(10)).unwrap();
+        assert_eq!(
+            balances(&storage, 2).unwrap(),
+            vec![
+                (Bucket::TicketPot, "ujuno".to_string(), Uint128::new(10)),
+                (Bucket::SeasonPasses, "ujuno".to_string(), Uint128::new(20)),
+            ]
+        );
+        assert!(rollover(&storage).unwrap().is_empty());
+    }
+}
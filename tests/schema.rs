@@ -0,0 +1,876 @@
+//! Checks the messages against the schema files exported in `./schema`: the committed
+//! files must match the current types, and randomly generated messages must survive a
+//! JSON round trip and validate against them.
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use cosmwasm_schema::schema_for;
+use cosmwasm_std::{from_binary, to_binary, Addr, Binary, Coin, Decimal, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::{Duration, Expiration, Scheduled};
+use jsonschema::JSONSchema;
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use wasmgame_contracts::msg::{
+    BidResponse, BinCountsResponse, ClaimStage, Coupon, ClaimableAmountsResponse, ClaimedAirdropsResponse,
+    ConfigResponse, ExecuteMsg, GameAmountsResponse, GameStatusResponse, InstantiateMsg,
+    IsClaimedResponse, LimitsResponse, MerkleRootsResponse, NoticesResponse, OutstandingResponse,
+    OwnershipAction, OwnershipResponse, PlayerAction, PrizeTokenAmount, QueryMsg, RebatesResponse, ReceiveMsg, RoundResponse,
+    RoundStage, SponsorshipsResponse, StagesResponse, StakingReceiveMsg, SudoMsg, TicketPriceResponse,
+    TokenGate, TreasuryResponse, VestingResponse, BlocklistResponse, ReferralsResponse,
+    LoyaltyPointsResponse, LoyaltyTiersResponse, SeasonPassResponse, PriceOracle, Raffle, Resolution,
+    BinMultipliersResponse, BinBoundariesResponse, OddsResponse, ContributorsResponse, IbcDenomsResponse,
+    MerkleRootKind, VerifyProofResponse, RemoteWinnersResponse,
+};
+use wasmgame_contracts::ibc::{IbcBidAck, IbcBidPacket};
+use wasmgame_contracts::merkle::HashAlgorithm;
+use wasmgame_contracts::nois::NoisCallback;
+use wasmgame_contracts::state::{AntiSnipe, Config, DustPolicy, LoyaltyTier, PriceStep, SeasonPass, Stage};
+
+// ======================================================================================
+// Schema files
+// ======================================================================================
+fn schema_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schema")
+}
+
+fn read_schema(name: &str) -> Value {
+    let path = schema_dir().join(format!("{}.json", name));
+    let file = fs::read(&path).unwrap_or_else(|_| panic!("missing schema file {:?}", path));
+    serde_json::from_slice(&file).unwrap()
+}
+
+/// Returns the schemas generated from the current types, as exported by
+/// `examples/schema.rs`, keyed by file name.
+fn current_schemas() -> Vec<(String, Value)> {
+    macro_rules! schemas {
+        ($($type:ty),* $(,)?) => {
+            vec![$({
+                let schema = schema_for!($type);
+                let name = schema.schema.metadata.as_ref().unwrap().title.clone().unwrap();
+                (to_snake_case(&name), serde_json::to_value(&schema).unwrap())
+            }),*]
+        };
+    }
+    schemas![
+        Config,
+        Stage,
+        InstantiateMsg,
+        ExecuteMsg,
+        QueryMsg,
+        ReceiveMsg,
+        StakingReceiveMsg,
+        SudoMsg,
+        IbcBidPacket,
+        IbcBidAck,
+        ConfigResponse,
+        OwnershipResponse,
+        LimitsResponse,
+        RoundResponse,
+        StagesResponse,
+        GameStatusResponse,
+        BidResponse,
+        BinCountsResponse,
+        OddsResponse,
+        MerkleRootsResponse,
+        GameAmountsResponse,
+        OutstandingResponse,
+        TreasuryResponse,
+        ClaimedAirdropsResponse,
+        IsClaimedResponse,
+        ClaimableAmountsResponse,
+        SponsorshipsResponse,
+        ContributorsResponse,
+        RebatesResponse,
+        TicketPriceResponse,
+        NoticesResponse,
+        VestingResponse,
+        BlocklistResponse,
+        IbcDenomsResponse,
+        ReferralsResponse,
+        LoyaltyPointsResponse,
+        LoyaltyTiersResponse,
+        SeasonPassResponse,
+        BinMultipliersResponse,
+        BinBoundariesResponse,
+        VerifyProofResponse,
+        RemoteWinnersResponse,
+    ]
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+#[test]
+fn schema_files_match_the_types() {
+    let schemas = current_schemas();
+    for (name, schema) in &schemas {
+        assert_eq!(&read_schema(name), schema, "{}.json is outdated, run `cargo schema`", name);
+    }
+
+    let exported: BTreeSet<String> = fs::read_dir(schema_dir())
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            path.file_stem().unwrap().to_string_lossy().into_owned()
+        })
+        .collect();
+    let expected: BTreeSet<String> = schemas.into_iter().map(|(name, _)| name).collect();
+    assert_eq!(exported, expected);
+}
+
+#[test]
+fn every_query_has_a_response_schema() {
+    let query_msg = read_schema("query_msg");
+    for variant in query_msg["oneOf"].as_array().unwrap() {
+        let query = variant["required"][0].as_str().unwrap();
+        let path = schema_dir().join(format!("{}_response.json", query));
+        assert!(path.exists(), "no response schema for the {} query", query);
+    }
+}
+
+// ======================================================================================
+// Round trips
+// ======================================================================================
+fn compiled_schema(cell: &'static OnceLock<JSONSchema>, name: &str) -> &'static JSONSchema {
+    cell.get_or_init(|| JSONSchema::compile(&read_schema(name)).unwrap())
+}
+
+/// Serializes the message as the contract does, checks that it is deserialized back to
+/// the same message and that its JSON validates against the schema.
+fn check_round_trip<T>(msg: &T, schema: &JSONSchema) -> Result<(), TestCaseError>
+where
+    T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let binary = to_binary(msg).unwrap();
+    let decoded: T = from_binary(&binary).unwrap();
+    prop_assert_eq!(&decoded, msg);
+
+    let json: Value = serde_json::from_slice(binary.as_slice()).unwrap();
+    if let Err(errors) = schema.validate(&json) {
+        let errors: Vec<String> = errors.map(|e| format!("{} at {}", e, e.instance_path)).collect();
+        return Err(TestCaseError::fail(format!("{} does not match the schema: {:?}", json, errors)));
+    }
+    Ok(())
+}
+
+fn round() -> impl Strategy<Value = Option<u64>> {
+    proptest::option::of(any::<u64>())
+}
+
+fn uint128() -> impl Strategy<Value = Uint128> {
+    any::<u128>().prop_map(Uint128::new)
+}
+
+fn address() -> impl Strategy<Value = String> {
+    "[a-z0-9]{1,45}"
+}
+
+fn proof() -> impl Strategy<Value = Vec<String>> {
+    proptest::collection::vec("[0-9a-f]{64}", 0..4)
+}
+
+fn binary(len: usize) -> impl Strategy<Value = Binary> {
+    proptest::collection::vec(any::<u8>(), len).prop_map(Binary::from)
+}
+
+fn prize_token_amount() -> impl Strategy<Value = PrizeTokenAmount> {
+    (address(), uint128()).prop_map(|(token, amount)| PrizeTokenAmount { token, amount })
+}
+
+fn coin() -> impl Strategy<Value = Coin> {
+    ("[a-z]{3,10}", uint128()).prop_map(|(denom, amount)| Coin { denom, amount })
+}
+
+fn scheduled() -> impl Strategy<Value = Scheduled> {
+    prop_oneof![
+        any::<u64>().prop_map(Scheduled::AtHeight),
+        any::<u64>().prop_map(|nanos| Scheduled::AtTime(Timestamp::from_nanos(nanos))),
+    ]
+}
+
+fn stage() -> impl Strategy<Value = Stage> {
+    (scheduled(), duration()).prop_map(|(start, duration)| Stage { start, duration })
+}
+
+fn price_schedule() -> impl Strategy<Value = Option<Vec<PriceStep>>> {
+    proptest::option::of(proptest::collection::vec(
+        (scheduled(), uint128()).prop_map(|(start, amount)| PriceStep { start, amount }),
+        0..3,
+    ))
+}
+
+fn duration() -> impl Strategy<Value = Duration> {
+    prop_oneof![
+        any::<u64>().prop_map(Duration::Height),
+        any::<u64>().prop_map(Duration::Time),
+    ]
+}
+
+fn receive_msg() -> impl Strategy<Value = ReceiveMsg> {
+    prop_oneof![
+        (
+            any::<u8>(),
+            proptest::option::of(any::<u32>()),
+            proptest::option::of(proof()),
+            proptest::option::of(any::<u128>().prop_map(Uint128::new)),
+            round(),
+        )
+            .prop_map(|(bin, tickets, allowlist_proof, prediction, round)| ReceiveMsg::Bid {
+                bin,
+                tickets,
+                allowlist_proof,
+                prediction,
+                round,
+            }),
+        (any::<u128>(), round()).prop_map(|(ratio, round)| ReceiveMsg::Sponsor {
+            ratio: Decimal::raw(ratio),
+            round,
+        }),
+        round().prop_map(|round| ReceiveMsg::FundPrizePool { round }),
+    ]
+}
+
+fn player_action() -> impl Strategy<Value = PlayerAction> {
+    prop_oneof![
+        (any::<u8>(), proptest::option::of(any::<u32>()), round())
+            .prop_map(|(bin, tickets, round)| PlayerAction::Bid { bin, tickets, round }),
+        (any::<u8>(), round()).prop_map(|(bin, round)| PlayerAction::ChangeBid { bin, round }),
+        round().prop_map(|round| PlayerAction::RemoveBid { round }),
+        (any::<u64>(), proof(), round())
+            .prop_map(|(weight, proof, round)| PlayerAction::ProveStake { weight, proof, round }),
+        (uint128(), proof(), proof(), round()).prop_map(
+            |(amount, proof_airdrop, proof_game, round)| PlayerAction::ClaimAirdrop {
+                amount,
+                proof_airdrop,
+                proof_game,
+                round,
+            }
+        ),
+        round().prop_map(|round| PlayerAction::ClaimPrize { round }),
+        (any::<u16>(), round())
+            .prop_map(|(share_bps, round)| PlayerAction::DonatePrize { share_bps, round }),
+    ]
+}
+
+fn anti_snipe() -> impl Strategy<Value = AntiSnipe> {
+    (any::<u64>(), any::<u64>(), any::<u64>()).prop_map(|(window, extension, max_extension)| AntiSnipe {
+        window,
+        extension,
+        max_extension,
+    })
+}
+
+fn dust_policy() -> impl Strategy<Value = DustPolicy> {
+    prop_oneof![Just(DustPolicy::LastClaimer), Just(DustPolicy::Owner), Just(DustPolicy::Burn)]
+}
+
+fn revenue_split() -> impl Strategy<Value = Vec<(String, Decimal)>> {
+    proptest::collection::vec((address(), any::<u128>().prop_map(Decimal::raw)), 1..4)
+}
+
+fn token_gate() -> impl Strategy<Value = TokenGate> {
+    (address(), uint128()).prop_map(|(token, min_balance)| TokenGate { token, min_balance })
+}
+
+fn coupon() -> impl Strategy<Value = Coupon> {
+    (any::<u16>(), proof()).prop_map(|(discount_bps, proof)| Coupon { discount_bps, proof })
+}
+
+fn season_pass() -> impl Strategy<Value = SeasonPass> {
+    (any::<u32>(), coin()).prop_map(|(rounds, price)| SeasonPass { rounds, price })
+}
+
+fn price_oracle() -> impl Strategy<Value = PriceOracle> {
+    (
+        address(),
+        "[A-Z]{3,5}",
+        any::<u128>().prop_map(Decimal::raw),
+        any::<u128>().prop_map(Decimal::raw),
+        proptest::option::of(uint128()),
+    )
+        .prop_map(|(contract, asset, min_price, bin_width, total_amount_game)| PriceOracle {
+            contract,
+            asset,
+            min_price,
+            bin_width,
+            total_amount_game,
+        })
+}
+
+fn raffle() -> impl Strategy<Value = Raffle> {
+    (address(), proptest::option::of(uint128()))
+        .prop_map(|(nois_proxy, total_amount_game)| Raffle { nois_proxy, total_amount_game })
+}
+
+fn resolution() -> impl Strategy<Value = Resolution> {
+    prop_oneof![
+        Just(Resolution::MerkleRoot {}),
+        Just(Resolution::OwnerSetsBin {}),
+        price_oracle().prop_map(Resolution::Oracle),
+        raffle().prop_map(Resolution::Randomness),
+    ]
+}
+
+fn nois_callback() -> impl Strategy<Value = NoisCallback> {
+    ("round-[0-9]{1,5}", any::<u64>(), "[0-9a-f]{64}").prop_map(|(job_id, published, randomness)| NoisCallback {
+        job_id,
+        published: Timestamp::from_nanos(published),
+        randomness,
+    })
+}
+
+fn loyalty_tier() -> impl Strategy<Value = LoyaltyTier> {
+    (any::<u64>(), any::<u16>()).prop_map(|(min_points, discount_bps)| LoyaltyTier { min_points, discount_bps })
+}
+
+fn ownership_action() -> impl Strategy<Value = OwnershipAction> {
+    prop_oneof![
+        (address(), proptest::option::of(any::<u64>().prop_map(Expiration::AtHeight))).prop_map(
+            |(new_owner, expiry)| OwnershipAction::TransferOwnership { new_owner, expiry }
+        ),
+        Just(OwnershipAction::AcceptOwnership),
+        Just(OwnershipAction::RenounceOwnership),
+    ]
+}
+
+fn instantiate_msg() -> impl Strategy<Value = InstantiateMsg> {
+    (
+        (proptest::option::of(address()), address(), coin(), price_schedule(), any::<u8>()),
+        (stage(), proptest::option::of(stage()), stage(), stage()),
+        (
+            proptest::option::of(uint128()),
+            proptest::option::of(1..=u32::MAX),
+            proptest::option::of(any::<u32>()),
+        ),
+        (
+            proptest::option::of(address()),
+            proptest::option::of("[a-z]{1,10}"),
+            proptest::option::of(address()),
+            proptest::option::of(any::<u32>()),
+        ),
+        (
+            proptest::option::of(1..=u32::MAX),
+            proptest::option::of(1..=u32::MAX),
+            proptest::option::of(any::<bool>()),
+            proptest::option::of(anti_snipe()),
+            proptest::option::of(any::<u16>()),
+        ),
+        (
+            proptest::option::of(any::<u32>()),
+            proptest::option::of(coin()),
+            proptest::option::of("factory/[a-z0-9]{1,45}/[a-z]{1,10}"),
+            proptest::option::of(duration()),
+            proptest::option::of(address()),
+            proptest::option::of(duration()),
+            proptest::option::of(uint128()),
+            proptest::option::of(dust_policy()),
+        ),
+        (
+            proptest::option::of(any::<u16>()),
+            proptest::option::of(address()),
+            proptest::option::of(revenue_split()),
+            proptest::option::of(any::<bool>()),
+            proptest::option::of(duration()),
+            proptest::option::of("[0-9a-f]{64}"),
+            proptest::option::of(token_gate()),
+            proptest::option::of(address()),
+            proptest::option::of(any::<u16>()),
+            proptest::option::of(season_pass()),
+        ),
+        (
+            proptest::option::of(address()),
+            proptest::option::of(address()),
+            proptest::option::of(address()),
+            proptest::option::of(resolution()),
+            proptest::option::of(any::<u16>()),
+            proptest::option::of(proptest::collection::vec(any::<u128>().prop_map(Uint128::new), 0..4)),
+            proptest::option::of(proptest::collection::vec("ibc/[0-9A-F]{64}", 0..3)),
+            proptest::option::of(address().prop_map(|address| format!("wasm.{}", address))),
+            proptest::option::of(prop_oneof![Just(HashAlgorithm::Sha256), Just(HashAlgorithm::Keccak256)]),
+        ),
+    )
+        .prop_map(|(
+            (owner, cw20_token_address, ticket_price, price_schedule, bins),
+            (stage_bid, stage_reveal, stage_claim_airdrop, stage_claim_prize),
+            (first_bidder_bonus, max_winners, min_participants),
+            (charity_address, address_prefix, ticket_cw20_address, max_claim_size),
+            (max_bids, max_per_bin, parimutuel, anti_snipe, remove_bid_penalty_bps),
+            (
+                max_bid_changes,
+                bid_change_fee,
+                prize_denom,
+                prize_vesting,
+                staking_contract,
+                rollover_grace_period,
+                crank_bounty,
+                dust_policy,
+            ),
+            (
+                protocol_fee_bps,
+                fee_collector,
+                revenue_split,
+                burn_unclaimed,
+                claim_deadline,
+                merkle_root_allowlist,
+                bid_token_gate,
+                bid_nft_gate,
+                referral_fee_bps,
+                season_pass,
+            ),
+            (
+                bid_receipts,
+                winner_badges,
+                attestor,
+                resolution,
+                adjacent_tier_bps,
+                bin_boundaries,
+                ibc_denoms,
+                ibc_bidder_port,
+                hash_algorithm,
+            ),
+        )| InstantiateMsg {
+            owner,
+            cw20_token_address,
+            ticket_price,
+            price_schedule,
+            bins,
+            stage_bid,
+            stage_reveal,
+            stage_claim_airdrop,
+            stage_claim_prize,
+            first_bidder_bonus,
+            max_winners,
+            min_participants,
+            charity_address,
+            address_prefix,
+            ticket_cw20_address,
+            max_claim_size,
+            max_bids,
+            max_per_bin,
+            parimutuel,
+            anti_snipe,
+            remove_bid_penalty_bps,
+            max_bid_changes,
+            bid_change_fee,
+            prize_denom,
+            prize_vesting,
+            staking_contract,
+            rollover_grace_period,
+            crank_bounty,
+            dust_policy,
+            protocol_fee_bps,
+            fee_collector,
+            revenue_split,
+            burn_unclaimed,
+            claim_deadline,
+            merkle_root_allowlist,
+            bid_token_gate,
+            bid_nft_gate,
+            referral_fee_bps,
+            season_pass,
+            bid_receipts,
+            winner_badges,
+            attestor,
+            resolution,
+            adjacent_tier_bps,
+            bin_boundaries,
+            ibc_denoms,
+            ibc_bidder_port,
+            hash_algorithm,
+        })
+}
+
+fn execute_msg() -> impl Strategy<Value = ExecuteMsg> {
+    prop_oneof![
+        proptest::option::of(address()).prop_map(|new_owner| ExecuteMsg::UpdateConfig { new_owner }),
+        ownership_action().prop_map(ExecuteMsg::UpdateOwnership),
+        "[a-z]{1,10}".prop_map(|subdenom| ExecuteMsg::CreatePrizeDenom { subdenom }),
+        (
+            (coin(), price_schedule(), any::<u8>()),
+            (stage(), proptest::option::of(stage()), stage(), stage()),
+            (
+                proptest::option::of(uint128()),
+                proptest::option::of(1..=u32::MAX),
+                proptest::option::of(any::<u32>()),
+            ),
+        )
+            .prop_map(|(
+                (ticket_price, price_schedule, bins),
+                (stage_bid, stage_reveal, stage_claim_airdrop, stage_claim_prize),
+                (first_bidder_bonus, max_winners, min_participants),
+            )| ExecuteMsg::StartNewRound {
+                ticket_price,
+                price_schedule,
+                bins,
+                stage_bid,
+                stage_reveal,
+                stage_claim_airdrop,
+                stage_claim_prize,
+                first_bidder_bonus,
+                max_winners,
+                min_participants,
+            }),
+        (stage(), stage(), stage()).prop_map(|(stage_bid, stage_claim_airdrop, stage_claim_prize)| {
+            ExecuteMsg::UpdateStages {
+                stage_bid,
+                stage_claim_airdrop,
+                stage_claim_prize,
+            }
+        }),
+        (
+            prop_oneof![Just(ClaimStage::ClaimAirdrop), Just(ClaimStage::ClaimPrize)],
+            duration(),
+            round(),
+        )
+            .prop_map(|(stage, extra_duration, round)| ExecuteMsg::ExtendStage {
+                stage,
+                extra_duration,
+                round,
+            }),
+        (
+            any::<u8>(),
+            proptest::option::of(any::<u32>()),
+            proptest::option::of(address()),
+            proptest::option::of(proof()),
+            proptest::option::of(coupon()),
+            proptest::option::of(address()),
+            proptest::option::of(any::<u128>().prop_map(Uint128::new)),
+            round(),
+        )
+            .prop_map(|(bin, tickets, recipient, allowlist_proof, coupon, referrer, prediction, round)| ExecuteMsg::Bid {
+                bin,
+                tickets,
+                recipient,
+                allowlist_proof,
+                coupon,
+                referrer,
+                prediction,
+                round,
+            }),
+        (proptest::collection::vec(any::<u8>(), 0..4), round())
+            .prop_map(|(bins, round)| ExecuteMsg::BidMultiple { bins, round }),
+        (address(), any::<u8>(), round())
+            .prop_map(|(recipient, bin, round)| ExecuteMsg::GiftBid { recipient, bin, round }),
+        ("[0-9a-f]{64}", proptest::option::of(proof()), round())
+            .prop_map(|(hash, allowlist_proof, round)| ExecuteMsg::CommitBid { hash, allowlist_proof, round }),
+        (any::<u8>(), "[a-z0-9]{0,16}", round())
+            .prop_map(|(bin, salt, round)| ExecuteMsg::RevealBid { bin, salt, round }),
+        (address(), uint128(), receive_msg()).prop_map(|(sender, amount, msg)| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender,
+                amount,
+                msg: to_binary(&msg).unwrap(),
+            })
+        }),
+        proptest::collection::vec(player_action(), 0..5)
+            .prop_map(|actions| ExecuteMsg::BatchActions { actions }),
+        (any::<u8>(), round()).prop_map(|(bin, round)| ExecuteMsg::ChangeBid { bin, round }),
+        (address(), round())
+            .prop_map(|(new_address, round)| ExecuteMsg::TransferBid { new_address, round }),
+        round().prop_map(|round| ExecuteMsg::RemoveBid { round }),
+        ("[0-9a-f]{64}", round()).prop_map(|(merkle_root_stake, round)| {
+            ExecuteMsg::RegisterStakeRoot { merkle_root_stake, round }
+        }),
+        ("[0-9a-f]{64}", round()).prop_map(|(merkle_root_coupon, round)| {
+            ExecuteMsg::RegisterCouponRoot { merkle_root_coupon, round }
+        }),
+        (any::<u64>(), proof(), round())
+            .prop_map(|(weight, proof, round)| ExecuteMsg::ProveStake { weight, proof, round }),
+        Just(ExecuteMsg::Pause {}),
+        Just(ExecuteMsg::Unpause {}),
+        (proptest::collection::vec(address(), 0..3), proptest::collection::vec(address(), 0..3))
+            .prop_map(|(add, remove)| ExecuteMsg::UpdateBlocklist { add, remove }),
+        Just(ExecuteMsg::BuySeasonPass {}),
+        proptest::collection::vec(loyalty_tier(), 0..4).prop_map(|tiers| ExecuteMsg::SetLoyaltyTiers { tiers }),
+        proptest::collection::vec((any::<u8>(), any::<u128>().prop_map(Decimal::raw)), 0..4)
+            .prop_map(|multipliers| ExecuteMsg::SetBinMultipliers { multipliers }),
+        round().prop_map(|round| ExecuteMsg::CancelGame { round }),
+        round().prop_map(|round| ExecuteMsg::RefundTicket { round }),
+        (
+            proptest::option::of(proptest::collection::vec(address(), 0..4)),
+            proptest::option::of(any::<u32>()),
+            round(),
+        )
+            .prop_map(|(addresses, limit, round)| ExecuteMsg::RefundBids { addresses, limit, round }),
+        (
+            "[0-9a-f]{64}",
+            proptest::option::of(uint128()),
+            "[0-9a-f]{64}",
+            proptest::option::of(uint128()),
+            proptest::option::of(any::<u64>()),
+            proptest::option::of(proptest::collection::vec(prize_token_amount(), 0..3)),
+            round(),
+        )
+            .prop_map(|(
+                merkle_root_airdrop,
+                total_amount_airdrop,
+                merkle_root_game,
+                total_amount_game,
+                expected_recipients,
+                prize_tokens,
+                round,
+            )| ExecuteMsg::RegisterMerkleRoots {
+                merkle_root_airdrop,
+                total_amount_airdrop,
+                merkle_root_game,
+                total_amount_game,
+                expected_recipients,
+                prize_tokens,
+                round,
+            }),
+        (
+            any::<u8>(),
+            proptest::option::of(uint128()),
+            proptest::option::of(proptest::collection::vec(prize_token_amount(), 0..3)),
+            round(),
+        )
+            .prop_map(|(bin, total_amount_game, prize_tokens, round)| ExecuteMsg::SetWinningBin {
+                bin,
+                total_amount_game,
+                prize_tokens,
+                round,
+            }),
+        round().prop_map(|round| ExecuteMsg::ResolveGame { round }),
+        round().prop_map(|round| ExecuteMsg::RequestRandomness { round }),
+        nois_callback().prop_map(|callback| ExecuteMsg::NoisReceive { callback }),
+        (
+            proptest::option::of("[0-9a-f]{64}"),
+            proptest::option::of("[0-9a-f]{64}"),
+            round(),
+        )
+            .prop_map(|(merkle_root_airdrop, merkle_root_game, round)| ExecuteMsg::RotateMerkleRoots {
+                merkle_root_airdrop,
+                merkle_root_game,
+                round,
+            }),
+        (
+            "[0-9a-f]{64}",
+            proptest::option::of(uint128()),
+            proptest::option::of(any::<u64>()),
+            round(),
+        )
+            .prop_map(|(merkle_root_airdrop, total_amount_airdrop, expected_recipients, round)| {
+                ExecuteMsg::RegisterAirdropRoot {
+                    merkle_root_airdrop,
+                    total_amount_airdrop,
+                    expected_recipients,
+                    round,
+                }
+            }),
+        (
+            "[0-9a-f]{64}",
+            proptest::option::of(uint128()),
+            proptest::option::of(proptest::collection::vec(prize_token_amount(), 0..3)),
+            round(),
+        )
+            .prop_map(|(merkle_root_game, total_amount_game, prize_tokens, round)| ExecuteMsg::RegisterGameRoot {
+                merkle_root_game,
+                total_amount_game,
+                prize_tokens,
+                round,
+            }),
+        (uint128(), proof(), proof(), proptest::option::of(address()), round()).prop_map(
+            |(amount, proof_airdrop, proof_game, recipient, round)| ExecuteMsg::ClaimAirdrop {
+                amount,
+                proof_airdrop,
+                proof_game,
+                recipient,
+                round,
+            }
+        ),
+        (address(), receive_msg(), uint128(), proof(), proof(), round()).prop_map(
+            |(contract, msg, amount, proof_airdrop, proof_game, round)| ExecuteMsg::ClaimAirdropTo {
+                contract,
+                msg: to_binary(&msg).unwrap(),
+                amount,
+                proof_airdrop,
+                proof_game,
+                round,
+            }
+        ),
+        (address(), uint128(), proof(), proof(), binary(64), binary(33), round()).prop_map(
+            |(claimer, amount, proof_airdrop, proof_game, signature, pubkey, round)| {
+                ExecuteMsg::ClaimAirdropFor {
+                    claimer,
+                    amount,
+                    proof_airdrop,
+                    proof_game,
+                    signature,
+                    pubkey,
+                    round,
+                }
+            }
+        ),
+        (proof(), proof(), round()).prop_map(|(proof_airdrop, proof_game, round)| {
+            ExecuteMsg::RegisterClaimProofs { proof_airdrop, proof_game, round }
+        }),
+        round().prop_map(|round| ExecuteMsg::ClearClaimProofs { round }),
+        (proptest::option::of(address()), proptest::option::of(any::<bool>()), round()).prop_map(
+            |(recipient, stake, round)| ExecuteMsg::ClaimPrize { recipient, stake, round }
+        ),
+        (uint128(), proof(), proof(), round()).prop_map(
+            |(amount, proof_airdrop, proof_game, round)| ExecuteMsg::ClaimAll {
+                amount,
+                proof_airdrop,
+                proof_game,
+                round,
+            }
+        ),
+        (any::<u16>(), round())
+            .prop_map(|(share_bps, round)| ExecuteMsg::DonatePrize { share_bps, round }),
+        (uint128(), round()).prop_map(|(rebate, round)| ExecuteMsg::FundRebates { rebate, round }),
+        round().prop_map(|round| ExecuteMsg::FundPrizePool { round }),
+        (proptest::collection::vec("ibc/[0-9A-F]{64}", 0..3), proptest::collection::vec("ibc/[0-9A-F]{64}", 0..3))
+            .prop_map(|(add, remove)| ExecuteMsg::UpdateIbcDenoms { add, remove }),
+        round().prop_map(|round| ExecuteMsg::ReclaimSponsorship { round }),
+        Just(ExecuteMsg::AckNotices {}),
+        any::<u8>().prop_map(|bin| ExecuteMsg::ClaimPrizeAndReenter { bin }),
+        round().prop_map(|round| ExecuteMsg::FinalizeGame { round }),
+        round().prop_map(|round| ExecuteMsg::RedistributeUnclaimed { round }),
+        round().prop_map(|round| ExecuteMsg::RollOverJackpot { round }),
+        Just(ExecuteMsg::ClaimVested {}),
+        ("[a-z0-9]{1,64}", proptest::option::of(uint128()), address(), round()).prop_map(
+            |(asset, amount, recipient, round)| ExecuteMsg::Withdraw { asset, amount, recipient, round }
+        ),
+        (address(), round()).prop_map(|(address, round)| ExecuteMsg::WithdrawAirdrop {
+            address: Addr::unchecked(address),
+            round,
+        }),
+        (address(), round()).prop_map(|(address, round)| ExecuteMsg::WithdrawPrize {
+            address: Addr::unchecked(address),
+            round,
+        }),
+        address().prop_map(|address| ExecuteMsg::WithdrawRollover { address: Addr::unchecked(address) }),
+        round().prop_map(|round| ExecuteMsg::BurnUnclaimed { round }),
+    ]
+}
+
+fn merkle_root_kind() -> impl Strategy<Value = MerkleRootKind> {
+    prop_oneof![
+        Just(MerkleRootKind::Airdrop),
+        Just(MerkleRootKind::Game),
+        Just(MerkleRootKind::Stake),
+        Just(MerkleRootKind::Coupon),
+    ]
+}
+
+fn query_msg() -> impl Strategy<Value = QueryMsg> {
+    prop_oneof![
+        Just(QueryMsg::Config {}),
+        Just(QueryMsg::Ownership {}),
+        Just(QueryMsg::Limits {}),
+        Just(QueryMsg::Round {}),
+        round().prop_map(|round| QueryMsg::Stages { round }),
+        round().prop_map(|round| QueryMsg::GameStatus { round }),
+        (address(), round()).prop_map(|(address, round)| QueryMsg::Bid { address, round }),
+        round().prop_map(|round| QueryMsg::BinCounts { round }),
+        (any::<u8>(), round()).prop_map(|(bin, round)| QueryMsg::Odds { bin, round }),
+        round().prop_map(|round| QueryMsg::MerkleRoots { round }),
+        round().prop_map(|round| QueryMsg::GameAmounts { round }),
+        round().prop_map(|round| QueryMsg::Outstanding { round }),
+        round().prop_map(|round| QueryMsg::Treasury { round }),
+        (proptest::option::of(address()), proptest::option::of(any::<u32>()), round()).prop_map(
+            |(start_after, limit, round)| QueryMsg::ClaimedAirdrops { start_after, limit, round }
+        ),
+        (address(), round()).prop_map(|(address, round)| QueryMsg::IsClaimed { address, round }),
+        (address(), round())
+            .prop_map(|(address, round)| QueryMsg::ClaimableAmounts { address, round }),
+        round().prop_map(|round| QueryMsg::Sponsorships { round }),
+        (proptest::option::of(address()), proptest::option::of(any::<u32>()), round()).prop_map(
+            |(start_after, limit, round)| QueryMsg::Contributors { start_after, limit, round }
+        ),
+        round().prop_map(|round| QueryMsg::Rebates { round }),
+        round().prop_map(|round| QueryMsg::TicketPrice { round }),
+        address().prop_map(|address| QueryMsg::Notices { address }),
+        address().prop_map(|address| QueryMsg::Vesting { address }),
+        (proptest::option::of(address()), proptest::option::of(any::<u32>()))
+            .prop_map(|(start_after, limit)| QueryMsg::Blocklist { start_after, limit }),
+        (proptest::option::of("ibc/[0-9A-F]{64}"), proptest::option::of(any::<u32>()))
+            .prop_map(|(start_after, limit)| QueryMsg::IbcDenoms { start_after, limit }),
+        ("channel-[0-9]{1,4}", proptest::option::of(address()), proptest::option::of(any::<u32>()), round())
+            .prop_map(|(channel_id, start_after, limit, round)| QueryMsg::RemoteWinners {
+                channel_id,
+                start_after,
+                limit,
+                round
+            }),
+        address().prop_map(|address| QueryMsg::Referrals { address }),
+        address().prop_map(|address| QueryMsg::LoyaltyPoints { address }),
+        Just(QueryMsg::LoyaltyTiers {}),
+        round().prop_map(|round| QueryMsg::BinMultipliers { round }),
+        Just(QueryMsg::BinBoundaries {}),
+        address().prop_map(|address| QueryMsg::SeasonPass { address }),
+        (merkle_root_kind(), address(), "[0-9]{1,20}", proof(), round()).prop_map(
+            |(root_kind, address, amount_or_bin, proof, round)| QueryMsg::VerifyProof {
+                root_kind,
+                address,
+                amount_or_bin,
+                proof,
+                round,
+            }
+        ),
+    ]
+}
+
+fn sudo_msg() -> impl Strategy<Value = SudoMsg> {
+    let stage = prop_oneof![
+        Just(RoundStage::Bid),
+        Just(RoundStage::Reveal),
+        Just(RoundStage::ClaimAirdrop),
+        Just(RoundStage::ClaimPrize),
+    ];
+    prop_oneof![
+        Just(SudoMsg::Pause {}),
+        Just(SudoMsg::Unpause {}),
+        (stage, scheduled(), round())
+            .prop_map(|(stage, end, round)| SudoMsg::AdjustStageEnd { stage, end, round }),
+        round().prop_map(|round| SudoMsg::ForceRefunds { round }),
+    ]
+}
+
+static INSTANTIATE_MSG_SCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+static EXECUTE_MSG_SCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+static QUERY_MSG_SCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+static RECEIVE_MSG_SCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+static SUDO_MSG_SCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+
+proptest! {
+    #[test]
+    fn instantiate_msg_round_trip(msg in instantiate_msg()) {
+        check_round_trip(&msg, compiled_schema(&INSTANTIATE_MSG_SCHEMA, "instantiate_msg"))?;
+    }
+
+    #[test]
+    fn execute_msg_round_trip(msg in execute_msg()) {
+        check_round_trip(&msg, compiled_schema(&EXECUTE_MSG_SCHEMA, "execute_msg"))?;
+    }
+
+    #[test]
+    fn query_msg_round_trip(msg in query_msg()) {
+        check_round_trip(&msg, compiled_schema(&QUERY_MSG_SCHEMA, "query_msg"))?;
+    }
+
+    #[test]
+    fn receive_msg_round_trip(msg in receive_msg()) {
+        check_round_trip(&msg, compiled_schema(&RECEIVE_MSG_SCHEMA, "receive_msg"))?;
+    }
+
+    #[test]
+    fn sudo_msg_round_trip(msg in sudo_msg()) {
+        check_round_trip(&msg, compiled_schema(&SUDO_MSG_SCHEMA, "sudo_msg"))?;
+    }
+}